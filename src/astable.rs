@@ -1,6 +1,9 @@
 use nom::IResult;
+
+use crate::error::ParseError;
+
 pub trait ASTable<'a> {
-    fn parse(s: &'a str) -> IResult<&'a str, Self>
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>>
     where
         Self: Sized;
 }
@@ -0,0 +1,308 @@
+//! Prints the tokens or the syntax tree of Go files, or only checks them for syntax errors.
+
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    io::{self, Read},
+    process,
+};
+
+use go_parser_rs::{
+    cst::{self, SyntaxElement, SyntaxKind, SyntaxNode},
+    diagnostic::{Diagnostic, Label},
+    error::Span,
+    goast,
+    recover::parse_source_file,
+    tokenize::{tokenizer::tokenize_lossless, Token},
+};
+
+const USAGE: &str = "usage: go-parser <command> [--format debug|tree|json|go-ast] [file ...]
+
+commands:
+    tokens  print the tokens of each file
+    ast     print the syntax tree of each file
+    check   only report syntax errors
+
+The standard input is read when no file is given, or for a file named `-`.
+`tree` is the default format. In JSON, the tree is the lossless syntax tree,
+and each file is printed on a line of its own. `go-ast` prints the tree of
+`ast` as the JSON of Go's `go/ast` nodes, a file per line too.
+
+Syntax errors are printed to the standard error, and make the exit status 1.
+The exit status is 2 when the arguments are wrong or a file cannot be read.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Tokens,
+    Ast,
+    Check,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// The Rust `Debug` output: the tokens of the tokenizer, or the typed AST.
+    Debug,
+    /// One token per line with its position, or the lossless syntax tree, indented.
+    Tree,
+    Json,
+    /// The typed AST as `go/ast` nodes.
+    GoAst,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    format: Format,
+    files: Vec<String>,
+}
+
+/// Returns `None` when help is asked for.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let command = match args.next().as_deref() {
+        Some("tokens") => Command::Tokens,
+        Some("ast") => Command::Ast,
+        Some("check") => Command::Check,
+        Some("-h") | Some("--help") | Some("help") => return Ok(None),
+        Some(other) => return Err(format!("unknown command `{}`", other)),
+        None => return Err("no command given".to_string()),
+    };
+    let mut format = Format::Tree;
+    let mut files = vec![];
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-f" | "--format" => args.next().ok_or("`--format` needs a value")?,
+            _ => match arg.strip_prefix("--format=") {
+                Some(value) => value.to_string(),
+                None if arg.starts_with('-') && arg != "-" => {
+                    return Err(format!("unknown option `{}`", arg))
+                }
+                None => {
+                    files.push(arg);
+                    continue;
+                }
+            },
+        };
+        format = match value.as_str() {
+            "debug" => Format::Debug,
+            "tree" => Format::Tree,
+            "json" => Format::Json,
+            "go-ast" => Format::GoAst,
+            _ => return Err(format!("unknown format `{}`", value)),
+        };
+    }
+    if format == Format::GoAst && command != Command::Ast {
+        return Err("the `go-ast` format is only for `ast`".to_string());
+    }
+    if files.is_empty() {
+        files.push("-".to_string());
+    }
+    Ok(Some(Args {
+        command,
+        format,
+        files,
+    }))
+}
+
+fn read_source(file: &str) -> io::Result<String> {
+    if file == "-" {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        Ok(source)
+    } else {
+        fs::read_to_string(file)
+    }
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("go-parser: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    let mut failed = false;
+    for (i, file) in args.files.iter().enumerate() {
+        let source = match read_source(file) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!("go-parser: {}: {}", file, error);
+                process::exit(2);
+            }
+        };
+        let name = if file == "-" { "<stdin>" } else { file };
+        let headers = args.files.len() > 1
+            && args.command != Command::Check
+            && !matches!(args.format, Format::Json | Format::GoAst);
+        if headers {
+            if i > 0 {
+                println!();
+            }
+            println!("==> {} <==", name);
+        }
+        let (output, diagnostics) = run(args.command, args.format, &source);
+        print!("{}", output);
+        for diagnostic in &diagnostics {
+            eprint!("{}", diagnostic.render(name, &source));
+        }
+        failed |= !diagnostics.is_empty();
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Returns what to print for `source`, and its syntax errors.
+fn run(command: Command, format: Format, source: &str) -> (String, Vec<Diagnostic>) {
+    match command {
+        Command::Tokens => tokens(format, source),
+        Command::Ast => {
+            let (file, errors) = parse_source_file(source);
+            let output = match format {
+                Format::Debug => format!("{:#?}\n", file),
+                Format::Tree => cst::parse(source).debug_tree(),
+                Format::Json => {
+                    let mut json = String::new();
+                    write_node_json(&mut json, &cst::parse(source));
+                    json + "\n"
+                }
+                Format::GoAst => goast::to_json(&file, source) + "\n",
+            };
+            let diagnostics = errors
+                .iter()
+                .map(|error| Diagnostic::from_parse_error(error, source))
+                .collect();
+            (output, diagnostics)
+        }
+        Command::Check => {
+            let (_, errors) = parse_source_file(source);
+            let diagnostics = errors
+                .iter()
+                .map(|error| Diagnostic::from_parse_error(error, source))
+                .collect();
+            (String::new(), diagnostics)
+        }
+    }
+}
+
+fn tokens(format: Format, source: &str) -> (String, Vec<Diagnostic>) {
+    let mut output = String::new();
+    let mut diagnostics = vec![];
+    let (mut start, mut line, mut column) = (0, 1, 1);
+    let mut json_items = vec![];
+    for token in tokenize_lossless(source) {
+        let text = token.text();
+        let kind = SyntaxKind::from(&token);
+        if let Token::Unknown(text) = token {
+            diagnostics.push(unknown_token(text, start));
+        }
+        if kind != SyntaxKind::Whitespace {
+            match format {
+                Format::Debug => writeln!(output, "{:?}", token).unwrap(),
+                Format::Tree => {
+                    writeln!(output, "{}:{}\t{:?}\t{:?}", line, column, kind, text).unwrap()
+                }
+                Format::Json => json_items.push(format!(
+                    "{{\"kind\":{},\"text\":{},\"start\":{},\"end\":{}}}",
+                    json_string(&format!("{:?}", kind)),
+                    json_string(text),
+                    start,
+                    start + text.len()
+                )),
+                // Refused by `parse_args`.
+                Format::GoAst => unreachable!(),
+            }
+        }
+        for c in text.chars() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        start += text.len();
+    }
+    if format == Format::Json {
+        writeln!(output, "[{}]", json_items.join(",")).unwrap();
+    }
+    (output, diagnostics)
+}
+
+fn unknown_token(text: &str, start: usize) -> Diagnostic {
+    let message = match text.chars().next() {
+        Some('"') | Some('`') => "string literal not terminated".to_string(),
+        Some('\'') => "rune literal not terminated".to_string(),
+        Some(c) if c.is_ascii_digit() || c == '.' => format!("invalid number `{}`", text),
+        _ => format!("invalid character `{}`", text),
+    };
+    Diagnostic {
+        message,
+        labels: vec![Label {
+            span: Span {
+                start,
+                end: start + text.len(),
+            },
+            message: "not a Go token".to_string(),
+        }],
+        notes: vec![],
+    }
+}
+
+/// Writes a node as `{"kind":…,"start":…,"end":…,"children":[…]}`,
+/// where a token has its `text` instead of `children`.
+fn write_node_json(out: &mut String, node: &SyntaxNode) {
+    let range = node.text_range();
+    write!(
+        out,
+        "{{\"kind\":{},\"start\":{},\"end\":{},\"children\":[",
+        json_string(&format!("{:?}", node.kind())),
+        range.start,
+        range.end
+    )
+    .unwrap();
+    for (i, child) in node.children_with_tokens().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        match child {
+            SyntaxElement::Node(node) => write_node_json(out, &node),
+            SyntaxElement::Token(token) => {
+                let range = token.text_range();
+                write!(
+                    out,
+                    "{{\"kind\":{},\"start\":{},\"end\":{},\"text\":{}}}",
+                    json_string(&format!("{:?}", token.kind())),
+                    range.start,
+                    range.end,
+                    json_string(token.text())
+                )
+                .unwrap();
+            }
+        }
+    }
+    out.push_str("]}");
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
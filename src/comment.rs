@@ -0,0 +1,157 @@
+use nom::{
+    bytes::complete::tag,
+    character::complete::{multispace0, space0},
+    combinator::{map, opt},
+    multi::many0,
+    sequence::{preceded, terminated},
+    IResult,
+};
+
+use crate::{astable::ASTable, error::ParseError, parse_util::comment};
+
+/// A single `//`-style or `/*`-style comment, markers included.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comment<'a>(
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))] pub &'a str,
+);
+
+impl<'a> Comment<'a> {
+    /// Returns the comment text without its comment markers.
+    /// ```
+    /// use go_parser_rs::comment::Comment;
+    /// assert_eq!(Comment("// Hello").text(), " Hello");
+    /// assert_eq!(Comment("/* Hello */").text(), " Hello ");
+    /// ```
+    pub fn text(&self) -> &'a str {
+        let text = self.0;
+        if let Some(line) = text.strip_prefix("//") {
+            line
+        } else {
+            &text[2..text.len() - 2]
+        }
+    }
+}
+
+impl<'a> ASTable<'a> for Comment<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::comment::Comment;
+    /// assert_eq!(Comment::parse("// a\nb"), Ok(("\nb", Comment("// a"))));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        map(comment, Comment)(s)
+    }
+}
+
+/// A sequence of comments with no other tokens and no empty lines between, like `go/ast`'s `CommentGroup`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentGroup<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub list: Vec<Comment<'a>>,
+}
+
+impl<'a> CommentGroup<'a> {
+    /// Returns the text of the comment group, following `go/ast`'s `CommentGroup.Text`:
+    /// comment markers, the first space of a line comment, and leading/trailing empty lines are removed,
+    /// and each line is terminated by a newline.
+    /// ```
+    /// use go_parser_rs::comment::{Comment, CommentGroup};
+    /// let group = CommentGroup { list: vec![Comment("// Add adds."), Comment("//"), Comment("// It is pure.")] };
+    /// assert_eq!(group.text(), "Add adds.\n\nIt is pure.\n");
+    /// ```
+    pub fn text(&self) -> String {
+        let mut lines = vec![];
+        for comment in &self.list {
+            let text = comment.text();
+            if comment.0.starts_with("//") {
+                lines.push(text.strip_prefix(' ').unwrap_or(text).trim_end());
+            } else {
+                lines.extend(text.lines().map(str::trim_end));
+            }
+        }
+        let start = lines.iter().position(|l| !l.is_empty());
+        let end = lines.iter().rposition(|l| !l.is_empty());
+        match (start, end) {
+            (Some(start), Some(end)) => lines[start..=end]
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl<'a> ASTable<'a> for CommentGroup<'a> {
+    /// Comments belong to the same group while at most one newline separates them.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::comment::{Comment, CommentGroup};
+    /// assert_eq!(
+    ///     CommentGroup::parse("// a\n  // b\n\n// c"),
+    ///     Ok(("\n\n// c", CommentGroup { list: vec![Comment("// a"), Comment("// b")] }))
+    /// );
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, first) = Comment::parse(s)?;
+        let next_line = preceded(terminated(space0, opt(tag("\n"))), space0);
+        let (s, mut list) = many0(preceded(next_line, Comment::parse))(s)?;
+        list.insert(0, first);
+        Ok((s, CommentGroup { list }))
+    }
+}
+
+/// Skips blank space and free-standing comments in front of a declaration and returns
+/// the comment group ending on the line right before it, if any.
+/// ```
+/// use go_parser_rs::comment::{parse_doc, Comment, CommentGroup};
+/// let doc = Some(CommentGroup { list: vec![Comment("// doc")] });
+/// assert_eq!(parse_doc("\n// doc\nfunc"), Ok(("func", doc)));
+/// assert_eq!(parse_doc("// detached\n\nfunc"), Ok(("func", None)));
+/// ```
+pub fn parse_doc(s: &str) -> IResult<&str, Option<CommentGroup<'_>>, ParseError<'_>> {
+    let (mut s, _) = multispace0(s)?;
+    let mut doc = None;
+    while let (rest, Some(group)) = opt(CommentGroup::parse)(s)? {
+        let (rest, _) = space0(rest)?;
+        let (next, newline) = opt(tag("\n"))(rest)?;
+        let (after_blank, _) = space0(next)?;
+        let adjacent = newline.is_some() && !after_blank.starts_with(is_line_break);
+        doc = if adjacent { Some(group) } else { None };
+        s = multispace0(rest)?.0;
+    }
+    Ok((s, doc))
+}
+
+fn is_line_break(c: char) -> bool {
+    c == '\n' || c == '\r'
+}
+
+#[test]
+fn test_parse_doc_keeps_last_group() {
+    let src = "// first\n\n// second\n// lines\nfunc";
+    assert_eq!(
+        parse_doc(src),
+        Ok((
+            "func",
+            Some(CommentGroup {
+                list: vec![Comment("// second"), Comment("// lines")]
+            })
+        ))
+    );
+}
+
+#[test]
+fn test_parse_doc_without_comments() {
+    assert_eq!(parse_doc("  \n\nfunc"), Ok(("func", None)));
+    assert_eq!(parse_doc("/* eol */ func"), Ok(("func", None)));
+}
+
+#[test]
+fn test_comment_group_block_text() {
+    let group = CommentGroup {
+        list: vec![Comment("/*\n Package math does math.\n*/")],
+    };
+    assert_eq!(group.text(), " Package math does math.\n");
+}
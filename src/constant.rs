@@ -0,0 +1,1274 @@
+//! Exact values of constant expressions.
+//!
+//! Go computes constant expressions with arbitrary precision: integers are unbounded and
+//! floating-point values are kept as exact fractions. An untyped constant only has to fit a
+//! type once it is used as a value of that type, while the operations on a typed constant must
+//! give a value of its type. [`eval`] computes an expression of literals, `iota`, `true` and
+//! `false`, conversions to predeclared types and operators, and [`Constant::convert`] gives the
+//! value a type.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, expression::Expression, GoType};
+//! use go_parser_rs::constant::{eval, ErrorKind, Value};
+//! let (_, expr) = Expression::parse("1 << 100 >> 98").unwrap();
+//! assert_eq!(eval(&expr, None).unwrap().value, Value::Int(4.into()));
+//!
+//! let (_, expr) = Expression::parse("(1 << 10) * 0.5").unwrap();
+//! let half_k = eval(&expr, None).unwrap();
+//! assert_eq!(half_k.to_string(), "512");
+//! assert_eq!(half_k.convert(GoType::Int8), Err(ErrorKind::Overflow));
+//!
+//! let (_, expr) = Expression::parse("int8(1) << 7").unwrap();
+//! let error = eval(&expr, None).unwrap_err();
+//! assert_eq!((error.kind, error.message.as_str()), (ErrorKind::Overflow, "constant 128 overflows int8"));
+//! ```
+
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::{
+    expression::{
+        operand::{Operand, OperandName},
+        Expression, PrimaryExpr, UnaryExpr,
+    },
+    literals::{integer::IntLit, Literal},
+    printer::print,
+    types::{primary_first_token, print_unary, Type, Untyped},
+    GoType,
+};
+
+/// The largest count a constant can be shifted by, that of the smallest nonzero float64.
+const SHIFT_BOUND: u64 = 1023 - 1 + 52;
+
+/// The value of a constant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    /// The bytes of the string, which need not be UTF-8.
+    String(Vec<u8>),
+    Int(BigInt),
+    Float(BigRational),
+    /// The real and imaginary parts.
+    Complex(BigRational, BigRational),
+}
+
+impl Value {
+    /// The value as an integer, if it is a number with no fractional or imaginary part.
+    pub fn to_int(&self) -> Option<BigInt> {
+        match self {
+            Value::Int(int) => Some(int.clone()),
+            Value::Float(float) if float.is_integer() => Some(float.to_integer()),
+            Value::Complex(re, im) if im.is_zero() && re.is_integer() => Some(re.to_integer()),
+            _ => None,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        match self {
+            Value::Int(int) => int.is_zero(),
+            Value::Float(float) => float.is_zero(),
+            Value::Complex(re, im) => re.is_zero() && im.is_zero(),
+            Value::Bool(_) | Value::String(_) => false,
+        }
+    }
+
+    fn to_float(&self) -> Option<BigRational> {
+        match self {
+            Value::Int(int) => Some(BigRational::from_integer(int.clone())),
+            Value::Float(float) => Some(float.clone()),
+            Value::Complex(re, im) if im.is_zero() => Some(re.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_complex(&self) -> Option<(BigRational, BigRational)> {
+        match self {
+            Value::Complex(re, im) => Some((re.clone(), im.clone())),
+            value => Some((value.to_float()?, BigRational::zero())),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::String(bytes) => f.write_str(&quote(bytes)),
+            Value::Int(int) => write!(f, "{}", int),
+            Value::Float(float) => f.write_str(&float_text(float)),
+            Value::Complex(re, im) => write!(f, "({} + {}i)", float_text(re), float_text(im)),
+        }
+    }
+}
+
+fn float_text(float: &BigRational) -> String {
+    let f = float.to_f64().filter(|f| f.is_finite());
+    match f {
+        Some(f) if float.is_integer() && f.abs() >= 1e21 => format!("{:e}", f),
+        _ if float.is_integer() => float.to_integer().to_string(),
+        Some(f) => f.to_string(),
+        _ => format!("{}/{}", float.numer(), float.denom()),
+    }
+}
+
+/// `bytes` as an interpreted string literal.
+fn quote(bytes: &[u8]) -> String {
+    let mut out = String::from("\"");
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            out.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A constant: its value, and either its [`Type::Untyped`] kind or the [`Type::Basic`] type it
+/// was given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
+    pub value: Value,
+    pub typ: Type<'static>,
+}
+
+impl Constant {
+    pub fn untyped(kind: Untyped, value: Value) -> Self {
+        Constant {
+            value,
+            typ: Type::Untyped(kind),
+        }
+    }
+
+    /// The constant of type `typ` with the value of this one, which must be representable by
+    /// a value of that type: integers must fit their size, and floating-point numbers are
+    /// rounded to theirs.
+    /// ```
+    /// use go_parser_rs::{constant::{Constant, ErrorKind, Value}, types::Untyped, GoType};
+    /// let big = Constant::untyped(Untyped::Int, Value::Int(300.into()));
+    /// assert_eq!(big.convert(GoType::Int16).unwrap().value, Value::Int(300.into()));
+    /// assert_eq!(big.convert(GoType::Uint8), Err(ErrorKind::Overflow));
+    /// assert_eq!(big.convert(GoType::String), Err(ErrorKind::InvalidConversion));
+    /// ```
+    pub fn convert(&self, typ: GoType) -> Result<Constant, ErrorKind> {
+        let typ = typ.unaliased();
+        let value = match (&self.value, typ) {
+            (Value::Bool(_), GoType::Bool) | (Value::String(_), GoType::String) => {
+                self.value.clone()
+            }
+            (value, typ) if typ.is_integer() => {
+                let int = value.to_int().ok_or(match value {
+                    Value::Float(_) | Value::Complex(..) => ErrorKind::Truncated,
+                    _ => ErrorKind::InvalidConversion,
+                })?;
+                let bits = size(typ);
+                let (min, max) = match typ.is_unsigned() {
+                    true => (BigInt::zero(), (BigInt::from(1) << bits) - 1),
+                    false => (
+                        -(BigInt::from(1) << (bits - 1)),
+                        (BigInt::from(1) << (bits - 1)) - 1,
+                    ),
+                };
+                if int < min || int > max {
+                    return Err(ErrorKind::Overflow);
+                }
+                Value::Int(int)
+            }
+            (value, typ) if typ.is_float() => {
+                let float = value.to_float().ok_or(match value {
+                    Value::Complex(..) => ErrorKind::Truncated,
+                    _ => ErrorKind::InvalidConversion,
+                })?;
+                Value::Float(round(&float, typ).ok_or(ErrorKind::Overflow)?)
+            }
+            (value, typ) if typ.is_complex() => {
+                let (re, im) = value.to_complex().ok_or(ErrorKind::InvalidConversion)?;
+                let part = match typ {
+                    GoType::Complex64 => GoType::Float32,
+                    _ => GoType::Float64,
+                };
+                let round = |float| round(float, part).ok_or(ErrorKind::Overflow);
+                Value::Complex(round(&re)?, round(&im)?)
+            }
+            _ => return Err(ErrorKind::InvalidConversion),
+        };
+        Ok(Constant {
+            value,
+            typ: Type::Basic(typ),
+        })
+    }
+
+    /// The operand written `text` as Go describes it in errors, like `x (untyped int constant 1)`.
+    pub(crate) fn describe(&self, text: &str) -> String {
+        let value = self.value.to_string();
+        match (&self.typ, text == value) {
+            (_, true) => format!("{} ({})", text, self.class()),
+            (Type::Untyped(kind), false) => {
+                format!("{} ({} constant {})", text, kind.name(), value)
+            }
+            (typ, false) => format!("{} (constant {} of type {})", text, value, typ),
+        }
+    }
+
+    fn class(&self) -> String {
+        match &self.typ {
+            Type::Untyped(kind) => format!("{} constant", kind.name()),
+            typ => format!("constant of type {}", typ),
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// The size in bits of an integer type.
+fn size(typ: GoType) -> usize {
+    match typ.unaliased() {
+        GoType::Int8 | GoType::Uint8 => 8,
+        GoType::Int16 | GoType::Uint16 => 16,
+        GoType::Int32 | GoType::Uint32 => 32,
+        _ => 64,
+    }
+}
+
+/// `float` rounded to the nearest value of the floating-point type `typ`, or `None` if it is
+/// too big for it.
+fn round(float: &BigRational, typ: GoType) -> Option<BigRational> {
+    let rounded = match typ {
+        GoType::Float32 => f64::from(float.to_f32()?),
+        _ => float.to_f64()?,
+    };
+    match rounded.is_finite() {
+        true => BigRational::from_float(rounded),
+        false => None,
+    }
+}
+
+/// What a name used in a constant expression denotes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Name {
+    Constant(Box<Constant>),
+    /// The predeclared `iota`.
+    Iota,
+    /// A type with a predeclared underlying type, which constants can be converted to.
+    Type(GoType),
+}
+
+/// What a predeclared name denotes, if it can be part of a constant expression.
+pub fn universe(name: &str) -> Option<Name> {
+    match name {
+        "true" | "false" => Some(Name::Constant(Box::new(Constant::untyped(
+            Untyped::Bool,
+            Value::Bool(name == "true"),
+        )))),
+        "iota" => Some(Name::Iota),
+        name => GoType::from_name(name).map(Name::Type),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// An expression whose value is only known at run time.
+    NotConstant,
+    /// An operator applied to a constant of a kind or type it is not defined on.
+    InvalidOperation,
+    MismatchedTypes,
+    /// A value that cannot be given a type, like a string converted to an integer type.
+    InvalidConversion,
+    DivisionByZero,
+    /// A value too big for its type.
+    Overflow,
+    /// A value with a fractional or imaginary part given a type that cannot have it.
+    Truncated,
+}
+
+/// An error of the evaluation of a constant expression, at the token `at` when it has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error<'a> {
+    pub kind: ErrorKind,
+    pub at: Option<&'a str>,
+    pub message: String,
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error<'_> {}
+
+/// Evaluates `expr`, where `iota` is the index of the constant spec it is part of, if any.
+/// Only the predeclared names are known, see [`eval_with`] for the others.
+pub fn eval<'a>(expr: &'a Expression<'a>, iota: Option<usize>) -> Result<Constant, Error<'a>> {
+    eval_with(expr, iota, &mut universe)
+}
+
+/// Like [`eval`], with the names denoting what `names` returns for them, and nothing constant
+/// when it returns `None`.
+/// ```
+/// use go_parser_rs::{astable::ASTable, expression::Expression};
+/// use go_parser_rs::constant::{eval_with, universe, Constant, ErrorKind, Name, Value};
+/// use go_parser_rs::types::Untyped;
+/// let (_, expr) = Expression::parse("KB / (iota - 2)").unwrap();
+/// let mut names = |name: &str| match name {
+///     "KB" => Some(Name::Constant(Box::new(Constant::untyped(Untyped::Int, Value::Int(1024.into()))))),
+///     name => universe(name),
+/// };
+/// assert_eq!(eval_with(&expr, Some(4), &mut names).unwrap().value, Value::Int(512.into()));
+/// let error = eval_with(&expr, Some(2), &mut names).unwrap_err();
+/// assert_eq!((error.kind, error.at), (ErrorKind::DivisionByZero, Some("/")));
+/// ```
+pub fn eval_with<'a>(
+    expr: &'a Expression<'a>,
+    iota: Option<usize>,
+    names: &mut dyn FnMut(&'a str) -> Option<Name>,
+) -> Result<Constant, Error<'a>> {
+    Evaluator { iota, names }.expr(expr)
+}
+
+struct Evaluator<'n, 'a> {
+    iota: Option<usize>,
+    names: &'n mut dyn FnMut(&'a str) -> Option<Name>,
+}
+
+fn error<'a, T>(kind: ErrorKind, at: Option<&'a str>, message: String) -> Result<T, Error<'a>> {
+    Err(Error { kind, at, message })
+}
+
+fn not_constant<'a, T>(at: Option<&'a str>, text: String) -> Result<T, Error<'a>> {
+    error(
+        ErrorKind::NotConstant,
+        at,
+        format!("{} is not constant", text),
+    )
+}
+
+impl<'n, 'a> Evaluator<'n, 'a> {
+    fn expr(&mut self, expr: &'a Expression<'a>) -> Result<Constant, Error<'a>> {
+        match expr {
+            Expression::UnaryExpr(unary) => self.unary_expr(unary),
+            Expression::BinExpr { left, op, right } => {
+                let (x, y) = (self.expr(left)?, self.expr(right)?);
+                match *op {
+                    "<<" | ">>" => shift(x, op, y, || print(&**left)),
+                    _ => binary(x, op, y, || print(expr)),
+                }
+            }
+            Expression::BadExpr(text) => not_constant(Some(text), text.to_string()),
+        }
+    }
+
+    fn unary_expr(&mut self, expr: &'a UnaryExpr<'a>) -> Result<Constant, Error<'a>> {
+        let unary = match expr {
+            UnaryExpr::PrimaryExpr(primary) => return self.primary(primary),
+            UnaryExpr::Unary(unary) => unary,
+        };
+        let x = self.unary_expr(&unary.expr)?;
+        let op = unary.op;
+        let value = match (op, &x.value) {
+            ("+", Value::Int(_) | Value::Float(_) | Value::Complex(..)) => x.value.clone(),
+            ("-", Value::Int(int)) => Value::Int(-int),
+            ("-", Value::Float(float)) => Value::Float(-float),
+            ("-", Value::Complex(re, im)) => Value::Complex(-re, -im),
+            ("!", Value::Bool(b)) => Value::Bool(!b),
+            ("^", Value::Int(int)) => match &x.typ {
+                Type::Basic(typ) if typ.is_unsigned() => {
+                    Value::Int(int ^ ((BigInt::from(1) << size(*typ)) - 1))
+                }
+                _ => Value::Int(-int - 1),
+            },
+            ("&" | "*" | "<-", _) => return not_constant(Some(op), print_unary(expr)),
+            _ => {
+                let message = format!(
+                    "invalid operation: operator {} not defined on {}",
+                    op,
+                    x.describe(&print_unary(&unary.expr))
+                );
+                return error(ErrorKind::InvalidOperation, Some(op), message);
+            }
+        };
+        typed(value, x.typ, Some(op))
+    }
+
+    fn primary(&mut self, expr: &'a PrimaryExpr<'a>) -> Result<Constant, Error<'a>> {
+        let at = primary_first_token(expr);
+        match expr {
+            PrimaryExpr::Operand(Operand::Literal(literal)) => Ok(literal_value(literal)),
+            PrimaryExpr::Operand(Operand::Parenthesized(expr)) => self.expr(expr),
+            PrimaryExpr::Operand(Operand::OperandName(OperandName::Identifier(name))) => {
+                match (self.names)(name) {
+                    Some(Name::Constant(constant)) => Ok(*constant),
+                    Some(Name::Iota) => match self.iota {
+                        Some(iota) => Ok(Constant::untyped(Untyped::Int, Value::Int(iota.into()))),
+                        None => error(
+                            ErrorKind::NotConstant,
+                            Some(name),
+                            "cannot use iota outside constant declaration".to_string(),
+                        ),
+                    },
+                    Some(Name::Type(_)) | None => not_constant(Some(name), name.to_string()),
+                }
+            }
+            PrimaryExpr::Call {
+                func,
+                args,
+                ellipsis: false,
+            } if args.len() == 1 => match self.conversion_type(func) {
+                Some(typ) => {
+                    let x = self.expr(&args[0])?;
+                    conversion(x, typ, || print(&args[0]), at)
+                }
+                None => not_constant(at, print(expr)),
+            },
+            expr => not_constant(at, print(expr)),
+        }
+    }
+
+    /// The type `func` denotes, when it is a conversion to a type constants can have.
+    fn conversion_type(&mut self, func: &'a PrimaryExpr<'a>) -> Option<GoType> {
+        match func {
+            PrimaryExpr::Operand(Operand::OperandName(OperandName::Identifier(name))) => {
+                match (self.names)(name)? {
+                    Name::Type(typ) => Some(typ),
+                    _ => None,
+                }
+            }
+            PrimaryExpr::Operand(Operand::Parenthesized(expr)) => match &**expr {
+                Expression::UnaryExpr(UnaryExpr::PrimaryExpr(func)) => self.conversion_type(func),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The constant of type `typ` with `value`, the result of an operation on a constant of that type.
+fn typed<'a>(value: Value, typ: Type<'static>, at: Option<&'a str>) -> Result<Constant, Error<'a>> {
+    let basic = match typ {
+        Type::Basic(basic) => basic,
+        typ => return Ok(Constant { value, typ }),
+    };
+    let constant = Constant::untyped(Untyped::Int, value);
+    constant.convert(basic).or_else(|kind| {
+        let message = match kind {
+            ErrorKind::Truncated => format!("constant {} truncated to {}", constant, typ),
+            _ => format!("constant {} overflows {}", constant, typ),
+        };
+        error(kind, at, message)
+    })
+}
+
+/// The conversion `typ(x)`, where `text` prints `x`.
+pub(crate) fn conversion<'a>(
+    x: Constant,
+    typ: GoType,
+    text: impl Fn() -> String,
+    at: Option<&'a str>,
+) -> Result<Constant, Error<'a>> {
+    // An integer converted to a string is the UTF-8 encoding of the character it is the code of.
+    if let (Value::Int(int), GoType::String) = (&x.value, typ) {
+        let c = int.to_u32().and_then(char::from_u32).unwrap_or('\u{fffd}');
+        return Ok(Constant {
+            value: Value::String(c.to_string().into_bytes()),
+            typ: Type::Basic(typ),
+        });
+    }
+    x.convert(typ).or_else(|kind| {
+        let integer = match &x.typ {
+            Type::Untyped(kind) => matches!(kind, Untyped::Int | Untyped::Rune),
+            Type::Basic(basic) => basic.is_integer(),
+            _ => false,
+        };
+        // Only an integer converted to an integer type gets the short message.
+        let integers = integer && typ.is_integer();
+        let message = match kind {
+            ErrorKind::Overflow if integers => format!("constant {} overflows {}", x, typ.name()),
+            ErrorKind::Truncated => format!(
+                "cannot convert {} to type {} (truncated)",
+                x.describe(&text()),
+                typ.name()
+            ),
+            _ => format!(
+                "cannot convert {} to type {}",
+                x.describe(&text()),
+                typ.name()
+            ),
+        };
+        error(kind, at, message)
+    })
+}
+
+/// `x op y`, where `text` prints the operation.
+fn binary<'a>(
+    x: Constant,
+    op: &'a str,
+    y: Constant,
+    text: impl Fn() -> String,
+) -> Result<Constant, Error<'a>> {
+    let at = Some(op);
+    let invalid = |x: &Constant| {
+        let message = format!(
+            "invalid operation: operator {} not defined on {} ({})",
+            op,
+            text(),
+            x.class()
+        );
+        error(ErrorKind::InvalidOperation, at, message)
+    };
+    let (x, y) = unify(x, y, at, &text)?;
+    let comparison = matches!(op, "==" | "!=" | "<" | "<=" | ">" | ">=");
+    if (op == "/" || op == "%") && y.value.is_zero() {
+        let message = "invalid operation: division by zero".to_string();
+        return error(ErrorKind::DivisionByZero, at, message);
+    }
+    let value = match (&x.value, &y.value) {
+        (Value::Bool(a), Value::Bool(b)) => match op {
+            "&&" => Value::Bool(*a && *b),
+            "||" => Value::Bool(*a || *b),
+            "==" => Value::Bool(a == b),
+            "!=" => Value::Bool(a != b),
+            _ => return invalid(&x),
+        },
+        (Value::String(a), Value::String(b)) => match op {
+            "+" => Value::String([&a[..], &b[..]].concat()),
+            op if comparison => Value::Bool(compare(a, op, b)),
+            _ => return invalid(&x),
+        },
+        (Value::Int(a), Value::Int(b)) => match op {
+            "+" => Value::Int(a + b),
+            "-" => Value::Int(a - b),
+            "*" => Value::Int(a * b),
+            // Truncated towards zero, like Go's.
+            "/" => Value::Int(a / b),
+            "%" => Value::Int(a % b),
+            "&" => Value::Int(a & b),
+            "|" => Value::Int(a | b),
+            "^" => Value::Int(a ^ b),
+            "&^" => Value::Int(a & !b),
+            op if comparison => Value::Bool(compare(a, op, b)),
+            _ => return invalid(&x),
+        },
+        (Value::Float(a), Value::Float(b)) => match op {
+            "+" => Value::Float(a + b),
+            "-" => Value::Float(a - b),
+            "*" => Value::Float(a * b),
+            "/" => Value::Float(a / b),
+            op if comparison => Value::Bool(compare(a, op, b)),
+            _ => return invalid(&x),
+        },
+        (Value::Complex(a, b), Value::Complex(c, d)) => match op {
+            "+" => Value::Complex(a + c, b + d),
+            "-" => Value::Complex(a - c, b - d),
+            "*" => Value::Complex(a * c - b * d, a * d + b * c),
+            "/" => {
+                let norm = c * c + d * d;
+                Value::Complex((a * c + b * d) / &norm, (b * c - a * d) / &norm)
+            }
+            "==" => Value::Bool(a == c && b == d),
+            "!=" => Value::Bool(a != c || b != d),
+            _ => return invalid(&x),
+        },
+        _ => unreachable!(
+            "operands of different kinds: {:?} and {:?}",
+            x.value, y.value
+        ),
+    };
+    match comparison {
+        true => Ok(Constant::untyped(Untyped::Bool, value)),
+        false => typed(value, x.typ, at),
+    }
+}
+
+fn compare<T: PartialOrd + ?Sized>(a: &T, op: &str, b: &T) -> bool {
+    match op {
+        "==" => a == b,
+        "!=" => a != b,
+        "<" => a < b,
+        "<=" => a <= b,
+        ">" => a > b,
+        _ => a >= b,
+    }
+}
+
+/// `x` and `y` converted to the same type: the type of the typed one, or the greater kind.
+fn unify<'a>(
+    x: Constant,
+    y: Constant,
+    at: Option<&'a str>,
+    text: impl Fn() -> String,
+) -> Result<(Constant, Constant), Error<'a>> {
+    let mismatch = || {
+        let message = format!(
+            "invalid operation: {} (mismatched types {} and {})",
+            text(),
+            x.typ,
+            y.typ
+        );
+        error(ErrorKind::MismatchedTypes, at, message)
+    };
+    match (&x.typ, &y.typ) {
+        (Type::Untyped(a), Type::Untyped(b)) if a == b => Ok((x, y)),
+        (Type::Untyped(a), Type::Untyped(b)) if a.is_numeric() && b.is_numeric() => {
+            let kind = *a.max(b);
+            match (promote(&x, kind), promote(&y, kind)) {
+                (Some(x), Some(y)) => Ok((x, y)),
+                _ => mismatch(),
+            }
+        }
+        (Type::Basic(a), Type::Basic(b)) if a.unaliased() == b.unaliased() => Ok((x, y)),
+        (Type::Basic(typ), Type::Untyped(_)) => match y.convert(*typ) {
+            Ok(y) => Ok((x, y)),
+            Err(ErrorKind::InvalidConversion) => mismatch(),
+            Err(kind) => error(kind, at, representation(&y, *typ, kind, &text)),
+        },
+        (Type::Untyped(_), Type::Basic(typ)) => match x.convert(*typ) {
+            Ok(x) => Ok((x, y)),
+            Err(ErrorKind::InvalidConversion) => mismatch(),
+            Err(kind) => error(kind, at, representation(&x, *typ, kind, &text)),
+        },
+        _ => mismatch(),
+    }
+}
+
+fn representation(x: &Constant, typ: GoType, kind: ErrorKind, text: impl Fn() -> String) -> String {
+    let reason = match kind {
+        ErrorKind::Truncated => "truncated",
+        _ => "overflows",
+    };
+    format!(
+        "invalid operation: {} ({} {} {})",
+        text(),
+        x,
+        reason,
+        typ.name()
+    )
+}
+
+/// The untyped constant `x` as one of the greater numeric kind `kind`.
+fn promote(x: &Constant, kind: Untyped) -> Option<Constant> {
+    let value = match kind {
+        Untyped::Int | Untyped::Rune => x.value.clone(),
+        Untyped::Float => Value::Float(x.value.to_float()?),
+        _ => {
+            let (re, im) = x.value.to_complex()?;
+            Value::Complex(re, im)
+        }
+    };
+    Some(Constant::untyped(kind, value))
+}
+
+fn shift<'a>(
+    x: Constant,
+    op: &'a str,
+    y: Constant,
+    text: impl Fn() -> String,
+) -> Result<Constant, Error<'a>> {
+    let at = Some(op);
+    let count = match (&y.typ, y.value.to_int()) {
+        (Type::Basic(typ), Some(count)) if !typ.is_integer() => (count, false),
+        (_, Some(count)) => {
+            let valid = !count.is_negative() && count <= BigInt::from(SHIFT_BOUND);
+            (count, valid)
+        }
+        (_, None) => (BigInt::zero(), false),
+    };
+    let count = match count {
+        (count, true) => count.to_usize().unwrap_or_default(),
+        _ => {
+            let message = format!("invalid shift count {}", y);
+            return error(ErrorKind::InvalidOperation, at, message);
+        }
+    };
+    let (int, typ) = match (x.value.to_int(), &x.typ) {
+        (Some(int), Type::Untyped(Untyped::Rune)) => (int, Type::Untyped(Untyped::Rune)),
+        (Some(int), Type::Untyped(_)) => (int, Type::Untyped(Untyped::Int)),
+        (Some(int), Type::Basic(typ)) if typ.is_integer() => (int, x.typ.clone()),
+        _ => {
+            let message = format!(
+                "invalid operation: shifted operand {} must be integer",
+                x.describe(&text())
+            );
+            return error(ErrorKind::InvalidOperation, at, message);
+        }
+    };
+    let int = match op {
+        "<<" => int << count,
+        // Rounded towards negative infinity, like an arithmetic shift.
+        _ => int >> count,
+    };
+    typed(Value::Int(int), typ, at)
+}
+
+/// The value of a literal, which is always an untyped constant.
+fn literal_value(literal: &Literal<'_>) -> Constant {
+    match literal {
+        Literal::IntLit(
+            IntLit::DecimalLit(text)
+            | IntLit::BinaryLit(text)
+            | IntLit::OctalLit(text)
+            | IntLit::HexLit(text),
+        ) => Constant::untyped(Untyped::Int, Value::Int(int_value(text))),
+        Literal::FloatLit(text) => {
+            Constant::untyped(Untyped::Float, Value::Float(float_value(text)))
+        }
+        Literal::ImaginaryLit(text) => {
+            let text = &text[..text.len() - 1];
+            let decimal = text.bytes().all(|b| b.is_ascii_digit() || b == b'_');
+            let im = match int_lit_syntax(text) {
+                // Leading zeros don't make it octal, for backward compatibility.
+                _ if decimal => BigRational::from_integer(int_value(text.trim_start_matches('0'))),
+                true => BigRational::from_integer(int_value(text)),
+                false => float_value(text),
+            };
+            Constant::untyped(Untyped::Complex, Value::Complex(BigRational::zero(), im))
+        }
+        Literal::RuneLit(text) => {
+            Constant::untyped(Untyped::Rune, Value::Int(rune_value(text).into()))
+        }
+        Literal::StringLit(text) => {
+            Constant::untyped(Untyped::String, Value::String(string_value(text)))
+        }
+    }
+}
+
+fn int_lit_syntax(text: &str) -> bool {
+    let hex = text.starts_with("0x") || text.starts_with("0X");
+    !text.contains('.') && !text.contains(if hex { ['p', 'P'] } else { ['e', 'E'] })
+}
+
+/// The value of an integer literal, whose base is given by its prefix.
+fn int_value(text: &str) -> BigInt {
+    let digits = text.replace('_', "");
+    let (digits, radix) = match digits.get(..2) {
+        Some("0x" | "0X") => (&digits[2..], 16),
+        Some("0b" | "0B") => (&digits[2..], 2),
+        Some("0o" | "0O") => (&digits[2..], 8),
+        _ if digits.len() > 1 && digits.starts_with('0') => (&digits[1..], 8),
+        _ if digits.is_empty() => ("0", 10),
+        _ => (&digits[..], 10),
+    };
+    BigInt::parse_bytes(digits.as_bytes(), radix).unwrap_or_default()
+}
+
+/// The exact value of a floating-point literal.
+fn float_value(text: &str) -> BigRational {
+    let text = text.replace('_', "");
+    let (mantissa, exponent, base, digit_base) = match text.get(..2) {
+        Some("0x" | "0X") => {
+            let (mantissa, exponent) = text[2..]
+                .split_once(['p', 'P'])
+                .unwrap_or((&text[2..], "0"));
+            (mantissa, exponent, 2, 16)
+        }
+        _ => {
+            let (mantissa, exponent) = text.split_once(['e', 'E']).unwrap_or((&text, "0"));
+            (mantissa, exponent, 10, 10)
+        }
+    };
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits =
+        BigInt::parse_bytes(format!("0{}{}", int, frac).as_bytes(), digit_base).unwrap_or_default();
+    // Each fractional digit is a power of the base of the digits, 16 being 2 to the 4th.
+    let per_digit = if digit_base == 16 { 4 } else { 1 };
+    let exponent = exponent.parse::<i64>().unwrap_or_default() - per_digit * frac.len() as i64;
+    let scale = BigInt::from(base).pow(exponent.unsigned_abs() as u32);
+    match exponent < 0 {
+        true => BigRational::new(digits, scale),
+        false => BigRational::from_integer(digits * scale),
+    }
+}
+
+/// The bytes of a string literal, its escapes replaced.
+fn string_value(text: &str) -> Vec<u8> {
+    if let Some(raw) = text.strip_prefix('`') {
+        // Carriage returns are discarded from raw strings.
+        return raw[..raw.len() - 1].replace('\r', "").into_bytes();
+    }
+    let mut bytes = vec![];
+    for unit in unescape(&text[1..text.len() - 1]) {
+        match unit {
+            Unit::Char(c) => push_char(&mut bytes, c),
+            Unit::Byte(byte) => bytes.push(byte),
+        }
+    }
+    bytes
+}
+
+/// The value of a rune literal, a code point or the byte of a byte escape.
+fn rune_value(text: &str) -> u32 {
+    match unescape(&text[1..text.len() - 1]).next() {
+        Some(Unit::Char(c)) => c as u32,
+        Some(Unit::Byte(byte)) => byte.into(),
+        None => 0,
+    }
+}
+
+/// What an interpreted string or a rune literal is made of.
+enum Unit {
+    Char(char),
+    /// A byte written with a hexadecimal or octal escape.
+    Byte(u8),
+}
+
+/// The units of the text between the quotes of a literal, its escapes replaced.
+fn unescape(text: &str) -> impl Iterator<Item = Unit> + '_ {
+    let mut chars = text.chars();
+    std::iter::from_fn(move || {
+        let c = chars.next()?;
+        if c != '\\' {
+            return Some(Unit::Char(c));
+        }
+        let unit = match chars.clone().next() {
+            Some('x') => {
+                chars.next();
+                Unit::Byte(escaped(&mut chars, 2, 16) as u8)
+            }
+            Some('0'..='7') => Unit::Byte(escaped(&mut chars, 3, 8) as u8),
+            Some(c @ ('u' | 'U')) => {
+                chars.next();
+                let code = escaped(&mut chars, if c == 'u' { 4 } else { 8 }, 16);
+                Unit::Char(char::from_u32(code).unwrap_or('\u{fffd}'))
+            }
+            _ => Unit::Char(match chars.next()? {
+                'a' => '\u{7}',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'v' => '\u{b}',
+                c => c,
+            }),
+        };
+        Some(unit)
+    })
+}
+
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+/// The number written with the next `len` digits of an escape.
+fn escaped(chars: &mut std::str::Chars<'_>, len: usize, radix: u32) -> u32 {
+    let digits: String = chars.take(len).collect();
+    u32::from_str_radix(&digits, radix).unwrap_or_default()
+}
+
+#[test]
+fn test_eval_literals_and_operators() {
+    use crate::astable::ASTable;
+
+    let cases = [
+        ("0x1.8p1 + .5", "3.5", "untyped float"),
+        ("1_000e-3 * 3i", "(0 + 3i)", "untyped complex"),
+        ("(1 + 2i) / (3 - 4i)", "(-0.2 + 0.4i)", "untyped complex"),
+        ("0o17 + 0b11 + 017 + 012i*0", "(33 + 0i)", "untyped complex"),
+        ("7 / 2", "3", "untyped int"),
+        ("7 / 2.0", "3.5", "untyped float"),
+        ("-7 % 3", "-1", "untyped int"),
+        ("-7 >> 1", "-4", "untyped int"),
+        ("1.0 << 65", "36893488147419103232", "untyped int"),
+        ("'a'+1", "98", "untyped rune"),
+        ("'a' + 1", "98", "untyped rune"),
+        ("'\\n'", "10", "untyped rune"),
+        ("'\\x00' + '\\377'", "255", "untyped rune"),
+        ("'\\u00e9' - 'é'", "0", "untyped rune"),
+        ("^0 &^ 6", "-7", "untyped int"),
+        ("^uint8(1)", "254", "uint8"),
+        ("float64(float32(0.1)) == 0.1", "false", "untyped bool"),
+        (
+            "\"a\\x41\\u00e9\" + `\\n`",
+            "\"aAé\\\\n\"",
+            "untyped string",
+        ),
+        ("string(0x1F600) < \"b\" || !true", "false", "untyped bool"),
+    ];
+    for (src, value, typ) in cases.iter() {
+        let (rest, expr) = Expression::parse(src).unwrap();
+        assert_eq!(rest, "");
+        let constant = eval(&expr, None).unwrap_or_else(|e| panic!("{}: {}", src, e));
+        assert_eq!(
+            (constant.to_string(), constant.typ.to_string()),
+            (value.to_string(), typ.to_string()),
+            "{}",
+            src
+        );
+    }
+
+    let errors = [
+        ("1 << -1", ErrorKind::InvalidOperation, "<<"),
+        ("1.5 << 1", ErrorKind::InvalidOperation, "<<"),
+        ("1 + \"a\"", ErrorKind::MismatchedTypes, "+"),
+        ("int8(1) + int16(1)", ErrorKind::MismatchedTypes, "+"),
+        ("int8(100) + 100", ErrorKind::Overflow, "+"),
+        ("-uint(1)", ErrorKind::Overflow, "-"),
+        ("uint64(1 << 64)", ErrorKind::Overflow, "uint64"),
+        ("int(2.5)", ErrorKind::Truncated, "int"),
+        ("1 % (2 - 2)", ErrorKind::DivisionByZero, "%"),
+        ("(1+1i) < 2", ErrorKind::InvalidOperation, "<"),
+        ("iota", ErrorKind::NotConstant, "iota"),
+        ("x * 2", ErrorKind::NotConstant, "x"),
+    ];
+    for (src, kind, at) in errors.iter() {
+        let (_, expr) = Expression::parse(src).unwrap();
+        let error = eval(&expr, None).unwrap_err();
+        assert_eq!((error.kind, error.at), (*kind, Some(*at)), "{}", src);
+    }
+}
+
+/// The value and the type of the constant expression `src`, or the kind and the message of its
+/// error.
+#[cfg(test)]
+fn eval_str(src: &str) -> Result<(String, String), (ErrorKind, String)> {
+    use crate::astable::ASTable;
+
+    let (rest, expr) = Expression::parse(src).unwrap();
+    assert_eq!(rest, "", "{}", src);
+    match eval(&expr, None) {
+        Ok(constant) => Ok((constant.to_string(), constant.typ.to_string())),
+        Err(error) => Err((error.kind, error.message)),
+    }
+}
+
+#[test]
+fn test_eval_integer_operators() {
+    let cases = [
+        ("7 + 3", "10"),
+        ("7 - 10", "-3"),
+        ("7 * -3", "-21"),
+        ("-7 / 2", "-3"),
+        ("7 % -3", "1"),
+        ("-7 % -3", "-1"),
+        ("6 & 3", "2"),
+        ("6 | 3", "7"),
+        ("6 ^ 3", "5"),
+        ("6 &^ 3", "4"),
+        ("-6 & 3", "2"),
+        ("^5", "-6"),
+        ("-(-5)", "5"),
+        ("+5", "5"),
+        ("1 << 3", "8"),
+        ("1 << 100 >> 99", "2"),
+        ("-1 >> 70", "-1"),
+        ("1 >> 70", "0"),
+        ("2.0 << 1", "4"),
+        ("1 << 2.0", "4"),
+        ("(1 << 64) - 1", "18446744073709551615"),
+    ];
+    for (src, value) in cases.iter() {
+        assert_eq!(
+            eval_str(src),
+            Ok((value.to_string(), "untyped int".to_string())),
+            "{}",
+            src
+        );
+    }
+}
+
+#[test]
+fn test_eval_comparisons_and_logic() {
+    let cases = [
+        ("1 == 1.0", "true"),
+        ("1 != 1i", "true"),
+        ("2 < 2.5", "true"),
+        ("3 <= 3", "true"),
+        ("'b' > 'a'", "true"),
+        ("1.5 >= 2", "false"),
+        ("\"ab\" < \"b\"", "true"),
+        ("\"a\" + \"b\" == \"ab\"", "true"),
+        ("(1 + 2i) == (1 + 2i)", "true"),
+        ("true && !false", "true"),
+        ("false || 1 > 2", "false"),
+        ("true == (1 < 2)", "true"),
+    ];
+    for (src, value) in cases.iter() {
+        assert_eq!(
+            eval_str(src),
+            Ok((value.to_string(), "untyped bool".to_string())),
+            "{}",
+            src
+        );
+    }
+}
+
+#[test]
+fn test_eval_promotes_untyped_kinds() {
+    let cases = [
+        ("1 + 'a'", "98", "untyped rune"),
+        ("'a' * 1.5", "145.5", "untyped float"),
+        ("1 + 1.5", "2.5", "untyped float"),
+        ("1.5 + 1i", "(1.5 + 1i)", "untyped complex"),
+        ("'a' + 0i", "(97 + 0i)", "untyped complex"),
+        ("1 / 3.0 * 3", "1", "untyped float"),
+        ("1 + float64(0.5)", "1.5", "float64"),
+        ("int8(1) + 'a'", "98", "int8"),
+        ("2.0 * int(3)", "6", "int"),
+        ("float32(1) / 3 * 3", "1", "float32"),
+        ("complex64(1i) * 2", "(0 + 2i)", "complex64"),
+        ("byte(1) + uint8(2)", "3", "uint8"),
+        ("rune(1) + int32(2)", "3", "int32"),
+    ];
+    for (src, value, typ) in cases.iter() {
+        assert_eq!(
+            eval_str(src),
+            Ok((value.to_string(), typ.to_string())),
+            "{}",
+            src
+        );
+    }
+}
+
+#[test]
+fn test_eval_conversions() {
+    let cases = [
+        ("int8(-128)", "-128", "int8"),
+        ("uint8(255)", "255", "uint8"),
+        ("uint64(1 << 64 - 1)", "18446744073709551615", "uint64"),
+        ("int(2.0)", "2", "int"),
+        ("int(1 + 0i)", "1", "int"),
+        ("float64(1 << 62)", "4611686018427387904", "float64"),
+        // Both operands are rounded to the type of the typed one.
+        ("float32(0.1) == 0.1", "true", "untyped bool"),
+        (
+            "float64(float32(0.1)) == float64(0.1)",
+            "false",
+            "untyped bool",
+        ),
+        ("complex128(1)", "(1 + 0i)", "complex128"),
+        ("float64(2 + 0i)", "2", "float64"),
+        ("string(65)", "\"A\"", "string"),
+        ("string(-1)", "\"\u{fffd}\"", "string"),
+        ("string(0x110000)", "\"\u{fffd}\"", "string"),
+        ("string(\"s\")", "\"s\"", "string"),
+        ("bool(1 < 2)", "true", "bool"),
+        ("uintptr(8)", "8", "uintptr"),
+    ];
+    for (src, value, typ) in cases.iter() {
+        assert_eq!(
+            eval_str(src),
+            Ok((value.to_string(), typ.to_string())),
+            "{}",
+            src
+        );
+    }
+}
+
+#[test]
+fn test_convert_representable_values() {
+    let int = |n: i64| Constant::untyped(Untyped::Int, Value::Int(n.into()));
+    let float = |n: f64| {
+        Constant::untyped(
+            Untyped::Float,
+            Value::Float(BigRational::from_float(n).unwrap()),
+        )
+    };
+    let bounds = [
+        (GoType::Int8, -128, 127),
+        (GoType::Int16, -32768, 32767),
+        (GoType::Int32, -(1 << 31), (1 << 31) - 1),
+        (GoType::Uint8, 0, 255),
+        (GoType::Uint16, 0, 65535),
+        (GoType::Uint32, 0, (1 << 32) - 1),
+        (GoType::Int64, i64::MIN, i64::MAX),
+    ];
+    for (typ, min, max) in bounds.iter() {
+        assert!(int(*min).convert(*typ).is_ok(), "{:?}", typ);
+        assert!(int(*max).convert(*typ).is_ok(), "{:?}", typ);
+        if *min > i64::MIN {
+            assert_eq!(
+                int(min - 1).convert(*typ),
+                Err(ErrorKind::Overflow),
+                "{:?}",
+                typ
+            );
+        }
+        if *max < i64::MAX {
+            assert_eq!(
+                int(max + 1).convert(*typ),
+                Err(ErrorKind::Overflow),
+                "{:?}",
+                typ
+            );
+        }
+    }
+    let big = Constant::untyped(Untyped::Int, Value::Int(BigInt::from(1) << 64));
+    assert_eq!(big.convert(GoType::Uint64), Err(ErrorKind::Overflow));
+    assert_eq!(big.convert(GoType::Uint), Err(ErrorKind::Overflow));
+    assert!(big.convert(GoType::Float32).is_ok());
+
+    assert_eq!(float(2.5).convert(GoType::Int), Err(ErrorKind::Truncated));
+    assert_eq!(
+        float(1e39).convert(GoType::Float32),
+        Err(ErrorKind::Overflow)
+    );
+    assert!(float(1e39).convert(GoType::Float64).is_ok());
+    let huge = Constant::untyped(
+        Untyped::Float,
+        Value::Float(BigRational::from_integer(BigInt::from(1) << 1024)),
+    );
+    assert_eq!(huge.convert(GoType::Float64), Err(ErrorKind::Overflow));
+    assert_eq!(huge.convert(GoType::Complex128), Err(ErrorKind::Overflow));
+
+    let complex = |re: i64, im: i64| {
+        Constant::untyped(
+            Untyped::Complex,
+            Value::Complex(
+                BigRational::from_integer(re.into()),
+                BigRational::from_integer(im.into()),
+            ),
+        )
+    };
+    assert_eq!(
+        complex(1, 1).convert(GoType::Float64),
+        Err(ErrorKind::Truncated)
+    );
+    assert_eq!(
+        complex(1, 1).convert(GoType::Int),
+        Err(ErrorKind::Truncated)
+    );
+    assert_eq!(
+        complex(1, 0).convert(GoType::Float32).unwrap().value,
+        float(1.0).value
+    );
+
+    let string = Constant::untyped(Untyped::String, Value::String(b"s".to_vec()));
+    let boolean = Constant::untyped(Untyped::Bool, Value::Bool(true));
+    assert_eq!(
+        string.convert(GoType::Int),
+        Err(ErrorKind::InvalidConversion)
+    );
+    assert_eq!(
+        boolean.convert(GoType::Float64),
+        Err(ErrorKind::InvalidConversion)
+    );
+    assert_eq!(
+        int(1).convert(GoType::Bool),
+        Err(ErrorKind::InvalidConversion)
+    );
+    assert_eq!(
+        int(1).convert(GoType::Byte).unwrap().typ,
+        Type::Basic(GoType::Uint8)
+    );
+}
+
+#[test]
+fn test_eval_typed_results_fit_their_type() {
+    let errors = [
+        (
+            "int8(127) + 1",
+            ErrorKind::Overflow,
+            "constant 128 overflows int8",
+        ),
+        (
+            "uint8(255) * 2",
+            ErrorKind::Overflow,
+            "constant 510 overflows uint8",
+        ),
+        (
+            "uint(0) - 1",
+            ErrorKind::Overflow,
+            "constant -1 overflows uint",
+        ),
+        (
+            "-int8(-128)",
+            ErrorKind::Overflow,
+            "constant 128 overflows int8",
+        ),
+        (
+            "int32(1) << 31",
+            ErrorKind::Overflow,
+            "constant 2147483648 overflows int32",
+        ),
+        (
+            "int(7) / 2.5",
+            ErrorKind::Truncated,
+            "invalid operation: int(7) / 2.5 (2.5 truncated int)",
+        ),
+        (
+            "int8(1) + 200",
+            ErrorKind::Overflow,
+            "invalid operation: int8(1) + 200 (200 overflows int8)",
+        ),
+        (
+            "int8(200)",
+            ErrorKind::Overflow,
+            "constant 200 overflows int8",
+        ),
+        (
+            "int(1.5)",
+            ErrorKind::Truncated,
+            "cannot convert 1.5 (untyped float constant) to type int (truncated)",
+        ),
+        (
+            "int(\"a\")",
+            ErrorKind::InvalidConversion,
+            "cannot convert \"a\" (untyped string constant) to type int",
+        ),
+        (
+            "float32(1e39)",
+            ErrorKind::Overflow,
+            "cannot convert 1e39 (untyped float constant) to type float32",
+        ),
+        (
+            "int8(1e3)",
+            ErrorKind::Overflow,
+            "cannot convert 1e3 (untyped float constant 1000) to type int8",
+        ),
+    ];
+    for (src, kind, message) in errors.iter() {
+        assert_eq!(eval_str(src), Err((*kind, message.to_string())), "{}", src);
+    }
+    assert_eq!(
+        eval_str("int(7) / 2"),
+        Ok(("3".to_string(), "int".to_string()))
+    );
+    assert_eq!(
+        eval_str("^uint16(0)"),
+        Ok(("65535".to_string(), "uint16".to_string()))
+    );
+    assert_eq!(
+        eval_str("^int8(0)"),
+        Ok(("-1".to_string(), "int8".to_string()))
+    );
+}
+
+#[test]
+fn test_eval_invalid_operations() {
+    let errors = [
+        ("-\"a\"", ErrorKind::InvalidOperation, "invalid operation: operator - not defined on \"a\" (untyped string constant)"),
+        ("!1", ErrorKind::InvalidOperation, "invalid operation: operator ! not defined on 1 (untyped int constant)"),
+        ("^1.5", ErrorKind::InvalidOperation, "invalid operation: operator ^ not defined on 1.5 (untyped float constant)"),
+        ("1.5 % 1", ErrorKind::InvalidOperation, "invalid operation: operator % not defined on 1.5 % 1 (untyped float constant)"),
+        ("\"a\" - \"b\"", ErrorKind::InvalidOperation, "invalid operation: operator - not defined on \"a\" - \"b\" (untyped string constant)"),
+        ("true + false", ErrorKind::InvalidOperation, "invalid operation: operator + not defined on true + false (untyped bool constant)"),
+        ("true < false", ErrorKind::InvalidOperation, "invalid operation: operator < not defined on true < false (untyped bool constant)"),
+        ("1 && true", ErrorKind::MismatchedTypes, "invalid operation: 1 && true (mismatched types untyped int and untyped bool)"),
+        ("1.0 / 0.0", ErrorKind::DivisionByZero, "invalid operation: division by zero"),
+        ("1i / 0", ErrorKind::DivisionByZero, "invalid operation: division by zero"),
+        ("1 << 1075", ErrorKind::InvalidOperation, "invalid shift count 1075"),
+        ("1 << float64(2)", ErrorKind::InvalidOperation, "invalid shift count 2"),
+        ("1 << 1.5", ErrorKind::InvalidOperation, "invalid shift count 1.5"),
+        ("float64(1) << 2", ErrorKind::InvalidOperation, "invalid operation: shifted operand float64(1) (constant 1 of type float64) must be integer"),
+        ("&1", ErrorKind::NotConstant, "&1 is not constant"),
+        ("len(\"s\")", ErrorKind::NotConstant, "len(\"s\") is not constant"),
+    ];
+    for (src, kind, message) in errors.iter() {
+        assert_eq!(eval_str(src), Err((*kind, message.to_string())), "{}", src);
+    }
+}
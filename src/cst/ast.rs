@@ -0,0 +1,415 @@
+//! Typed views over the nodes of a lossless syntax tree.
+//!
+//! A view is a [`SyntaxNode`] of a known kind, with accessors for its parts. Parts missing from
+//! an incomplete source are `None`.
+//!
+//! ```
+//! use go_parser_rs::cst::ast::{AstNode, Decl, SourceFile};
+//! let file = SourceFile::parse("package m\n\nimport f \"fmt\"\n\nfunc (t T) Len() int { return 0 }\n");
+//! assert_eq!(file.package_clause().unwrap().name().unwrap().text(), "m");
+//! let import = file.imports().next().unwrap().specs().next().unwrap();
+//! assert_eq!((import.name().unwrap().text(), import.path().unwrap().text()), ("f", "\"fmt\""));
+//! let func = match file.decls().next() {
+//!     Some(Decl::Func(func)) => func,
+//!     _ => unreachable!(),
+//! };
+//! assert_eq!(func.name().unwrap().text(), "Len");
+//! assert_eq!(func.receiver().unwrap().syntax().to_string(), "(t T)");
+//! assert_eq!(func.body().unwrap().statements().count(), 1);
+//! ```
+
+use super::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// A typed view of a node.
+pub trait AstNode: Sized {
+    fn can_cast(kind: SyntaxKind) -> bool;
+
+    fn cast(node: SyntaxNode) -> Option<Self>;
+
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+macro_rules! ast_node {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct $name(SyntaxNode);
+
+            impl AstNode for $name {
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == SyntaxKind::$name
+                }
+
+                fn cast(node: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(node.kind()) {
+                        Some($name(node))
+                    } else {
+                        None
+                    }
+                }
+
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.0
+                }
+            }
+        )*
+    };
+}
+
+ast_node!(
+    SourceFile,
+    PackageClause,
+    ImportDecl,
+    ImportSpec,
+    FuncDecl,
+    ParamList,
+    TypeParamList,
+    ConstDecl,
+    VarDecl,
+    TypeDecl,
+    /// A const, var or type spec.
+    Spec,
+    Block,
+    LabeledStmt,
+    IfStmt,
+    ForStmt,
+    SwitchStmt,
+    SelectStmt,
+    CaseClause,
+    FieldList,
+    Field,
+    LiteralValue,
+);
+
+fn child<N: AstNode>(node: &SyntaxNode) -> Option<N> {
+    node.children().find_map(N::cast)
+}
+
+fn children<'n, N: AstNode + 'n>(node: &'n SyntaxNode) -> impl Iterator<Item = N> + 'n {
+    node.children().filter_map(N::cast)
+}
+
+fn token(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    node.tokens().find(|token| token.kind() == kind)
+}
+
+/// The comments of a declaration before its keyword, that is its doc comment group.
+fn doc(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    node.tokens()
+        .take_while(|token| token.kind().is_trivia())
+        .filter(|token| token.kind() == SyntaxKind::Comment)
+        .collect()
+}
+
+impl SourceFile {
+    pub fn parse(text: &str) -> Self {
+        SourceFile(super::parse(text))
+    }
+
+    pub fn package_clause(&self) -> Option<PackageClause> {
+        child(&self.0)
+    }
+
+    pub fn imports(&self) -> impl Iterator<Item = ImportDecl> + '_ {
+        children(&self.0)
+    }
+
+    pub fn decls(&self) -> impl Iterator<Item = Decl> + '_ {
+        children(&self.0)
+    }
+}
+
+impl PackageClause {
+    pub fn doc(&self) -> Vec<SyntaxToken> {
+        doc(&self.0)
+    }
+
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+}
+
+impl ImportDecl {
+    pub fn doc(&self) -> Vec<SyntaxToken> {
+        doc(&self.0)
+    }
+
+    pub fn specs(&self) -> impl Iterator<Item = ImportSpec> + '_ {
+        children(&self.0)
+    }
+}
+
+impl ImportSpec {
+    /// The package name or `.` before the path.
+    pub fn name(&self) -> Option<SyntaxToken> {
+        self.0
+            .tokens()
+            .take_while(|token| token.kind() != SyntaxKind::StringLit)
+            .find(|token| matches!(token.kind(), SyntaxKind::Ident | SyntaxKind::Symbol))
+    }
+
+    /// The path, quotes included.
+    pub fn path(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::StringLit)
+    }
+}
+
+/// A top-level declaration other than the package clause and imports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decl {
+    Func(FuncDecl),
+    Const(ConstDecl),
+    Var(VarDecl),
+    Type(TypeDecl),
+}
+
+impl AstNode for Decl {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::FuncDecl
+                | SyntaxKind::ConstDecl
+                | SyntaxKind::VarDecl
+                | SyntaxKind::TypeDecl
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let decl = match node.kind() {
+            SyntaxKind::FuncDecl => Decl::Func(FuncDecl(node)),
+            SyntaxKind::ConstDecl => Decl::Const(ConstDecl(node)),
+            SyntaxKind::VarDecl => Decl::Var(VarDecl(node)),
+            SyntaxKind::TypeDecl => Decl::Type(TypeDecl(node)),
+            _ => return None,
+        };
+        Some(decl)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Decl::Func(decl) => decl.syntax(),
+            Decl::Const(decl) => decl.syntax(),
+            Decl::Var(decl) => decl.syntax(),
+            Decl::Type(decl) => decl.syntax(),
+        }
+    }
+}
+
+impl FuncDecl {
+    pub fn doc(&self) -> Vec<SyntaxToken> {
+        doc(&self.0)
+    }
+
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    /// The parameter list before the name of a method.
+    pub fn receiver(&self) -> Option<ParamList> {
+        let name = self.name()?;
+        children::<ParamList>(&self.0).find(|params| params.0.index() < name.index())
+    }
+
+    pub fn type_params(&self) -> Option<TypeParamList> {
+        child(&self.0)
+    }
+
+    fn signature(&self) -> impl Iterator<Item = ParamList> + '_ {
+        let name = self.name().map_or(0, |name| name.index());
+        children::<ParamList>(&self.0).filter(move |params| params.0.index() > name)
+    }
+
+    pub fn params(&self) -> Option<ParamList> {
+        self.signature().next()
+    }
+
+    /// The results in parentheses. A single unnamed result is only a token run of the node.
+    pub fn results(&self) -> Option<ParamList> {
+        self.signature().nth(1)
+    }
+
+    pub fn body(&self) -> Option<Block> {
+        child(&self.0)
+    }
+}
+
+impl ConstDecl {
+    pub fn doc(&self) -> Vec<SyntaxToken> {
+        doc(&self.0)
+    }
+
+    pub fn specs(&self) -> impl Iterator<Item = Spec> + '_ {
+        children(&self.0)
+    }
+}
+
+impl VarDecl {
+    pub fn doc(&self) -> Vec<SyntaxToken> {
+        doc(&self.0)
+    }
+
+    pub fn specs(&self) -> impl Iterator<Item = Spec> + '_ {
+        children(&self.0)
+    }
+}
+
+impl TypeDecl {
+    pub fn doc(&self) -> Vec<SyntaxToken> {
+        doc(&self.0)
+    }
+
+    pub fn specs(&self) -> impl Iterator<Item = Spec> + '_ {
+        children(&self.0)
+    }
+}
+
+impl Block {
+    /// The statements of the block, or the case clauses of a switch or select statement.
+    pub fn statements(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.0.children()
+    }
+}
+
+impl LabeledStmt {
+    pub fn label(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn statement(&self) -> Option<SyntaxNode> {
+        self.0.children().next()
+    }
+}
+
+/// The branch after the `else` of an if statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElseBranch {
+    If(IfStmt),
+    Block(Block),
+}
+
+impl IfStmt {
+    pub fn then_branch(&self) -> Option<Block> {
+        child(&self.0)
+    }
+
+    pub fn else_branch(&self) -> Option<ElseBranch> {
+        let then = self.then_branch()?;
+        self.0
+            .children()
+            .filter(|node| node.index() > then.0.index())
+            .find_map(|node| match node.kind() {
+                SyntaxKind::IfStmt => Some(ElseBranch::If(IfStmt(node))),
+                SyntaxKind::Block => Some(ElseBranch::Block(Block(node))),
+                _ => None,
+            })
+    }
+}
+
+impl ForStmt {
+    pub fn body(&self) -> Option<Block> {
+        self.0.children().filter_map(Block::cast).last()
+    }
+}
+
+impl SwitchStmt {
+    pub fn clauses(&self) -> impl Iterator<Item = CaseClause> {
+        case_clauses(&self.0)
+    }
+}
+
+impl SelectStmt {
+    pub fn clauses(&self) -> impl Iterator<Item = CaseClause> {
+        case_clauses(&self.0)
+    }
+}
+
+fn case_clauses(node: &SyntaxNode) -> impl Iterator<Item = CaseClause> {
+    let body = node.children().filter_map(Block::cast).last();
+    let clauses: Vec<_> = body.iter().flat_map(|body| children(&body.0)).collect();
+    clauses.into_iter()
+}
+
+impl CaseClause {
+    pub fn is_default(&self) -> bool {
+        self.0
+            .tokens()
+            .any(|token| token.kind() == SyntaxKind::Keyword && token.text() == "default")
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.0.children()
+    }
+}
+
+impl FieldList {
+    pub fn fields(&self) -> impl Iterator<Item = Field> + '_ {
+        children(&self.0)
+    }
+}
+
+#[test]
+fn test_views() {
+    let file = SourceFile::parse(
+        "package m
+
+// Op does it.
+func Op[T any](x T) (T, error) {
+	if x {
+	} else if y {
+	} else {
+		switch {
+		case a:
+		default:
+		}
+	}
+	for range xs { L: f() }
+}
+
+type (
+	A struct { x, y int }
+	B = A
+)
+",
+    );
+    let decls: Vec<_> = file.decls().collect();
+    let func = match &decls[0] {
+        Decl::Func(func) => func,
+        _ => unreachable!(),
+    };
+    assert_eq!(func.doc()[0].text(), "// Op does it.");
+    assert_eq!(func.receiver(), None);
+    assert_eq!(func.type_params().unwrap().syntax().to_string(), "[T any]");
+    assert_eq!(func.params().unwrap().syntax().to_string(), "(x T)");
+    assert_eq!(func.results().unwrap().syntax().to_string(), "(T, error)");
+
+    let stmts: Vec<_> = func.body().unwrap().statements().collect();
+    let if_stmt = IfStmt::cast(stmts[0].clone()).unwrap();
+    let else_if = match if_stmt.else_branch() {
+        Some(ElseBranch::If(else_if)) => else_if,
+        _ => unreachable!(),
+    };
+    let else_block = match else_if.else_branch() {
+        Some(ElseBranch::Block(block)) => block,
+        _ => unreachable!(),
+    };
+    let switch = SwitchStmt::cast(else_block.statements().next().unwrap()).unwrap();
+    let defaults: Vec<_> = switch.clauses().map(|c| c.is_default()).collect();
+    assert_eq!(defaults, vec![false, true]);
+    let for_stmt = ForStmt::cast(stmts[1].clone()).unwrap();
+    let labeled = LabeledStmt::cast(for_stmt.body().unwrap().statements().next().unwrap()).unwrap();
+    assert_eq!(labeled.label().unwrap().text(), "L");
+    assert_eq!(labeled.statement().unwrap().to_string(), "f()");
+
+    let types = match &decls[1] {
+        Decl::Type(types) => types,
+        _ => unreachable!(),
+    };
+    let specs: Vec<_> = types.specs().collect();
+    assert_eq!(specs.len(), 2);
+    let fields = child::<FieldList>(specs[0].syntax()).unwrap();
+    assert_eq!(
+        fields.fields().next().unwrap().syntax().to_string(),
+        "x, y int"
+    );
+}
@@ -0,0 +1,665 @@
+//! A lossless concrete syntax tree.
+//!
+//! Unlike the syntax tree of [`ASTable`](crate::astable::ASTable), it keeps every byte of the
+//! source: white space and comments are tokens of the tree, so printing it gives the source back
+//! exactly, even when the source has syntax errors.
+//!
+//! The tree is split the way `rowan` splits it. [`GreenNode`]s are immutable, know only their kind,
+//! text length and children, and can be shared between trees. [`SyntaxNode`]s are built on demand
+//! over a green tree and know their parent and position in the text. [`ast`] has typed views over
+//! the nodes.
+//!
+//! The tree is built from [`tokenize_lossless`](crate::tokenize::tokenizer::tokenize_lossless) and
+//! goes down to statements, struct fields and the specs of declarations. Expressions are kept as
+//! flat runs of tokens, except for blocks and literal values nested in them.
+//!
+//! ```
+//! use go_parser_rs::cst::{self, SyntaxKind};
+//! let src = "package main\n\n// main runs.\nfunc main() {\n\tprintln( 1 ) // one\n}\n";
+//! let file = cst::parse(src);
+//! assert_eq!(file.to_string(), src);
+//! let func = file.children().find(|node| node.kind() == SyntaxKind::FuncDecl).unwrap();
+//! assert_eq!(func.text_range(), 14..src.len() - 1);
+//! ```
+
+use std::{fmt, ops::Range, rc::Rc, sync::Arc};
+
+use crate::tokenize::Token;
+
+pub mod ast;
+mod parser;
+mod reparse;
+
+pub use parser::parse_green;
+pub use reparse::{reparse, TextEdit};
+
+/// Parses `text` into a lossless syntax tree.
+pub fn parse(text: &str) -> SyntaxNode {
+    SyntaxNode::new_root(parse_green(text))
+}
+
+/// The kind of a token or a node of the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    // Tokens
+    Whitespace,
+    Comment,
+    Ident,
+    Keyword,
+    IntLit,
+    FloatLit,
+    ImaginaryLit,
+    RuneLit,
+    StringLit,
+    /// An operator or a punctuation, delimiters included.
+    Symbol,
+    /// Text no token starts with.
+    Unknown,
+
+    // Nodes
+    SourceFile,
+    PackageClause,
+    ImportDecl,
+    ImportSpec,
+    FuncDecl,
+    /// The parameters, results or receiver of a function declaration.
+    ParamList,
+    TypeParamList,
+    ConstDecl,
+    VarDecl,
+    TypeDecl,
+    /// A const, var or type spec.
+    Spec,
+    Block,
+    /// A statement with no nodes of its own kind.
+    Stmt,
+    LabeledStmt,
+    IfStmt,
+    ForStmt,
+    SwitchStmt,
+    SelectStmt,
+    CaseClause,
+    /// The body of a struct or interface type.
+    FieldList,
+    Field,
+    LiteralValue,
+    /// Tokens that do not fit the grammar.
+    Error,
+}
+
+impl SyntaxKind {
+    /// Whether the kind is a token that is not part of the grammar, white space or a comment.
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Comment)
+    }
+}
+
+impl From<&Token<'_>> for SyntaxKind {
+    fn from(token: &Token<'_>) -> Self {
+        match token {
+            Token::Comment(_) => SyntaxKind::Comment,
+            Token::Ident(_) => SyntaxKind::Ident,
+            Token::IntLit(_) => SyntaxKind::IntLit,
+            Token::FloatLit(_) => SyntaxKind::FloatLit,
+            Token::ImaginaryLit(_) => SyntaxKind::ImaginaryLit,
+            Token::RuneLit(_) => SyntaxKind::RuneLit,
+            Token::StringLit(_) => SyntaxKind::StringLit,
+            Token::Keyword(_) => SyntaxKind::Keyword,
+            Token::LDel(_) | Token::RDel(_) | Token::Symbol(_) => SyntaxKind::Symbol,
+            Token::Whitespace(_) => SyntaxKind::Whitespace,
+            Token::Unknown(_) => SyntaxKind::Unknown,
+        }
+    }
+}
+
+/// An immutable token of a green tree, holding its text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: Box<str>,
+}
+
+impl GreenToken {
+    pub fn new(kind: SyntaxKind, text: &str) -> Self {
+        GreenToken {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// A child of a green node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Arc<GreenNode>),
+    Token(Arc<GreenToken>),
+}
+
+impl GreenElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind,
+            GreenElement::Token(token) => token.kind,
+        }
+    }
+
+    pub fn text_len(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.text_len,
+            GreenElement::Token(token) => token.text.len(),
+        }
+    }
+}
+
+/// An immutable node of a green tree. It knows its text length but not its position, so equal
+/// subtrees can be shared, within a tree and between the trees of successive versions of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    text_len: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let text_len = children.iter().map(GreenElement::text_len).sum();
+        GreenNode {
+            kind,
+            text_len,
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text_len(&self) -> usize {
+        self.text_len
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    fn write_text(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => node.write_text(f)?,
+                GreenElement::Token(token) => f.write_str(&token.text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_text(f)
+    }
+}
+
+/// Builds a green tree from tokens, in the order of the source.
+#[derive(Default)]
+pub struct GreenNodeBuilder {
+    parents: Vec<(SyntaxKind, usize)>,
+    children: Vec<GreenElement>,
+}
+
+/// A position in the children being built, to start a node there later, see
+/// [`GreenNodeBuilder::start_node_at`].
+#[derive(Debug, Clone, Copy)]
+pub struct Checkpoint(usize);
+
+impl GreenNodeBuilder {
+    pub fn start_node(&mut self, kind: SyntaxKind) {
+        self.parents.push((kind, self.children.len()));
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.children.len())
+    }
+
+    /// Starts a node holding the children added since the checkpoint.
+    pub fn start_node_at(&mut self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        self.parents.push((kind, checkpoint.0));
+    }
+
+    pub fn token(&mut self, kind: SyntaxKind, text: &str) {
+        let token = Arc::new(GreenToken::new(kind, text));
+        self.children.push(GreenElement::Token(token));
+    }
+
+    /// Adds an already built node, like a subtree kept from a previous parse.
+    pub fn node(&mut self, node: Arc<GreenNode>) {
+        self.children.push(GreenElement::Node(node));
+    }
+
+    pub fn finish_node(&mut self) {
+        let (kind, first_child) = self.parents.pop().expect("no node to finish");
+        let children = self.children.split_off(first_child);
+        let node = GreenNode::new(kind, children);
+        self.children.push(GreenElement::Node(Arc::new(node)));
+    }
+
+    /// Returns the root node. Every started node must be finished, and the root be the only child.
+    pub fn finish(mut self) -> Arc<GreenNode> {
+        assert!(self.parents.is_empty(), "unfinished nodes");
+        match (self.children.pop(), self.children.is_empty()) {
+            (Some(GreenElement::Node(root)), true) => root,
+            _ => panic!("the root must be a single node"),
+        }
+    }
+}
+
+struct NodeData {
+    green: Arc<GreenNode>,
+    parent: Option<SyntaxNode>,
+    /// The position of the node among the children of its parent.
+    index: usize,
+    offset: usize,
+}
+
+/// A node of a green tree, with its parent and its position in the text.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+/// A token of a green tree, with its parent and its position in the text.
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Arc<GreenToken>,
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    pub fn new_root(green: Arc<GreenNode>) -> Self {
+        SyntaxNode(Rc::new(NodeData {
+            green,
+            parent: None,
+            index: 0,
+            offset: 0,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind
+    }
+
+    pub fn green(&self) -> &Arc<GreenNode> {
+        &self.0.green
+    }
+
+    pub fn parent(&self) -> Option<&SyntaxNode> {
+        self.0.parent.as_ref()
+    }
+
+    /// The position of the node among the children of its parent.
+    pub fn index(&self) -> usize {
+        self.0.index
+    }
+
+    /// The byte range of the node in the source.
+    pub fn text_range(&self) -> Range<usize> {
+        self.0.offset..self.0.offset + self.0.green.text_len
+    }
+
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        std::iter::successors(Some(self.clone()), |node| node.parent().cloned())
+    }
+
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.0.offset;
+        self.0
+            .green
+            .children
+            .iter()
+            .enumerate()
+            .map(move |(index, child)| {
+                let start = offset;
+                offset += child.text_len();
+                match child {
+                    GreenElement::Node(green) => {
+                        SyntaxElement::Node(SyntaxNode(Rc::new(NodeData {
+                            green: green.clone(),
+                            parent: Some(self.clone()),
+                            index,
+                            offset: start,
+                        })))
+                    }
+                    GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                        green: green.clone(),
+                        parent: self.clone(),
+                        index,
+                        offset: start,
+                    }),
+                }
+            })
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children_with_tokens().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// The tokens of the node, not those of its child nodes.
+    pub fn tokens(&self) -> impl Iterator<Item = SyntaxToken> + '_ {
+        self.children_with_tokens().filter_map(|child| match child {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        })
+    }
+
+    /// The node and all the nodes below it, in the order of the source.
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode> {
+        let mut stack = vec![self.clone()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let mut children: Vec<_> = node.children().collect();
+            children.reverse();
+            stack.extend(children);
+            Some(node)
+        })
+    }
+
+    /// All the tokens below the node, in the order of the source.
+    pub fn descendant_tokens(&self) -> impl Iterator<Item = SyntaxToken> {
+        let mut stack = vec![SyntaxElement::Node(self.clone())];
+        std::iter::from_fn(move || loop {
+            match stack.pop()? {
+                SyntaxElement::Token(token) => return Some(token),
+                SyntaxElement::Node(node) => {
+                    let mut children: Vec<_> = node.children_with_tokens().collect();
+                    children.reverse();
+                    stack.extend(children);
+                }
+            }
+        })
+    }
+
+    pub fn first_token(&self) -> Option<SyntaxToken> {
+        self.children_with_tokens().find_map(|child| match child {
+            SyntaxElement::Node(node) => node.first_token(),
+            SyntaxElement::Token(token) => Some(token),
+        })
+    }
+
+    pub fn last_token(&self) -> Option<SyntaxToken> {
+        let children: Vec<_> = self.children_with_tokens().collect();
+        children.into_iter().rev().find_map(|child| match child {
+            SyntaxElement::Node(node) => node.last_token(),
+            SyntaxElement::Token(token) => Some(token),
+        })
+    }
+
+    /// Returns the green tree of the root with this node replaced by `green`.
+    /// The nodes beside the path from the root to this node are shared with this tree.
+    pub fn replace_with(&self, green: Arc<GreenNode>) -> Arc<GreenNode> {
+        let mut green = green;
+        let mut node = self;
+        while let Some(parent) = node.parent() {
+            let mut children = parent.green().children.clone();
+            children[node.index()] = GreenElement::Node(green);
+            green = Arc::new(GreenNode::new(parent.kind(), children));
+            node = parent;
+        }
+        green
+    }
+
+    /// Returns the tree as indented lines of kinds and ranges, with the text of tokens.
+    /// ```
+    /// let tree = go_parser_rs::cst::parse("package m\n").debug_tree();
+    /// assert_eq!(
+    ///     tree,
+    ///     "SourceFile@0..10\n  PackageClause@0..9\n    Keyword@0..7 \"package\"\n    Whitespace@7..8 \" \"\n    Ident@8..9 \"m\"\n  Whitespace@9..10 \"\\n\"\n"
+    /// );
+    /// ```
+    pub fn debug_tree(&self) -> String {
+        let mut out = String::new();
+        self.write_debug_tree(&mut out, 0);
+        out
+    }
+
+    fn write_debug_tree(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        out.push_str(&format!("{}{:?}\n", indent, self));
+        for child in self.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => node.write_debug_tree(out, depth + 1),
+                SyntaxElement::Token(token) => {
+                    out.push_str(&format!("{}  {:?} {:?}\n", indent, token, token.text()))
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = self.text_range();
+        write!(f, "{:?}@{}..{}", self.kind(), range.start, range.end)
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.green.write_text(f)
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    pub fn parent(&self) -> &SyntaxNode {
+        &self.parent
+    }
+
+    /// The position of the token among the children of its parent.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The byte range of the token in the source.
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text.len()
+    }
+
+    /// The token right after this one in the source.
+    pub fn next_token(&self) -> Option<SyntaxToken> {
+        let mut parent = self.parent.clone();
+        let mut index = self.index;
+        loop {
+            let next =
+                parent
+                    .children_with_tokens()
+                    .skip(index + 1)
+                    .find_map(|child| match child {
+                        SyntaxElement::Node(node) => node.first_token(),
+                        SyntaxElement::Token(token) => Some(token),
+                    });
+            if next.is_some() {
+                return next;
+            }
+            index = parent.index();
+            parent = parent.parent()?.clone();
+        }
+    }
+}
+
+impl PartialEq for SyntaxToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl Eq for SyntaxToken {}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = self.text_range();
+        write!(f, "{:?}@{}..{}", self.kind(), range.start, range.end)
+    }
+}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+impl SyntaxElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.text_range(),
+            SyntaxElement::Token(token) => token.text_range(),
+        }
+    }
+}
+
+#[test]
+fn test_fixtures_round_trip() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    let mut count = 0;
+    for entry in std::fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        let src = std::fs::read_to_string(&path).unwrap();
+        let tree = parse(&src);
+        assert_eq!(tree.to_string(), src, "{}", path.display());
+        assert_eq!(tree.text_range(), 0..src.len());
+        let tokens: String = tree.descendant_tokens().map(|t| t.to_string()).collect();
+        assert_eq!(tokens, src, "{}", path.display());
+        count += 1;
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn test_structure() {
+    let src = "package m
+
+import (
+	\"fmt\"
+	s \"strings\"
+)
+
+// Doc.
+func (t *T) M(x int) (int, error) {
+	if v := []int{1}; len(v) > x {
+		return T{x}.n, nil
+	} else if x {
+	}
+L:
+	for {
+		switch y := f(func() {}); y {
+		case 1, 2:
+			break L
+		}
+	}
+}
+
+type T struct {
+	n int `tag`
+}
+";
+    let kinds = |node: &SyntaxNode| node.descendants().map(|n| n.kind()).collect::<Vec<_>>();
+    use SyntaxKind::*;
+    assert_eq!(
+        kinds(&parse(src)),
+        vec![
+            SourceFile,
+            PackageClause,
+            ImportDecl,
+            ImportSpec,
+            ImportSpec,
+            FuncDecl,
+            ParamList,
+            ParamList,
+            ParamList,
+            Block,
+            IfStmt,
+            LiteralValue,
+            Block,
+            Stmt,
+            LiteralValue,
+            IfStmt,
+            Block,
+            LabeledStmt,
+            ForStmt,
+            Block,
+            SwitchStmt,
+            Block,
+            Block,
+            CaseClause,
+            Stmt,
+            TypeDecl,
+            Spec,
+            FieldList,
+            Field,
+        ]
+    );
+    let func = parse(src).children().nth(2).unwrap();
+    assert!(func.to_string().starts_with("// Doc.\nfunc"));
+    let decls = parse("var a = 1 // a\nvar b = 2\n\n// c\n\nvar c = 3\n");
+    let decls: Vec<_> = decls.children().map(|decl| decl.to_string()).collect();
+    assert_eq!(decls, vec!["var a = 1", "var b = 2", "var c = 3"]);
+}
+
+#[test]
+fn test_errors_are_kept() {
+    for src in [
+        "package m\n}\nfunc f() { ) ]\n",
+        "func (",
+        "var x = [}",
+        "if x {",
+    ] {
+        let tree = parse(src);
+        assert_eq!(tree.to_string(), src);
+    }
+    let tree = parse("package m\n@ x\nfunc f()");
+    assert_eq!(
+        tree.children().map(|n| n.kind()).collect::<Vec<_>>(),
+        vec![
+            SyntaxKind::PackageClause,
+            SyntaxKind::Error,
+            SyntaxKind::FuncDecl
+        ]
+    );
+}
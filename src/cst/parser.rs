@@ -0,0 +1,577 @@
+use std::sync::Arc;
+
+use crate::tokenize::tokenizer::tokenize_lossless;
+
+use super::{
+    Checkpoint, GreenNode, GreenNodeBuilder,
+    SyntaxKind::{self, *},
+};
+
+/// Parses `text` into a green tree, see [`parse`](super::parse).
+pub fn parse_green(text: &str) -> Arc<GreenNode> {
+    let mut parser = Parser::new(text);
+    parser.source_file();
+    parser.builder.finish()
+}
+
+/// The node [`parse_node`] parses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Entry {
+    Block,
+    /// The block of a switch or select statement.
+    CaseBody,
+    /// A top-level declaration other than the package clause.
+    Decl,
+}
+
+/// Parses the first `len` of `tokens` as one node of `entry`, the rest being the lookahead a full
+/// parse would see after the node. Returns `None` unless the node is made of exactly these tokens.
+pub(super) fn parse_node(
+    entry: Entry,
+    tokens: Vec<(SyntaxKind, &str)>,
+    len: usize,
+) -> Option<Arc<GreenNode>> {
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        builder: GreenNodeBuilder::default(),
+        last: None,
+    };
+    match entry {
+        Entry::Block => parser.block(),
+        Entry::CaseBody => parser.case_body(),
+        Entry::Decl => {
+            let checkpoint = parser.builder.checkpoint();
+            if parser.at("func") {
+                parser.func_decl(checkpoint);
+            } else {
+                parser.decl(checkpoint);
+            }
+        }
+    }
+    if parser.pos != len {
+        return None;
+    }
+    Some(parser.builder.finish())
+}
+
+const DECL_KEYWORDS: [&str; 6] = ["package", "import", "func", "const", "var", "type"];
+
+/// Builds the tree with one token of lookahead past trivia. Like Go's parser, it tells a block
+/// from a composite literal by the control clause it is in, and ends a statement at a newline
+/// after the tokens Go inserts a semicolon after.
+pub(super) struct Parser<'a> {
+    tokens: Vec<(SyntaxKind, &'a str)>,
+    /// The next token to add to the tree, trivia included.
+    pos: usize,
+    builder: GreenNodeBuilder,
+    /// The last token added that is not trivia.
+    last: Option<(SyntaxKind, &'a str)>,
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn new(text: &'a str) -> Self {
+        let tokens = tokenize_lossless(text)
+            .iter()
+            .map(|token| (SyntaxKind::from(token), token.text()))
+            .collect();
+        Parser {
+            tokens,
+            pos: 0,
+            builder: GreenNodeBuilder::default(),
+            last: None,
+        }
+    }
+
+    fn next_significant(&self) -> usize {
+        (self.pos..self.tokens.len())
+            .find(|&i| !self.tokens[i].0.is_trivia())
+            .unwrap_or(self.tokens.len())
+    }
+
+    /// The `n`th token ahead that is not trivia.
+    fn nth(&self, n: usize) -> Option<(SyntaxKind, &'a str)> {
+        self.tokens[self.pos..]
+            .iter()
+            .filter(|(kind, _)| !kind.is_trivia())
+            .nth(n)
+            .copied()
+    }
+
+    /// Whether the next token is the symbol or keyword `text`.
+    fn at(&self, text: &str) -> bool {
+        matches!(self.nth(0), Some((Symbol | Keyword, t)) if t == text)
+    }
+
+    fn at_closer(&self) -> bool {
+        self.at(")") || self.at("]") || self.at("}")
+    }
+
+    fn newline_before_next(&self) -> bool {
+        self.tokens[self.pos..self.next_significant()]
+            .iter()
+            .any(|(_, text)| text.contains('\n'))
+    }
+
+    /// Whether the statement ends before the next token, at a ";", a closing delimiter or
+    /// a newline Go inserts a semicolon at.
+    fn at_terminator(&self) -> bool {
+        let ends_line = match self.last {
+            Some((Ident | IntLit | FloatLit | ImaginaryLit | RuneLit | StringLit, _)) => true,
+            Some((Keyword, keyword)) => {
+                matches!(keyword, "break" | "continue" | "fallthrough" | "return")
+            }
+            Some((Symbol, symbol)) => matches!(symbol, ")" | "]" | "}" | "++" | "--"),
+            _ => false,
+        };
+        self.nth(0).is_none()
+            || self.at(";")
+            || self.at_closer()
+            || (ends_line && self.newline_before_next())
+    }
+
+    /// Whether a `[` here starts an array or slice type rather than an index.
+    fn at_operand_start(&self) -> bool {
+        !matches!(
+            self.last,
+            Some((
+                Ident | IntLit | FloatLit | ImaginaryLit | RuneLit | StringLit,
+                _
+            )) | Some((Symbol, ")" | "]" | "}"))
+        )
+    }
+
+    fn at_type_start(&self) -> bool {
+        match self.nth(0) {
+            Some((Ident, _)) => true,
+            Some((Symbol, symbol)) => matches!(symbol, "[" | "*" | "(" | "<-"),
+            Some((Keyword, keyword)) => {
+                matches!(keyword, "map" | "chan" | "func" | "struct" | "interface")
+            }
+            _ => false,
+        }
+    }
+
+    fn token(&mut self) {
+        let (kind, text) = self.tokens[self.pos];
+        self.builder.token(kind, text);
+        self.pos += 1;
+    }
+
+    fn trivia(&mut self) {
+        while self.pos < self.next_significant() {
+            self.token();
+        }
+    }
+
+    /// Adds the next token that is not trivia, and the trivia before it.
+    fn bump(&mut self) {
+        self.trivia();
+        if self.pos < self.tokens.len() {
+            self.last = Some(self.tokens[self.pos]);
+            self.token();
+        }
+    }
+
+    /// Starts a node at the next token, leaving the trivia before it to the parent.
+    fn start(&mut self, kind: SyntaxKind) {
+        self.trivia();
+        self.builder.start_node(kind);
+    }
+
+    fn finish(&mut self) {
+        self.builder.finish_node();
+    }
+
+    fn error_token(&mut self) {
+        self.start(Error);
+        self.bump();
+        self.finish();
+    }
+
+    /// Adds the trivia before the comment group that documents the next token, if any,
+    /// and returns the position the node of the documented declaration starts at.
+    fn doc(&mut self) -> Checkpoint {
+        let end = self.next_significant();
+        let newlines = |range: std::ops::Range<usize>| {
+            self.tokens[range]
+                .iter()
+                .map(|(_, text)| text.matches('\n').count())
+                .sum::<usize>()
+        };
+        let mut group_start = end;
+        let mut last_comment = None;
+        for i in self.pos..end {
+            // A comment on the line of the previous token belongs to that line, not to a group below.
+            if self.tokens[i].0 == Comment && self.last.is_some() && newlines(self.pos..i) == 0 {
+                continue;
+            }
+            if self.tokens[i].0 == Comment {
+                match last_comment {
+                    Some(last) if newlines(last + 1..i) <= 1 => {}
+                    _ => group_start = i,
+                }
+                last_comment = Some(i);
+            }
+        }
+        let doc_start = match last_comment {
+            Some(last) if newlines(last + 1..end) == 1 => group_start,
+            _ => end,
+        };
+        while self.pos < doc_start {
+            self.token();
+        }
+        self.builder.checkpoint()
+    }
+
+    fn source_file(&mut self) {
+        self.builder.start_node(SourceFile);
+        loop {
+            let checkpoint = self.doc();
+            match self.nth(0) {
+                None => break,
+                Some((Symbol, ";")) => self.bump(),
+                Some((Keyword, "package")) => {
+                    self.builder.start_node_at(checkpoint, PackageClause);
+                    self.bump();
+                    if let Some((Ident, _)) = self.nth(0) {
+                        self.bump();
+                    }
+                    self.finish();
+                }
+                Some((Keyword, "func")) => self.func_decl(checkpoint),
+                Some((Keyword, "import" | "const" | "var" | "type")) => self.decl(checkpoint),
+                _ => {
+                    self.builder.start_node_at(checkpoint, Error);
+                    if self.at_closer() {
+                        self.bump();
+                    } else if self.expr_token(false) {
+                        // A declaration on the next line ends the error, even without a semicolon.
+                        let stop = |p: &Self| {
+                            p.at_terminator()
+                                || (p.newline_before_next()
+                                    && DECL_KEYWORDS.iter().any(|keyword| p.at(keyword)))
+                        };
+                        self.expr_tokens(stop, false);
+                    }
+                    self.finish();
+                }
+            }
+        }
+        self.trivia();
+        self.finish();
+    }
+
+    // FunctionDecl = "func" [ Receiver ] FunctionName [ TypeParameters ] Signature [ FunctionBody ] .
+    fn func_decl(&mut self, checkpoint: Checkpoint) {
+        self.builder.start_node_at(checkpoint, FuncDecl);
+        self.bump();
+        if self.at("(") {
+            self.group(Some(ParamList));
+        }
+        if let Some((Ident, _)) = self.nth(0) {
+            self.bump();
+        }
+        if self.at("[") {
+            self.group(Some(TypeParamList));
+        }
+        self.signature(Some(ParamList));
+        if self.at("{") {
+            self.block();
+        }
+        self.finish();
+    }
+
+    /// Parameters [ Result ], the results on the same line as the parameters.
+    fn signature(&mut self, params: Option<SyntaxKind>) {
+        if self.at("(") {
+            self.group(params);
+        }
+        if self.newline_before_next() {
+            return;
+        }
+        if self.at("(") {
+            self.group(params);
+        } else if self.at_type_start() {
+            self.typ();
+        }
+    }
+
+    /// ImportDecl, ConstDecl, VarDecl or TypeDecl: the keyword and one spec or a group of them.
+    fn decl(&mut self, checkpoint: Checkpoint) {
+        let (kind, spec) = match self.nth(0) {
+            Some((_, "import")) => (ImportDecl, ImportSpec),
+            Some((_, "const")) => (ConstDecl, Spec),
+            Some((_, "var")) => (VarDecl, Spec),
+            _ => (TypeDecl, Spec),
+        };
+        self.builder.start_node_at(checkpoint, kind);
+        self.bump();
+        if self.at("(") {
+            self.bump();
+            loop {
+                match self.nth(0) {
+                    None | Some((Symbol, ")" | "]" | "}")) => break,
+                    Some((Symbol, ";")) => self.bump(),
+                    _ => self.line(spec),
+                }
+            }
+            if self.at(")") {
+                self.bump();
+            }
+        } else if !self.at_terminator() {
+            self.line(spec);
+        }
+        self.finish();
+    }
+
+    /// A node of `kind` holding the tokens up to the end of the statement.
+    fn line(&mut self, kind: SyntaxKind) {
+        self.start(kind);
+        if self.expr_token(false) {
+            self.expr_tokens(Self::at_terminator, false);
+        }
+        self.finish();
+    }
+
+    /// Adds tokens until `stop` or until [`Self::expr_token`] stops.
+    fn expr_tokens(&mut self, stop: impl Fn(&Self) -> bool, control: bool) {
+        while !stop(self) && self.expr_token(control) {}
+    }
+
+    /// Adds the next token, with the group, type, literal value or function body it starts.
+    /// Stops at a closing delimiter, and at the block of a statement in a control clause.
+    fn expr_token(&mut self, control: bool) -> bool {
+        let (kind, text) = match self.nth(0) {
+            Some(token) => token,
+            None => return false,
+        };
+        match (kind, text) {
+            (Symbol, ")" | "]" | "}") => return false,
+            (Symbol, "{") if control => return false,
+            (Symbol, "{") => self.literal_value(),
+            (Symbol, "[") if self.at_operand_start() => self.literal_type(),
+            (Symbol, "(" | "[") => self.group(None),
+            (Keyword, "func") => {
+                self.bump();
+                self.signature(None);
+                if self.at("{") {
+                    self.block();
+                }
+            }
+            (Keyword, "map" | "chan" | "struct" | "interface") => self.literal_type(),
+            _ => self.bump(),
+        }
+        true
+    }
+
+    /// A group in parentheses or brackets, in a node of `kind` if given.
+    fn group(&mut self, kind: Option<SyntaxKind>) {
+        if let Some(kind) = kind {
+            self.start(kind);
+        }
+        let close = if self.at("(") { ")" } else { "]" };
+        self.bump();
+        self.expr_tokens(|_| false, false);
+        if self.at(close) {
+            self.bump();
+        }
+        if kind.is_some() {
+            self.finish();
+        }
+    }
+
+    /// A type, and the literal value after it if any, which is a composite literal
+    /// even in a control clause.
+    fn literal_type(&mut self) {
+        self.typ();
+        if self.at("{") && !self.newline_before_next() {
+            self.literal_value();
+        }
+    }
+
+    fn typ(&mut self) {
+        match self.nth(0) {
+            Some((Symbol, "[")) => {
+                self.group(None);
+                self.typ();
+            }
+            Some((Symbol, "*" | "<-")) => {
+                self.bump();
+                self.typ();
+            }
+            Some((Symbol, "(")) => self.group(None),
+            Some((Keyword, "map")) => {
+                self.bump();
+                if self.at("[") {
+                    self.group(None);
+                }
+                self.typ();
+            }
+            Some((Keyword, "chan")) => {
+                self.bump();
+                if self.at("<-") {
+                    self.bump();
+                }
+                self.typ();
+            }
+            Some((Keyword, "func")) => {
+                self.bump();
+                self.signature(None);
+            }
+            Some((Keyword, "struct" | "interface")) => {
+                self.bump();
+                if self.at("{") {
+                    self.field_list();
+                }
+            }
+            Some((Ident, _)) => {
+                self.bump();
+                while self.at(".") && matches!(self.nth(1), Some((Ident, _))) {
+                    self.bump();
+                    self.bump();
+                }
+                if self.at("[") && !self.newline_before_next() {
+                    self.group(None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn literal_value(&mut self) {
+        self.start(LiteralValue);
+        self.bump();
+        self.expr_tokens(|_| false, false);
+        if self.at("}") {
+            self.bump();
+        }
+        self.finish();
+    }
+
+    fn field_list(&mut self) {
+        self.start(FieldList);
+        self.bump();
+        loop {
+            match self.nth(0) {
+                None | Some((Symbol, "}")) => break,
+                Some((Symbol, ";")) => self.bump(),
+                Some((Symbol, ")" | "]")) => self.error_token(),
+                _ => self.line(Field),
+            }
+        }
+        if self.at("}") {
+            self.bump();
+        }
+        self.finish();
+    }
+
+    fn block(&mut self) {
+        self.start(Block);
+        self.bump();
+        self.stmt_list(|_| false);
+        if self.at("}") {
+            self.bump();
+        }
+        self.finish();
+    }
+
+    fn stmt_list(&mut self, stop: fn(&Self) -> bool) {
+        loop {
+            match self.nth(0) {
+                None | Some((Symbol, "}")) => break,
+                _ if stop(self) => break,
+                Some((Symbol, ";")) => self.bump(),
+                Some((Symbol, ")" | "]")) => self.error_token(),
+                _ => self.stmt(),
+            }
+        }
+    }
+
+    fn stmt(&mut self) {
+        match self.nth(0) {
+            Some((Keyword, "if")) => self.if_stmt(),
+            Some((Keyword, "for")) => self.control_stmt(ForStmt, Self::block),
+            Some((Keyword, "switch")) => self.control_stmt(SwitchStmt, Self::case_body),
+            Some((Keyword, "select")) => self.control_stmt(SelectStmt, Self::case_body),
+            Some((Symbol, "{")) => self.block(),
+            Some((Keyword, "const" | "var" | "type")) => {
+                let checkpoint = self.doc();
+                self.decl(checkpoint);
+            }
+            Some((Ident, _)) if matches!(self.nth(1), Some((Symbol, ":"))) => {
+                self.start(LabeledStmt);
+                self.bump();
+                self.bump();
+                let labeled = !matches!(self.nth(0), None | Some((Symbol, "}" | ";")))
+                    && !self.at("case")
+                    && !self.at("default");
+                if labeled {
+                    self.stmt();
+                }
+                self.finish();
+            }
+            _ => self.line(Stmt),
+        }
+    }
+
+    // IfStmt = "if" [ SimpleStmt ";" ] Expression Block [ "else" ( IfStmt | Block ) ] .
+    fn if_stmt(&mut self) {
+        self.start(IfStmt);
+        self.bump();
+        self.expr_tokens(|_| false, true);
+        if self.at("{") {
+            self.block();
+        }
+        if self.at("else") {
+            self.bump();
+            if self.at("if") {
+                self.if_stmt();
+            } else if self.at("{") {
+                self.block();
+            }
+        }
+        self.finish();
+    }
+
+    /// A for, switch or select statement: the keyword, the clause and the body.
+    fn control_stmt(&mut self, kind: SyntaxKind, body: fn(&mut Self)) {
+        self.start(kind);
+        self.bump();
+        self.expr_tokens(|_| false, true);
+        if self.at("{") {
+            body(self);
+        }
+        self.finish();
+    }
+
+    /// The block of a switch or select statement, made of case clauses.
+    fn case_body(&mut self) {
+        self.start(Block);
+        self.bump();
+        loop {
+            match self.nth(0) {
+                None | Some((Symbol, "}")) => break,
+                Some((Keyword, "case" | "default")) => self.case_clause(),
+                Some((Symbol, ";")) => self.bump(),
+                Some((Symbol, ")" | "]")) => self.error_token(),
+                _ => self.stmt(),
+            }
+        }
+        if self.at("}") {
+            self.bump();
+        }
+        self.finish();
+    }
+
+    fn case_clause(&mut self) {
+        self.start(CaseClause);
+        self.bump();
+        self.expr_tokens(|p| p.at(":"), false);
+        if self.at(":") {
+            self.bump();
+        }
+        self.stmt_list(|p| p.at("case") || p.at("default"));
+        self.finish();
+    }
+}
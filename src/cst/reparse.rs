@@ -0,0 +1,362 @@
+use std::ops::Range;
+
+use crate::tokenize::{tokenizer::tokenize_lossless, Token};
+
+use super::{
+    parse,
+    parser::{parse_node, Entry},
+    GreenElement, SyntaxKind, SyntaxNode, SyntaxToken,
+};
+
+/// A change of a text: `range` is replaced by `insert`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub insert: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, insert: &str) -> Self {
+        TextEdit {
+            range,
+            insert: insert.to_string(),
+        }
+    }
+
+    pub fn apply(&self, text: &mut String) {
+        text.replace_range(self.range.clone(), &self.insert);
+    }
+}
+
+/// How many tokens past trivia the parser may look at after a node to decide where it ends.
+const LOOKAHEAD: usize = 3;
+
+/// Returns the tree of the text of `root` changed by `edit`, the same as [`parse`] of that text.
+///
+/// Only the smallest block or top-level declaration around the edit is parsed again, and the rest
+/// of the tree is shared with `root`. When the edit can change more than that node, like an
+/// unbalanced brace or an opening quote does, the parent is tried, up to the whole file.
+/// ```
+/// use go_parser_rs::cst::{parse, reparse, TextEdit};
+/// use std::sync::Arc;
+/// let src = "package m\n\nfunc f() {\n\tx := 1\n}\n\nfunc g() {}\n";
+/// let tree = parse(src);
+/// let one = src.find('1').unwrap();
+/// let edit = TextEdit::new(one..one + 1, "2 + 3");
+/// let new_tree = reparse(&tree, &edit);
+/// assert_eq!(new_tree.to_string(), src.replace("x := 1", "x := 2 + 3"));
+/// // `g` is not parsed again.
+/// let g = |tree: &go_parser_rs::cst::SyntaxNode| tree.children().last().unwrap().green().clone();
+/// assert!(Arc::ptr_eq(&g(&tree), &g(&new_tree)));
+/// ```
+pub fn reparse(root: &SyntaxNode, edit: &TextEdit) -> SyntaxNode {
+    let covering = covering_node(root, &edit.range);
+    for node in covering.ancestors() {
+        if let Some(new_node) = reparse_node(&node, edit) {
+            return SyntaxNode::new_root(new_node);
+        }
+    }
+    let mut text = root.to_string();
+    edit.apply(&mut text);
+    parse(&text)
+}
+
+/// The smallest node whose text holds the whole edited range.
+fn covering_node(root: &SyntaxNode, range: &Range<usize>) -> SyntaxNode {
+    let mut node = root.clone();
+    loop {
+        let child = node.children().find(|child| {
+            let child_range = child.text_range();
+            child_range.start <= range.start && range.end <= child_range.end
+        });
+        match child {
+            Some(child) => node = child,
+            None => return node,
+        }
+    }
+}
+
+/// Parses `node` changed by `edit` on its own, and returns the new root, or `None` when the edit
+/// can change the tree beyond the node.
+fn reparse_node(node: &SyntaxNode, edit: &TextEdit) -> Option<std::sync::Arc<super::GreenNode>> {
+    let range = node.text_range();
+    let entry = match (node.kind(), node.parent().map(SyntaxNode::kind)) {
+        // A function literal in the clause has a block too, parsed as statements: the body is not
+        // told from it without parsing the clause again.
+        (SyntaxKind::Block, Some(SyntaxKind::SwitchStmt | SyntaxKind::SelectStmt)) => {
+            let parent = node.parent()?;
+            if parent.tokens().any(|token| token.text() == "func") {
+                return None;
+            }
+            Entry::CaseBody
+        }
+        (SyntaxKind::Block, _) => Entry::Block,
+        (
+            SyntaxKind::FuncDecl
+            | SyntaxKind::ImportDecl
+            | SyntaxKind::ConstDecl
+            | SyntaxKind::VarDecl
+            | SyntaxKind::TypeDecl,
+            Some(SyntaxKind::SourceFile),
+        ) => Entry::Decl,
+        _ => return None,
+    };
+    // The first token decides how the node is parsed and where its parent starts it,
+    // and the closing brace of a block where it ends.
+    let first = node
+        .descendant_tokens()
+        .find(|token| !token.kind().is_trivia())?;
+    let end = match entry {
+        Entry::Decl => range.end,
+        _ => range.end - 1,
+    };
+    if edit.range.start < first.text_range().end || edit.range.end > end {
+        return None;
+    }
+
+    let mut text = node.to_string();
+    let relative = TextEdit::new(
+        edit.range.start - range.start..edit.range.end - range.start,
+        &edit.insert,
+    );
+    relative.apply(&mut text);
+    let tokens = tokenize_lossless(&text);
+    let new_first = tokens
+        .iter()
+        .find(|token| !SyntaxKind::from(*token).is_trivia());
+    if new_first.map(Token::text) != Some(first.text()) || !ends_at_boundary(&tokens, node) {
+        return None;
+    }
+
+    let len = tokens.len();
+    let mut tokens: Vec<_> = tokens
+        .iter()
+        .map(|token| (SyntaxKind::from(token), token.text()))
+        .collect();
+    let lookahead: Vec<_> =
+        std::iter::successors(node.last_token()?.next_token(), |token| token.next_token())
+            .scan(0, |significant, token| {
+                if *significant == LOOKAHEAD {
+                    return None;
+                }
+                if !token.kind().is_trivia() {
+                    *significant += 1;
+                }
+                Some(token)
+            })
+            .collect();
+    tokens.extend(lookahead.iter().map(|token| (token.kind(), token.text())));
+    let green = parse_node(entry, tokens, len)?;
+    if entry != Entry::Decl {
+        let closed = matches!(
+            green.children().last(),
+            Some(GreenElement::Token(token)) if token.text() == "}"
+        );
+        if !closed {
+            return None;
+        }
+    }
+    Some(node.replace_with(green))
+}
+
+/// Whether the tokens of the changed node end where a full tokenization of the changed file would
+/// end a token, so that the tokens after the node stay as they are. A quote or a comment left open
+/// in the node or before it could take the text after the node, and the last token could run into
+/// the next one.
+fn ends_at_boundary(tokens: &[Token], node: &SyntaxNode) -> bool {
+    let opens_literal = |token: &Token| matches!(token, Token::Unknown(text) if text.starts_with(&['"', '`', '\''][..]));
+    let texts = || {
+        tokens
+            .iter()
+            .map(|token| (matches!(token, Token::Comment(_)), token.text()))
+    };
+    if tokens.iter().any(opens_literal) || joins(texts(), '/', '*') {
+        return false;
+    }
+    // The changed text can close a quote or a comment left open before it, even from a comment or
+    // a literal of its own, or join the lines of an open quote to run into it.
+    if open_before(node) {
+        return false;
+    }
+    let last = match tokens.last() {
+        Some(last) => last.text(),
+        None => return false,
+    };
+    let next: String = std::iter::successors(node.last_token().and_then(|t| t.next_token()), |t| {
+        t.next_token()
+    })
+    .take(LOOKAHEAD)
+    .map(|token| token.text().to_string())
+    .collect();
+    let joined = format!("{}{}", last, next);
+    tokenize_lossless(&joined).first().map(Token::text) == Some(last)
+}
+
+/// Whether a quote or a comment is left open in the file before `node`.
+fn open_before(node: &SyntaxNode) -> bool {
+    let root = node.ancestors().last().unwrap_or_else(|| node.clone());
+    let start = node.text_range().start;
+    let before: Vec<_> = root
+        .descendant_tokens()
+        .take_while(|token| token.text_range().end <= start)
+        .collect();
+    let opens_literal = |token: &SyntaxToken| {
+        token.kind() == SyntaxKind::Unknown && token.text().starts_with(&['"', '`', '\''][..])
+    };
+    before.iter().any(opens_literal)
+        || joins(
+            before
+                .iter()
+                .map(|token| (token.kind() == SyntaxKind::Comment, token.text())),
+            '/',
+            '*',
+        )
+}
+
+/// Whether the text of two adjacent tokens reads `first` then `second` across their boundary, like
+/// the `/*` of `/` and `*=`. Text after a comment is not joined to it.
+fn joins<'t>(tokens: impl Iterator<Item = (bool, &'t str)>, first: char, second: char) -> bool {
+    let mut previous: Option<(bool, &str)> = None;
+    for (comment, text) in tokens {
+        if let Some((false, before)) = previous {
+            if before.ends_with(first) && text.starts_with(second) {
+                return true;
+            }
+        }
+        previous = Some((comment, text));
+    }
+    false
+}
+
+#[test]
+fn test_reparse_matches_full_parse() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    let inserts = [
+        "",
+        "x",
+        "{",
+        "}",
+        "(",
+        ")",
+        "\"",
+        "`",
+        "'",
+        "/*",
+        "*/",
+        "//",
+        "\n",
+        ";",
+        "1.5",
+        "func",
+        "case 1:",
+        "x := T{1}\n",
+        "if x {\n}",
+        " else ",
+        "type",
+        "var y = 2\n",
+        "\n}\nfunc h() {",
+    ];
+    let mut seed: u64 = 42;
+    let mut random = |n: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize % n.max(1)
+    };
+    // Comments opened across the boundary of two tokens, comments and quotes closed after they
+    // were left open before the changed node, and the body of a function literal in a switch
+    // clause: the edit replaces `from` by `to` at its first place in the source.
+    let cases = [
+        (
+            "package m\n\nvar a = 1\n\nvar b = 2 /* b */\n",
+            "= 1",
+            "/*= 1",
+        ),
+        (
+            "package m\n\nfunc f() {\n\tx / 1\n}\n\nvar b /* b */\n",
+            "/ 1",
+            "/*= 1",
+        ),
+        ("/*\nfunc{{}}", "{}", "*/"),
+        ("/*\nfunc f() {\n\tx := 1\n}\n", "1", "*/"),
+        ("/*\nfunc f() {\n\tx := 1\n}\n", "1", "1 */"),
+        ("/*\nfunc f() {\n\tx := 1 // y\n}\n", "y", "*/"),
+        ("/*\nfunc f() {\n\tx := `1`\n}\n", "1", "*/"),
+        ("package m\n\nvar s = \"x; type T [\n]int\"\n", "[\n]", "[]"),
+        ("`\nfunc f() {\n\tx := 1\n}\n", "1", "`"),
+        (
+            "package m\n\nfunc f() {\n\tswitch func() {\n\tcase 1:\n\t}\n}\n",
+            "1",
+            "2",
+        ),
+    ];
+    for (src, from, to) in cases.iter() {
+        let start = src.find(from).unwrap();
+        let edit = TextEdit::new(start..start + from.len(), to);
+        let text = src.replacen(from, to, 1);
+        assert_eq!(
+            reparse(&parse(src), &edit).green(),
+            parse(&text).green(),
+            "{:?}",
+            text
+        );
+    }
+    for entry in std::fs::read_dir(fixtures).unwrap() {
+        let src = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        let mut tree = parse(&src);
+        let mut text = src.clone();
+        for _ in 0..300 {
+            let mut start = random(text.len() + 1);
+            while !text.is_char_boundary(start) {
+                start -= 1;
+            }
+            let mut end = (start + random(8)).min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            let edit = TextEdit::new(start..end, inserts[random(inserts.len())]);
+            tree = reparse(&tree, &edit);
+            edit.apply(&mut text);
+            assert_eq!(
+                tree.green(),
+                parse(&text).green(),
+                "{:?} in {:?}",
+                edit,
+                text
+            );
+        }
+    }
+}
+
+#[test]
+fn test_reparse_keeps_unchanged_nodes() {
+    let src = "package m\n\nfunc f() {\n\tif x {\n\t\ty()\n\t}\n\tz()\n}\n\nvar v = 1\n";
+    let tree = parse(src);
+    let y = src.find("y()").unwrap();
+    let new_tree = reparse(&tree, &TextEdit::new(y..y + 1, "w"));
+    let func = |tree: &SyntaxNode| tree.children().nth(1).unwrap();
+    let stmts = |tree: &SyntaxNode| {
+        let body = func(tree).children().last().unwrap();
+        body.children()
+            .map(|stmt| stmt.green().clone())
+            .collect::<Vec<_>>()
+    };
+    // Only the block of the if statement was parsed again.
+    assert!(!std::sync::Arc::ptr_eq(
+        &stmts(&tree)[0],
+        &stmts(&new_tree)[0]
+    ));
+    assert!(std::sync::Arc::ptr_eq(
+        &stmts(&tree)[1],
+        &stmts(&new_tree)[1]
+    ));
+    let var = |tree: &SyntaxNode| tree.children().last().unwrap().green().clone();
+    assert!(std::sync::Arc::ptr_eq(&var(&tree), &var(&new_tree)));
+
+    // An edit that closes the block early changes the rest of the function.
+    let new_tree = reparse(&tree, &TextEdit::new(y..y, "}\n"));
+    assert_eq!(
+        new_tree.green(),
+        parse(&src.replacen("y()", "}\ny()", 1)).green()
+    );
+}
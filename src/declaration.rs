@@ -0,0 +1,336 @@
+use nom::{
+    branch::alt,
+    combinator::{cut, map, opt, peek},
+    sequence::{preceded, tuple},
+    IResult,
+};
+
+use crate::{
+    astable::ASTable,
+    comment::CommentGroup,
+    error::{context, ParseError},
+    expression::Expression,
+    parse_identifier_list,
+    parse_util::{identifier, list_of, reserved, symbol},
+    typ::{parse_type_parameters, GoType, TypeParamDecl},
+};
+
+/// Declaration = ConstDecl | TypeDecl | VarDecl .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Declaration<'a> {
+    ConstDecl(ConstDecl<'a>),
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    TypeDecl(TypeDecl<'a>),
+    VarDecl(VarDecl<'a>),
+}
+
+impl<'a> ASTable<'a> for Declaration<'a> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        alt((
+            map(ConstDecl::parse, Declaration::ConstDecl),
+            map(TypeDecl::parse, Declaration::TypeDecl),
+            map(VarDecl::parse, Declaration::VarDecl),
+        ))(s)
+    }
+}
+
+impl<'a> Declaration<'a> {
+    pub(crate) fn doc_mut(&mut self) -> &mut Option<CommentGroup<'a>> {
+        match self {
+            Declaration::ConstDecl(decl) => &mut decl.doc,
+            Declaration::TypeDecl(decl) => &mut decl.doc,
+            Declaration::VarDecl(decl) => &mut decl.doc,
+        }
+    }
+}
+
+/// ConstDecl = "const" ( ConstSpec | "(" { ConstSpec ";" } ")" ) .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConstDecl<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub doc: Option<CommentGroup<'a>>,
+    pub specs: Vec<ConstSpec<'a>>,
+}
+
+impl<'a> ASTable<'a> for ConstDecl<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::declaration::ConstDecl;
+    /// let (s, decl) = ConstDecl::parse("const (\n\tA int = iota // first\n\n\tB\n\tC, D = 1, 2\n)").unwrap();
+    /// assert_eq!((s, decl.specs.len()), ("", 3));
+    /// assert!(decl.specs[0].typ.is_some());
+    /// assert!(decl.specs[1].values.is_empty());
+    /// let (_, decl) = ConstDecl::parse("const Pi = 3").unwrap();
+    /// assert_eq!(decl.specs[0].identifiers, vec!["Pi"]);
+    /// assert!(ConstDecl::parse("const X int").is_err());
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("const")(s)?;
+        let specs = context(
+            || "in const declaration".to_string(),
+            parse_group(ConstSpec::parse),
+        );
+        let (s, specs) = cut(specs)(s)?;
+        Ok((s, ConstDecl { doc: None, specs }))
+    }
+}
+
+/// ConstSpec = IdentifierList [ [ Type ] "=" ExpressionList ] .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConstSpec<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub identifiers: Vec<&'a str>,
+    pub typ: Option<GoType<'a>>,
+    /// Empty when the spec repeats the expressions of the previous one, see [`ConstDecl::constants`].
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub values: Vec<Expression<'a>>,
+}
+
+impl<'a> ASTable<'a> for ConstSpec<'a> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, identifiers) = parse_identifier_list(s)?;
+        let (s, typ) = opt(GoType::parse)(s)?;
+        let mut init = preceded(symbol("="), Expression::parse_list);
+        let (s, values) = match typ {
+            // A type must be followed by the expressions.
+            Some(_) => init(s)?,
+            None => map(opt(init), Option::unwrap_or_default)(s)?,
+        };
+        Ok((
+            s,
+            ConstSpec {
+                identifiers,
+                typ,
+                values,
+            },
+        ))
+    }
+}
+
+/// A constant declared by a [`ConstDecl`], with the type and expression it gets
+/// after implicit repetition.
+#[derive(Debug, PartialEq)]
+pub struct Constant<'d, 'a> {
+    pub name: &'a str,
+    /// The index of the spec declaring the constant, which is the value of `iota` in its expression.
+    pub iota: usize,
+    pub typ: Option<&'d GoType<'a>>,
+    /// `None` when there are fewer expressions than identifiers.
+    pub value: Option<&'d Expression<'a>>,
+}
+
+impl<'a> ConstDecl<'a> {
+    /// Lists the constants of the declaration. A spec without expressions repeats the type and
+    /// expressions of the last spec that has some, evaluated with its own `iota`.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::declaration::ConstDecl;
+    /// let src = "const (\n\t_ = iota\n\tKB ByteSize = 1 << (10 * iota)\n\tMB\n\tGB\n)";
+    /// let (_, decl) = ConstDecl::parse(src).unwrap();
+    /// let constants = decl.constants();
+    /// let names: Vec<_> = constants.iter().map(|c| (c.name, c.iota)).collect();
+    /// assert_eq!(names, vec![("_", 0), ("KB", 1), ("MB", 2), ("GB", 3)]);
+    /// assert_eq!(constants[3].value, decl.specs[1].values.first());
+    /// assert_eq!(constants[3].typ, decl.specs[1].typ.as_ref());
+    /// ```
+    pub fn constants(&self) -> Vec<Constant<'_, 'a>> {
+        let mut previous: (Option<&GoType<'a>>, &[Expression<'a>]) = (None, &[]);
+        self.specs
+            .iter()
+            .enumerate()
+            .flat_map(|(iota, spec)| {
+                if !spec.values.is_empty() {
+                    previous = (spec.typ.as_ref(), &spec.values);
+                }
+                let (typ, values) = previous;
+                spec.identifiers
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, name)| Constant {
+                        name,
+                        iota,
+                        typ,
+                        value: values.get(i),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// VarDecl = "var" ( VarSpec | "(" { VarSpec ";" } ")" ) .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VarDecl<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub doc: Option<CommentGroup<'a>>,
+    pub specs: Vec<VarSpec<'a>>,
+}
+
+impl<'a> ASTable<'a> for VarDecl<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::declaration::VarDecl;
+    /// let (s, decl) = VarDecl::parse("var (\n\tx, y int\n\tz = f()\n\tw []byte = nil\n)").unwrap();
+    /// assert_eq!((s, decl.specs.len()), ("", 3));
+    /// assert!(decl.specs[0].values.is_empty());
+    /// assert!(decl.specs[1].typ.is_none());
+    /// assert!(VarDecl::parse("var x").is_err());
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("var")(s)?;
+        let specs = context(
+            || "in var declaration".to_string(),
+            parse_group(VarSpec::parse),
+        );
+        let (s, specs) = cut(specs)(s)?;
+        Ok((s, VarDecl { doc: None, specs }))
+    }
+}
+
+/// VarSpec = IdentifierList ( Type [ "=" ExpressionList ] | "=" ExpressionList ) .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VarSpec<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub identifiers: Vec<&'a str>,
+    pub typ: Option<GoType<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub values: Vec<Expression<'a>>,
+}
+
+impl<'a> ASTable<'a> for VarSpec<'a> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, identifiers) = parse_identifier_list(s)?;
+        let (s, typ) = opt(GoType::parse)(s)?;
+        let mut init = preceded(symbol("="), Expression::parse_list);
+        let (s, values) = match typ {
+            Some(_) => map(opt(init), Option::unwrap_or_default)(s)?,
+            // Without a type, the expressions are needed to know it.
+            None => init(s)?,
+        };
+        Ok((
+            s,
+            VarSpec {
+                identifiers,
+                typ,
+                values,
+            },
+        ))
+    }
+}
+
+/// TypeDecl = "type" ( TypeSpec | "(" { TypeSpec ";" } ")" ) .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeDecl<'a> {
+    pub doc: Option<CommentGroup<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub specs: Vec<TypeSpec<'a>>,
+}
+
+impl<'a> ASTable<'a> for TypeDecl<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::declaration::{TypeDecl, TypeSpec};
+    /// let (s, decl) = TypeDecl::parse("type (\n\tPoint struct{ X, Y int }\n\tP = Point\n)").unwrap();
+    /// assert_eq!(s, "");
+    /// assert!(matches!(decl.specs[0], TypeSpec::TypeDef { name: "Point", .. }));
+    /// assert!(matches!(decl.specs[1], TypeSpec::AliasDecl { name: "P", .. }));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("type")(s)?;
+        let specs = context(
+            || "in type declaration".to_string(),
+            parse_group(TypeSpec::parse),
+        );
+        let (s, specs) = cut(specs)(s)?;
+        Ok((s, TypeDecl { doc: None, specs }))
+    }
+}
+
+/// TypeSpec = AliasDecl | TypeDef .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypeSpec<'a> {
+    /// AliasDecl = identifier [ TypeParameters ] "=" Type .
+    AliasDecl {
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        name: &'a str,
+        type_params: Vec<TypeParamDecl<'a>>,
+        typ: GoType<'a>,
+    },
+    /// TypeDef = identifier [ TypeParameters ] Type .
+    TypeDef {
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        name: &'a str,
+        type_params: Vec<TypeParamDecl<'a>>,
+        typ: GoType<'a>,
+    },
+}
+
+impl<'a> ASTable<'a> for TypeSpec<'a> {
+    /// A `[` after the name starts an array type if the brackets hold an expression, and type parameters
+    /// otherwise. Like in Go, `[P *C]` is then an array length, unless written `[P *C,]`.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::declaration::TypeSpec;
+    /// let type_params = |src| match TypeSpec::parse(src) {
+    ///     Ok(("", TypeSpec::TypeDef { type_params, .. })) => type_params.len(),
+    ///     other => panic!("{:?}", other),
+    /// };
+    /// assert_eq!(type_params("Set[K comparable] map[K]struct{}"), 1);
+    /// assert_eq!(type_params("Buf [N]byte"), 0);
+    /// assert_eq!(type_params("T[P *C] int"), 0);
+    /// assert_eq!(type_params("T[P *C,] int"), 1);
+    /// assert_eq!(type_params("Pair[K, V any] struct{ k K; v V }"), 1);
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, name) = identifier(s)?;
+        let array_length = tuple((symbol("["), Expression::parse, symbol("]")));
+        let (s, type_params) = match peek(array_length)(s) {
+            Ok(_) => (s, vec![]),
+            Err(_) => map(opt(parse_type_parameters), Option::unwrap_or_default)(s)?,
+        };
+        let (s, alias) = opt(symbol("="))(s)?;
+        let (s, typ) = GoType::parse(s)?;
+        match alias {
+            Some(_) => Ok((
+                s,
+                TypeSpec::AliasDecl {
+                    name,
+                    type_params,
+                    typ,
+                },
+            )),
+            None => Ok((
+                s,
+                TypeSpec::TypeDef {
+                    name,
+                    type_params,
+                    typ,
+                },
+            )),
+        }
+    }
+}
+
+/// Spec | "(" { Spec ";" } ")"
+fn parse_group<'a, T, F>(spec: F) -> impl Fn(&'a str) -> IResult<&'a str, Vec<T>, ParseError<'a>>
+where
+    F: Fn(&'a str) -> IResult<&'a str, T, ParseError<'a>> + Copy,
+{
+    move |s: &'a str| alt((list_of("(", spec, ")"), map(spec, |spec| vec![spec])))(s)
+}
+
+#[test]
+fn test_constants_with_missing_values() {
+    let (_, decl) = ConstDecl::parse("const (\n\tA, B = 1\n\tC\n)").unwrap();
+    let constants = decl.constants();
+    assert!(constants[0].value.is_some());
+    assert_eq!(constants[1].value, None);
+    assert_eq!((constants[2].name, constants[2].iota), ("C", 1));
+    assert_eq!(constants[2].value, decl.specs[0].values.first());
+}
@@ -0,0 +1,297 @@
+use std::fmt::Write;
+
+use crate::{
+    error::{ParseError, Span},
+    resolve, types,
+};
+
+const TAB_WIDTH: usize = 4;
+
+/// A line and a column, both starting at 1. Columns count characters, not bytes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// ```
+    /// use go_parser_rs::diagnostic::Position;
+    /// let source = "package main\nfunc f()";
+    /// assert_eq!(Position::of(source, 0), Position { line: 1, column: 1 });
+    /// assert_eq!(Position::of(source, 18), Position { line: 2, column: 6 });
+    /// ```
+    pub fn of(source: &str, offset: usize) -> Self {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// A part of the source to point at, with a short explanation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// An error ready to be shown to a human, rustc style:
+///
+/// ```text
+/// error: expected `)`, found `y`
+///  --> main.go:2:14
+///   |
+/// 2 | func f(x int y) int
+///   |              ^ expected `)`
+///   |
+///   = note: in parameter list of func f
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    /// The first label is the primary one, its position is printed in the header.
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Builds a diagnostic out of an error returned while parsing `source`.
+    pub fn from_parse_error(error: &ParseError, source: &str) -> Self {
+        Diagnostic {
+            message: error.message(),
+            labels: vec![Label {
+                span: error.span(source),
+                message: error
+                    .expectation()
+                    .unwrap_or_else(|| "unexpected token".to_string()),
+            }],
+            notes: error.context.clone(),
+        }
+    }
+
+    /// Builds a diagnostic out of an error of the resolution of the file whose text is `source`,
+    /// without labels when the error is not in it.
+    pub fn from_resolve_error(error: &resolve::Error, source: &str) -> Self {
+        let label = match error.kind {
+            resolve::ErrorKind::Redeclared => "redeclared here",
+            resolve::ErrorKind::NoNewVariables => "all already declared in this scope",
+            resolve::ErrorKind::UndefinedLabel => "not defined in this function",
+            resolve::ErrorKind::UnusedLabel => "defined here",
+            resolve::ErrorKind::SharedText => "also another identifier",
+        };
+        let mut labels: Vec<_> = error
+            .span(source)
+            .map(|span| Label {
+                span,
+                message: label.to_string(),
+            })
+            .into_iter()
+            .collect();
+        // The previous declaration may be in another file.
+        if let Some(span) = error
+            .previous
+            .and_then(|name| resolve::span_in(source, name))
+        {
+            labels.push(Label {
+                span,
+                message: "previous declaration".to_string(),
+            });
+        }
+        Diagnostic {
+            message: error.message(),
+            labels,
+            notes: vec![],
+        }
+    }
+
+    /// Builds a diagnostic out of a type error of the file whose text is `source`, without a label
+    /// when the error is not in it.
+    pub fn from_type_error(error: &types::Error, source: &str) -> Self {
+        // The message spans several lines for the counts of values.
+        let mut lines = error.message.split("\n\t");
+        let message = lines.next().unwrap_or_default().to_string();
+        let label = match error.kind {
+            types::ErrorKind::MismatchedTypes => "mismatched types",
+            types::ErrorKind::InvalidOperation => "invalid operation",
+            types::ErrorKind::NotAssignable => "not assignable",
+            types::ErrorKind::InvalidConversion => "invalid conversion",
+            types::ErrorKind::WrongArgumentCount => "wrong number of arguments",
+            types::ErrorKind::WrongResultCount => "wrong number of return values",
+            types::ErrorKind::AssignmentMismatch => "assignment mismatch",
+            types::ErrorKind::NotCallable => "not a function",
+            types::ErrorKind::NotAnExpression => "not a value",
+            types::ErrorKind::NotAType => "not a type",
+            types::ErrorKind::MissingFieldOrMethod => "undefined",
+            types::ErrorKind::NonBooleanCondition => "expected a boolean",
+            types::ErrorKind::MultipleValues => "several values",
+            types::ErrorKind::NoValue => "no value",
+            types::ErrorKind::InvalidCompositeLit => "invalid composite literal",
+            types::ErrorKind::UntypedNil => "untyped nil",
+            types::ErrorKind::DivisionByZero => "division by zero",
+            types::ErrorKind::Overflow => "overflows",
+            types::ErrorKind::Truncated => "truncated",
+            types::ErrorKind::Undefined => "not declared",
+            types::ErrorKind::IndexOutOfRange => "out of range",
+        };
+        Diagnostic {
+            message,
+            labels: error
+                .span(source)
+                .map(|span| Label {
+                    span,
+                    message: label.to_string(),
+                })
+                .into_iter()
+                .collect(),
+            notes: lines.map(str::to_string).collect(),
+        }
+    }
+
+    /// Renders the diagnostic with the lines of `source` its labels point at.
+    /// `file_name` is only used for the location header.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::diagnostic::Diagnostic;
+    /// use go_parser_rs::SourceFile;
+    /// let source = "package main\n\nfunc f(x int y) int\n";
+    /// let error = match SourceFile::parse(source) {
+    ///     Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => e,
+    ///     other => panic!("{:?}", other),
+    /// };
+    /// let rendered = Diagnostic::from_parse_error(&error, source).render("main.go", source);
+    /// assert_eq!(
+    ///     rendered,
+    ///     "error: expected `)`, found `y`
+    ///  --> main.go:3:14
+    ///   |
+    /// 3 | func f(x int y) int
+    ///   |              ^ expected `)`
+    ///   |
+    ///   = note: in parameter list of func f
+    /// "
+    /// );
+    /// ```
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut lines: Vec<usize> = self
+            .labels
+            .iter()
+            .map(|label| Position::of(source, label.span.start).line)
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        let gutter = lines.last().map_or(1, |line| line.to_string().len());
+        let pad = " ".repeat(gutter);
+
+        let mut out = String::new();
+        writeln!(out, "error: {}", self.message).unwrap();
+        if let Some(primary) = self.labels.first() {
+            let position = Position::of(source, primary.span.start);
+            writeln!(
+                out,
+                "{}--> {}:{}:{}",
+                pad, file_name, position.line, position.column
+            )
+            .unwrap();
+        }
+        writeln!(out, "{} |", pad).unwrap();
+        for line in lines {
+            let text = source.split('\n').nth(line - 1).unwrap_or("");
+            let source_line = format!("{:>width$} | {}", line, expand_tabs(text), width = gutter);
+            writeln!(out, "{}", source_line.trim_end()).unwrap();
+            for label in &self.labels {
+                if Position::of(source, label.span.start).line != line {
+                    continue;
+                }
+                let (column, width) = underline(source, label.span);
+                writeln!(
+                    out,
+                    "{} | {}{} {}",
+                    pad,
+                    " ".repeat(column),
+                    "^".repeat(width),
+                    label.message
+                )
+                .unwrap();
+            }
+        }
+        if !self.notes.is_empty() {
+            writeln!(out, "{} |", pad).unwrap();
+        }
+        for note in &self.notes {
+            writeln!(out, "{} = note: {}", pad, note).unwrap();
+        }
+        out
+    }
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// The display column and the width of the carets for `span`, which are cut at the end of its first line.
+/// An empty span, or one pointing at the line break itself, still gets a single caret.
+fn underline(source: &str, span: Span) -> (usize, usize) {
+    let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[span.start..]
+        .find('\n')
+        .map_or(source.len(), |i| span.start + i);
+    let column = display_width(&source[line_start..span.start]);
+    let width = display_width(&source[span.start..span.end.min(line_end)]);
+    (column, width.max(1))
+}
+
+#[test]
+fn test_render_expands_tabs() {
+    let source = "package main\n\tfunc";
+    let diagnostic = Diagnostic {
+        message: "oops".to_string(),
+        labels: vec![Label {
+            span: Span { start: 14, end: 18 },
+            message: "here".to_string(),
+        }],
+        notes: vec![],
+    };
+    assert_eq!(
+        diagnostic.render("a.go", source),
+        "error: oops\n --> a.go:2:2\n  |\n2 |     func\n  |     ^^^^ here\n"
+    );
+}
+
+#[test]
+fn test_render_end_of_file_and_several_lines() {
+    let source = "a\nb\n";
+    let diagnostic = Diagnostic {
+        message: "oops".to_string(),
+        labels: vec![
+            Label {
+                span: Span { start: 4, end: 4 },
+                message: "file ends here".to_string(),
+            },
+            Label {
+                span: Span { start: 0, end: 1 },
+                message: "started here".to_string(),
+            },
+        ],
+        notes: vec![],
+    };
+    assert_eq!(
+        diagnostic.render("a.go", source),
+        "error: oops
+ --> a.go:3:1
+  |
+1 | a
+  | ^ started here
+3 |
+  | ^ file ends here
+"
+    );
+}
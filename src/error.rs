@@ -0,0 +1,230 @@
+use std::{collections::BTreeSet, fmt};
+
+use nom::{error::ErrorKind, IResult};
+
+/// Something a parser was looking for when it failed.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum Expected {
+    /// A keyword or an operator spelled exactly like this, e.g. `func` or `(`.
+    Token(&'static str),
+    /// A class of tokens or a grammar production, e.g. identifier or type.
+    Kind(&'static str),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "`{}`", token),
+            Expected::Kind(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+/// A byte range in the source, end exclusive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The error produced by every parser of this crate.
+///
+/// It remembers where parsing failed (`input` is the rest of the source at that point),
+/// what could have appeared there and the constructs that were being parsed around it,
+/// innermost first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError<'a> {
+    pub input: &'a str,
+    pub expected: BTreeSet<Expected>,
+    pub context: Vec<String>,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(input: &'a str, expected: Expected) -> Self {
+        let mut error = Self::from_input(input);
+        error.expected.insert(expected);
+        error
+    }
+
+    fn from_input(input: &'a str) -> Self {
+        Self {
+            input,
+            expected: BTreeSet::new(),
+            context: vec![],
+        }
+    }
+
+    /// The text of the token found where something else was expected, empty at the end of the input.
+    /// ```
+    /// use go_parser_rs::error::{Expected, ParseError};
+    /// assert_eq!(ParseError::new("foo(x)", Expected::Token(";")).found(), "foo");
+    /// assert_eq!(ParseError::new("(x)", Expected::Token(";")).found(), "(");
+    /// assert_eq!(ParseError::new("", Expected::Token(";")).found(), "");
+    /// ```
+    pub fn found(&self) -> &'a str {
+        let word_len = self
+            .input
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.input.len());
+        let len = match (word_len, self.input.chars().next()) {
+            (0, Some(c)) => c.len_utf8(),
+            (len, _) => len,
+        };
+        &self.input[..len]
+    }
+
+    /// Locates the error in `source`, which must be the text handed to the parser that failed.
+    /// ```
+    /// use go_parser_rs::error::{Expected, ParseError, Span};
+    /// let source = "package 1";
+    /// let error = ParseError::new(&source[8..], Expected::Kind("identifier"));
+    /// assert_eq!(error.span(source), Span { start: 8, end: 9 });
+    /// ```
+    pub fn span(&self, source: &str) -> Span {
+        let start = source.len() - self.input.len();
+        Span {
+            start,
+            end: start + self.found().len(),
+        }
+    }
+
+    /// The error message without context, e.g. "expected one of `(`, identifier, found `)`".
+    pub fn message(&self) -> String {
+        let found = match self.found() {
+            "" => "end of file".to_string(),
+            "\n" => "newline".to_string(),
+            found => format!("`{}`", found),
+        };
+        match self.expectation() {
+            Some(expectation) => format!("{}, found {}", expectation, found),
+            None => format!("unexpected {}", found),
+        }
+    }
+
+    /// What was expected, e.g. "expected one of `(`, identifier", if anything is known about it.
+    pub fn expectation(&self) -> Option<String> {
+        let expected: Vec<_> = self.expected.iter().map(Expected::to_string).collect();
+        match expected.as_slice() {
+            [] => None,
+            [one] => Some(format!("expected {}", one)),
+            many => Some(format!("expected one of {}", many.join(", "))),
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.expected.extend(other.expected);
+        for context in other.context {
+            if !self.context.contains(&context) {
+                self.context.push(context);
+            }
+        }
+        self
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for ParseError<'a> {
+    fn from_error_kind(input: &'a str, _kind: ErrorKind) -> Self {
+        Self::from_input(input)
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    /// Keeps the error that got furthest into the input, merging the two when they are at the same place.
+    fn or(self, other: Self) -> Self {
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => self.merge(other),
+        }
+    }
+}
+
+impl<'a> From<nom::error::Error<&'a str>> for ParseError<'a> {
+    fn from(error: nom::error::Error<&'a str>) -> Self {
+        Self::from_input(error.input)
+    }
+}
+
+impl fmt::Display for ParseError<'_> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::SourceFile;
+    /// let error = match SourceFile::parse("package main\nfunc f(x int y) int\n") {
+    ///     Err(nom::Err::Failure(error)) => error,
+    ///     other => panic!("{:?}", other),
+    /// };
+    /// assert_eq!(error.to_string(), "expected `)`, found `y`\n  in parameter list of func f");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())?;
+        for context in &self.context {
+            write!(f, "\n  {}", context)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError<'_> {}
+
+/// Reports a failure of `parser` that did not consume anything as a missing `expected`,
+/// which is more helpful than whatever the parser's first combinator was looking for.
+pub(crate) fn expected<'a, O, F>(
+    expected: Expected,
+    mut parser: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>,
+{
+    move |s: &'a str| {
+        parser(s).map_err(|e| {
+            e.map(|e| match e {
+                e if e.input.len() == s.len() => ParseError::new(s, expected.clone()),
+                e => e,
+            })
+        })
+    }
+}
+
+/// Pushes a description of the construct being parsed, e.g. "in parameter list of func f",
+/// onto the context stack of any error coming out of `parser`.
+pub(crate) fn context<'a, O, C, F>(
+    context: C,
+    mut parser: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>
+where
+    C: Fn() -> String,
+    F: FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>,
+{
+    move |s: &'a str| {
+        parser(s).map_err(|e| {
+            e.map(|mut e| {
+                e.context.push(context());
+                e
+            })
+        })
+    }
+}
+
+#[test]
+fn test_or_keeps_furthest_error() {
+    use nom::error::ParseError as _;
+    let source = "abc";
+    let near = ParseError::new(source, Expected::Token("("));
+    let far = ParseError::new(&source[1..], Expected::Kind("identifier"));
+    assert_eq!(near.clone().or(far.clone()), far);
+    assert_eq!(far.clone().or(near), far);
+}
+
+#[test]
+fn test_or_merges_errors_at_same_place() {
+    use nom::error::ParseError as _;
+    let a = ParseError::new("x", Expected::Token("("));
+    let b = ParseError::new("x", Expected::Kind("identifier"));
+    assert_eq!(
+        a.or(b).message(),
+        "expected one of `(`, identifier, found `x`"
+    );
+}
@@ -1,21 +1,79 @@
 pub mod operand;
+use std::cell::Cell;
+
 use nom::{
     branch::alt,
-    combinator::{map, opt},
-    IResult, Parser,
+    combinator::{map, opt, peek, verify},
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, terminated},
+    IResult,
+};
+
+use crate::{
+    astable::ASTable,
+    error::{Expected, ParseError},
+    parse_util::{identifier, symbol, Restore},
+    typ::{parse_type_args, ChanDir, GoType, TypeLit, TypeName},
 };
 
-use crate::{astable::ASTable, parse_util::symbol};
+use self::operand::{LiteralValue, Operand, OperandName};
+
+thread_local! {
+    /// Whether an expression is being parsed in the header of an `if`, `for` or `switch` statement,
+    /// where a `{` after a type name opens the statement's block rather than a composite literal.
+    static CONTROL_CLAUSE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `parser` on the header of a control statement, see [`CONTROL_CLAUSE`].
+pub(crate) fn control_clause<'a, O, F>(
+    parser: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>,
+{
+    with_control_clause(true, parser)
+}
+
+/// Runs `parser` inside brackets, where composite literals are allowed again even in a control clause.
+pub(crate) fn nested<'a, O, F>(
+    parser: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>,
+{
+    with_control_clause(false, parser)
+}
+
+fn with_control_clause<'a, O, F>(
+    value: bool,
+    mut parser: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O, ParseError<'a>>,
+{
+    move |s| {
+        let _restore = Restore::set(&CONTROL_CLAUSE, value);
+        parser(s)
+    }
+}
+
+fn in_control_clause() -> bool {
+    CONTROL_CLAUSE.with(Cell::get)
+}
 
-use self::operand::Operand;
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression<'a> {
     UnaryExpr(UnaryExpr<'a>),
     BinExpr {
         left: Box<Self>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
         op: &'a str,
         right: Box<Self>,
     },
+    /// The source text of an expression that failed to parse, see [`crate::recover`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    BadExpr(&'a str),
 }
 
 impl<'a> Expression<'a> {
@@ -32,19 +90,9 @@ impl<'a> Expression<'a> {
     /// assert_eq!(Expression::parse_mul_expr("1*2 +3").unwrap().0, "+3");
     /// assert_eq!(Expression::parse_mul_expr("1*2 *33").unwrap().0, "");
     /// ```
-    pub fn parse_mul_expr(s: &'a str) -> IResult<&'a str, Self> {
-        let mut parser = map(UnaryExpr::parse, |expr| Expression::UnaryExpr(expr));
-        let (mut s, mut left) = parser.parse(s)?;
-        while let (a, Some(op)) = opt(mul_op)(s)? {
-            let result = parser.parse(a)?;
-            left = Expression::BinExpr {
-                left: Box::new(left),
-                op,
-                right: Box::new(result.1),
-            };
-            s = result.0;
-        }
-        Ok((s, left))
+    pub fn parse_mul_expr(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let unary_expr = |s| map(UnaryExpr::parse, Expression::UnaryExpr)(s);
+        Self::parse_binary(s, unary_expr, mul_op)
     }
 
     /// ```
@@ -54,23 +102,94 @@ impl<'a> Expression<'a> {
     /// assert_eq!(Expression::parse_add_expr("1*2 *33").unwrap().0, "");
     /// assert_eq!(Expression::parse_add_expr("true == false").unwrap().0, "== false");
     /// ```
-    pub fn parse_add_expr(s: &'a str) -> IResult<&'a str, Self> {
-        let (mut s, mut left) = Expression::parse_mul_expr(s)?;
-        while let (a, Some(op)) = opt(add_op)(s)? {
-            let result = Expression::parse_mul_expr(a)?;
+    pub fn parse_add_expr(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_binary(s, Self::parse_mul_expr, add_op)
+    }
+
+    /// ```
+    /// use go_parser_rs::expression::Expression;
+    /// assert_eq!(Expression::parse_rel_expr("a+1 < b && c").unwrap().0, "&& c");
+    /// ```
+    pub fn parse_rel_expr(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_binary(s, Self::parse_add_expr, rel_op)
+    }
+
+    /// ```
+    /// use go_parser_rs::expression::Expression;
+    /// assert_eq!(Expression::parse_and_expr("a && b == c || d").unwrap().0, "|| d");
+    /// ```
+    pub fn parse_and_expr(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_binary(s, Self::parse_rel_expr, and_op)
+    }
+
+    /// ```
+    /// use go_parser_rs::expression::Expression;
+    /// assert_eq!(Expression::parse_or_expr("a || b && c").unwrap().0, "");
+    /// ```
+    pub fn parse_or_expr(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_binary(s, Self::parse_and_expr, or_op)
+    }
+
+    /// Parses a left-associative chain of one precedence level.
+    fn parse_binary<P, O>(
+        s: &'a str,
+        operand: P,
+        operator: O,
+    ) -> IResult<&'a str, Self, ParseError<'a>>
+    where
+        P: Fn(&'a str) -> IResult<&'a str, Self, ParseError<'a>>,
+        O: Fn(&'a str) -> IResult<&'a str, &'a str, ParseError<'a>>,
+    {
+        let (mut s, mut left) = operand(s)?;
+        while let (a, Some(op)) = opt(&operator)(s)? {
+            let (rest, right) = operand(a)?;
             left = Expression::BinExpr {
                 left: Box::new(left),
                 op,
-                right: Box::new(result.1),
+                right: Box::new(right),
             };
-            s = result.0;
+            s = rest;
         }
         Ok((s, left))
     }
+
+    /// ExpressionList = Expression { "," Expression } .
+    /// ```
+    /// use go_parser_rs::expression::Expression;
+    /// let (s, list) = Expression::parse_list("a, b+1, f(x) = 1").unwrap();
+    /// assert_eq!((s, list.len()), ("= 1", 3));
+    /// ```
+    pub fn parse_list(s: &'a str) -> IResult<&'a str, Vec<Self>, ParseError<'a>> {
+        separated_list1(symbol(","), Self::parse)(s)
+    }
+}
+
+impl<'a> ASTable<'a> for Expression<'a> {
+    /// Expression = UnaryExpr | Expression binary_op Expression .
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::expression::{Expression, UnaryExpr, PrimaryExpr, operand::{Operand, OperandName}};
+    /// let name = |n| Box::new(Expression::UnaryExpr(UnaryExpr::PrimaryExpr(PrimaryExpr::Operand(
+    ///     Operand::OperandName(OperandName::Identifier(n)),
+    /// ))));
+    /// assert_eq!(
+    ///     Expression::parse("a || b && c"),
+    ///     Ok(("", Expression::BinExpr {
+    ///         left: name("a"),
+    ///         op: "||",
+    ///         right: Box::new(Expression::BinExpr { left: name("b"), op: "&&", right: name("c") }),
+    ///     }))
+    /// );
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_or_expr(s)
+    }
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unary<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     pub op: &'a str,
     pub expr: Box<UnaryExpr<'a>>,
 }
@@ -93,7 +212,7 @@ impl<'a> ASTable<'a> for Unary<'a> {
     ///    ))
     /// );
     /// ```
-    fn parse(s: &'a str) -> IResult<&'a str, Self> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
         let (s, op) = unary_op(s)?;
         let (s, expr) = UnaryExpr::parse(s)?;
         Ok((
@@ -107,7 +226,9 @@ impl<'a> ASTable<'a> for Unary<'a> {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryExpr<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     PrimaryExpr(PrimaryExpr<'a>),
     Unary(Unary<'a>),
 }
@@ -118,18 +239,84 @@ impl<'a> ASTable<'a> for UnaryExpr<'a> {
     /// use go_parser_rs::expression::{UnaryExpr, PrimaryExpr, operand::{Operand, OperandName}};
     /// use go_parser_rs::literals::{integer::IntLit, Literal};
     /// assert_eq!(UnaryExpr::parse("1+2"), Ok(("+2", UnaryExpr::PrimaryExpr(PrimaryExpr::Operand(Operand::Literal(Literal::IntLit(IntLit::DecimalLit("1"))))))));
+    /// assert!(matches!(UnaryExpr::parse("<-chan int)"), Ok((")", UnaryExpr::PrimaryExpr(PrimaryExpr::Operand(Operand::TypeLit(_)))))));
+    /// assert!(matches!(UnaryExpr::parse("<-chan int(c)"), Ok(("", UnaryExpr::Unary(_)))));
     /// ```
-    fn parse(s: &'a str) -> IResult<&'a str, Self> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
         alt((
-            map(Unary::parse, UnaryExpr::Unary),
+            map(Unary::parse, recv_chan_type),
             map(PrimaryExpr::parse, UnaryExpr::PrimaryExpr),
         ))(s)
     }
 }
 
+/// Reads `<-` before a channel type as part of the type, like in `make(<-chan int)` or
+/// `(<-chan int)(c)`. `<-chan int(c)` stays a receive from a conversion.
+fn recv_chan_type(mut unary: Unary<'_>) -> UnaryExpr<'_> {
+    if unary.op == "<-" {
+        if let UnaryExpr::PrimaryExpr(PrimaryExpr::Operand(Operand::TypeLit(lit))) =
+            &mut *unary.expr
+        {
+            if let TypeLit::ChannelType {
+                dir: dir @ ChanDir::Both,
+                ..
+            } = &mut **lit
+            {
+                *dir = ChanDir::Recv;
+                return *unary.expr;
+            }
+        }
+    }
+    UnaryExpr::Unary(unary)
+}
+
+/// PrimaryExpr = Operand | PrimaryExpr Selector | PrimaryExpr Index | PrimaryExpr Slice |
+///               PrimaryExpr TypeAssertion | PrimaryExpr Arguments | PrimaryExpr TypeArgs .
+///
+/// A composite literal of a named type, like `pkg.Set[string]{}`, is parsed as a postfix too and
+/// becomes an [`Operand::CompositeLit`].
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrimaryExpr<'a> {
     Operand(Operand<'a>),
+    /// Selector = "." identifier .
+    Selector {
+        expr: Box<Self>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        selector: &'a str,
+    },
+    /// Index = "[" Expression "]" .
+    Index {
+        expr: Box<Self>,
+        index: Box<Expression<'a>>,
+    },
+    /// Slice = "[" [ Expression ] ":" [ Expression ] "]" |
+    ///         "[" [ Expression ] ":" Expression ":" Expression "]" .
+    Slice {
+        expr: Box<Self>,
+        low: Option<Box<Expression<'a>>>,
+        high: Option<Box<Expression<'a>>>,
+        max: Option<Box<Expression<'a>>>,
+    },
+    /// TypeAssertion = "." "(" Type ")" .
+    TypeAssertion {
+        expr: Box<Self>,
+        typ: Box<GoType<'a>>,
+    },
+    /// TypeArgs = "[" TypeList [ "," ] "]" .
+    ///
+    /// A single type argument that is also an expression, like `f[int]`, is parsed as an [`Self::Index`].
+    Instantiation {
+        expr: Box<Self>,
+        type_args: Vec<GoType<'a>>,
+    },
+    /// Arguments = "(" [ ExpressionList [ "..." ] [ "," ] ] ")" .
+    Call {
+        func: Box<Self>,
+        args: Vec<Expression<'a>>,
+        /// Whether the last argument is followed by "...".
+        ellipsis: bool,
+    },
 }
 
 impl<'a> ASTable<'a> for PrimaryExpr<'a> {
@@ -138,9 +325,180 @@ impl<'a> ASTable<'a> for PrimaryExpr<'a> {
     /// use go_parser_rs::expression::{PrimaryExpr, operand::{Operand, OperandName}};
     /// use go_parser_rs::literals::{integer::IntLit, Literal};
     /// assert_eq!(PrimaryExpr::parse("1+2"), Ok(("+2", PrimaryExpr::Operand(Operand::Literal(Literal::IntLit(IntLit::DecimalLit("1")))))));
+    /// assert!(matches!(PrimaryExpr::parse("f(x)[1:2]"), Ok(("", PrimaryExpr::Slice { .. }))));
+    /// assert!(matches!(PrimaryExpr::parse("a.b.c(x, y...)"), Ok(("", PrimaryExpr::Call { ellipsis: true, .. }))));
+    /// assert!(matches!(PrimaryExpr::parse("x.(io.Reader)"), Ok(("", PrimaryExpr::TypeAssertion { .. }))));
+    /// // `.(type)` is only allowed in type switches, which parse it themselves.
+    /// assert!(matches!(PrimaryExpr::parse("x.(type)"), Ok((".(type)", PrimaryExpr::Operand(_)))));
+    /// assert!(matches!(PrimaryExpr::parse("Map[int, string](xs, f)"), Ok(("", PrimaryExpr::Call { func, .. }))
+    ///     if matches!(*func, PrimaryExpr::Instantiation { .. })));
+    /// assert!(matches!(PrimaryExpr::parse("pkg.Set[string]{}"), Ok(("", PrimaryExpr::Operand(Operand::CompositeLit { .. })))));
     /// ```
-    fn parse(s: &'a str) -> IResult<&'a str, Self> {
-        map(Operand::parse, PrimaryExpr::Operand)(s)
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (mut s, mut expr) = map(Operand::parse, PrimaryExpr::Operand)(s)?;
+        loop {
+            if !in_control_clause() && symbol("{")(s).is_ok() {
+                match expr.into_literal_type() {
+                    Ok(typ) => {
+                        let (rest, value) = LiteralValue::parse(s)?;
+                        expr = PrimaryExpr::Operand(Operand::CompositeLit {
+                            typ: Box::new(typ),
+                            value,
+                        });
+                        s = rest;
+                        continue;
+                    }
+                    Err(other) => expr = other,
+                }
+            }
+            let before_literal = !in_control_clause()
+                && matches!(expr, PrimaryExpr::Operand(Operand::OperandName(_)));
+            let (rest, postfix) = opt(alt((
+                map(preceded(symbol("."), identifier), Postfix::Selector),
+                map(
+                    delimited(
+                        pair(symbol("."), symbol("(")),
+                        nested(GoType::parse),
+                        symbol(")"),
+                    ),
+                    Postfix::TypeAssertion,
+                ),
+                // `List[int]{}` would otherwise be read as an index followed by a block.
+                map(
+                    verify(
+                        terminated(parse_type_args, peek(symbol("{"))),
+                        |_: &Vec<GoType>| before_literal,
+                    ),
+                    Postfix::TypeArgs,
+                ),
+                Self::parse_index_or_slice,
+                map(parse_type_args, Postfix::TypeArgs),
+                Self::parse_arguments,
+            )))(s)?;
+            let postfix = match postfix {
+                Some(postfix) => postfix,
+                None => return Ok((s, expr)),
+            };
+            let inner = Box::new(expr);
+            expr = match postfix {
+                Postfix::Selector(selector) => PrimaryExpr::Selector {
+                    expr: inner,
+                    selector,
+                },
+                Postfix::Index(index) => PrimaryExpr::Index {
+                    expr: inner,
+                    index: Box::new(index),
+                },
+                Postfix::Slice(low, high, max) => PrimaryExpr::Slice {
+                    expr: inner,
+                    low: low.map(Box::new),
+                    high: high.map(Box::new),
+                    max: max.map(Box::new),
+                },
+                Postfix::TypeAssertion(typ) => PrimaryExpr::TypeAssertion {
+                    expr: inner,
+                    typ: Box::new(typ),
+                },
+                Postfix::TypeArgs(type_args) => PrimaryExpr::Instantiation {
+                    expr: inner,
+                    type_args,
+                },
+                Postfix::Arguments(args, ellipsis) => PrimaryExpr::Call {
+                    func: inner,
+                    args,
+                    ellipsis,
+                },
+            };
+            s = rest;
+        }
+    }
+}
+
+/// The part of a PrimaryExpr following its operand.
+enum Postfix<'a> {
+    Selector(&'a str),
+    Index(Expression<'a>),
+    Slice(
+        Option<Expression<'a>>,
+        Option<Expression<'a>>,
+        Option<Expression<'a>>,
+    ),
+    TypeAssertion(GoType<'a>),
+    TypeArgs(Vec<GoType<'a>>),
+    Arguments(Vec<Expression<'a>>, bool),
+}
+
+impl<'a> PrimaryExpr<'a> {
+    /// The type of a composite literal, if this expression names one, or else the expression itself.
+    fn into_literal_type(self) -> Result<GoType<'a>, Self> {
+        let type_name = |name| match name {
+            OperandName::Identifier(name) => TypeName::Identifier(name),
+            OperandName::QualifiedIdent(name) => TypeName::QualifiedIdent(name),
+        };
+        match self {
+            PrimaryExpr::Operand(Operand::OperandName(name)) => {
+                Ok(GoType::TypeName(type_name(name)))
+            }
+            PrimaryExpr::Instantiation { expr, type_args } => match *expr {
+                PrimaryExpr::Operand(Operand::OperandName(name)) => Ok(GoType::Instantiation {
+                    name: type_name(name),
+                    type_args,
+                }),
+                expr => Err(PrimaryExpr::Instantiation {
+                    expr: Box::new(expr),
+                    type_args,
+                }),
+            },
+            expr => Err(expr),
+        }
+    }
+
+    fn parse_index_or_slice(s: &'a str) -> IResult<&'a str, Postfix<'a>, ParseError<'a>> {
+        nested(Self::parse_index_or_slice_body)(s)
+    }
+
+    fn parse_index_or_slice_body(s: &'a str) -> IResult<&'a str, Postfix<'a>, ParseError<'a>> {
+        let (s, _) = symbol("[")(s)?;
+        let (s, low) = opt(Expression::parse)(s)?;
+        let (s, colon) = opt(symbol(":"))(s)?;
+        let (s, postfix) = match (low, colon) {
+            (Some(index), None) => (s, Postfix::Index(index)),
+            (None, None) => {
+                return Err(nom::Err::Error(ParseError::new(
+                    s,
+                    Expected::Kind("expression"),
+                )))
+            }
+            (low, Some(_)) => {
+                let (s, high) = opt(Expression::parse)(s)?;
+                let (s, max) = match high {
+                    Some(_) => opt(preceded(symbol(":"), Expression::parse))(s)?,
+                    None => (s, None),
+                };
+                (s, Postfix::Slice(low, high, max))
+            }
+        };
+        let (s, _) = symbol("]")(s)?;
+        Ok((s, postfix))
+    }
+
+    fn parse_arguments(s: &'a str) -> IResult<&'a str, Postfix<'a>, ParseError<'a>> {
+        nested(Self::parse_arguments_body)(s)
+    }
+
+    fn parse_arguments_body(s: &'a str) -> IResult<&'a str, Postfix<'a>, ParseError<'a>> {
+        let (s, _) = symbol("(")(s)?;
+        let (s, args) = opt(Expression::parse_list)(s)?;
+        let (s, ellipsis) = match args {
+            Some(_) => opt(symbol("..."))(s)?,
+            None => (s, None),
+        };
+        let (s, _) = opt(symbol(","))(s)?;
+        let (s, _) = symbol(")")(s)?;
+        Ok((
+            s,
+            Postfix::Arguments(args.unwrap_or_default(), ellipsis.is_some()),
+        ))
     }
 }
 
@@ -148,7 +506,7 @@ impl<'a> ASTable<'a> for PrimaryExpr<'a> {
 /// use go_parser_rs::expression::or_op;
 /// assert_eq!(or_op("||true"), Ok(("true", "||")));
 /// ```
-pub fn or_op(s: &str) -> IResult<&str, &str> {
+pub fn or_op(s: &str) -> IResult<&str, &str, ParseError<'_>> {
     symbol("||")(s)
 }
 
@@ -156,7 +514,7 @@ pub fn or_op(s: &str) -> IResult<&str, &str> {
 /// use go_parser_rs::expression::and_op;
 /// assert_eq!(and_op("&&true"), Ok(("true", "&&")));
 /// ```
-pub fn and_op(s: &str) -> IResult<&str, &str> {
+pub fn and_op(s: &str) -> IResult<&str, &str, ParseError<'_>> {
     symbol("&&")(s)
 }
 
@@ -170,7 +528,7 @@ pub fn and_op(s: &str) -> IResult<&str, &str> {
 /// assert_eq!(rel_op(">a"), Ok(("a", ">")));
 /// assert_eq!(rel_op(">= a"), Ok(("a", ">=")));
 /// ```
-pub fn rel_op(s: &str) -> IResult<&str, &str> {
+pub fn rel_op(s: &str) -> IResult<&str, &str, ParseError<'_>> {
     alt((
         symbol("=="),
         symbol("!="),
@@ -190,7 +548,7 @@ pub fn rel_op(s: &str) -> IResult<&str, &str> {
 /// assert_eq!(add_op("^a"), Ok(("a", "^")));
 /// assert!(add_op("*a").is_err());
 /// ```
-pub fn add_op(s: &str) -> IResult<&str, &str> {
+pub fn add_op(s: &str) -> IResult<&str, &str, ParseError<'_>> {
     alt((symbol("+"), symbol("-"), symbol("|"), symbol("^")))(s)
 }
 
@@ -205,7 +563,7 @@ pub fn add_op(s: &str) -> IResult<&str, &str> {
 /// assert_eq!(mul_op("& x"), Ok(("x", "&")));
 /// assert_eq!(mul_op("&^ x"), Ok(("x", "&^")));
 /// ```
-pub fn mul_op(s: &str) -> IResult<&str, &str> {
+pub fn mul_op(s: &str) -> IResult<&str, &str, ParseError<'_>> {
     alt((
         symbol("*"),
         symbol("/"),
@@ -227,7 +585,7 @@ pub fn mul_op(s: &str) -> IResult<&str, &str> {
 /// assert_eq!(unary_op("&1"), Ok(("1", "&")));
 /// assert_eq!(unary_op("<- ch"), Ok(("ch", "<-")));
 /// ```
-pub fn unary_op(s: &str) -> IResult<&str, &str> {
+pub fn unary_op(s: &str) -> IResult<&str, &str, ParseError<'_>> {
     alt((
         symbol("+"),
         symbol("-"),
@@ -238,3 +596,17 @@ pub fn unary_op(s: &str) -> IResult<&str, &str> {
         symbol("<-"),
     ))(s)
 }
+
+#[test]
+fn test_control_clause_reset_after_panic() {
+    let mut parser = control_clause(|_: &str| -> IResult<&str, (), ParseError> {
+        assert!(in_control_clause());
+        panic!("in control clause")
+    });
+    assert!(std::panic::catch_unwind(move || parser("x")).is_err());
+    assert!(!in_control_clause());
+    // A composite literal is read again after the panic.
+    let (s, expr) = Expression::parse("T{1}").unwrap();
+    assert_eq!(s, "");
+    assert!(matches!(expr, Expression::UnaryExpr(_)));
+}
@@ -1,17 +1,48 @@
-use nom::{branch::alt, combinator::map, IResult};
+use nom::{
+    branch::alt,
+    combinator::{map, opt, peek, verify},
+    multi::separated_list0,
+    sequence::{delimited, pair, terminated, tuple},
+    IResult,
+};
 
 use crate::{
-    astable::ASTable, identifier::QualifiedIdent, literals::Literal, parse_util::identifier,
+    astable::ASTable,
+    error::ParseError,
+    identifier::QualifiedIdent,
+    literals::Literal,
+    parse_util::{identifier, reserved, symbol},
+    statement::Block,
+    typ::{GoType, Signature, TypeLit},
 };
 
-use super::Expression;
+use super::{nested, Expression};
 
-/// Operand = Literal | OperandName | "(" Expression ")"
+/// Operand = Literal | OperandName | FunctionLit | "(" Expression ")" .
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operand<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     Literal(Literal<'a>),
     OperandName(OperandName<'a>),
+    FunctionLit(Box<FunctionLit<'a>>),
     Parenthesized(Box<Expression<'a>>),
+    /// A type literal converted to, like `[]byte(s)`, or passed to a built-in, like
+    /// `make(chan int)`. Like conversions to type names, conversions are parsed as calls:
+    ///
+    /// Conversion = Type "(" Expression [ "," ] ")" .
+    ///
+    /// Pointer types are not type literals here, `*T(x)` being the indirection of a conversion.
+    TypeLit(Box<TypeLit<'a>>),
+    /// CompositeLit = LiteralType LiteralValue .
+    /// LiteralType  = StructType | ArrayType | "[" "..." "]" ElementType | SliceType | MapType |
+    ///                TypeName [ TypeArgs ] .
+    ///
+    /// Literals of a named type are parsed as a postfix of a [`super::PrimaryExpr`].
+    CompositeLit {
+        typ: Box<GoType<'a>>,
+        value: LiteralValue<'a>,
+    },
 }
 
 impl<'a> ASTable<'a> for Operand<'a> {
@@ -23,18 +54,169 @@ impl<'a> ASTable<'a> for Operand<'a> {
     /// use go_parser_rs::astable::ASTable;
     /// assert_eq!(Operand::parse("1+2"), Ok(("+2", Operand::Literal(Literal::IntLit(IntLit::DecimalLit("1"))))));
     /// assert_eq!(Operand::parse("x.y"), Ok(("", Operand::OperandName(OperandName::QualifiedIdent(QualifiedIdent{package_name: "x", identifier: "y"})))));
+    /// assert!(matches!(Operand::parse("(1 + x) * 2"), Ok(("* 2", Operand::Parenthesized(_)))));
+    /// assert!(matches!(Operand::parse("[...]string{\"a\", \"b\"}"), Ok(("", Operand::CompositeLit { .. }))));
+    /// assert!(matches!(Operand::parse("map[string]int{\"a\": 1}"), Ok(("", Operand::CompositeLit { .. }))));
+    /// assert!(matches!(Operand::parse("func(x int) int { return -x }(1)"), Ok(("(1)", Operand::FunctionLit(_)))));
+    /// assert!(matches!(Operand::parse("[]byte(s)"), Ok(("(s)", Operand::TypeLit(_)))));
+    /// assert!(matches!(Operand::parse("chan int)"), Ok((")", Operand::TypeLit(_)))));
+    /// assert!(Operand::parse("[]int + 1").is_err());
     /// ```
-    fn parse(s: &'a str) -> IResult<&'a str, Self> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
         alt((
             map(Literal::parse, Operand::Literal),
+            map(pair(literal_type, LiteralValue::parse), |(typ, value)| {
+                Operand::CompositeLit {
+                    typ: Box::new(typ),
+                    value,
+                }
+            }),
+            map(FunctionLit::parse, |lit| {
+                Operand::FunctionLit(Box::new(lit))
+            }),
+            map(operand_type, |typ| Operand::TypeLit(Box::new(typ))),
             map(OperandName::parse, Operand::OperandName),
-            // TODO: Add Parenthesized
+            map(
+                delimited(symbol("("), nested(Expression::parse), symbol(")")),
+                |expr| Operand::Parenthesized(Box::new(expr)),
+            ),
+        ))(s)
+    }
+}
+
+/// FunctionLit = "func" Signature FunctionBody .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionLit<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub signature: Signature<'a>,
+    pub body: Block<'a>,
+}
+
+impl<'a> ASTable<'a> for FunctionLit<'a> {
+    /// The body is a block even in the header of a control statement.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::expression::operand::FunctionLit;
+    /// let (s, lit) = FunctionLit::parse("func(a, b int) (int, error) {\n\treturn a + b, nil\n}").unwrap();
+    /// assert_eq!((s, lit.signature.params.len(), lit.body.0.len()), ("", 1, 1));
+    /// assert!(FunctionLit::parse("func(a int)").is_err());
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("func")(s)?;
+        let (s, signature) = Signature::parse(s)?;
+        let (s, body) = nested(Block::parse)(s)?;
+        Ok((s, FunctionLit { signature, body }))
+    }
+}
+
+/// The type of a composite literal that is not a type name.
+fn literal_type(s: &str) -> IResult<&str, GoType<'_>, ParseError<'_>> {
+    let type_lit = verify(TypeLit::parse, |typ| {
+        matches!(
+            typ,
+            TypeLit::ArrayType { .. }
+                | TypeLit::SliceType(_)
+                | TypeLit::MapType { .. }
+                | TypeLit::StructType(_)
+        )
+    });
+    alt((
+        map(
+            pair(
+                tuple((symbol("["), symbol("..."), symbol("]"))),
+                GoType::parse,
+            ),
+            |(_, elem)| {
+                GoType::TypeLit(TypeLit::ArrayType {
+                    len: None,
+                    elem: Box::new(elem),
+                })
+            },
+        ),
+        map(type_lit, GoType::TypeLit),
+    ))(s)
+}
+
+/// A type literal that is converted to or is an argument, followed by `(`, `,` or `)`.
+fn operand_type(s: &str) -> IResult<&str, TypeLit<'_>, ParseError<'_>> {
+    let type_lit = verify(TypeLit::parse, |typ| match typ {
+        TypeLit::ArrayType { len, .. } => len.is_some(),
+        TypeLit::PointerType(_) => false,
+        _ => true,
+    });
+    terminated(type_lit, peek(alt((symbol("("), symbol(","), symbol(")")))))(s)
+}
+
+/// LiteralValue = "{" [ ElementList [ "," ] ] "}" .
+/// ElementList  = KeyedElement { "," KeyedElement } .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LiteralValue<'a>(
+    #[cfg_attr(feature = "serde", serde(borrow))] pub Vec<KeyedElement<'a>>,
+);
+
+impl<'a> ASTable<'a> for LiteralValue<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::expression::operand::{Element, LiteralValue};
+    /// let (s, value) = LiteralValue::parse("{X: 1, Y: {2, 3},\n}").unwrap();
+    /// assert_eq!((s, value.0.len()), ("", 2));
+    /// assert!(matches!(value.0[1].value, Element::LiteralValue(_)));
+    /// assert_eq!(LiteralValue::parse("{}"), Ok(("", LiteralValue(vec![]))));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let elements = terminated(
+            separated_list0(symbol(","), KeyedElement::parse),
+            opt(symbol(",")),
+        );
+        map(
+            delimited(symbol("{"), nested(elements), symbol("}")),
+            LiteralValue,
+        )(s)
+    }
+}
+
+/// KeyedElement = [ Key ":" ] Element .
+/// Key          = FieldName | Expression | LiteralValue .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyedElement<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub key: Option<Element<'a>>,
+    pub value: Element<'a>,
+}
+
+impl<'a> ASTable<'a> for KeyedElement<'a> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, key) = opt(terminated(Element::parse, symbol(":")))(s)?;
+        let (s, value) = Element::parse(s)?;
+        Ok((s, KeyedElement { key, value }))
+    }
+}
+
+/// Element = Expression | LiteralValue .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Element<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Expression(Expression<'a>),
+    LiteralValue(LiteralValue<'a>),
+}
+
+impl<'a> ASTable<'a> for Element<'a> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        alt((
+            map(Expression::parse, Element::Expression),
+            map(LiteralValue::parse, Element::LiteralValue),
         ))(s)
     }
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OperandName<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     Identifier(&'a str),
     QualifiedIdent(QualifiedIdent<'a>),
 }
@@ -47,7 +229,7 @@ impl<'a> ASTable<'a> for OperandName<'a> {
     /// assert_eq!(OperandName::parse("x.y"), Ok(("", OperandName::QualifiedIdent(QualifiedIdent{package_name: "x", identifier: "y"}))));
     /// assert_eq!(OperandName::parse("vmw"), Ok(("", OperandName::Identifier("vmw"))));
     ///```
-    fn parse(s: &'a str) -> IResult<&'a str, Self> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
         alt((
             map(QualifiedIdent::parse, Self::QualifiedIdent),
             map(identifier, Self::Identifier),
@@ -0,0 +1,1553 @@
+//! Export of syntax trees as the JSON of Go's `go/ast` nodes.
+//!
+//! Each node is an object whose `NodeType` is the name of the `go/ast` type it maps to, like
+//! `"FuncDecl"` for an `*ast.FuncDecl`, with the fields of that type under their Go names, as the
+//! dumps of `asty` have them. `Pos` and `End` are the results of the `Pos()` and `End()` methods.
+//!
+//! Positions are the `token.Pos` values of a file set holding only the exported file: the byte
+//! offset plus one, with 0 for `token.NoPos`. The tree keeps the text of identifiers, literals and
+//! operators, and the positions of keywords and punctuation are found by reading the source along
+//! with the tree; a token that is not where the tree says it should be is at `NoPos`.
+//!
+//! `nil` slices and pointers are `null`, tokens like `Tok` and `Op` are their Go spelling, like
+//! `"const"` or `"+="`, and the `Kind` of a `BasicLit` is one of `"INT"`, `"FLOAT"`, `"IMAG"`,
+//! `"CHAR"` and `"STRING"`. Objects and scopes (`Obj`, `Scope`, `Unresolved`) are left out.
+//!
+//! ```
+//! use go_parser_rs::{goast, recover::parse_source_file};
+//! let src = "package m\n\nvar x = a + 1\n";
+//! let (file, _) = parse_source_file(src);
+//! let json = goast::to_json(&file, src);
+//! assert!(json.starts_with(r#"{"NodeType":"File","Pos":1,"End":25,"#));
+//! assert!(json.contains(
+//!     r#"{"NodeType":"BinaryExpr","Pos":20,"End":25,"X":{"NodeType":"Ident","Pos":20,"End":21,"NamePos":20,"Name":"a"},"OpPos":22,"Op":"+","#
+//! ));
+//! ```
+
+use std::fmt;
+
+use crate::{
+    comment::{Comment, CommentGroup},
+    declaration::{Declaration, TypeSpec},
+    expression::{
+        operand::{Element, KeyedElement, LiteralValue, Operand, OperandName},
+        Expression, PrimaryExpr, UnaryExpr,
+    },
+    identifier::QualifiedIdent,
+    literals::{integer::IntLit, Literal},
+    statement::{
+        control::{CaseClause, Else, ForClause, ForStmt, IfStmt, RangeVars},
+        simple::SimpleStmt,
+        Block, Statement,
+    },
+    tokenize::{tokenizer::tokenize_lossless, Token},
+    typ::{
+        ChanDir, FieldDecl, GoType, InterfaceElem, ParameterDecl, Signature, TypeElem, TypeLit,
+        TypeName, TypeParamDecl, TypeTerm,
+    },
+    Function, ImportDeclaration, Receiver, SourceFile, TopLevel,
+};
+
+/// Returns `file` as the JSON of an `*ast.File`. `source` must be the text `file` was parsed from.
+pub fn to_json(file: &SourceFile, source: &str) -> String {
+    Exporter::new(source).file(file).to_string()
+}
+
+#[derive(Debug, Clone)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn string(s: &str) -> Self {
+        Json::String(s.to_string())
+    }
+
+    /// A Go slice, which is `nil` when empty.
+    fn list(items: Vec<Json>) -> Self {
+        if items.is_empty() {
+            Json::Null
+        } else {
+            Json::Array(items)
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => {
+                f.write_str("\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '\n' => f.write_str("\\n")?,
+                        '\r' => f.write_str("\\r")?,
+                        '\t' => f.write_str("\\t")?,
+                        c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                f.write_str("\"")
+            }
+            Json::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}:{}", Json::string(name), value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+type Fields = Vec<(&'static str, Json)>;
+
+/// Walks the tree in source order, with a cursor in the tokens of the source to find the
+/// positions of the tokens the tree does not keep.
+struct Exporter<'s> {
+    source: &'s str,
+    /// The offsets and texts of the tokens, comments and white space left out.
+    tokens: Vec<(usize, &'s str)>,
+    comments: Vec<(usize, &'s str)>,
+    /// The index in `tokens` of the token after the last one read.
+    next: usize,
+    /// The byte range of each node being exported, from the first to the last token read in it.
+    ranges: Vec<Option<(usize, usize)>>,
+}
+
+impl<'s> Exporter<'s> {
+    fn new(source: &'s str) -> Self {
+        let mut tokens = vec![];
+        let mut comments = vec![];
+        let mut offset = 0;
+        for token in tokenize_lossless(source) {
+            let text = token.text();
+            match token {
+                Token::Whitespace(_) => {}
+                Token::Comment(_) => comments.push((offset, text)),
+                _ => tokens.push((offset, text)),
+            }
+            offset += text.len();
+        }
+        Exporter {
+            source,
+            tokens,
+            comments,
+            next: 0,
+            ranges: vec![],
+        }
+    }
+
+    fn record(&mut self, start: usize, end: usize) {
+        if let Some(range) = self.ranges.last_mut() {
+            *range = Some(match *range {
+                Some((first, last)) => (first.min(start), last.max(end)),
+                None => (start, end),
+            });
+        }
+    }
+
+    /// Builds a node of `go/ast` type `kind`, whose `Pos` and `End` cover the tokens read by `fields`.
+    fn node(&mut self, kind: &'static str, fields: impl FnOnce(&mut Self) -> Fields) -> Json {
+        self.ranges.push(None);
+        let fields = fields(self);
+        let range = self.ranges.pop().unwrap();
+        if let Some((start, end)) = range {
+            self.record(start, end);
+        }
+        let (pos, end) = range.map_or((0, 0), |(start, end)| (start + 1, end + 1));
+        let mut object = vec![
+            ("NodeType", Json::string(kind)),
+            ("Pos", Json::Number(pos)),
+            ("End", Json::Number(end)),
+        ];
+        object.extend(fields);
+        Json::Object(object)
+    }
+
+    /// The offset of `text` if it is a slice of the source.
+    fn offset_of(&self, text: &str) -> Option<usize> {
+        let offset = (text.as_ptr() as usize).checked_sub(self.source.as_ptr() as usize)?;
+        if offset + text.len() <= self.source.len() {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    /// The index of the next token, past the separators `,` and `;` unless `text` is one of them.
+    fn peek_index(&self, text: &str) -> usize {
+        let mut i = self.next;
+        while let Some(&(_, token)) = self.tokens.get(i) {
+            if token == text || (token != "," && token != ";") {
+                break;
+            }
+            i += 1;
+        }
+        i
+    }
+
+    fn at(&self, text: &str) -> bool {
+        matches!(self.tokens.get(self.peek_index(text)), Some(&(_, token)) if token == text)
+    }
+
+    /// Reads the next token if it is `text`, and returns its position, or `NoPos`.
+    fn token(&mut self, text: &str) -> usize {
+        let i = self.peek_index(text);
+        match self.tokens.get(i) {
+            Some(&(offset, token)) if token == text => {
+                self.next = i + 1;
+                self.record(offset, offset + text.len());
+                offset + 1
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns the position of `text`, kept by the tree, and moves the cursor after it.
+    fn text(&mut self, text: &str) -> usize {
+        match self.offset_of(text) {
+            Some(offset) => {
+                let end = offset + text.len();
+                self.next = self.tokens.partition_point(|&(start, _)| start < end);
+                self.record(offset, end);
+                offset + 1
+            }
+            None => self.token(text),
+        }
+    }
+
+    fn ident(&mut self, name: &str) -> Json {
+        self.node("Ident", |e| {
+            vec![
+                ("NamePos", Json::Number(e.text(name))),
+                ("Name", Json::string(name)),
+            ]
+        })
+    }
+
+    fn idents(&mut self, names: &[&str]) -> Json {
+        Json::list(names.iter().map(|name| self.ident(name)).collect())
+    }
+
+    fn basic_lit(&mut self, pos: usize, kind: &str, value: &str) -> Json {
+        self.node("BasicLit", |e| {
+            if pos != 0 {
+                e.record(pos - 1, pos - 1 + value.len());
+            }
+            vec![
+                ("ValuePos", Json::Number(pos)),
+                ("Kind", Json::string(kind)),
+                ("Value", Json::string(value)),
+            ]
+        })
+    }
+
+    /// `From` and `To` of a bad node covering `text`.
+    fn bad(&mut self, kind: &'static str, text: &str) -> Json {
+        self.node(kind, |e| {
+            let from = e.text(text);
+            vec![
+                ("From", Json::Number(from)),
+                ("To", Json::Number(from + text.len())),
+            ]
+        })
+    }
+
+    fn comment(&mut self, comment: &Comment) -> Json {
+        self.node("Comment", |e| {
+            vec![
+                ("Slash", Json::Number(e.text(comment.0))),
+                ("Text", Json::string(comment.0)),
+            ]
+        })
+    }
+
+    /// Runs `f` without moving the cursor or widening the nodes being exported.
+    fn detached(&mut self, f: impl FnOnce(&mut Self) -> Json) -> Json {
+        let (ranges, next) = (std::mem::take(&mut self.ranges), self.next);
+        let json = f(self);
+        self.ranges = ranges;
+        self.next = next;
+        json
+    }
+
+    /// A doc comment, which is not part of the range of the node it documents.
+    fn doc(&mut self, doc: &Option<CommentGroup>) -> Json {
+        match doc {
+            Some(doc) => self.detached(|e| {
+                e.node("CommentGroup", |e| {
+                    let list = doc.list.iter().map(|comment| e.comment(comment)).collect();
+                    vec![("List", Json::Array(list))]
+                })
+            }),
+            None => Json::Null,
+        }
+    }
+
+    /// All the comments of the source, in groups of comments on consecutive lines.
+    fn comments(&mut self) -> Json {
+        let mut groups: Vec<Vec<(usize, &str)>> = vec![];
+        let mut last_end = 0;
+        for &(offset, text) in &self.comments {
+            let between = &self.source[last_end..offset];
+            let adjacent = between.trim().is_empty() && between.matches('\n').count() <= 1;
+            match groups.last_mut() {
+                Some(group) if adjacent => group.push((offset, text)),
+                _ => groups.push(vec![(offset, text)]),
+            }
+            last_end = offset + text.len();
+        }
+        let groups = groups
+            .into_iter()
+            .map(|group| {
+                self.node("CommentGroup", |e| {
+                    let list = group
+                        .iter()
+                        .map(|&(offset, text)| {
+                            e.node("Comment", |e| {
+                                e.record(offset, offset + text.len());
+                                vec![
+                                    ("Slash", Json::Number(offset + 1)),
+                                    ("Text", Json::string(text)),
+                                ]
+                            })
+                        })
+                        .collect();
+                    vec![("List", Json::Array(list))]
+                })
+            })
+            .collect();
+        Json::list(groups)
+    }
+
+    fn file(&mut self, file: &SourceFile) -> Json {
+        self.node("File", |e| {
+            let doc = e.doc(&file.doc);
+            let package = e.token("package");
+            let name = match file.package {
+                "" => Json::Null,
+                name => e.ident(name),
+            };
+            let mut decls = vec![];
+            let mut imports = vec![];
+            let mut specs = file.imports.iter().peekable();
+            while specs.peek().is_some() {
+                decls.push(e.node("GenDecl", |e| {
+                    let tok_pos = e.token("import");
+                    let lparen = e.token("(");
+                    let mut group = vec![];
+                    while let Some(spec) = specs.next() {
+                        let spec = e.import_spec(spec);
+                        imports.push(spec.clone());
+                        group.push(spec);
+                        if lparen == 0 || specs.peek().is_none() || e.at(")") {
+                            break;
+                        }
+                    }
+                    let rparen = if lparen == 0 { 0 } else { e.token(")") };
+                    vec![
+                        ("Doc", Json::Null),
+                        ("TokPos", Json::Number(tok_pos)),
+                        ("Tok", Json::string("import")),
+                        ("Lparen", Json::Number(lparen)),
+                        ("Specs", Json::Array(group)),
+                        ("Rparen", Json::Number(rparen)),
+                    ]
+                }));
+            }
+            decls.extend(file.decls.iter().map(|decl| e.top_level(decl)));
+            vec![
+                ("Doc", doc),
+                ("Package", Json::Number(package)),
+                ("Name", name),
+                ("Decls", Json::list(decls)),
+                ("FileStart", Json::Number(1)),
+                ("FileEnd", Json::Number(e.source.len() + 1)),
+                ("Imports", Json::list(imports)),
+                ("Comments", e.detached(Self::comments)),
+            ]
+        })
+    }
+
+    fn import_spec(&mut self, spec: &ImportDeclaration) -> Json {
+        self.node("ImportSpec", |e| {
+            let name = spec.pkg_name_opt.map_or(Json::Null, |name| e.ident(name));
+            // The path is kept without its quotes.
+            let path = match e.offset_of(spec.path) {
+                Some(offset) if offset > 0 => {
+                    let literal = &e.source[offset - 1..offset + spec.path.len() + 1];
+                    let pos = e.text(literal);
+                    e.basic_lit(pos, "STRING", literal)
+                }
+                _ => e.basic_lit(0, "STRING", &format!("{:?}", spec.path)),
+            };
+            vec![
+                ("Doc", Json::Null),
+                ("Name", name),
+                ("Path", path),
+                ("Comment", Json::Null),
+                ("EndPos", Json::Number(0)),
+            ]
+        })
+    }
+
+    fn top_level(&mut self, decl: &TopLevel) -> Json {
+        match decl {
+            TopLevel::Function(function) => self.func_decl(function),
+            TopLevel::Declaration(decl) => self.gen_decl(decl),
+            TopLevel::BadDecl(text) => self.bad("BadDecl", text),
+            // Not in the declarations of a file.
+            TopLevel::Pkg(_) | TopLevel::Import(_) => Json::Null,
+        }
+    }
+
+    fn func_decl(&mut self, function: &Function) -> Json {
+        self.node("FuncDecl", |e| {
+            let doc = e.doc(&function.doc);
+            let func = e.token("func");
+            let recv = function
+                .receiver
+                .as_ref()
+                .map_or(Json::Null, |receiver| e.receiver(receiver));
+            let name = e.ident(function.name);
+            let type_params = e.type_params(&function.type_params);
+            let typ = e.func_type(func, type_params, &function.signature);
+            let body = function
+                .body
+                .as_ref()
+                .map_or(Json::Null, |body| e.block(body));
+            vec![
+                ("Doc", doc),
+                ("Recv", recv),
+                ("Name", name),
+                ("Type", typ),
+                ("Body", body),
+            ]
+        })
+    }
+
+    fn receiver(&mut self, receiver: &Receiver) -> Json {
+        self.node("FieldList", |e| {
+            let opening = e.token("(");
+            let field = e.node("Field", |e| {
+                let names = receiver
+                    .name
+                    .map_or(Json::Null, |name| Json::Array(vec![e.ident(name)]));
+                let typ = if receiver.pointer {
+                    e.node("StarExpr", |e| {
+                        let star = e.token("*");
+                        let x = e.receiver_type(receiver);
+                        vec![("Star", Json::Number(star)), ("X", x)]
+                    })
+                } else {
+                    e.receiver_type(receiver)
+                };
+                vec![
+                    ("Doc", Json::Null),
+                    ("Names", names),
+                    ("Type", typ),
+                    ("Tag", Json::Null),
+                    ("Comment", Json::Null),
+                ]
+            });
+            let closing = e.token(")");
+            vec![
+                ("Opening", Json::Number(opening)),
+                ("List", Json::Array(vec![field])),
+                ("Closing", Json::Number(closing)),
+            ]
+        })
+    }
+
+    fn receiver_type(&mut self, receiver: &Receiver) -> Json {
+        if receiver.type_params.is_empty() {
+            return self.ident(receiver.base_type);
+        }
+        let base_type = |e: &mut Self| e.ident(receiver.base_type);
+        let params = |e: &mut Self| {
+            let params = receiver.type_params.iter();
+            params.map(|param| e.ident(param)).collect()
+        };
+        self.index_expr(base_type, receiver.type_params.len(), params)
+    }
+
+    /// An `IndexExpr`, or an `IndexListExpr` when there are several indices.
+    fn index_expr(
+        &mut self,
+        x: impl FnOnce(&mut Self) -> Json,
+        count: usize,
+        indices: impl FnOnce(&mut Self) -> Vec<Json>,
+    ) -> Json {
+        let kind = if count == 1 {
+            "IndexExpr"
+        } else {
+            "IndexListExpr"
+        };
+        self.node(kind, |e| {
+            let x = x(e);
+            let lbrack = e.token("[");
+            let mut indices = indices(e);
+            let rbrack = e.token("]");
+            let indices = if count == 1 {
+                ("Index", indices.pop().unwrap_or(Json::Null))
+            } else {
+                ("Indices", Json::Array(indices))
+            };
+            vec![
+                ("X", x),
+                ("Lbrack", Json::Number(lbrack)),
+                indices,
+                ("Rbrack", Json::Number(rbrack)),
+            ]
+        })
+    }
+
+    fn type_params(&mut self, params: &[TypeParamDecl]) -> Json {
+        if params.is_empty() {
+            return Json::Null;
+        }
+        self.node("FieldList", |e| {
+            let opening = e.token("[");
+            let list = params
+                .iter()
+                .map(|param| {
+                    e.node("Field", |e| {
+                        let names = e.idents(&param.identifiers);
+                        let typ = e.type_elem(&param.constraint);
+                        vec![
+                            ("Doc", Json::Null),
+                            ("Names", names),
+                            ("Type", typ),
+                            ("Tag", Json::Null),
+                            ("Comment", Json::Null),
+                        ]
+                    })
+                })
+                .collect();
+            let closing = e.token("]");
+            vec![
+                ("Opening", Json::Number(opening)),
+                ("List", Json::Array(list)),
+                ("Closing", Json::Number(closing)),
+            ]
+        })
+    }
+
+    /// A `FuncType` whose `func` keyword, if any, and type parameters have already been read.
+    fn func_type(&mut self, func: usize, type_params: Json, signature: &Signature) -> Json {
+        self.node("FuncType", |e| {
+            if func != 0 {
+                e.record(func - 1, func - 1 + "func".len());
+            }
+            let params = e.params(&signature.params, true);
+            let results = e.params(&signature.results, false);
+            vec![
+                ("Func", Json::Number(func)),
+                ("TypeParams", type_params),
+                ("Params", params),
+                ("Results", results),
+            ]
+        })
+    }
+
+    /// The parameters or the results of a signature. Results without parentheses are a list
+    /// without `Opening` and `Closing`, and no results are `nil`.
+    fn params(&mut self, params: &[ParameterDecl], required: bool) -> Json {
+        if params.is_empty() && !required && !self.at("(") {
+            return Json::Null;
+        }
+        self.node("FieldList", |e| {
+            let opening = e.token("(");
+            let list = params
+                .iter()
+                .map(|param| {
+                    e.node("Field", |e| {
+                        let names = match &param.identifiers {
+                            Some(names) => e.idents(names),
+                            None => Json::Null,
+                        };
+                        let typ = if param.is_variadic {
+                            e.node("Ellipsis", |e| {
+                                let ellipsis = e.token("...");
+                                let elt = e.go_type(&param.go_type);
+                                vec![("Ellipsis", Json::Number(ellipsis)), ("Elt", elt)]
+                            })
+                        } else {
+                            e.go_type(&param.go_type)
+                        };
+                        vec![
+                            ("Doc", Json::Null),
+                            ("Names", names),
+                            ("Type", typ),
+                            ("Tag", Json::Null),
+                            ("Comment", Json::Null),
+                        ]
+                    })
+                })
+                .collect();
+            let closing = if opening == 0 { 0 } else { e.token(")") };
+            vec![
+                ("Opening", Json::Number(opening)),
+                ("List", Json::list(list)),
+                ("Closing", Json::Number(closing)),
+            ]
+        })
+    }
+
+    fn gen_decl(&mut self, decl: &Declaration) -> Json {
+        let (doc, tok) = match decl {
+            Declaration::ConstDecl(decl) => (&decl.doc, "const"),
+            Declaration::VarDecl(decl) => (&decl.doc, "var"),
+            Declaration::TypeDecl(decl) => (&decl.doc, "type"),
+        };
+        self.node("GenDecl", |e| {
+            let doc = e.doc(doc);
+            let tok_pos = e.token(tok);
+            let lparen = e.token("(");
+            let specs = match decl {
+                Declaration::ConstDecl(decl) => {
+                    let specs = decl.specs.iter();
+                    specs
+                        .map(|spec| e.value_spec(&spec.identifiers, &spec.typ, &spec.values))
+                        .collect()
+                }
+                Declaration::VarDecl(decl) => {
+                    let specs = decl.specs.iter();
+                    specs
+                        .map(|spec| e.value_spec(&spec.identifiers, &spec.typ, &spec.values))
+                        .collect()
+                }
+                Declaration::TypeDecl(decl) => {
+                    decl.specs.iter().map(|spec| e.type_spec(spec)).collect()
+                }
+            };
+            let rparen = if lparen == 0 { 0 } else { e.token(")") };
+            vec![
+                ("Doc", doc),
+                ("TokPos", Json::Number(tok_pos)),
+                ("Tok", Json::string(tok)),
+                ("Lparen", Json::Number(lparen)),
+                ("Specs", Json::list(specs)),
+                ("Rparen", Json::Number(rparen)),
+            ]
+        })
+    }
+
+    fn value_spec(&mut self, names: &[&str], typ: &Option<GoType>, values: &[Expression]) -> Json {
+        self.node("ValueSpec", |e| {
+            let names = e.idents(names);
+            let typ = typ.as_ref().map_or(Json::Null, |typ| e.go_type(typ));
+            if !values.is_empty() {
+                e.token("=");
+            }
+            let values = e.exprs(values);
+            vec![
+                ("Doc", Json::Null),
+                ("Names", names),
+                ("Type", typ),
+                ("Values", values),
+                ("Comment", Json::Null),
+            ]
+        })
+    }
+
+    fn type_spec(&mut self, spec: &TypeSpec) -> Json {
+        let (name, type_params, typ, alias) = match spec {
+            TypeSpec::AliasDecl {
+                name,
+                type_params,
+                typ,
+            } => (name, type_params, typ, true),
+            TypeSpec::TypeDef {
+                name,
+                type_params,
+                typ,
+            } => (name, type_params, typ, false),
+        };
+        self.node("TypeSpec", |e| {
+            let name = e.ident(name);
+            let type_params = e.type_params(type_params);
+            let assign = if alias { e.token("=") } else { 0 };
+            let typ = e.go_type(typ);
+            vec![
+                ("Doc", Json::Null),
+                ("Name", name),
+                ("TypeParams", type_params),
+                ("Assign", Json::Number(assign)),
+                ("Type", typ),
+                ("Comment", Json::Null),
+            ]
+        })
+    }
+
+    fn go_type(&mut self, typ: &GoType) -> Json {
+        match typ {
+            GoType::TypeName(name) => self.type_name(name),
+            GoType::Instantiation { name, type_args } => self.index_expr(
+                |e| e.type_name(name),
+                type_args.len(),
+                |e| type_args.iter().map(|arg| e.go_type(arg)).collect(),
+            ),
+            GoType::TypeLit(lit) => self.type_lit(lit),
+        }
+    }
+
+    fn type_name(&mut self, name: &TypeName) -> Json {
+        match name {
+            TypeName::Identifier(name) => self.ident(name),
+            TypeName::QualifiedIdent(ident) => self.qualified_ident(ident),
+        }
+    }
+
+    fn qualified_ident(&mut self, ident: &QualifiedIdent) -> Json {
+        self.node("SelectorExpr", |e| {
+            let x = e.ident(ident.package_name);
+            let sel = e.ident(ident.identifier);
+            vec![("X", x), ("Sel", sel)]
+        })
+    }
+
+    fn type_lit(&mut self, lit: &TypeLit) -> Json {
+        match lit {
+            TypeLit::ArrayType { len, elem } => self.node("ArrayType", |e| {
+                let lbrack = e.token("[");
+                let len = match len {
+                    Some(len) => e.expr(len),
+                    None => e.node("Ellipsis", |e| {
+                        let ellipsis = e.token("...");
+                        vec![("Ellipsis", Json::Number(ellipsis)), ("Elt", Json::Null)]
+                    }),
+                };
+                e.token("]");
+                let elt = e.go_type(elem);
+                vec![("Lbrack", Json::Number(lbrack)), ("Len", len), ("Elt", elt)]
+            }),
+            TypeLit::SliceType(elem) => self.node("ArrayType", |e| {
+                let lbrack = e.token("[");
+                e.token("]");
+                let elt = e.go_type(elem);
+                vec![
+                    ("Lbrack", Json::Number(lbrack)),
+                    ("Len", Json::Null),
+                    ("Elt", elt),
+                ]
+            }),
+            TypeLit::StructType(fields) => self.node("StructType", |e| {
+                let keyword = e.token("struct");
+                let fields = e.field_list(|e| fields.iter().map(|field| e.field(field)).collect());
+                vec![
+                    ("Struct", Json::Number(keyword)),
+                    ("Fields", fields),
+                    ("Incomplete", Json::Bool(false)),
+                ]
+            }),
+            TypeLit::PointerType(typ) => self.node("StarExpr", |e| {
+                let star = e.token("*");
+                let x = e.go_type(typ);
+                vec![("Star", Json::Number(star)), ("X", x)]
+            }),
+            TypeLit::FunctionType(signature) => {
+                let func = self.token("func");
+                self.func_type(func, Json::Null, signature)
+            }
+            TypeLit::InterfaceType(elems) => self.node("InterfaceType", |e| {
+                let keyword = e.token("interface");
+                let methods =
+                    e.field_list(|e| elems.iter().map(|elem| e.interface_elem(elem)).collect());
+                vec![
+                    ("Interface", Json::Number(keyword)),
+                    ("Methods", methods),
+                    ("Incomplete", Json::Bool(false)),
+                ]
+            }),
+            TypeLit::MapType { key, value } => self.node("MapType", |e| {
+                let map = e.token("map");
+                e.token("[");
+                let key = e.go_type(key);
+                e.token("]");
+                let value = e.go_type(value);
+                vec![("Map", Json::Number(map)), ("Key", key), ("Value", value)]
+            }),
+            TypeLit::ChannelType { dir, elem } => self.node("ChanType", |e| {
+                let (begin, arrow, dir) = match dir {
+                    ChanDir::Both => (e.token("chan"), 0, 3),
+                    ChanDir::Send => (e.token("chan"), e.token("<-"), 1),
+                    ChanDir::Recv => {
+                        let arrow = e.token("<-");
+                        e.token("chan");
+                        (arrow, arrow, 2)
+                    }
+                };
+                let value = e.go_type(elem);
+                vec![
+                    ("Begin", Json::Number(begin)),
+                    ("Arrow", Json::Number(arrow)),
+                    ("Dir", Json::Number(dir)),
+                    ("Value", value),
+                ]
+            }),
+        }
+    }
+
+    /// The fields of a struct or the methods of an interface, in braces.
+    fn field_list(&mut self, list: impl FnOnce(&mut Self) -> Vec<Json>) -> Json {
+        self.node("FieldList", |e| {
+            let opening = e.token("{");
+            let list = list(e);
+            let closing = e.token("}");
+            vec![
+                ("Opening", Json::Number(opening)),
+                ("List", Json::list(list)),
+                ("Closing", Json::Number(closing)),
+            ]
+        })
+    }
+
+    fn field(&mut self, field: &FieldDecl) -> Json {
+        self.node("Field", |e| {
+            let names = e.idents(&field.identifiers);
+            let typ = e.go_type(&field.go_type);
+            let tag = match field.tag {
+                Some(tag) => {
+                    let pos = e.text(tag);
+                    e.basic_lit(pos, "STRING", tag)
+                }
+                None => Json::Null,
+            };
+            vec![
+                ("Doc", Json::Null),
+                ("Names", names),
+                ("Type", typ),
+                ("Tag", tag),
+                ("Comment", Json::Null),
+            ]
+        })
+    }
+
+    fn interface_elem(&mut self, elem: &InterfaceElem) -> Json {
+        self.node("Field", |e| {
+            let (names, typ) = match elem {
+                InterfaceElem::Method { name, signature } => {
+                    let name = e.ident(name);
+                    let typ = e.func_type(0, Json::Null, signature);
+                    (Json::Array(vec![name]), typ)
+                }
+                InterfaceElem::Type(elem) => (Json::Null, e.type_elem(elem)),
+            };
+            vec![
+                ("Doc", Json::Null),
+                ("Names", names),
+                ("Type", typ),
+                ("Tag", Json::Null),
+                ("Comment", Json::Null),
+            ]
+        })
+    }
+
+    /// The terms of a union are the operands of `|` binary expressions, and `~T` is a unary one.
+    fn type_elem(&mut self, elem: &TypeElem) -> Json {
+        self.union(&elem.0)
+    }
+
+    fn union(&mut self, terms: &[TypeTerm]) -> Json {
+        let (last, init) = match terms.split_last() {
+            Some(split) => split,
+            None => return Json::Null,
+        };
+        if init.is_empty() {
+            return self.type_term(last);
+        }
+        self.node("BinaryExpr", |e| {
+            let x = e.union(init);
+            let op_pos = e.token("|");
+            let y = e.type_term(last);
+            vec![
+                ("X", x),
+                ("OpPos", Json::Number(op_pos)),
+                ("Op", Json::string("|")),
+                ("Y", y),
+            ]
+        })
+    }
+
+    fn type_term(&mut self, term: &TypeTerm) -> Json {
+        if !term.tilde {
+            return self.go_type(&term.go_type);
+        }
+        self.node("UnaryExpr", |e| {
+            let op_pos = e.token("~");
+            let x = e.go_type(&term.go_type);
+            vec![
+                ("OpPos", Json::Number(op_pos)),
+                ("Op", Json::string("~")),
+                ("X", x),
+            ]
+        })
+    }
+
+    fn exprs(&mut self, exprs: &[Expression]) -> Json {
+        Json::list(exprs.iter().map(|expr| self.expr(expr)).collect())
+    }
+
+    fn expr(&mut self, expr: &Expression) -> Json {
+        match expr {
+            Expression::UnaryExpr(expr) => self.unary_expr(expr),
+            Expression::BinExpr { left, op, right } => self.node("BinaryExpr", |e| {
+                let x = e.expr(left);
+                let op_pos = e.text(op);
+                let y = e.expr(right);
+                vec![
+                    ("X", x),
+                    ("OpPos", Json::Number(op_pos)),
+                    ("Op", Json::string(op)),
+                    ("Y", y),
+                ]
+            }),
+            Expression::BadExpr(text) => self.bad("BadExpr", text),
+        }
+    }
+
+    fn unary_expr(&mut self, expr: &UnaryExpr) -> Json {
+        match expr {
+            UnaryExpr::PrimaryExpr(expr) => self.primary_expr(expr),
+            UnaryExpr::Unary(unary) if unary.op == "*" => self.node("StarExpr", |e| {
+                let star = e.text(unary.op);
+                let x = e.unary_expr(&unary.expr);
+                vec![("Star", Json::Number(star)), ("X", x)]
+            }),
+            UnaryExpr::Unary(unary) => self.node("UnaryExpr", |e| {
+                let op_pos = e.text(unary.op);
+                let x = e.unary_expr(&unary.expr);
+                vec![
+                    ("OpPos", Json::Number(op_pos)),
+                    ("Op", Json::string(unary.op)),
+                    ("X", x),
+                ]
+            }),
+        }
+    }
+
+    fn primary_expr(&mut self, expr: &PrimaryExpr) -> Json {
+        match expr {
+            PrimaryExpr::Operand(operand) => self.operand(operand),
+            PrimaryExpr::Selector { expr, selector } => self.node("SelectorExpr", |e| {
+                let x = e.primary_expr(expr);
+                e.token(".");
+                let sel = e.ident(selector);
+                vec![("X", x), ("Sel", sel)]
+            }),
+            PrimaryExpr::Index { expr, index } => {
+                self.index_expr(|e| e.primary_expr(expr), 1, |e| vec![e.expr(index)])
+            }
+            PrimaryExpr::Slice {
+                expr,
+                low,
+                high,
+                max,
+            } => self.node("SliceExpr", |e| {
+                let x = e.primary_expr(expr);
+                let lbrack = e.token("[");
+                let low = low.as_ref().map_or(Json::Null, |low| e.expr(low));
+                e.token(":");
+                let high = high.as_ref().map_or(Json::Null, |high| e.expr(high));
+                let slice3 = max.is_some();
+                if slice3 {
+                    e.token(":");
+                }
+                let max = max.as_ref().map_or(Json::Null, |max| e.expr(max));
+                let rbrack = e.token("]");
+                vec![
+                    ("X", x),
+                    ("Lbrack", Json::Number(lbrack)),
+                    ("Low", low),
+                    ("High", high),
+                    ("Max", max),
+                    ("Slice3", Json::Bool(slice3)),
+                    ("Rbrack", Json::Number(rbrack)),
+                ]
+            }),
+            PrimaryExpr::TypeAssertion { expr, typ } => {
+                self.type_assert_expr(expr, |e| e.go_type(typ))
+            }
+            PrimaryExpr::Instantiation { expr, type_args } => self.index_expr(
+                |e| e.primary_expr(expr),
+                type_args.len(),
+                |e| type_args.iter().map(|arg| e.go_type(arg)).collect(),
+            ),
+            PrimaryExpr::Call {
+                func,
+                args,
+                ellipsis,
+            } => self.node("CallExpr", |e| {
+                let fun = e.primary_expr(func);
+                let lparen = e.token("(");
+                let args = e.exprs(args);
+                let ellipsis = if *ellipsis { e.token("...") } else { 0 };
+                let rparen = e.token(")");
+                vec![
+                    ("Fun", fun),
+                    ("Lparen", Json::Number(lparen)),
+                    ("Args", args),
+                    ("Ellipsis", Json::Number(ellipsis)),
+                    ("Rparen", Json::Number(rparen)),
+                ]
+            }),
+        }
+    }
+
+    /// `x.(T)`, or `x.(type)` in a type switch, where `typ` reads the `type` keyword.
+    fn type_assert_expr(
+        &mut self,
+        expr: &PrimaryExpr,
+        typ: impl FnOnce(&mut Self) -> Json,
+    ) -> Json {
+        self.node("TypeAssertExpr", |e| {
+            let x = e.primary_expr(expr);
+            e.token(".");
+            let lparen = e.token("(");
+            let typ = typ(e);
+            let rparen = e.token(")");
+            vec![
+                ("X", x),
+                ("Lparen", Json::Number(lparen)),
+                ("Type", typ),
+                ("Rparen", Json::Number(rparen)),
+            ]
+        })
+    }
+
+    fn operand(&mut self, operand: &Operand) -> Json {
+        match operand {
+            Operand::Literal(literal) => self.literal(literal),
+            Operand::OperandName(OperandName::Identifier(name)) => self.ident(name),
+            Operand::OperandName(OperandName::QualifiedIdent(ident)) => self.qualified_ident(ident),
+            Operand::Parenthesized(expr) => self.node("ParenExpr", |e| {
+                let lparen = e.token("(");
+                let x = e.expr(expr);
+                let rparen = e.token(")");
+                vec![
+                    ("Lparen", Json::Number(lparen)),
+                    ("X", x),
+                    ("Rparen", Json::Number(rparen)),
+                ]
+            }),
+            Operand::FunctionLit(lit) => self.node("FuncLit", |e| {
+                let func = e.token("func");
+                let typ = e.func_type(func, Json::Null, &lit.signature);
+                let body = e.block(&lit.body);
+                vec![("Type", typ), ("Body", body)]
+            }),
+            Operand::TypeLit(lit) => self.type_lit(lit),
+            Operand::CompositeLit { typ, value } => self.composite_lit(Some(typ), value),
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) -> Json {
+        match literal {
+            Literal::IntLit(
+                IntLit::DecimalLit(lit)
+                | IntLit::BinaryLit(lit)
+                | IntLit::OctalLit(lit)
+                | IntLit::HexLit(lit),
+            ) => {
+                let pos = self.text(lit);
+                self.basic_lit(pos, "INT", lit)
+            }
+            Literal::FloatLit(lit) => {
+                let pos = self.text(lit);
+                self.basic_lit(pos, "FLOAT", lit)
+            }
+            Literal::ImaginaryLit(lit) => {
+                let pos = self.text(lit);
+                self.basic_lit(pos, "IMAG", lit)
+            }
+            Literal::RuneLit(lit) => {
+                let pos = self.text(lit);
+                self.basic_lit(pos, "CHAR", lit)
+            }
+            Literal::StringLit(lit) => {
+                let pos = self.text(lit);
+                self.basic_lit(pos, "STRING", lit)
+            }
+        }
+    }
+
+    /// A composite literal, whose type is left out for the elements of another one.
+    fn composite_lit(&mut self, typ: Option<&GoType>, value: &LiteralValue) -> Json {
+        self.node("CompositeLit", |e| {
+            let typ = typ.map_or(Json::Null, |typ| e.go_type(typ));
+            let lbrace = e.token("{");
+            let elts = value.0.iter().map(|elt| e.keyed_element(elt)).collect();
+            let rbrace = e.token("}");
+            vec![
+                ("Type", typ),
+                ("Lbrace", Json::Number(lbrace)),
+                ("Elts", Json::list(elts)),
+                ("Rbrace", Json::Number(rbrace)),
+                ("Incomplete", Json::Bool(false)),
+            ]
+        })
+    }
+
+    fn keyed_element(&mut self, element: &KeyedElement) -> Json {
+        let key = match &element.key {
+            Some(key) => key,
+            None => return self.element(&element.value),
+        };
+        self.node("KeyValueExpr", |e| {
+            let key = e.element(key);
+            let colon = e.token(":");
+            let value = e.element(&element.value);
+            vec![
+                ("Key", key),
+                ("Colon", Json::Number(colon)),
+                ("Value", value),
+            ]
+        })
+    }
+
+    fn element(&mut self, element: &Element) -> Json {
+        match element {
+            Element::Expression(expr) => self.expr(expr),
+            Element::LiteralValue(value) => self.composite_lit(None, value),
+        }
+    }
+
+    fn block(&mut self, block: &Block) -> Json {
+        self.node("BlockStmt", |e| {
+            let lbrace = e.token("{");
+            let list = e.stmts(&block.0);
+            let rbrace = e.token("}");
+            vec![
+                ("Lbrace", Json::Number(lbrace)),
+                ("List", list),
+                ("Rbrace", Json::Number(rbrace)),
+            ]
+        })
+    }
+
+    fn stmts(&mut self, stmts: &[Statement]) -> Json {
+        Json::list(stmts.iter().map(|stmt| self.stmt(stmt)).collect())
+    }
+
+    fn stmt(&mut self, stmt: &Statement) -> Json {
+        match stmt {
+            Statement::DeclStmt(decl) => {
+                self.node("DeclStmt", |e| vec![("Decl", e.gen_decl(decl))])
+            }
+            Statement::SimpleStmt(stmt) => self.simple_stmt(stmt),
+            Statement::Block(block) => self.block(block),
+            Statement::IfStmt(stmt) => self.if_stmt(stmt),
+            Statement::SwitchStmt(stmt) => self.node("SwitchStmt", |e| {
+                let switch = e.token("switch");
+                let init = e.opt_simple_stmt(&stmt.init);
+                let tag = stmt.tag.as_ref().map_or(Json::Null, |tag| e.expr(tag));
+                let exprs = |e: &mut Self, exprs: &Vec<Expression>| e.exprs(exprs);
+                let body = e.clauses(&stmt.clauses, "CaseClause", "List", exprs);
+                vec![
+                    ("Switch", Json::Number(switch)),
+                    ("Init", init),
+                    ("Tag", tag),
+                    ("Body", body),
+                ]
+            }),
+            Statement::TypeSwitchStmt(stmt) => self.node("TypeSwitchStmt", |e| {
+                let switch = e.token("switch");
+                let init = e.opt_simple_stmt(&stmt.init);
+                let guard = |e: &mut Self| {
+                    e.type_assert_expr(&stmt.expr, |e| {
+                        e.token("type");
+                        Json::Null
+                    })
+                };
+                let assign = match stmt.binding {
+                    Some(name) => e.node("AssignStmt", |e| {
+                        let lhs = e.ident(name);
+                        let tok_pos = e.token(":=");
+                        let rhs = guard(e);
+                        vec![
+                            ("Lhs", Json::Array(vec![lhs])),
+                            ("TokPos", Json::Number(tok_pos)),
+                            ("Tok", Json::string(":=")),
+                            ("Rhs", Json::Array(vec![rhs])),
+                        ]
+                    }),
+                    None => e.node("ExprStmt", |e| vec![("X", guard(e))]),
+                };
+                let types = |e: &mut Self, types: &Vec<GoType>| {
+                    Json::list(types.iter().map(|typ| e.go_type(typ)).collect())
+                };
+                let body = e.clauses(&stmt.clauses, "CaseClause", "List", types);
+                vec![
+                    ("Switch", Json::Number(switch)),
+                    ("Init", init),
+                    ("Assign", assign),
+                    ("Body", body),
+                ]
+            }),
+            Statement::SelectStmt(stmt) => self.node("SelectStmt", |e| {
+                let select = e.token("select");
+                let body = e.clauses(&stmt.clauses, "CommClause", "Comm", Self::simple_stmt);
+                vec![("Select", Json::Number(select)), ("Body", body)]
+            }),
+            Statement::ForStmt(stmt) => self.for_stmt(stmt),
+            Statement::GoStmt(call) => self.node("GoStmt", |e| {
+                let go = e.token("go");
+                let call = e.primary_expr(call);
+                vec![("Go", Json::Number(go)), ("Call", call)]
+            }),
+            Statement::DeferStmt(call) => self.node("DeferStmt", |e| {
+                let defer = e.token("defer");
+                let call = e.primary_expr(call);
+                vec![("Defer", Json::Number(defer)), ("Call", call)]
+            }),
+            Statement::ReturnStmt(results) => self.node("ReturnStmt", |e| {
+                let keyword = e.token("return");
+                let results = e.exprs(results);
+                vec![("Return", Json::Number(keyword)), ("Results", results)]
+            }),
+            Statement::BreakStmt(label) => self.branch_stmt("break", *label),
+            Statement::ContinueStmt(label) => self.branch_stmt("continue", *label),
+            Statement::GotoStmt(label) => self.branch_stmt("goto", Some(label)),
+            Statement::FallthroughStmt => self.branch_stmt("fallthrough", None),
+            Statement::LabeledStmt { label, stmt } => self.node("LabeledStmt", |e| {
+                let label = e.ident(label);
+                let colon = e.token(":");
+                let stmt = e.stmt(stmt);
+                vec![
+                    ("Label", label),
+                    ("Colon", Json::Number(colon)),
+                    ("Stmt", stmt),
+                ]
+            }),
+            Statement::BadStmt(text) => self.bad("BadStmt", text),
+        }
+    }
+
+    fn branch_stmt(&mut self, tok: &str, label: Option<&str>) -> Json {
+        self.node("BranchStmt", |e| {
+            let tok_pos = e.token(tok);
+            let label = label.map_or(Json::Null, |label| e.ident(label));
+            vec![
+                ("TokPos", Json::Number(tok_pos)),
+                ("Tok", Json::string(tok)),
+                ("Label", label),
+            ]
+        })
+    }
+
+    /// The body of a switch or select statement, with a `kind` clause for each of `clauses`,
+    /// whose case is the field `field`.
+    fn clauses<C>(
+        &mut self,
+        clauses: &[CaseClause<C>],
+        kind: &'static str,
+        field: &'static str,
+        case: impl Fn(&mut Self, &C) -> Json,
+    ) -> Json {
+        self.node("BlockStmt", |e| {
+            let lbrace = e.token("{");
+            let list = clauses
+                .iter()
+                .map(|clause| {
+                    e.node(kind, |e| {
+                        let (pos, case) = match &clause.case {
+                            Some(c) => (e.token("case"), case(e, c)),
+                            None => (e.token("default"), Json::Null),
+                        };
+                        let colon = e.token(":");
+                        let body = e.stmts(&clause.body);
+                        vec![
+                            ("Case", Json::Number(pos)),
+                            (field, case),
+                            ("Colon", Json::Number(colon)),
+                            ("Body", body),
+                        ]
+                    })
+                })
+                .collect();
+            let rbrace = e.token("}");
+            vec![
+                ("Lbrace", Json::Number(lbrace)),
+                ("List", Json::list(list)),
+                ("Rbrace", Json::Number(rbrace)),
+            ]
+        })
+    }
+
+    fn opt_simple_stmt(&mut self, stmt: &Option<SimpleStmt>) -> Json {
+        stmt.as_ref()
+            .map_or(Json::Null, |stmt| self.simple_stmt(stmt))
+    }
+
+    fn simple_stmt(&mut self, stmt: &SimpleStmt) -> Json {
+        match stmt {
+            SimpleStmt::EmptyStmt => self.node("EmptyStmt", |_| {
+                vec![
+                    ("Semicolon", Json::Number(0)),
+                    ("Implicit", Json::Bool(true)),
+                ]
+            }),
+            SimpleStmt::ExpressionStmt(expr) => {
+                self.node("ExprStmt", |e| vec![("X", e.expr(expr))])
+            }
+            SimpleStmt::SendStmt { channel, value } => self.node("SendStmt", |e| {
+                let channel = e.expr(channel);
+                let arrow = e.token("<-");
+                let value = e.expr(value);
+                vec![
+                    ("Chan", channel),
+                    ("Arrow", Json::Number(arrow)),
+                    ("Value", value),
+                ]
+            }),
+            SimpleStmt::IncDecStmt { expr, op } => self.node("IncDecStmt", |e| {
+                let x = e.expr(expr);
+                let tok_pos = e.text(op);
+                vec![
+                    ("X", x),
+                    ("TokPos", Json::Number(tok_pos)),
+                    ("Tok", Json::string(op)),
+                ]
+            }),
+            SimpleStmt::Assignment { lhs, op, rhs } => {
+                self.assign_stmt(|e| e.exprs(lhs), |e| e.text(op), op, rhs)
+            }
+            SimpleStmt::ShortVarDecl {
+                identifiers,
+                values,
+            } => self.assign_stmt(|e| e.idents(identifiers), |e| e.token(":="), ":=", values),
+        }
+    }
+
+    fn assign_stmt(
+        &mut self,
+        lhs: impl FnOnce(&mut Self) -> Json,
+        tok_pos: impl FnOnce(&mut Self) -> usize,
+        tok: &str,
+        rhs: &[Expression],
+    ) -> Json {
+        self.node("AssignStmt", |e| {
+            let lhs = lhs(e);
+            let tok_pos = tok_pos(e);
+            let rhs = e.exprs(rhs);
+            vec![
+                ("Lhs", lhs),
+                ("TokPos", Json::Number(tok_pos)),
+                ("Tok", Json::string(tok)),
+                ("Rhs", rhs),
+            ]
+        })
+    }
+
+    fn if_stmt(&mut self, stmt: &IfStmt) -> Json {
+        self.node("IfStmt", |e| {
+            let keyword = e.token("if");
+            let init = e.opt_simple_stmt(&stmt.init);
+            let cond = e.expr(&stmt.cond);
+            let body = e.block(&stmt.then);
+            let otherwise = match &stmt.otherwise {
+                Some(otherwise) => {
+                    e.token("else");
+                    match otherwise {
+                        Else::If(stmt) => e.if_stmt(stmt),
+                        Else::Block(block) => e.block(block),
+                    }
+                }
+                None => Json::Null,
+            };
+            vec![
+                ("If", Json::Number(keyword)),
+                ("Init", init),
+                ("Cond", cond),
+                ("Body", body),
+                ("Else", otherwise),
+            ]
+        })
+    }
+
+    fn for_stmt(&mut self, stmt: &ForStmt) -> Json {
+        let (vars, expr) = match &stmt.clause {
+            Some(ForClause::RangeClause { vars, expr }) => (vars, expr),
+            clause => {
+                return self.node("ForStmt", |e| {
+                    let keyword = e.token("for");
+                    let (init, cond, post) = match clause {
+                        Some(ForClause::Condition(cond)) => (Json::Null, e.expr(cond), Json::Null),
+                        Some(ForClause::ForClause { init, cond, post }) => {
+                            let init = e.opt_simple_stmt(init);
+                            let cond = cond.as_ref().map_or(Json::Null, |cond| e.expr(cond));
+                            (init, cond, e.opt_simple_stmt(post))
+                        }
+                        _ => (Json::Null, Json::Null, Json::Null),
+                    };
+                    let body = e.block(&stmt.body);
+                    vec![
+                        ("For", Json::Number(keyword)),
+                        ("Init", init),
+                        ("Cond", cond),
+                        ("Post", post),
+                        ("Body", body),
+                    ]
+                })
+            }
+        };
+        self.node("RangeStmt", |e| {
+            let keyword = e.token("for");
+            let (key, value, tok) = match vars {
+                Some(RangeVars::Declared(names)) => {
+                    let mut names = names.iter().map(|name| e.ident(name));
+                    (names.next(), names.next(), ":=")
+                }
+                Some(RangeVars::Assigned(exprs)) => {
+                    let mut exprs = exprs.iter().map(|expr| e.expr(expr));
+                    (exprs.next(), exprs.next(), "=")
+                }
+                None => (None, None, "ILLEGAL"),
+            };
+            let tok_pos = e.token(tok);
+            let range = e.token("range");
+            let x = e.expr(expr);
+            let body = e.block(&stmt.body);
+            vec![
+                ("For", Json::Number(keyword)),
+                ("Key", key.unwrap_or(Json::Null)),
+                ("Value", value.unwrap_or(Json::Null)),
+                ("TokPos", Json::Number(tok_pos)),
+                ("Tok", Json::string(tok)),
+                ("Range", Json::Number(range)),
+                ("X", x),
+                ("Body", body),
+            ]
+        })
+    }
+}
+
+#[test]
+fn test_positions_point_at_their_tokens() {
+    use crate::recover::parse_source_file;
+    use serde_json::Value;
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/nodes.go");
+    let src = std::fs::read_to_string(path).unwrap();
+    let (file, errors) = parse_source_file(&src);
+    assert!(errors.is_empty());
+    let json: Value = serde_json::from_str(&to_json(&file, &src)).unwrap();
+
+    // The tokens at fixed positions, where `None` is the text of another field of the node.
+    let tokens = [
+        ("Package", Some("package")),
+        ("Func", Some("func")),
+        ("Struct", Some("struct")),
+        ("Interface", Some("interface")),
+        ("Map", Some("map")),
+        ("If", Some("if")),
+        ("For", Some("for")),
+        ("Range", Some("range")),
+        ("Switch", Some("switch")),
+        ("Select", Some("select")),
+        ("Go", Some("go")),
+        ("Defer", Some("defer")),
+        ("Return", Some("return")),
+        ("Lbrace", Some("{")),
+        ("Rbrace", Some("}")),
+        ("Lparen", Some("(")),
+        ("Rparen", Some(")")),
+        ("Lbrack", Some("[")),
+        ("Rbrack", Some("]")),
+        ("Colon", Some(":")),
+        ("Star", Some("*")),
+        ("Arrow", Some("<-")),
+        ("Ellipsis", Some("...")),
+        ("Assign", Some("=")),
+        ("Slash", None),
+        ("NamePos", None),
+        ("ValuePos", None),
+        ("OpPos", None),
+        ("TokPos", None),
+    ];
+    fn check(node: &Value, src: &str, tokens: &[(&str, Option<&str>)], checked: &mut usize) {
+        match node {
+            Value::Object(object) => {
+                if let (Some(pos), Some(end)) = (object.get("Pos"), object.get("End")) {
+                    let (pos, end) = (pos.as_u64().unwrap(), end.as_u64().unwrap());
+                    assert!(0 < pos && pos < end, "{}", node);
+                }
+                for &(field, text) in tokens {
+                    let pos = match object.get(field).and_then(Value::as_u64) {
+                        Some(pos) if pos > 0 => pos as usize - 1,
+                        _ => continue,
+                    };
+                    let text = text.unwrap_or_else(|| {
+                        let fields = ["Text", "Name", "Value", "Op", "Tok"];
+                        fields
+                            .iter()
+                            .find_map(|name| object.get(*name)?.as_str())
+                            .unwrap()
+                    });
+                    assert!(src[pos..].starts_with(text), "{} in {}", field, node);
+                    *checked += 1;
+                }
+                object.values().for_each(|v| check(v, src, tokens, checked));
+            }
+            Value::Array(array) => array.iter().for_each(|v| check(v, src, tokens, checked)),
+            _ => {}
+        }
+    }
+    let mut checked = 0;
+    check(&json, &src, &tokens, &mut checked);
+    assert!(checked > 200, "{}", checked);
+
+    assert_eq!(json["Name"]["Name"], "m");
+    assert_eq!(json["Imports"].as_array().unwrap().len(), 3);
+    assert_eq!(json["Comments"].as_array().unwrap().len(), 2);
+    let decls = json["Decls"].as_array().unwrap();
+    let kinds: Vec<_> = decls.iter().map(|decl| decl["NodeType"].as_str()).collect();
+    let gen_decl = Some("GenDecl");
+    assert_eq!(
+        kinds,
+        [
+            gen_decl,
+            gen_decl,
+            gen_decl,
+            gen_decl,
+            gen_decl,
+            gen_decl,
+            Some("FuncDecl")
+        ]
+    );
+    let func = &decls[6];
+    assert_eq!(func["Recv"]["List"][0]["Type"]["NodeType"], "StarExpr");
+    assert_eq!(
+        func["Type"]["Params"]["List"][0]["Type"]["NodeType"],
+        "Ellipsis"
+    );
+    assert_eq!(
+        func["Body"]["End"].as_u64(),
+        Some(src.trim_end().len() as u64 + 1)
+    );
+}
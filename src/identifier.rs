@@ -2,12 +2,16 @@ use nom::IResult;
 
 use crate::{
     astable::ASTable,
+    error::ParseError,
     parse_util::{identifier, symbol},
 };
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QualifiedIdent<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     pub package_name: &'a str,
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     pub identifier: &'a str,
 }
 
@@ -18,7 +22,7 @@ impl<'a> ASTable<'a> for QualifiedIdent<'a> {
     /// use go_parser_rs::identifier::QualifiedIdent;
     /// assert_eq!(QualifiedIdent::parse("x.y"), Ok(("", QualifiedIdent{ package_name: "x", identifier: "y" })));
     /// ```
-    fn parse(s: &'a str) -> IResult<&'a str, QualifiedIdent<'a>> {
+    fn parse(s: &'a str) -> IResult<&'a str, QualifiedIdent<'a>, ParseError<'a>> {
         let (s, package_name) = identifier(s)?;
         let (s, _) = symbol(".")(s)?;
         let (s, identifier) = identifier(s)?;
@@ -1,24 +1,50 @@
 pub mod astable;
+pub mod comment;
+pub mod constant;
+pub mod cst;
+pub mod declaration;
+pub mod diagnostic;
+pub mod error;
 pub mod expression;
+pub mod goast;
 pub mod identifier;
 pub mod literals;
+pub mod methods;
+pub mod owned;
+pub mod package;
 mod parse_util;
+pub mod printer;
+pub mod recover;
+pub mod resolve;
+#[cfg(feature = "serde")]
+pub mod schema;
+pub mod statement;
 pub mod tokenize;
 pub mod typ;
+pub mod types;
+pub mod visit;
+pub mod visit_mut;
 
 use nom::{
-    bytes::streaming::take_while,
-    character::complete::space0,
-    combinator::opt,
+    branch::alt,
+    combinator::{cut, eof, map, opt},
     multi::many0,
     sequence::tuple,
-    sequence::{delimited, preceded},
+    sequence::{pair, preceded, terminated},
     IResult,
 };
 
-use parse_util::{identifier, reserved, symbol};
-
-#[derive(Debug, PartialEq)]
+use astable::ASTable;
+use comment::{parse_doc, CommentGroup};
+use declaration::Declaration;
+use error::{context, ParseError};
+use literals::string::string_lit;
+use parse_util::{identifier, list_of, reserved, symbol, terminator};
+use statement::Block;
+use typ::{parse_parameters, parse_result, parse_type_parameters, Signature, TypeParamDecl};
+
+/// A predeclared type, see [`types`] for all the others.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum GoType {
     Int,
     Uint8,
@@ -33,14 +59,25 @@ pub enum GoType {
     Float64,
     Complex64,
     Complex128,
+    /// An alias of `uint8`.
     Byte,
+    /// An alias of `int32`.
     Rune,
     String,
+    Bool,
+    Uint,
+    Uintptr,
 }
 
-impl From<&str> for GoType {
-    fn from(s: &str) -> Self {
-        match s {
+impl GoType {
+    /// The predeclared type named `name`, if any.
+    /// ```
+    /// use go_parser_rs::GoType;
+    /// assert_eq!(GoType::from_name("uintptr"), Some(GoType::Uintptr));
+    /// assert_eq!(GoType::from_name("error"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        let typ = match name {
             "int" => GoType::Int,
             "uint8" => GoType::Uint8,
             "uint16" => GoType::Uint16,
@@ -57,28 +94,140 @@ impl From<&str> for GoType {
             "byte" => GoType::Byte,
             "rune" => GoType::Rune,
             "string" => GoType::String,
-            _ => unreachable!("{}", s),
+            "bool" => GoType::Bool,
+            "uint" => GoType::Uint,
+            "uintptr" => GoType::Uintptr,
+            _ => return None,
+        };
+        Some(typ)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GoType::Int => "int",
+            GoType::Uint8 => "uint8",
+            GoType::Uint16 => "uint16",
+            GoType::Uint32 => "uint32",
+            GoType::Uint64 => "uint64",
+            GoType::Int8 => "int8",
+            GoType::Int16 => "int16",
+            GoType::Int32 => "int32",
+            GoType::Int64 => "int64",
+            GoType::Float32 => "float32",
+            GoType::Float64 => "float64",
+            GoType::Complex64 => "complex64",
+            GoType::Complex128 => "complex128",
+            GoType::Byte => "byte",
+            GoType::Rune => "rune",
+            GoType::String => "string",
+            GoType::Bool => "bool",
+            GoType::Uint => "uint",
+            GoType::Uintptr => "uintptr",
+        }
+    }
+
+    /// The type an alias stands for, `byte` and `rune` being the same types as `uint8` and `int32`.
+    pub fn unaliased(self) -> Self {
+        match self {
+            GoType::Byte => GoType::Uint8,
+            GoType::Rune => GoType::Int32,
+            typ => typ,
         }
     }
+
+    pub fn is_integer(self) -> bool {
+        self.is_unsigned()
+            || matches!(
+                self,
+                GoType::Int
+                    | GoType::Int8
+                    | GoType::Int16
+                    | GoType::Int32
+                    | GoType::Int64
+                    | GoType::Rune
+            )
+    }
+
+    pub fn is_unsigned(self) -> bool {
+        matches!(
+            self,
+            GoType::Uint
+                | GoType::Uint8
+                | GoType::Uint16
+                | GoType::Uint32
+                | GoType::Uint64
+                | GoType::Uintptr
+                | GoType::Byte
+        )
+    }
+
+    pub fn is_float(self) -> bool {
+        matches!(self, GoType::Float32 | GoType::Float64)
+    }
+
+    pub fn is_complex(self) -> bool {
+        matches!(self, GoType::Complex64 | GoType::Complex128)
+    }
+
+    pub fn is_numeric(self) -> bool {
+        self.is_integer() || self.is_float() || self.is_complex()
+    }
+}
+
+impl From<&str> for GoType {
+    fn from(s: &str) -> Self {
+        Self::from_name(s).unwrap_or_else(|| unreachable!("{}", s))
+    }
 }
 
+/// A function, or a method when it has a receiver.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function<'a> {
+    pub doc: Option<CommentGroup<'a>>,
+    pub receiver: Option<Receiver<'a>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     pub name: &'a str,
-    pub params: Parameters<'a>,
-    pub ret: GoType,
+    pub type_params: Vec<TypeParamDecl<'a>>,
+    pub signature: Signature<'a>,
+    /// `None` for a function implemented outside Go, e.g. in assembly.
+    pub body: Option<Block<'a>>,
 }
 
+/// The receiver of a method, e.g. `(l *List[T])`.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receiver<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub name: Option<&'a str>,
+    pub pointer: bool,
+    /// The name of the type the method belongs to.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub base_type: &'a str,
+    /// The names given to the type parameters of a generic base type.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub type_params: Vec<&'a str>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TopLevel<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     Pkg(&'a str),
     Import(Vec<ImportDeclaration<'a>>),
     Function(Function<'a>),
+    Declaration(Declaration<'a>),
+    /// The source text of a declaration that failed to parse, see [`recover`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    BadDecl(&'a str),
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImportDeclaration<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     pkg_name_opt: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     path: &'a str,
 }
 
@@ -86,103 +235,236 @@ impl<'a> ImportDeclaration<'a> {
     fn new(pkg_name_opt: Option<&'a str>, path: &'a str) -> Self {
         Self { pkg_name_opt, path }
     }
+
+    /// The import path, without its quotes.
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// Whether the exported names of the package are declared in the file scope, as with
+    /// `import . "fmt"`.
+    pub fn is_dot(&self) -> bool {
+        self.pkg_name_opt == Some(".")
+    }
+
+    /// The name the package is imported as: the explicit one, like `str` in
+    /// `import str "strings"` or `.` for a dot import, or else the last element of the path.
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, SourceFile};
+    /// let src = "package m\nimport (\n\t\"net/http\"\n\tstr \"strings\"\n\t. \"fmt\"\n)\n";
+    /// let (_, file) = SourceFile::parse(src).unwrap();
+    /// let names: Vec<_> = file.imports.iter().map(|import| import.name()).collect();
+    /// assert_eq!(names, vec!["http", "str", "."]);
+    /// ```
+    pub fn name(&self) -> &'a str {
+        self.pkg_name_opt
+            .unwrap_or_else(|| self.path.rsplit('/').next().unwrap_or(self.path))
+    }
+}
+
+/// SourceFile = PackageClause ";" { ImportDecl ";" } { TopLevelDecl ";" } .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceFile<'a> {
+    /// The comment group right before the package clause.
+    pub doc: Option<CommentGroup<'a>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub package: &'a str,
+    pub imports: Vec<ImportDeclaration<'a>>,
+    pub decls: Vec<TopLevel<'a>>,
+}
+
+impl<'a> ASTable<'a> for SourceFile<'a> {
+    /// Parses a whole file, so the input must be consumed completely.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::SourceFile;
+    /// let (_, file) = SourceFile::parse("// Package m does math.\npackage m\n\nimport \"fmt\"\n").unwrap();
+    /// assert_eq!(file.package, "m");
+    /// assert_eq!(file.doc.unwrap().text(), "Package m does math.\n");
+    /// assert_eq!(file.imports.len(), 1);
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, doc) = parse_doc(s)?;
+        let package_clause = context(|| "in package clause".to_string(), package_clause);
+        let (s, package) = terminated(package_clause, terminator)(s)?;
+        let import_decl = preceded(parse_doc, parse_import_decl);
+        let (mut s, import_decls) = many0(terminated(import_decl, terminator))(s)?;
+        // Not `many0`, which would throw away the error of a broken declaration.
+        let mut decls = vec![];
+        while !parse_doc(s)?.0.is_empty() {
+            let (rest, decl) = terminated(parse_top_level_decl, terminator)(s)?;
+            decls.push(decl);
+            s = rest;
+        }
+        let (s, _) = parse_doc(s)?;
+        let (s, _) = eof(s)?;
+        let imports = import_decls
+            .into_iter()
+            .flat_map(|decl| match decl {
+                TopLevel::Import(imports) => imports,
+                _ => unreachable!(),
+            })
+            .collect();
+        Ok((
+            s,
+            SourceFile {
+                doc,
+                package,
+                imports,
+                decls,
+            },
+        ))
+    }
 }
 
 // PackageClause  = "package" PackageName .
 // PackageName    = identifier .
-pub fn parse_package_clause(s: &str) -> IResult<&str, TopLevel> {
+pub fn parse_package_clause(s: &str) -> IResult<&str, TopLevel<'_>, ParseError<'_>> {
+    map(package_clause, TopLevel::Pkg)(s)
+}
+
+fn package_clause(s: &str) -> IResult<&str, &str, ParseError<'_>> {
     let (s, _) = reserved("package")(s)?;
-    let (s, pkg_name) = identifier(s)?;
-    Ok((s, TopLevel::Pkg(pkg_name)))
+    identifier(s)
 }
 
 // ImportDecl       = "import" ( ImportSpec | "(" { ImportSpec ";" } ")" ) .
 // ImportSpec       = [ "." | PackageName ] ImportPath .
 // ImportPath       = string_lit .
-pub fn parse_import_decl(s: &str) -> IResult<&str, TopLevel> {
+pub fn parse_import_decl(s: &str) -> IResult<&str, TopLevel<'_>, ParseError<'_>> {
     let (s, _) = reserved("import")(s)?;
-    // TODO: multiple '(' packages ')'
-    let mut parser = opt(identifier);
-    let (s, _pkg_name_opt) = parser(s)?;
-    // Take the left value for package name.
-    let pkg_name_opt = _pkg_name_opt.map(|s| s);
-    let (s, pkg_path) = parse_string_literal(s)?;
-    Ok((
-        s,
-        TopLevel::Import(vec![ImportDeclaration::new(pkg_name_opt, pkg_path)]),
-    ))
+    let specs = alt((
+        list_of("(", parse_import_spec, ")"),
+        map(parse_import_spec, |spec| vec![spec]),
+    ));
+    let specs = context(|| "in import declaration".to_string(), specs);
+    map(cut(specs), TopLevel::Import)(s)
 }
 
-fn parse_go_type(s: &str) -> IResult<&str, GoType> {
-    let (s, typ) = identifier(s)?;
-    let (s, _) = space0(s)?;
-    Ok((s, GoType::from(typ)))
+fn parse_import_spec(s: &str) -> IResult<&str, ImportDeclaration<'_>, ParseError<'_>> {
+    let (s, pkg_name_opt) = opt(alt((symbol("."), identifier)))(s)?;
+    let (s, pkg_path) = parse_string_literal(s)?;
+    Ok((s, ImportDeclaration::new(pkg_name_opt, pkg_path)))
 }
 
-// Thanks to drumato!
-// https://github.com/Drumato/peachili/blob/codegen/src/compiler/common/frontend/pass/parser/primitive.rs#L14
-fn parse_string_literal(i: &str) -> nom::IResult<&str, &str> {
-    let (rest, contents) =
-        delimited(symbol("\""), take_while(|b: char| b != '"'), symbol("\""))(i)?;
-    Ok((rest, contents))
+// ImportPath = string_lit .
+fn parse_string_literal(i: &str) -> nom::IResult<&str, &str, ParseError<'_>> {
+    let (rest, literal) = string_lit(i)?;
+    Ok((rest, &literal[1..literal.len() - 1]))
 }
 
-// FunctionDecl = "func" FunctionName Signature [ FunctionBody ] .
-// FunctionBody = . // TODO: Implement block.
-fn parse_function_decl<'a>(s: &'a str) -> IResult<&'a str, Function<'a>> {
-    // func f (x int) string
-    let (s, _) = reserved("func")(s)?;
-    let (s, name) = identifier(s)?;
-    let (s, params) = parse_parameters(s)?;
-    let (s, ret) = parse_go_type(s)?;
-    let (s, _) = space0(s)?;
-    Ok((s, Function { name, params, ret }))
+// TopLevelDecl = Declaration | FunctionDecl | MethodDecl .
+// The comment group right before a declaration becomes its doc comment.
+fn parse_top_level_decl(s: &str) -> IResult<&str, TopLevel<'_>, ParseError<'_>> {
+    let (s, doc) = parse_doc(s)?;
+    let (s, mut decl) = alt((
+        map(parse_function_decl, TopLevel::Function),
+        map(Declaration::parse, TopLevel::Declaration),
+    ))(s)?;
+    match &mut decl {
+        TopLevel::Function(function) => function.doc = doc,
+        TopLevel::Declaration(declaration) => *declaration.doc_mut() = doc,
+        _ => unreachable!(),
+    }
+    Ok((s, decl))
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Parameters<'a>(Option<ParameterList<'a>>);
-// Parameters     = "(" [ ParameterList [ "," ] ] ")" .
-fn parse_parameters(s: &str) -> IResult<&str, Parameters> {
-    let parse_parameter_list_opt = opt(tuple((parse_parameter_list, opt(symbol(",")))));
-    let (s, parameter_list_opt) = delimited(symbol("("), parse_parameter_list_opt, symbol(")"))(s)?;
-    let parameter_list = parameter_list_opt.map(|(s, _)| s);
-    Ok((s, Parameters(parameter_list)))
+// FunctionDecl = "func" FunctionName [ TypeParameters ] Signature [ FunctionBody ] .
+// MethodDecl   = "func" Receiver MethodName Signature [ FunctionBody ] .
+// FunctionBody = Block .
+fn parse_function_decl<'a>(s: &'a str) -> IResult<&'a str, Function<'a>, ParseError<'a>> {
+    let (s, function) = parse_function_head(s)?;
+    if !s.starts_with('{') {
+        return Ok((s, function));
+    }
+    let body = context(
+        || format!("in body of func {}", function.name),
+        Block::parse,
+    );
+    let (s, body) = cut(body)(s)?;
+    let body = Some(body);
+    Ok((s, Function { body, ..function }))
 }
 
-#[derive(Debug, PartialEq)]
-pub struct ParameterList<'a>(Vec<ParameterDecl<'a>>);
-// ParameterList  = ParameterDecl { "," ParameterDecl } .
-fn parse_parameter_list<'a>(s: &'a str) -> IResult<&'a str, ParameterList<'a>> {
-    let (s, f) = parse_parameter_decl(s)?;
-    let (s, mut decls) = many0(preceded(symbol(","), parse_parameter_decl))(s)?;
-    decls.insert(0, f);
-    Ok((s, ParameterList(decls)))
+// "func" [ Receiver ] FunctionName [ TypeParameters ] Signature
+pub(crate) fn parse_function_head<'a>(
+    s: &'a str,
+) -> IResult<&'a str, Function<'a>, ParseError<'a>> {
+    // func (l *List) f (x int) string
+    let (s, _) = reserved("func")(s)?;
+    let (s, receiver) = match symbol("(")(s) {
+        Ok(_) => {
+            let receiver = context(|| "in method receiver".to_string(), parse_receiver);
+            map(cut(receiver), Some)(s)?
+        }
+        Err(_) => (s, None),
+    };
+    let (s, name) = cut(identifier)(s)?;
+    let type_params = context(
+        || format!("in type parameters of func {}", name),
+        parse_type_parameters,
+    );
+    let (s, type_params) = match symbol("[")(s) {
+        Ok(_) => cut(type_params)(s)?,
+        Err(_) => (s, vec![]),
+    };
+    let parameters = context(
+        || format!("in parameter list of func {}", name),
+        parse_parameters,
+    );
+    let (s, params) = cut(parameters)(s)?;
+    let result = context(|| format!("in result of func {}", name), parse_result);
+    let (s, results) = cut(result)(s)?;
+    Ok((
+        s,
+        Function {
+            doc: None,
+            receiver,
+            name,
+            type_params,
+            signature: Signature { params, results },
+            body: None,
+        },
+    ))
 }
 
-#[derive(Debug, PartialEq)]
-pub struct ParameterDecl<'a> {
-    identifiers: Option<Vec<&'a str>>,
-    is_variadic: bool,
-    go_type: GoType,
-}
-// ParameterDecl  = [ IdentifierList ] [ "..." ] Type .
-fn parse_parameter_decl(s: &str) -> IResult<&str, ParameterDecl> {
-    let (s, identifiers) = opt(parse_identifier_list)(s)?;
-    let (s, is_variadic_opt) = opt(reserved("..."))(s)?;
-    let is_variadic = is_variadic_opt.is_some();
-    let (s, go_type) = parse_go_type(s)?;
+// Receiver = Parameters .
+// It declares a single parameter whose type is a type name, possibly generic, or a pointer to one:
+// "(" [ identifier ] [ "*" ] identifier [ "[" IdentifierList "]" ] [ "," ] ")"
+fn parse_receiver(s: &str) -> IResult<&str, Receiver<'_>, ParseError<'_>> {
+    let base_type = |s| {
+        tuple((
+            map(opt(symbol("*")), |pointer| pointer.is_some()),
+            identifier,
+            map(
+                opt(preceded(
+                    symbol("["),
+                    cut(terminated(parse_identifier_list, symbol("]"))),
+                )),
+                Option::unwrap_or_default,
+            ),
+        ))(s)
+    };
+    let (s, _) = symbol("(")(s)?;
+    let (s, (name, (pointer, base_type, type_params))) = alt((
+        pair(map(identifier, Some), base_type),
+        map(base_type, |base_type| (None, base_type)),
+    ))(s)?;
+    let (s, _) = pair(opt(symbol(",")), symbol(")"))(s)?;
     Ok((
         s,
-        ParameterDecl {
-            identifiers,
-            is_variadic,
-            go_type,
+        Receiver {
+            name,
+            pointer,
+            base_type,
+            type_params,
         },
     ))
 }
 
 // IdentifierList = identifier { "," identifier } .
-fn parse_identifier_list(s: &str) -> IResult<&str, Vec<&str>> {
+pub(crate) fn parse_identifier_list(s: &str) -> IResult<&str, Vec<&str>, ParseError<'_>> {
     let (s, i) = identifier(s)?;
     let (s, mut result) = many0(preceded(symbol(","), identifier))(s)?;
     result.insert(0, i);
@@ -225,157 +507,244 @@ fn test_import_decl() {
     );
 
     // import . "lib/math"
+    assert_eq!(
+        parse_import_decl("import . \"lib/math\""),
+        Ok((
+            "",
+            TopLevel::Import(vec![ImportDeclaration::new(Some("."), "lib/math")])
+        ))
+    );
+
+    // import ( "fmt"; m "lib/math" )
+    assert_eq!(
+        parse_import_decl("import (\n\t\"fmt\"\n\tm \"lib/math\"\n)"),
+        Ok((
+            "",
+            TopLevel::Import(vec![
+                ImportDeclaration::new(None, "fmt"),
+                ImportDeclaration::new(Some("m"), "lib/math")
+            ])
+        ))
+    );
 }
 
 #[test]
 fn test_go_type() {
-    assert_eq!(parse_go_type("int"), Ok(("", GoType::Int)));
-    assert_eq!(parse_go_type("uint8"), Ok(("", GoType::Uint8)));
-    assert_eq!(parse_go_type("uint16"), Ok(("", GoType::Uint16)));
-    assert_eq!(parse_go_type("uint32"), Ok(("", GoType::Uint32)));
-    assert_eq!(parse_go_type("uint64"), Ok(("", GoType::Uint64)));
-    assert_eq!(parse_go_type("int8"), Ok(("", GoType::Int8)));
-    assert_eq!(parse_go_type("int16"), Ok(("", GoType::Int16)));
-    assert_eq!(parse_go_type("int32"), Ok(("", GoType::Int32)));
-    assert_eq!(parse_go_type("int64"), Ok(("", GoType::Int64)));
-    assert_eq!(parse_go_type("float32"), Ok(("", GoType::Float32)));
-    assert_eq!(parse_go_type("float64"), Ok(("", GoType::Float64)));
-    assert_eq!(parse_go_type("complex64"), Ok(("", GoType::Complex64)));
-    assert_eq!(parse_go_type("complex128"), Ok(("", GoType::Complex128)));
-    assert_eq!(parse_go_type("byte"), Ok(("", GoType::Byte)));
-    assert_eq!(parse_go_type("rune"), Ok(("", GoType::Rune)));
-    assert_eq!(parse_go_type("string"), Ok(("", GoType::String)));
+    assert_eq!(GoType::from("int"), GoType::Int);
+    assert_eq!(GoType::from("uint8"), GoType::Uint8);
+    assert_eq!(GoType::from("uint16"), GoType::Uint16);
+    assert_eq!(GoType::from("uint32"), GoType::Uint32);
+    assert_eq!(GoType::from("uint64"), GoType::Uint64);
+    assert_eq!(GoType::from("int8"), GoType::Int8);
+    assert_eq!(GoType::from("int16"), GoType::Int16);
+    assert_eq!(GoType::from("int32"), GoType::Int32);
+    assert_eq!(GoType::from("int64"), GoType::Int64);
+    assert_eq!(GoType::from("float32"), GoType::Float32);
+    assert_eq!(GoType::from("float64"), GoType::Float64);
+    assert_eq!(GoType::from("complex64"), GoType::Complex64);
+    assert_eq!(GoType::from("complex128"), GoType::Complex128);
+    assert_eq!(GoType::from("byte"), GoType::Byte);
+    assert_eq!(GoType::from("rune"), GoType::Rune);
+    assert_eq!(GoType::from("string"), GoType::String);
+    assert_eq!(GoType::from("bool"), GoType::Bool);
+    assert_eq!(GoType::from("uint"), GoType::Uint);
+    assert_eq!(GoType::from("uintptr"), GoType::Uintptr);
 }
 
 #[test]
 fn test_func_decl() {
+    use typ::{ParameterDecl, TypeName};
+    let named = |name| typ::GoType::TypeName(TypeName::Identifier(name));
     assert_eq!(
         parse_function_decl("func f (x int) string"),
         Ok((
             "",
             Function {
+                doc: None,
+                receiver: None,
                 name: "f",
-                params: Parameters(Some(ParameterList(vec![ParameterDecl {
-                    identifiers: Some(vec!["x"]),
-                    is_variadic: false,
-                    go_type: GoType::Int
-                }]))),
-                ret: GoType::String
+                type_params: vec![],
+                signature: Signature {
+                    params: vec![ParameterDecl {
+                        identifiers: Some(vec!["x"]),
+                        is_variadic: false,
+                        go_type: named("int")
+                    }],
+                    results: vec![ParameterDecl {
+                        identifiers: None,
+                        is_variadic: false,
+                        go_type: named("string")
+                    }]
+                },
+                body: None
             }
         ))
     );
+    let (_, function) = parse_function_decl("func main() {\n}").unwrap();
+    assert!(function.signature.results.is_empty());
+    assert_eq!(function.body, Some(Block(vec![])));
 }
 
 #[test]
-fn test_parameters() {
-    assert_eq!(
-        parse_parameters("(x int)"),
-        Ok((
-            "",
-            Parameters(Some(ParameterList(vec![ParameterDecl {
-                identifiers: Some(vec!["x"]),
-                is_variadic: false,
-                go_type: GoType::Int
-            }])))
-        ))
-    );
+fn test_generic_func_decl() {
+    let src = "func Map[T, U any](xs []T, f func(T) U) []U";
+    let (s, function) = parse_function_decl(src).unwrap();
+    assert_eq!(s, "");
+    assert_eq!(function.type_params.len(), 1);
+    assert_eq!(function.type_params[0].identifiers, vec!["T", "U"]);
+    assert_eq!(function.signature.params.len(), 2);
+
+    let error = match parse_function_decl("func F[T](x T)") {
+        Err(nom::Err::Failure(e)) => e,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(error.message(), "expected type, found `]`");
+    assert_eq!(error.context, vec!["in type parameters of func F"]);
+}
 
+#[test]
+fn test_generic_instantiation() {
+    let src = "func f(xs []int) {
+	var l List[int]
+	ys := Map[int, string](xs, strconv.Itoa)
+	set := pkg.Set[string]{}
+	if len(ys) > 0 {
+		l.Push(1)
+	}
+}";
+    let (s, function) = parse_function_decl(src).unwrap();
+    assert_eq!(s, "");
+    let statements = function.body.unwrap().0;
+    assert_eq!(statements.len(), 4);
+    assert!(matches!(statements[3], statement::Statement::IfStmt(_)));
+}
+
+#[test]
+fn test_method_decl() {
+    let (s, method) = parse_function_decl("func (l *List[T]) Push(v T) {}").unwrap();
+    assert_eq!(s, "");
+    assert_eq!(method.name, "Push");
     assert_eq!(
-        parse_parameters("(x, y int, z string)"),
-        Ok((
-            "",
-            Parameters(Some(ParameterList(vec![
-                ParameterDecl {
-                    identifiers: Some(vec!["x", "y"]),
-                    is_variadic: false,
-                    go_type: GoType::Int
-                },
-                ParameterDecl {
-                    identifiers: Some(vec!["z"]),
-                    is_variadic: false,
-                    go_type: GoType::String
-                }
-            ])))
-        ))
+        method.receiver,
+        Some(Receiver {
+            name: Some("l"),
+            pointer: true,
+            base_type: "List",
+            type_params: vec!["T"]
+        })
     );
+    let (_, method) = parse_function_decl("func (Point) String() string").unwrap();
+    let receiver = method.receiver.unwrap();
+    assert_eq!((receiver.name, receiver.pointer), (None, false));
+    let (_, method) = parse_function_decl("func (p Pair[K, _]) Key() K").unwrap();
+    assert_eq!(method.receiver.unwrap().type_params, vec!["K", "_"]);
+
+    let error = match parse_function_decl("func (l *List[T) Push()") {
+        Err(nom::Err::Failure(e)) => e,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(error.message(), "expected `]`, found `)`");
+    assert_eq!(error.context, vec!["in method receiver"]);
 }
 
 #[test]
-fn test_parameter_list() {
+fn test_identifier_list() {
+    assert_eq!(parse_identifier_list("x"), Ok(("", vec!["x"])));
+    assert_eq!(parse_identifier_list("x, y"), Ok(("", vec!["x", "y"])));
+    assert_eq!(parse_identifier_list("x, y z"), Ok(("z", vec!["x", "y"])));
+}
+
+#[test]
+fn test_source_file() {
+    let src = "// Package m is a test.
+package m
+
+import \"fmt\"
+import s \"strings\"
+
+// f returns a string.
+// It has a doc comment.
+func f (x int) string
+
+// A detached comment.
+
+func g (y int) int // trailing comment
+
+// Sizes.
+const (
+	KB = 1 << (10 * (iota + 1))
+	MB
+)
+";
+    let (rest, file) = SourceFile::parse(src).unwrap();
+    assert_eq!(rest, "");
+    assert_eq!(file.doc.unwrap().text(), "Package m is a test.\n");
+    assert_eq!(file.package, "m");
     assert_eq!(
-        parse_parameter_list("x int"),
-        Ok((
-            "",
-            ParameterList(vec![ParameterDecl {
-                identifiers: Some(vec!["x"]),
-                is_variadic: false,
-                go_type: GoType::Int
-            }])
-        ))
+        file.imports,
+        vec![
+            ImportDeclaration::new(None, "fmt"),
+            ImportDeclaration::new(Some("s"), "strings")
+        ]
     );
-
+    let docs: Vec<_> = file
+        .decls
+        .iter()
+        .map(|decl| match decl {
+            TopLevel::Function(f) => f.doc.as_ref().map(CommentGroup::text),
+            TopLevel::Declaration(Declaration::ConstDecl(decl)) => {
+                decl.doc.as_ref().map(CommentGroup::text)
+            }
+            _ => unreachable!(),
+        })
+        .collect();
     assert_eq!(
-        parse_parameter_list("x, y int, z string"),
-        Ok((
-            "",
-            ParameterList(vec![
-                ParameterDecl {
-                    identifiers: Some(vec!["x", "y"]),
-                    is_variadic: false,
-                    go_type: GoType::Int
-                },
-                ParameterDecl {
-                    identifiers: Some(vec!["z"]),
-                    is_variadic: false,
-                    go_type: GoType::String
-                }
-            ])
-        ))
+        docs,
+        vec![
+            Some("f returns a string.\nIt has a doc comment.\n".to_string()),
+            None,
+            Some("Sizes.\n".to_string())
+        ]
     );
 }
 
 #[test]
-fn test_parameter_decl() {
+fn test_source_file_errors() {
+    let error = |src| match SourceFile::parse(src) {
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => e,
+        other => panic!("{:?}", other),
+    };
+
+    let e = error("package 1");
+    assert_eq!(e.message(), "expected identifier, found `1`");
+    assert_eq!(e.context, vec!["in package clause"]);
+    assert_eq!(e.span("package 1"), error::Span { start: 8, end: 9 });
+
+    let e = error("package main\nimport fmt\n");
+    assert_eq!(e.message(), "expected string literal, found newline");
+    assert_eq!(e.context, vec!["in import declaration"]);
+
+    let e = error("package main\nfunc f(x int) (n int, error)\n");
     assert_eq!(
-        parse_parameter_decl("x int"),
-        Ok((
-            "",
-            ParameterDecl {
-                identifiers: Some(vec!["x"]),
-                is_variadic: false,
-                go_type: GoType::Int
-            }
-        ))
+        e.message(),
+        "expected parameters all named or all unnamed, found `(`"
     );
+    assert_eq!(e.context, vec!["in result of func f"]);
 
+    let e = error("package main\nx := 1\n");
     assert_eq!(
-        parse_parameter_decl("x, y int"),
-        Ok((
-            "",
-            ParameterDecl {
-                identifiers: Some(vec!["x", "y"]),
-                is_variadic: false,
-                go_type: GoType::Int
-            }
-        ))
+        e.message(),
+        "expected one of `const`, `func`, `type`, `var`, found `x`"
     );
 
+    let e = error("package main\ntype T struct {\n\tx int\n\t1\n}\n");
     assert_eq!(
-        parse_parameter_decl("x, y ... int"),
-        Ok((
-            "",
-            ParameterDecl {
-                identifiers: Some(vec!["x", "y"]),
-                is_variadic: true,
-                go_type: GoType::Int
-            }
-        ))
+        e.message(),
+        "expected one of `*`, `}`, identifier, found `1`"
     );
-}
+    assert_eq!(e.context, vec!["in type declaration"]);
 
-#[test]
-fn test_identifier_list() {
-    assert_eq!(parse_identifier_list("x"), Ok(("", vec!["x"])));
-    assert_eq!(parse_identifier_list("x, y"), Ok(("", vec!["x", "y"])));
-    assert_eq!(parse_identifier_list("x, y z"), Ok(("z", vec!["x", "y"])));
+    let e = error("package main\nconst (\n\tA = 1\n\tB int\n)\n");
+    assert_eq!(e.message(), "expected `=`, found newline");
+    assert_eq!(e.context, vec!["in const declaration"]);
 }
@@ -0,0 +1,105 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{one_of, satisfy},
+    combinator::{not, opt, recognize},
+    sequence::{pair, tuple},
+    IResult,
+};
+
+use crate::{error::ParseError, parse_util::space0};
+
+use super::integer::{binary_lit, decimal_digits, hex_digits, hex_lit, octal_lit};
+
+/// float_lit = decimal_float_lit | hex_float_lit .
+///
+/// The literal is returned as written.
+/// ```
+/// use go_parser_rs::literals::float::float_lit;
+/// assert_eq!(float_lit("1.5 + x"), Ok(("+ x", "1.5")));
+/// assert_eq!(float_lit("1_000.e-3"), Ok(("", "1_000.e-3")));
+/// assert_eq!(float_lit(".25"), Ok(("", ".25")));
+/// assert_eq!(float_lit("6E23"), Ok(("", "6E23")));
+/// assert_eq!(float_lit("0x1.8p+1"), Ok(("", "0x1.8p+1")));
+/// assert!(float_lit("42").is_err()); // an integer literal
+/// assert!(float_lit("0x1.8").is_err()); // invalid: a hexadecimal mantissa needs an exponent
+/// ```
+pub fn float_lit(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    let (s, lit) = raw_float_lit(s).map_err(nom::Err::<ParseError>::convert)?;
+    let (s, _) = space0(s)?;
+    Ok((s, lit))
+}
+
+/// imaginary_lit = (decimal_digits | int_lit | float_lit) "i" .
+/// ```
+/// use go_parser_rs::literals::float::imaginary_lit;
+/// assert_eq!(imaginary_lit("1.5i * x"), Ok(("* x", "1.5i")));
+/// assert_eq!(imaginary_lit("0123i"), Ok(("", "0123i"))); // decimal, for backward compatibility
+/// assert_eq!(imaginary_lit("0x10i"), Ok(("", "0x10i")));
+/// assert!(imaginary_lit("1.5").is_err());
+/// ```
+pub fn imaginary_lit(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    let imaginary = |mantissa: fn(&str) -> IResult<&str, &str>| recognize(pair(mantissa, tag("i")));
+    let (s, lit) = alt((
+        imaginary(raw_float_lit),
+        imaginary(hex_lit),
+        imaginary(binary_lit),
+        imaginary(octal_lit),
+        imaginary(decimal_digits),
+    ))(s)
+    .map_err(nom::Err::<ParseError>::convert)?;
+    let (s, _) = space0(s)?;
+    Ok((s, lit))
+}
+
+fn raw_float_lit(s: &str) -> IResult<&str, &str> {
+    let (s, lit) = alt((hex_float_lit, decimal_float_lit))(s)?;
+    // `1.` followed by an identifier is no literal, and neither is `1.2.3`.
+    let (s, _) = not(satisfy(|c| {
+        c == '.' || c == '_' || (c.is_alphanumeric() && c != 'i')
+    }))(s)?;
+    Ok((s, lit))
+}
+
+/// decimal_float_lit = decimal_digits "." [ decimal_digits ] [ decimal_exponent ] |
+///                     decimal_digits decimal_exponent |
+///                     "." decimal_digits [ decimal_exponent ] .
+fn decimal_float_lit(s: &str) -> IResult<&str, &str> {
+    alt((
+        recognize(tuple((
+            decimal_digits,
+            tag("."),
+            opt(decimal_digits),
+            opt(decimal_exponent),
+        ))),
+        recognize(pair(decimal_digits, decimal_exponent)),
+        recognize(tuple((tag("."), decimal_digits, opt(decimal_exponent)))),
+    ))(s)
+}
+
+/// decimal_exponent = ( "e" | "E" ) [ "+" | "-" ] decimal_digits .
+fn decimal_exponent(s: &str) -> IResult<&str, &str> {
+    recognize(tuple((one_of("eE"), opt(one_of("+-")), decimal_digits)))(s)
+}
+
+/// hex_float_lit = "0" ( "x" | "X" ) hex_mantissa hex_exponent .
+/// hex_mantissa  = [ "_" ] hex_digits "." [ hex_digits ] | [ "_" ] hex_digits | "." hex_digits .
+/// hex_exponent  = ( "p" | "P" ) [ "+" | "-" ] decimal_digits .
+fn hex_float_lit(s: &str) -> IResult<&str, &str> {
+    let mantissa = alt((
+        recognize(tuple((
+            opt(tag("_")),
+            hex_digits,
+            opt(pair(tag("."), opt(hex_digits))),
+        ))),
+        recognize(pair(tag("."), hex_digits)),
+    ));
+    recognize(tuple((
+        tag("0"),
+        one_of("xX"),
+        mantissa,
+        one_of("pP"),
+        opt(one_of("+-")),
+        decimal_digits,
+    )))(s)
+}
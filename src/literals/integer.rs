@@ -1,20 +1,27 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{one_of, space0},
+    character::complete::one_of,
     combinator::{map, not, opt, recognize},
     multi::many0,
     sequence::{pair, tuple},
     IResult,
 };
 
+use crate::{error::ParseError, parse_util::space0};
+
 use super::letter_and_digit::{binary_digit, decimal_digit, hex_digit, octal_digit};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntLit<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     DecimalLit(&'a str),
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     BinaryLit(&'a str),
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     OctalLit(&'a str),
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     HexLit(&'a str),
 }
 
@@ -34,6 +41,14 @@ impl<'a> IntLit<'a> {
     pub fn hex_lit(v: &'a str) -> Self {
         Self::HexLit(v)
     }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::DecimalLit(v) | Self::BinaryLit(v) | Self::OctalLit(v) | Self::HexLit(v) => {
+                v.len()
+            }
+        }
+    }
 }
 
 /// int_lit = decimal_lit | binary_lit | octal_lit | hex_lit .
@@ -45,14 +60,15 @@ impl<'a> IntLit<'a> {
 /// assert_eq!(int_lit("0O600"), Ok(("", IntLit::octal_lit("0O600"))));
 /// assert_eq!(int_lit("0xBadF4ce"), Ok(("", IntLit::hex_lit("0xBadF4ce"))));
 ///```
-pub fn int_lit(s: &str) -> IResult<&str, IntLit> {
+pub fn int_lit(s: &str) -> IResult<&str, IntLit<'_>, ParseError<'_>> {
     // Calling `decimal_lit` should be last because it can parse only "0" when input is "0x~" "0b~"...
     let (s, int) = alt((
         map(binary_lit, IntLit::binary_lit),
         map(octal_lit, IntLit::octal_lit),
         map(hex_lit, IntLit::hex_lit),
         map(decimal_lit, IntLit::decimal_lit),
-    ))(s)?;
+    ))(s)
+    .map_err(nom::Err::<ParseError>::convert)?;
     let (s, _) = space0(s)?;
     Ok((s, int))
 }
@@ -75,15 +91,14 @@ pub fn decimal_digits(s: &str) -> IResult<&str, &str> {
     Ok((s, digits))
 }
 
-/// Note: Failed on some test cases now. use this function carefully.
 /// decimal_lit    = "0" | ( "1" … "9" ) [ [ "_" ] decimal_digits ].
 /// ```
 /// use go_parser_rs::literals::integer::decimal_lit;
 /// assert_eq!(decimal_lit("0"), Ok(("", "0")));
 /// assert_eq!(decimal_lit("123456789"), Ok(("", "123456789")));
 /// assert_eq!(decimal_lit("12_3_45_6789"), Ok(("", "12_3_45_6789")));
-/// assert!(decimal_lit("0123").is_err()); // TODO: Fix this case, invalid: non zero literal can start with 0.
-/// assert!(decimal_lit("00").is_err()); // TODO: Fix this case, invalid: zero literal can't start with multi times 0.
+/// assert!(decimal_lit("0123").is_err()); // invalid: non zero literal can start with 0.
+/// assert!(decimal_lit("00").is_err()); // invalid: zero literal can't start with multi times 0.
 /// assert!(decimal_lit("12__3_45_6789").is_err()); // invalid: only one _ at a time
 /// ```
 pub fn decimal_lit(s: &str) -> IResult<&str, &str> {
@@ -92,7 +107,8 @@ pub fn decimal_lit(s: &str) -> IResult<&str, &str> {
     // [ [ "_" ] decimal_digits ]
     let parser = many0(pair(opt(tag("_")), decimal_digits));
     let (s, digits) = recognize(alt((tag("0"), recognize(pair(_1to9, parser)))))(s)?;
-    let (s, _) = not(tag("_"))(s)?;
+    // A digit right after "0" makes it an octal literal, not a decimal one.
+    let (s, _) = not(one_of("_0123456789"))(s)?;
     Ok((s, digits))
 }
 
@@ -119,11 +135,12 @@ pub fn binary_digits(s: &str) -> IResult<&str, &str> {
 /// assert_eq!(binary_lit("0B101"), Ok(("", "0B101")));
 /// assert_eq!(binary_lit("0b_01_1_1_10_0"), Ok(("", "0b_01_1_1_10_0")));
 /// assert!(binary_lit("0_B101").is_err()); // invalid: _ must separate successive digits
+/// assert!(binary_lit("0101").is_err()); // an octal literal
 ///```
 pub fn binary_lit(s: &str) -> IResult<&str, &str> {
     recognize(tuple((
         tag("0"),
-        opt(alt((tag("b"), tag("B")))),
+        alt((tag("b"), tag("B"))),
         opt(tag("_")),
         binary_digits,
     )))(s)
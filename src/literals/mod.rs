@@ -1,42 +1,95 @@
 use nom::{branch::alt, combinator::map, IResult};
 
-use crate::astable::ASTable;
+use crate::{
+    astable::ASTable,
+    error::{expected, Expected, ParseError},
+};
 
 use self::{
+    float::{float_lit, imaginary_lit},
     integer::{int_lit, IntLit},
-    rune::Rune,
+    rune::rune_lit,
+    string::string_lit,
 };
 
+pub mod float;
 pub mod integer;
 pub mod letter_and_digit;
 pub mod rune;
+pub mod string;
 
 ///
 /// Literal     = BasicLit | CompositeLit | FunctionLit .
 /// OperandName = identifier | QualifiedIdent .
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Literal<'a> {
     /// BasicLit    = int_lit | float_lit | imaginary_lit | rune_lit | string_lit .
     IntLit(IntLit<'a>),
-    FloatLit,
-    ImaginaryLit,
-    RuneLit(Rune),
+    /// The literals are kept as written, their values are computed by [`crate::constant`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    FloatLit(&'a str),
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    ImaginaryLit(&'a str),
+    /// The literal as written, with its quotes.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    RuneLit(&'a str),
+    /// The literal as written, with its quotes.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     StringLit(&'a str),
     // TODO: Composite, FunctionLit...
 }
 
 impl<'a> Literal<'a> {
-    fn parse_int_lit(s: &'a str) -> IResult<&'a str, Self> {
-        map(int_lit, Self::IntLit)(s)
+    fn parse_int_lit(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        map(
+            expected(Expected::Kind("integer literal"), int_lit),
+            Self::IntLit,
+        )(s)
+    }
+
+    fn parse_float_lit(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        map(
+            expected(Expected::Kind("floating-point literal"), float_lit),
+            Self::FloatLit,
+        )(s)
+    }
+
+    fn parse_imaginary_lit(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        map(
+            expected(Expected::Kind("imaginary literal"), imaginary_lit),
+            Self::ImaginaryLit,
+        )(s)
+    }
+
+    fn parse_string_lit(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        map(string_lit, Self::StringLit)(s)
     }
 
-    fn parse_rune_lit(s: &'a str) -> IResult<&'a str, Self> {
-        map(Rune::parse, Self::RuneLit)(s)
+    fn parse_rune_lit(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        map(
+            expected(Expected::Kind("rune literal"), rune_lit),
+            Self::RuneLit,
+        )(s)
     }
 }
 
 impl<'a> ASTable<'a> for Literal<'a> {
-    fn parse(s: &'a str) -> IResult<&'a str, Self> {
-        alt((Self::parse_int_lit, Self::parse_rune_lit))(s)
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::literals::{integer::IntLit, Literal};
+    /// assert_eq!(Literal::parse("1 + x"), Ok(("+ x", Literal::IntLit(IntLit::DecimalLit("1")))));
+    /// assert_eq!(Literal::parse("1.5e3"), Ok(("", Literal::FloatLit("1.5e3"))));
+    /// assert_eq!(Literal::parse("0x1p-2i"), Ok(("", Literal::ImaginaryLit("0x1p-2i"))));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        // An integer literal is the start of a floating-point one, which is the start of an imaginary one.
+        alt((
+            Self::parse_imaginary_lit,
+            Self::parse_float_lit,
+            Self::parse_int_lit,
+            Self::parse_rune_lit,
+            Self::parse_string_lit,
+        ))(s)
     }
 }
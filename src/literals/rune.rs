@@ -1,24 +1,43 @@
 use nom::{
-    character::complete::{anychar, char},
-    sequence::delimited,
+    branch::alt,
+    bytes::complete::take_while_m_n,
+    character::complete::{char, none_of, one_of},
+    combinator::recognize,
+    sequence::{delimited, preceded},
     IResult,
 };
 
-use crate::astable::ASTable;
+use crate::{error::ParseError, parse_util::space0};
 
-#[derive(Debug, PartialEq)]
-/// NOTE: char is used as value, but perhaps it will be &str in future because of escaping char.
-pub struct Rune(pub char);
-
-impl<'a> ASTable<'a> for Rune {
-    ///
-    ///```
-    /// use go_parser_rs::literals::rune::Rune;
-    /// use go_parser_rs::astable::ASTable;
-    /// assert_eq!(Rune::parse("'a'bcd"), Ok(("bcd", Rune('a'))));
-    ///```
-    fn parse(s: &'a str) -> IResult<&'a str, Self> {
-        let (s, c) = delimited(char('\''), anychar, char('\''))(s)?;
-        Ok((s, Self(c)))
-    }
+/// rune_lit         = "'" ( unicode_value | byte_value ) "'" .
+/// unicode_value    = unicode_char | little_u_value | big_u_value | escaped_char .
+/// byte_value       = octal_byte_value | hex_byte_value .
+///
+/// The literal is returned as written, quotes and escapes included, like by
+/// [`string_lit`](super::string::string_lit).
+/// ```
+/// use go_parser_rs::literals::rune::rune_lit;
+/// assert_eq!(rune_lit("'a' + 1"), Ok(("+ 1", "'a'")));
+/// assert_eq!(rune_lit(r"'\n'"), Ok(("", r"'\n'")));
+/// assert_eq!(rune_lit(r"'\x00'"), Ok(("", r"'\x00'")));
+/// assert_eq!(rune_lit(r"'é'"), Ok(("", r"'é'")));
+/// assert!(rune_lit("'ab'").is_err());
+/// assert!(rune_lit(r"'\x0'").is_err());
+/// ```
+pub fn rune_lit(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    let hex = |len| take_while_m_n(len, len, |c: char| c.is_ascii_hexdigit());
+    let escape = preceded(
+        char('\\'),
+        alt((
+            preceded(char('x'), hex(2)),
+            preceded(char('u'), hex(4)),
+            preceded(char('U'), hex(8)),
+            take_while_m_n(3, 3, |c: char| ('0'..='7').contains(&c)),
+            recognize(one_of("abfnrtv\\'")),
+        )),
+    );
+    let value = alt((recognize(escape), recognize(none_of("'\\\n"))));
+    let (s, lit) = recognize(delimited(char('\''), value, char('\'')))(s)?;
+    let (s, _) = space0(s)?;
+    Ok((s, lit))
 }
@@ -0,0 +1,39 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag},
+    character::complete::{anychar, none_of},
+    combinator::{opt, recognize},
+    multi::many0,
+    sequence::{delimited, preceded},
+    IResult,
+};
+
+use crate::{
+    error::{expected, Expected, ParseError},
+    parse_util::space0,
+};
+
+/// string_lit             = raw_string_lit | interpreted_string_lit .
+/// raw_string_lit         = "`" { unicode_char | newline } "`" .
+/// interpreted_string_lit = `"` { unicode_value | byte_value } `"` .
+///
+/// The literal is returned as written, quotes and escapes included.
+/// ```
+/// use go_parser_rs::literals::string::string_lit;
+/// assert_eq!(string_lit(r#""a\"b" + x"#), Ok(("+ x", r#""a\"b""#)));
+/// assert_eq!(string_lit("`a\\n\nb`"), Ok(("", "`a\\n\nb`")));
+/// assert!(string_lit("\"a\nb\"").is_err()); // invalid: interpreted strings can't contain newlines
+/// ```
+pub fn string_lit(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    let raw = delimited(tag("`"), opt(is_not("`")), tag("`"));
+    let escaped = preceded(tag("\\"), anychar);
+    let interpreted = delimited(
+        tag("\""),
+        many0(alt((escaped, none_of("\"\\\n")))),
+        tag("\""),
+    );
+    let literal = alt((recognize(raw), recognize(interpreted)));
+    let (s, lit) = expected(Expected::Kind("string literal"), literal)(s)?;
+    let (s, _) = space0(s)?;
+    Ok((s, lit))
+}
@@ -0,0 +1,630 @@
+//! Method sets, and whether types implement interfaces.
+//!
+//! The method set of a defined type `T` holds the methods declared with a `T` receiver, and the
+//! one of `*T` also holds those declared with a `*T` receiver. Both get the methods promoted from
+//! the fields `T` embeds, the shallowest first: a name found more than once at the same depth, or
+//! naming a field, is not promoted. The methods of an embedded `*E`, or of an `E` embedded in a
+//! `*T`, are all promoted, while only the ones with an `E` receiver are promoted from an `E`
+//! embedded in a `T`.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, resolve::resolve, types::{check, Type}, SourceFile};
+//! let src = "package shapes
+//!
+//! type Shape interface {
+//! \tArea() float64
+//! }
+//!
+//! type Square struct {
+//! \tSide float64
+//! }
+//!
+//! func (s *Square) Area() float64 {
+//! \treturn s.Side * s.Side
+//! }
+//! ";
+//! let (_, file) = SourceFile::parse(src).unwrap();
+//! let info = check([&file], &resolve([&file]));
+//! let square = info.object_type(&src[src.find("Square struct").unwrap()..]).unwrap();
+//! let shape = info.object_type(&src[src.find("Shape interface").unwrap()..]).unwrap();
+//! assert!(info.method_set(square).is_empty());
+//! assert_eq!(info.method_set(&Type::Pointer(Box::new(square.clone()))).len(), 1);
+//! assert_eq!(
+//!     info.implements(square, shape).unwrap_err().to_string(),
+//!     "Square does not implement Shape (method Area has pointer receiver)"
+//! );
+//! assert!(info.implements(&Type::Pointer(Box::new(square.clone())), shape).is_ok());
+//! ```
+//!
+//! The methods of the types of imported packages are not known: a type embedding one may have
+//! more methods than its method set holds, so it is taken to implement any interface.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use crate::types::{identical_funcs, Func, Method, Named, Type};
+
+/// A method of a method set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection<'a> {
+    pub name: &'a str,
+    /// The signature, without the receiver.
+    pub func: Func<'a>,
+    /// The embedded fields the method is promoted through, the outermost first.
+    pub path: Vec<&'a str>,
+    /// Whether the method is declared with a pointer receiver.
+    pub pointer_receiver: bool,
+}
+
+/// The methods that can be called on the values of a type, sorted by name.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MethodSet<'a> {
+    methods: Vec<Selection<'a>>,
+    complete: bool,
+}
+
+impl<'a> MethodSet<'a> {
+    /// The method called `name`.
+    pub fn lookup(&self, name: &str) -> Option<&Selection<'a>> {
+        let i = self
+            .methods
+            .binary_search_by(|method| method.name.cmp(name))
+            .ok()?;
+        Some(&self.methods[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Selection<'a>> {
+        self.methods.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.methods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.methods.is_empty()
+    }
+
+    /// Whether all the methods are known, which they are not when a type of an imported package,
+    /// or a type parameter, is embedded.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+}
+
+/// Why a type does not implement an interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingMethods<'a> {
+    pub typ: Box<Type<'a>>,
+    pub iface: Box<Type<'a>>,
+    /// The methods of the interface the type lacks, sorted by name.
+    pub methods: Vec<Missing<'a>>,
+}
+
+/// A method of an interface a type lacks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Missing<'a> {
+    /// No method of that name is in the method set of the type.
+    Method(Method<'a>),
+    /// The method has a pointer receiver, so it is only in the method set of a pointer.
+    PointerReceiver(Method<'a>),
+    /// The method has another signature.
+    WrongType { have: Method<'a>, want: Method<'a> },
+}
+
+impl<'a> Missing<'a> {
+    /// The method of the interface.
+    pub fn method(&self) -> &Method<'a> {
+        match self {
+            Missing::Method(method) | Missing::PointerReceiver(method) => method,
+            Missing::WrongType { want, .. } => want,
+        }
+    }
+}
+
+/// Like Go, only tells about the first missing method.
+impl fmt::Display for MissingMethods<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} does not implement {}", self.typ, self.iface)?;
+        match self.methods.first() {
+            Some(Missing::Method(method)) => write!(f, " (missing method {})", method.name),
+            Some(Missing::PointerReceiver(method)) => {
+                write!(f, " (method {} has pointer receiver)", method.name)
+            }
+            Some(Missing::WrongType { have, want }) => write!(
+                f,
+                " (wrong type for method {})\n\t\thave {}{}\n\t\twant {}{}",
+                want.name, have.name, have.func, want.name, want.func
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for MissingMethods<'_> {}
+
+/// What computing method sets needs to know about the types of a package.
+pub(crate) trait Types<'a> {
+    /// The underlying type of `typ`, [`Type::Invalid`] when it is not known.
+    fn underlying(&mut self, typ: &Type<'a>) -> Type<'a>;
+
+    /// The methods declared for the defined type `named`, with whether their receiver is a
+    /// pointer.
+    fn declared(&mut self, named: &Named<'a>) -> Vec<(Method<'a>, bool)>;
+}
+
+/// A method found looking up the methods of a type, with whether it is reached through a pointer.
+struct Found<'a> {
+    selection: Selection<'a>,
+    indirect: bool,
+}
+
+/// The methods of `typ` and of the fields it embeds, including those with a pointer receiver
+/// when they are not reached through a pointer, with whether all of them are known.
+fn methods<'a>(types: &mut impl Types<'a>, typ: &Type<'a>) -> (Vec<Found<'a>>, bool) {
+    let (typ, indirect) = match typ {
+        Type::Pointer(base) => match types.underlying(base) {
+            // Pointers to pointers and to interfaces have no methods.
+            Type::Pointer(_) | Type::Interface(_) => return (vec![], true),
+            Type::Invalid => return (vec![], false),
+            _ => ((**base).clone(), true),
+        },
+        typ => (typ.clone(), false),
+    };
+    let mut complete = true;
+    // The methods by name, `None` for the names that are ambiguous or name a field.
+    let mut found: BTreeMap<&'a str, Option<Found<'a>>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    let mut current = vec![(typ, indirect, vec![])];
+    while !current.is_empty() {
+        let mut names: BTreeMap<&'a str, Vec<Option<Found<'a>>>> = BTreeMap::new();
+        let mut next = vec![];
+        let mut depth_seen = vec![];
+        for (typ, indirect, path) in current {
+            if let Type::Named(
+                named @ Named {
+                    spec: Some(spec), ..
+                },
+            ) = &typ
+            {
+                let spec = *spec as *const _ as usize;
+                if seen.contains(&spec) {
+                    continue;
+                }
+                depth_seen.push(spec);
+                for (method, pointer_receiver) in types.declared(named) {
+                    let selection = Selection {
+                        name: method.name,
+                        func: method.func,
+                        path: path.clone(),
+                        pointer_receiver,
+                    };
+                    let found = Found {
+                        selection,
+                        indirect,
+                    };
+                    names.entry(method.name).or_default().push(Some(found));
+                }
+            }
+            match types.underlying(&typ) {
+                Type::Struct(fields) => {
+                    for field in fields {
+                        names.entry(field.name).or_default().push(None);
+                        if !field.embedded {
+                            continue;
+                        }
+                        let mut path = path.clone();
+                        path.push(field.name);
+                        match field.typ {
+                            Type::Pointer(base) => next.push((*base, true, path)),
+                            typ => next.push((typ, indirect, path)),
+                        }
+                    }
+                }
+                Type::Interface(methods) => {
+                    for method in methods {
+                        let selection = Selection {
+                            name: method.name,
+                            func: method.func,
+                            path: path.clone(),
+                            pointer_receiver: false,
+                        };
+                        let found = Found {
+                            selection,
+                            indirect,
+                        };
+                        names.entry(method.name).or_default().push(Some(found));
+                    }
+                }
+                Type::Invalid => complete = false,
+                _ => {}
+            }
+        }
+        for (name, mut candidates) in names {
+            if found.contains_key(name) {
+                continue;
+            }
+            let method = match candidates.len() {
+                1 => candidates.pop().flatten(),
+                _ => None,
+            };
+            found.insert(name, method);
+        }
+        seen.extend(depth_seen);
+        current = next;
+    }
+    (found.into_values().flatten().collect(), complete)
+}
+
+/// The method set of `typ`.
+pub(crate) fn method_set<'a>(types: &mut impl Types<'a>, typ: &Type<'a>) -> MethodSet<'a> {
+    let (methods, complete) = methods(types, typ);
+    let methods = methods
+        .into_iter()
+        .filter(|found| found.indirect || !found.selection.pointer_receiver)
+        .map(|found| found.selection)
+        .collect();
+    MethodSet { methods, complete }
+}
+
+/// Whether the method set of `typ` holds the methods of the interface `iface`. A type that is not
+/// an interface is taken as an interface without methods.
+pub(crate) fn implements<'a>(
+    types: &mut impl Types<'a>,
+    typ: &Type<'a>,
+    iface: &Type<'a>,
+) -> Result<(), MissingMethods<'a>> {
+    let wanted = match types.underlying(iface) {
+        Type::Interface(methods) => methods,
+        _ => vec![],
+    };
+    if wanted.is_empty() || matches!(typ, Type::Invalid | Type::TypeParam(_)) {
+        return Ok(());
+    }
+    let (methods, complete) = methods(types, typ);
+    let mut missing = vec![];
+    for want in wanted {
+        let found = methods
+            .iter()
+            .find(|found| found.selection.name == want.name);
+        match found {
+            None if complete => missing.push(Missing::Method(want)),
+            None => {}
+            Some(found) if found.selection.pointer_receiver && !found.indirect => {
+                missing.push(Missing::PointerReceiver(want))
+            }
+            Some(found) if !identical_funcs(&found.selection.func, &want.func) => {
+                let have = Method {
+                    name: found.selection.name,
+                    func: found.selection.func.clone(),
+                };
+                missing.push(Missing::WrongType { have, want })
+            }
+            Some(_) => {}
+        }
+    }
+    match missing.is_empty() {
+        true => Ok(()),
+        false => Err(MissingMethods {
+            typ: Box::new(typ.clone()),
+            iface: Box::new(iface.clone()),
+            methods: missing,
+        }),
+    }
+}
+
+#[test]
+fn test_method_sets() {
+    use crate::{astable::ASTable, resolve::resolve, types::check, SourceFile};
+    let src = "package m
+
+type Reader interface {
+\tRead() string
+}
+
+type ReadCloser interface {
+\tReader
+\tClose() error
+}
+
+type File struct{}
+
+func (f File) Read() string { return \"\" }
+
+func (f *File) Close() error { return nil }
+
+type Value struct {
+\tFile
+}
+
+type Ref struct {
+\t*File
+}
+
+type Both struct {
+\tValue
+\tRef
+}
+
+type Counter struct {
+\tFile
+}
+
+func (c Counter) Read() int { return 0 }
+
+var _ ReadCloser = Value{}
+var _ ReadCloser = &Value{}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let typ = |name: &str| {
+        let at = src.find(&format!("type {} ", name)).unwrap() + 5;
+        info.object_type(&src[at..]).unwrap().clone()
+    };
+    let pointer = |name: &str| Type::Pointer(Box::new(typ(name)));
+    let names = |typ: &Type<'_>| {
+        let set = info.method_set(typ);
+        let names: Vec<_> = set.iter().map(|method| method.name).collect();
+        names.join(" ")
+    };
+    assert_eq!(names(&typ("File")), "Read");
+    assert_eq!(names(&pointer("File")), "Close Read");
+    assert_eq!(names(&typ("Value")), "Read");
+    assert_eq!(names(&pointer("Value")), "Close Read");
+    assert_eq!(names(&typ("Ref")), "Close Read");
+    assert_eq!(names(&typ("Both")), "");
+    assert_eq!(names(&typ("ReadCloser")), "Close Read");
+    assert_eq!(names(&pointer("ReadCloser")), "");
+    let read = info
+        .method_set(&typ("Ref"))
+        .lookup("Read")
+        .cloned()
+        .unwrap();
+    assert_eq!((read.path, read.pointer_receiver), (vec!["File"], false));
+
+    let iface = typ("ReadCloser");
+    assert!(info.implements(&typ("Ref"), &iface).is_ok());
+    assert!(info.implements(&pointer("Value"), &iface).is_ok());
+    let error = info.implements(&typ("Value"), &iface).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Value does not implement ReadCloser (method Close has pointer receiver)"
+    );
+    let error = info.implements(&typ("Both"), &iface).unwrap_err();
+    let missing: Vec<_> = error.methods.iter().map(|m| m.method().name).collect();
+    assert_eq!(missing, ["Close", "Read"]);
+    assert_eq!(
+        info.implements(&pointer("Counter"), &iface)
+            .unwrap_err()
+            .to_string(),
+        "*Counter does not implement ReadCloser (wrong type for method Read)\n\t\thave Read() int\n\t\twant Read() string"
+    );
+
+    let messages: Vec<_> = info.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        ["cannot use value of type Value as ReadCloser value in variable declaration: Value does not implement ReadCloser (method Close has pointer receiver)"]
+    );
+}
+
+#[test]
+fn test_method_sets_of_embedded_pointers() {
+    use crate::{astable::ASTable, resolve::resolve, types::check, SourceFile};
+    let src = "package m
+
+type E struct{}
+
+func (E) Value() {}
+
+func (*E) Pointer() {}
+
+type T struct {
+\t*E
+}
+
+type U struct {
+\tT
+}
+
+type V struct {
+\t*U
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let typ = |name: &str| {
+        let at = src.find(&format!("type {} ", name)).unwrap() + 5;
+        info.object_type(&src[at..]).unwrap().clone()
+    };
+    let pointer = |name: &str| Type::Pointer(Box::new(typ(name)));
+    let names = |typ: &Type<'_>| {
+        let set = info.method_set(typ);
+        let names: Vec<_> = set.iter().map(|method| method.name).collect();
+        names.join(" ")
+    };
+    // `*E` embedded in `T` gives `T` the methods of both receivers, at every depth below.
+    assert_eq!(names(&typ("E")), "Value");
+    assert_eq!(names(&pointer("E")), "Pointer Value");
+    assert_eq!(names(&typ("T")), "Pointer Value");
+    assert_eq!(names(&pointer("T")), "Pointer Value");
+    assert_eq!(names(&typ("U")), "Pointer Value");
+    assert_eq!(names(&typ("V")), "Pointer Value");
+    let pointer_method = info
+        .method_set(&typ("V"))
+        .lookup("Pointer")
+        .cloned()
+        .unwrap();
+    assert_eq!(pointer_method.path, ["U", "T", "E"]);
+    assert!(pointer_method.pointer_receiver);
+    assert!(info.method_set(&typ("T")).is_complete());
+    // Pointers to pointers have no methods.
+    assert!(info
+        .method_set(&Type::Pointer(Box::new(pointer("T"))))
+        .is_empty());
+}
+
+#[test]
+fn test_method_sets_by_depth() {
+    use crate::{astable::ASTable, resolve::resolve, types::check, SourceFile};
+    let src = "package m
+
+type A struct{}
+
+func (A) M() {}
+
+func (A) N() {}
+
+type B struct{}
+
+func (B) M() {}
+
+type Deep struct {
+\tA
+}
+
+type Shallow struct {
+\tDeep
+\tB
+}
+
+type Own struct {
+\tA
+\tB
+}
+
+func (Own) M() {}
+
+type Field struct {
+\tA
+\tN int
+}
+
+type Cycle struct {
+\t*Cycle
+\tA
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let typ = |name: &str| {
+        let at = src.find(&format!("type {} ", name)).unwrap() + 5;
+        info.object_type(&src[at..]).unwrap().clone()
+    };
+    let selections = |name: &str| {
+        let set = info.method_set(&typ(name));
+        let selections: Vec<_> = set
+            .iter()
+            .map(|method| format!("{}:{}", method.name, method.path.join(".")))
+            .collect();
+        selections.join(" ")
+    };
+    // `B.M` at depth 1 hides `A.M` at depth 2.
+    assert_eq!(selections("Shallow"), "M:B N:Deep.A");
+    // Methods declared for the type hide the promoted ones, which are ambiguous otherwise.
+    assert_eq!(selections("Own"), "M: N:A");
+    // A field hides the method of the same name.
+    assert_eq!(selections("Field"), "M:A");
+    assert_eq!(selections("Cycle"), "M:A N:A");
+}
+
+#[test]
+fn test_method_sets_of_generic_types() {
+    use crate::{astable::ASTable, resolve::resolve, types::check, SourceFile};
+    let src = "package m
+
+type List[T any] struct {
+\titems []T
+}
+
+func (l *List[T]) Push(v T) {}
+
+func (l List[T]) Len() int { return len(l.items) }
+
+type Pusher interface {
+\tPush(int)
+}
+
+type Lener interface {
+\tLen() int
+}
+
+var list List[int]
+
+var _ Pusher = &list
+var _ Lener = list
+var _ interface{ Push(string) } = &list
+var _ Pusher = list
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let at = src.find("list List").unwrap();
+    let list = info.object_type(&src[at..]).unwrap().clone();
+    assert_eq!(list.to_string(), "List[int]");
+    let set = info.method_set(&Type::Pointer(Box::new(list.clone())));
+    let methods: Vec<_> = set.iter().map(|method| method.name).collect();
+    assert_eq!(methods, ["Len", "Push"]);
+    assert!(info.method_set(&list).lookup("Push").is_none());
+
+    // The type arguments replace the type parameters of the receiver.
+    let messages: Vec<_> = info.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "cannot use value of type *List[int] as interface{Push(string)} value in variable declaration: *List[int] does not implement interface{Push(string)} (wrong type for method Push)\n\t\thave Push(int)\n\t\twant Push(string)",
+            "cannot use value of type List[int] as Pusher value in variable declaration: List[int] does not implement Pusher (method Push has pointer receiver)",
+        ]
+    );
+}
+
+#[test]
+fn test_implements_interfaces() {
+    use crate::{astable::ASTable, resolve::resolve, types::check, SourceFile};
+    let src = "package m
+
+type Reader interface {
+\tRead() string
+}
+
+type Writer interface {
+\tWrite(string)
+}
+
+type ReadWriter interface {
+\tReader
+\tWriter
+\tRead() string
+}
+
+type Empty interface{}
+
+type S struct{}
+
+func (S) Write(s string) {}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let typ = |name: &str| {
+        let at = src.find(&format!("type {} ", name)).unwrap() + 5;
+        info.object_type(&src[at..]).unwrap().clone()
+    };
+    // An interface embedding another with the same method has it once.
+    assert_eq!(info.method_set(&typ("ReadWriter")).len(), 2);
+    assert!(info.implements(&typ("ReadWriter"), &typ("Reader")).is_ok());
+    assert!(info.implements(&typ("S"), &typ("Writer")).is_ok());
+    assert!(info.implements(&typ("S"), &typ("Empty")).is_ok());
+    assert_eq!(
+        info.implements(&typ("Reader"), &typ("ReadWriter"))
+            .unwrap_err()
+            .to_string(),
+        "Reader does not implement ReadWriter (missing method Write)"
+    );
+    let error = info.implements(&typ("S"), &typ("ReadWriter")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "S does not implement ReadWriter (missing method Read)"
+    );
+    assert_eq!(error.methods.len(), 1);
+    assert_eq!(info.errors, []);
+}
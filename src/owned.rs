@@ -0,0 +1,550 @@
+//! Syntax trees that no longer borrow from the source.
+//!
+//! Every node borrows its identifiers, literals and comments from the parsed input. An [`Owned`]
+//! node keeps that text along with the tree, so that it can be cached or sent to another thread
+//! after the source buffer is dropped, and frees it when dropped.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, owned::Owned, SourceFile};
+//! let src = String::from("package main\nfunc main() {}\n");
+//! let file: Owned<SourceFile<'static>> = Owned::new(&SourceFile::parse(&src).unwrap().1);
+//! drop(src);
+//! let package = std::thread::spawn(move || file.get().package.to_string());
+//! assert_eq!(package.join().unwrap(), "main");
+//! ```
+//!
+//! Each occurrence of a text gets its own copy, so that identifiers are still told apart by their
+//! address, like [`resolve`](crate::resolve) and [`types`](crate::types) do.
+
+use std::{cell::RefCell, fmt, mem::ManuallyDrop, ptr};
+
+use crate::{
+    comment::{Comment, CommentGroup},
+    declaration::{ConstDecl, ConstSpec, Declaration, TypeDecl, TypeSpec, VarDecl, VarSpec},
+    expression::{
+        operand::{Element, FunctionLit, KeyedElement, LiteralValue, Operand, OperandName},
+        Expression, PrimaryExpr, Unary, UnaryExpr,
+    },
+    identifier::QualifiedIdent,
+    literals::{integer::IntLit, Literal},
+    statement::{
+        control::{
+            CaseClause, Else, ForClause, ForStmt, IfStmt, RangeVars, SelectStmt, SwitchStmt,
+            TypeSwitchStmt,
+        },
+        simple::SimpleStmt,
+        Block, Statement,
+    },
+    typ::{
+        ChanDir, FieldDecl, GoType, InterfaceElem, ParameterDecl, Signature, TypeElem, TypeLit,
+        TypeName, TypeParamDecl, TypeTerm,
+    },
+    Function, ImportDeclaration, Receiver, SourceFile, TopLevel,
+};
+
+/// The size of the buffers [`Strings`] copies text into, unless a text is longer.
+const CHUNK: usize = 4096;
+
+/// Copies of text, freed when dropped.
+///
+/// ```
+/// use go_parser_rs::{astable::ASTable, owned::{Detach, Strings}, SourceFile};
+/// let strings = Strings::default();
+/// let src = String::from("package main\n");
+/// let file = SourceFile::parse(&src).unwrap().1.detach(&strings);
+/// drop(src);
+/// assert_eq!(file.package, "main");
+/// ```
+#[derive(Debug, Default)]
+pub struct Strings {
+    chunks: RefCell<Vec<String>>,
+}
+
+impl Strings {
+    /// Copies `text`, which stays at the same address until `self` is dropped.
+    pub fn alloc(&self, text: &str) -> &str {
+        let mut chunks = self.chunks.borrow_mut();
+        let chunk = match chunks.last_mut() {
+            Some(chunk) if chunk.capacity() - chunk.len() >= text.len() => chunk,
+            _ => {
+                chunks.push(String::with_capacity(text.len().max(CHUNK)));
+                chunks.last_mut().unwrap()
+            }
+        };
+        let start = chunk.len();
+        chunk.push_str(text);
+        let copy = &chunk[start..];
+        // SAFETY: a chunk is never pushed to beyond its capacity, so its buffer is never moved,
+        // and nothing is removed from it until `self` is dropped.
+        unsafe { &*(copy as *const str) }
+    }
+
+    fn into_chunks(self) -> Vec<String> {
+        self.chunks.into_inner()
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A syntax tree node, or a field of one, whose text can be copied out of its source.
+///
+/// [`Owned`] relies on `Of<'b>` being the node itself borrowing for `'b` instead, so the trait
+/// is only implemented by this crate.
+pub trait Detach: sealed::Sealed {
+    /// The same node borrowing its text for `'b`.
+    type Of<'b>;
+
+    /// Copies the node, with its text copied into `strings`.
+    fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b>;
+}
+
+/// A node of type `N`, a node type borrowing for `'static` like `SourceFile<'static>`, with the
+/// text it borrows.
+///
+/// [`Owned::get`] lends the node for no longer than the `Owned` lives.
+pub struct Owned<N: Detach> {
+    // Declared first, to be dropped before the text it borrows.
+    node: N,
+    source: String,
+    /// The buffers of [`Strings`] holding the text, only kept to be borrowed from.
+    #[allow(dead_code)]
+    chunks: Vec<String>,
+}
+
+impl<N: Detach> Owned<N> {
+    /// Copies `node` and its text.
+    pub fn new<T: Detach<Of<'static> = N>>(node: &T) -> Self {
+        let (node, chunks) = detach_static(node);
+        Owned {
+            node,
+            source: String::new(),
+            chunks,
+        }
+    }
+
+    /// Parses `source` with `parse`, and keeps it to borrow from.
+    /// ```
+    /// use go_parser_rs::{owned::Owned, recover::parse_source_file, SourceFile};
+    /// let file: Owned<SourceFile<'static>> =
+    ///     Owned::parse(String::from("package main\n"), |src| parse_source_file(src).0);
+    /// assert_eq!(file.get().package.as_ptr(), file.source()[8..].as_ptr());
+    /// ```
+    pub fn parse<F>(source: String, parse: F) -> Self
+    where
+        F: for<'s> FnOnce(&'s str) -> N::Of<'s>,
+    {
+        let node = parse(&source);
+        Owned {
+            // SAFETY: `node` borrows from the buffer of `source`, which moving `source` does not
+            // move.
+            node: unsafe { extend::<N::Of<'_>, N>(node) },
+            source,
+            chunks: vec![],
+        }
+    }
+
+    pub fn get(&self) -> &N::Of<'_> {
+        // SAFETY: `N::Of<'_>` is `N` borrowing for a shorter lifetime, during which the text it
+        // borrows is kept by `self`.
+        unsafe { &*(&self.node as *const N as *const N::Of<'_>) }
+    }
+
+    /// The source given to [`Owned::parse`], empty otherwise.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Copies `node`, with the buffers holding its text.
+fn detach_static<T: Detach>(node: &T) -> (T::Of<'static>, Vec<String>) {
+    let strings = Strings::default();
+    let copy = node.detach(&strings);
+    // SAFETY: the text of `copy` is in `strings`, returned along with it.
+    let copy = unsafe { extend::<T::Of<'_>, T::Of<'static>>(copy) };
+    (copy, strings.into_chunks())
+}
+
+/// Changes the lifetime the node `from` borrows for, `To` being `From` borrowing for another one.
+unsafe fn extend<From, To>(from: From) -> To {
+    let from = ManuallyDrop::new(from);
+    ptr::read(&*from as *const From as *const To)
+}
+
+impl<N: Detach + fmt::Debug> fmt::Debug for Owned<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.node.fmt(f)
+    }
+}
+
+impl<N: Detach + PartialEq> PartialEq for Owned<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<N: Detach + serde::Serialize> serde::Serialize for Owned<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.node.serialize(serializer)
+    }
+}
+
+/// The text of the node is copied, even when it could be borrowed from the input, so that an
+/// `Owned` node can be read with `DeserializeOwned` functions like `serde_json::from_reader`.
+#[cfg(feature = "serde")]
+impl<'de, N: Detach> serde::Deserialize<'de> for Owned<N>
+where
+    N::Of<'de>: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let strings = Strings::default();
+        let node =
+            crate::schema::with_strings(&strings, || N::Of::<'de>::deserialize(deserializer))?;
+        Ok(Owned {
+            // SAFETY: `with_strings` has the text of `node` copied into `strings`, kept along
+            // with it.
+            node: unsafe { extend::<N::Of<'de>, N>(node) },
+            source: String::new(),
+            chunks: strings.into_chunks(),
+        })
+    }
+}
+
+impl sealed::Sealed for &str {}
+
+impl Detach for &str {
+    type Of<'b> = &'b str;
+
+    fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+        strings.alloc(self)
+    }
+}
+
+impl<T: Detach> sealed::Sealed for Option<T> {}
+
+impl<T: Detach> Detach for Option<T> {
+    type Of<'b> = Option<T::Of<'b>>;
+
+    fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+        self.as_ref().map(|node| node.detach(strings))
+    }
+}
+
+impl<T: Detach> sealed::Sealed for Vec<T> {}
+
+impl<T: Detach> Detach for Vec<T> {
+    type Of<'b> = Vec<T::Of<'b>>;
+
+    fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+        self.iter().map(|node| node.detach(strings)).collect()
+    }
+}
+
+impl<T: Detach> sealed::Sealed for Box<T> {}
+
+impl<T: Detach> Detach for Box<T> {
+    type Of<'b> = Box<T::Of<'b>>;
+
+    fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+        Box::new((**self).detach(strings))
+    }
+}
+
+impl<C: Detach> sealed::Sealed for CaseClause<'_, C> {}
+
+impl<C: Detach> Detach for CaseClause<'_, C> {
+    type Of<'b> = CaseClause<'b, C::Of<'b>>;
+
+    fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+        CaseClause {
+            case: self.case.detach(strings),
+            body: self.body.detach(strings),
+        }
+    }
+}
+
+/// Implements [`Detach`] for types that borrow nothing.
+macro_rules! already_owned {
+    ($($name:ty),*) => {
+        $(
+            impl sealed::Sealed for $name {}
+
+            impl Detach for $name {
+                type Of<'b> = Self;
+
+                fn detach<'b>(&self, _: &'b Strings) -> Self::Of<'b> {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+already_owned!(bool, ChanDir);
+
+/// Implements [`Detach`] by copying every field of a struct or of each variant of an enum.
+macro_rules! detach {
+    (struct $name:ident { $($field:ident),* $(,)? }) => {
+        impl sealed::Sealed for $name<'_> {}
+
+        impl Detach for $name<'_> {
+            type Of<'b> = $name<'b>;
+
+            fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+                $name { $($field: self.$field.detach(strings)),* }
+            }
+        }
+    };
+    (struct $name:ident($field:tt)) => {
+        impl sealed::Sealed for $name<'_> {}
+
+        impl Detach for $name<'_> {
+            type Of<'b> = $name<'b>;
+
+            fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+                $name(self.$field.detach(strings))
+            }
+        }
+    };
+    (enum $name:ident {
+        $($variant:ident $(($($value:ident),*))? $({ $($field:ident),* })?),* $(,)?
+    }) => {
+        impl sealed::Sealed for $name<'_> {}
+
+        impl Detach for $name<'_> {
+            type Of<'b> = $name<'b>;
+
+            fn detach<'b>(&self, strings: &'b Strings) -> Self::Of<'b> {
+                match self {
+                    $(
+                        $name::$variant $(($($value),*))? $({ $($field),* })? => {
+                            $name::$variant
+                                $(($($value.detach(strings)),*))?
+                                $({ $($field: $field.detach(strings)),* })?
+                        }
+                    )*
+                }
+            }
+        }
+    };
+}
+
+detach!(struct SourceFile { doc, package, imports, decls });
+detach!(struct ImportDeclaration { pkg_name_opt, path });
+detach!(
+    enum TopLevel {
+        Pkg(name),
+        Import(imports),
+        Function(function),
+        Declaration(decl),
+        BadDecl(text),
+    }
+);
+detach!(struct Function { doc, receiver, name, type_params, signature, body });
+detach!(struct Receiver { name, pointer, base_type, type_params });
+detach!(struct Comment(0));
+detach!(struct CommentGroup { list });
+
+detach!(
+    enum Declaration {
+        ConstDecl(decl),
+        TypeDecl(decl),
+        VarDecl(decl),
+    }
+);
+detach!(struct ConstDecl { doc, specs });
+detach!(struct ConstSpec { identifiers, typ, values });
+detach!(struct VarDecl { doc, specs });
+detach!(struct VarSpec { identifiers, typ, values });
+detach!(struct TypeDecl { doc, specs });
+detach!(enum TypeSpec {
+    AliasDecl { name, type_params, typ },
+    TypeDef { name, type_params, typ },
+});
+
+detach!(enum GoType { TypeName(name), Instantiation { name, type_args }, TypeLit(lit) });
+detach!(
+    enum TypeName {
+        Identifier(name),
+        QualifiedIdent(name),
+    }
+);
+detach!(struct QualifiedIdent { package_name, identifier });
+detach!(enum TypeLit {
+    ArrayType { len, elem },
+    SliceType(elem),
+    StructType(fields),
+    PointerType(base),
+    FunctionType(signature),
+    InterfaceType(elems),
+    MapType { key, value },
+    ChannelType { dir, elem },
+});
+detach!(struct FieldDecl { identifiers, go_type, tag });
+detach!(enum InterfaceElem { Method { name, signature }, Type(elem) });
+detach!(struct TypeElem(0));
+detach!(struct TypeTerm { tilde, go_type });
+detach!(struct TypeParamDecl { identifiers, constraint });
+detach!(struct Signature { params, results });
+detach!(struct ParameterDecl { identifiers, is_variadic, go_type });
+
+detach!(enum Expression { UnaryExpr(expr), BinExpr { left, op, right }, BadExpr(text) });
+detach!(struct Unary { op, expr });
+detach!(
+    enum UnaryExpr {
+        PrimaryExpr(expr),
+        Unary(unary),
+    }
+);
+detach!(enum PrimaryExpr {
+    Operand(operand),
+    Selector { expr, selector },
+    Index { expr, index },
+    Slice { expr, low, high, max },
+    TypeAssertion { expr, typ },
+    Instantiation { expr, type_args },
+    Call { func, args, ellipsis },
+});
+detach!(enum Operand {
+    Literal(literal),
+    OperandName(name),
+    FunctionLit(lit),
+    Parenthesized(expr),
+    TypeLit(lit),
+    CompositeLit { typ, value },
+});
+detach!(struct FunctionLit { signature, body });
+detach!(
+    enum OperandName {
+        Identifier(name),
+        QualifiedIdent(name),
+    }
+);
+detach!(struct LiteralValue(0));
+detach!(struct KeyedElement { key, value });
+detach!(
+    enum Element {
+        Expression(expr),
+        LiteralValue(value),
+    }
+);
+detach!(
+    enum Literal {
+        IntLit(lit),
+        FloatLit(lit),
+        ImaginaryLit(lit),
+        RuneLit(rune),
+        StringLit(lit),
+    }
+);
+detach!(
+    enum IntLit {
+        DecimalLit(lit),
+        BinaryLit(lit),
+        OctalLit(lit),
+        HexLit(lit),
+    }
+);
+
+detach!(enum Statement {
+    DeclStmt(decl),
+    SimpleStmt(stmt),
+    Block(block),
+    IfStmt(stmt),
+    SwitchStmt(stmt),
+    TypeSwitchStmt(stmt),
+    SelectStmt(stmt),
+    ForStmt(stmt),
+    GoStmt(expr),
+    DeferStmt(expr),
+    ReturnStmt(results),
+    BreakStmt(label),
+    ContinueStmt(label),
+    GotoStmt(label),
+    FallthroughStmt,
+    LabeledStmt { label, stmt },
+    BadStmt(text),
+});
+detach!(struct Block(0));
+detach!(enum SimpleStmt {
+    EmptyStmt,
+    ExpressionStmt(expr),
+    SendStmt { channel, value },
+    IncDecStmt { expr, op },
+    Assignment { lhs, op, rhs },
+    ShortVarDecl { identifiers, values },
+});
+detach!(struct IfStmt { init, cond, then, otherwise });
+detach!(
+    enum Else {
+        If(stmt),
+        Block(block),
+    }
+);
+detach!(struct ForStmt { clause, body });
+detach!(enum ForClause {
+    Condition(cond),
+    ForClause { init, cond, post },
+    RangeClause { vars, expr },
+});
+detach!(
+    enum RangeVars {
+        Declared(names),
+        Assigned(exprs),
+    }
+);
+detach!(struct SwitchStmt { init, tag, clauses });
+detach!(struct TypeSwitchStmt { init, binding, expr, clauses });
+detach!(struct SelectStmt { clauses });
+
+#[test]
+fn test_owned_keeps_tree() {
+    use crate::astable::ASTable;
+    let src = "package m
+
+// Sizes.
+const (
+	KB = 1 << (10 * (iota + 1))
+	MB
+)
+
+func (l *List[T]) Each(f func(T)) {
+	for _, x := range l.items {
+		switch v := any(x).(type) {
+		case fmt.Stringer:
+			f(v)
+		}
+	}
+	m := map[string][]int{\"a\": {1, 2}}
+	go func() { m[\"b\"] = []int(nil) }()
+}
+";
+    let owned: Owned<SourceFile<'static>> =
+        Owned::new(&SourceFile::parse(&String::from(src)).unwrap().1);
+    assert_eq!(*owned.get(), SourceFile::parse(src).unwrap().1);
+}
+
+#[test]
+fn test_owned_tree_resolves() {
+    use crate::{astable::ASTable, resolve::resolve, types::check};
+    let src = "package m
+
+func f() int {
+	x := 1
+	return x + 1
+}
+
+func g() string {
+	x := \"s\"
+	return x + \"t\"
+}
+";
+    let owned: Owned<SourceFile<'static>> = Owned::new(&SourceFile::parse(src).unwrap().1);
+    let file = owned.get();
+    let resolution = resolve([file]);
+    assert!(resolution.errors.is_empty(), "{:?}", resolution.errors);
+    let info = check([file], &resolution);
+    assert!(info.errors.is_empty(), "{:?}", info.errors);
+}
@@ -0,0 +1,274 @@
+//! Loading the `.go` files of a directory as one package.
+//!
+//! [`read_dir`] reads the files, and [`Sources::parse`] parses them into a [`Package`] that
+//! borrows from the sources, like the trees of single files do. Each file keeps its own imports,
+//! while [`Package::decls`] goes through the declarations of all of them.
+//!
+//! ```
+//! use std::path::PathBuf;
+//! use go_parser_rs::package::{ErrorKind, Source, Sources};
+//! let source = |path: &str, text: &str| Source {
+//!     path: PathBuf::from(path),
+//!     text: text.to_string(),
+//! };
+//! let sources = Sources {
+//!     files: vec![
+//!         source("a.go", "package geo\n\nimport \"math\"\n\nfunc Sqrt(x float64) float64 { return math.Sqrt(x) }\n"),
+//!         source("b.go", "package geo\n\ntype Point struct{ X, Y float64 }\n"),
+//!         source("c.go", "// Misplaced.\npackage main\n"),
+//!     ],
+//! };
+//! let (package, errors) = sources.parse();
+//! assert_eq!(package.name, "geo");
+//! assert_eq!(package.decls().count(), 2);
+//! assert_eq!(package.files[0].ast.imports.len(), 1);
+//! assert_eq!(errors.len(), 1);
+//! assert_eq!(errors[0].kind, ErrorKind::MismatchedPackage);
+//! assert_eq!(
+//!     errors[0].to_string(),
+//!     "c.go:2:9: package main, expected geo as in a.go"
+//! );
+//! ```
+//!
+//! Like the `go` tool, the files whose name starts with `_` or `.` are left out, and so are the
+//! files of an external test package: `p_test` in a `_test.go` file of the package `p`. Build
+//! constraints are not looked at.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diagnostic::Position, error::ParseError, recover::parse_source_file, resolve, SourceFile,
+    TopLevel,
+};
+
+/// The text of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// The files of a package, in the order their declarations are merged.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Sources {
+    pub files: Vec<Source>,
+}
+
+/// Reads the `.go` files of `dir`, sorted by name, with the `_test.go` files if `tests` is set.
+pub fn read_dir(dir: impl AsRef<Path>, tests: bool) -> io::Result<Sources> {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name,
+            None => continue,
+        };
+        let go = name.ends_with(".go") && !name.starts_with(['_', '.']);
+        if go && (tests || !name.ends_with("_test.go")) && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    let mut files = vec![];
+    for path in paths {
+        let text = fs::read_to_string(&path)?;
+        files.push(Source { path, text });
+    }
+    Ok(Sources { files })
+}
+
+/// A parsed file of a package.
+#[derive(Debug, PartialEq)]
+pub struct File<'a> {
+    pub path: &'a Path,
+    pub source: &'a str,
+    pub ast: SourceFile<'a>,
+}
+
+/// The files of a package, see [`Sources::parse`].
+#[derive(Debug, PartialEq)]
+pub struct Package<'a> {
+    /// The name of the package clause of the first file that is not a test, or of the first file
+    /// if they all are. Empty when there are no files.
+    pub name: &'a str,
+    pub files: Vec<File<'a>>,
+}
+
+impl<'a> Package<'a> {
+    /// The top-level declarations of the files, the imports aside, with the file of each.
+    pub fn decls(&self) -> impl Iterator<Item = (&File<'a>, &TopLevel<'a>)> {
+        self.files
+            .iter()
+            .flat_map(|file| file.ast.decls.iter().map(move |decl| (file, decl)))
+    }
+
+    /// The trees of the files, to [`resolve`](crate::resolve::resolve) and
+    /// [`check`](crate::types::check) them.
+    pub fn source_files(&self) -> impl Iterator<Item = &SourceFile<'a>> {
+        self.files.iter().map(|file| &file.ast)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Syntax,
+    /// A file whose package clause names another package than the first file.
+    MismatchedPackage,
+}
+
+/// An error of a file of a package, at `position` in the file `path`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error<'a> {
+    pub kind: ErrorKind,
+    pub path: &'a Path,
+    pub position: Position,
+    pub message: String,
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.position.line,
+            self.position.column,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for Error<'_> {}
+
+impl Sources {
+    /// Parses the files, recovering from syntax errors like
+    /// [`parse_source_file`](crate::recover::parse_source_file), and reports the syntax errors
+    /// and the files of another package. The files of another package are left out.
+    pub fn parse(&self) -> (Package<'_>, Vec<Error<'_>>) {
+        let mut errors = vec![];
+        let mut files = vec![];
+        for source in &self.files {
+            let (ast, syntax_errors) = parse_source_file(&source.text);
+            errors.extend(syntax_errors.iter().map(|error| syntax(source, error)));
+            files.push(File {
+                path: &source.path,
+                source: &source.text,
+                ast,
+            });
+        }
+        // A broken package clause leaves the name empty, and matches any package.
+        let named = |file: &&File<'_>| !file.ast.package.is_empty();
+        let first = files
+            .iter()
+            .filter(named)
+            .find(|file| !is_test(file.path))
+            .or_else(|| files.iter().find(named));
+        let (name, first_path) = match first {
+            Some(file) => (file.ast.package, file.path),
+            None => ("", Path::new("")),
+        };
+        files.retain(|file| {
+            let package = file.ast.package;
+            if package.is_empty() || package == name {
+                return true;
+            }
+            let external_test = is_test(file.path) && package.strip_suffix("_test") == Some(name);
+            if !external_test {
+                let offset = resolve::span_in(file.source, package).map_or(0, |span| span.start);
+                errors.push(Error {
+                    kind: ErrorKind::MismatchedPackage,
+                    path: file.path,
+                    position: Position::of(file.source, offset),
+                    message: format!(
+                        "package {}, expected {} as in {}",
+                        package,
+                        name,
+                        first_path.display()
+                    ),
+                });
+            }
+            false
+        });
+        (Package { name, files }, errors)
+    }
+}
+
+fn is_test(path: &Path) -> bool {
+    path.to_str().is_some_and(|path| path.ends_with("_test.go"))
+}
+
+fn syntax<'a>(source: &'a Source, error: &ParseError<'_>) -> Error<'a> {
+    Error {
+        kind: ErrorKind::Syntax,
+        path: &source.path,
+        position: Position::of(&source.text, error.span(&source.text).start),
+        message: error.message(),
+    }
+}
+
+#[test]
+fn test_read_dir() {
+    let dir = std::env::temp_dir().join(format!("go-parser-rs-package-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let files = [
+        ("point.go", "package geo\n\ntype Point struct{ X, Y float64 }\n"),
+        ("dist.go", "package geo\n\nimport \"math\"\n\nfunc (p Point) Dist() float64 {\n\treturn math.Hypot(p.X, p.Y)\n}\n"),
+        ("point_test.go", "package geo\n\nimport \"testing\"\n\nfunc TestDist(t *testing.T) {}\n"),
+        ("example_test.go", "package geo_test\n\nfunc Example() {}\n"),
+        ("broken.go", "package geo\n\nfunc f( {}\n"),
+        ("other.go", "\n  package shapes\n"),
+        ("_ignored.go", "package ignored\n"),
+        ("notes.txt", "package notes\n"),
+    ];
+    for (name, text) in files {
+        fs::write(dir.join(name), text).unwrap();
+    }
+
+    let sources = read_dir(&dir, false).unwrap();
+    let names: Vec<_> = sources
+        .files
+        .iter()
+        .map(|file| file.path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, ["broken.go", "dist.go", "other.go", "point.go"]);
+    let (package, errors) = sources.parse();
+    assert_eq!(package.name, "geo");
+    assert_eq!(package.files.len(), 3);
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            let name = error.path.file_name().unwrap().to_str().unwrap();
+            (error.kind, name, error.position.line, error.position.column)
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::Syntax, "broken.go", 3, 9),
+            (ErrorKind::MismatchedPackage, "other.go", 2, 11),
+        ]
+    );
+    let imports: Vec<_> = package
+        .files
+        .iter()
+        .map(|file| file.ast.imports.len())
+        .collect();
+    assert_eq!(imports, [0, 1, 0]);
+    let functions = package
+        .decls()
+        .filter(|(_, decl)| matches!(decl, TopLevel::Function(_)))
+        .count();
+    assert_eq!(functions, 1);
+    let resolution = resolve::resolve(package.source_files());
+    let info = crate::types::check(package.source_files(), &resolution);
+    assert!(info.errors.is_empty(), "{:?}", info.errors);
+
+    let sources = read_dir(&dir, true).unwrap();
+    let (package, errors) = sources.parse();
+    assert_eq!(package.files.len(), 4);
+    assert_eq!(errors.len(), 2);
+    fs::remove_dir_all(&dir).unwrap();
+}
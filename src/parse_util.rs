@@ -1,29 +1,254 @@
+use std::{cell::Cell, thread::LocalKey};
+
 use nom::{
-    bytes::complete::tag,
-    character::complete::{alphanumeric1, space0},
-    combinator::not,
+    branch::alt,
+    bytes::complete::{is_not, tag, take_until, take_while},
+    character::complete::{multispace1, satisfy, space1},
+    combinator::{eof, not, peek, recognize, verify},
+    multi::many0,
+    sequence::{delimited, pair},
     IResult,
 };
 
-pub fn reserved(keyword: &'static str) -> impl Fn(&str) -> IResult<&str, &str> {
+use crate::error::{expected, Expected, ParseError};
+
+pub(crate) const KEYWORDS: [&str; 25] = [
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+/// Operators and punctuation, longest first so that the first match is the longest one.
+const OPERATORS: [&str; 48] = [
+    "&^=", "<<=", ">>=", "...", "&&", "||", "<-", "++", "--", "==", "!=", "<=", ">=", ":=", "+=",
+    "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<", ">>", "&^", "+", "-", "*", "/", "%", "&", "|",
+    "^", "<", ">", "=", "!", "~", "(", ")", "[", "]", "{", "}", ",", ";", ".", ":",
+];
+
+/// Go inserts a semicolon when a line ends with one of these, see [`space_after`].
+const LINE_ENDING_OPERATORS: [&str; 5] = [")", "]", "}", "++", "--"];
+const LINE_ENDING_KEYWORDS: [&str; 4] = ["break", "continue", "fallthrough", "return"];
+
+/// Consumes spaces, tabs and comments, but never a newline so callers can still see line ends.
+/// A line comment stops right before its terminating newline.
+pub fn space0(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    recognize(many0(alt((space1, tag("\r"), comment))))(s)
+}
+
+/// Consumes white space including newlines, and comments.
+pub fn multispace0(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    recognize(many0(alt((multispace1, comment))))(s)
+}
+
+/// Skips the white space after a token. Newlines are kept after the tokens Go's automatic semicolon
+/// insertion applies to (identifiers, literals, `break`, `continue`, `fallthrough`, `return`, `++`, `--`,
+/// `)`, `]` and `}`) so that they can end a statement, and skipped after any other token.
+fn space_after<'a>(token: &str, s: &'a str) -> IResult<&'a str, &'a str, ParseError<'a>> {
+    if LINE_ENDING_OPERATORS.contains(&token) || LINE_ENDING_KEYWORDS.contains(&token) {
+        space0(s)
+    } else {
+        multispace0(s)
+    }
+}
+
+/// comment = "//" { any_char_except_newline } | "/*" { any_char } "*/" .
+pub fn comment(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    alt((
+        recognize(pair(tag("//"), alt((is_not("\n"), tag(""))))),
+        recognize(delimited(tag("/*"), take_until("*/"), tag("*/"))),
+    ))(s)
+}
+
+pub fn reserved(keyword: &'static str) -> impl Fn(&str) -> IResult<&str, &str, ParseError<'_>> {
     move |s: &str| {
-        let (s, keyword) = tag(keyword)(s)?;
-        let (s, _) = not(alphanumeric1)(s)?;
-        let (s, _) = space0(s)?;
+        let mut keyword_parser = expected(
+            Expected::Token(keyword),
+            pair(tag(keyword), not(satisfy(is_identifier_char))),
+        );
+        let (s, (keyword, _)) = keyword_parser(s)?;
+        let (s, _) = space_after(keyword, s)?;
         Ok((s, keyword))
     }
 }
 
-pub fn symbol(sym: &'static str) -> impl Fn(&str) -> IResult<&str, &str> {
+/// Parses an operator or a punctuation. Like Go's scanner, it reads the longest operator at the input,
+/// so `symbol("+")` does not match the beginning of `+=` or `++`.
+pub fn symbol(sym: &'static str) -> impl Fn(&str) -> IResult<&str, &str, ParseError<'_>> {
     move |s: &str| {
-        let (s, _) = tag(sym)(s)?;
-        let (s, _) = space0(s)?;
-        Ok((s, sym))
+        let mut operator = expected(Expected::Token(sym), verify(operator, |op: &str| op == sym));
+        let (s, op) = operator(s)?;
+        let (s, _) = space_after(sym, s)?;
+        Ok((s, op))
+    }
+}
+
+/// Reads the longest operator or punctuation at the start of the input.
+fn operator(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    match OPERATORS.iter().find(|op| s.starts_with(*op)) {
+        Some(op) => Ok((&s[op.len()..], &s[..op.len()])),
+        None => Err(nom::Err::Error(ParseError::new(
+            s,
+            Expected::Kind("operator"),
+        ))),
+    }
+}
+
+/// Consumes the ";" that ends a declaration or statement. A newline or the end of input ends one as well.
+pub fn terminator(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    let (s, _) = space0(s)?;
+    expected(
+        Expected::Kind("`;` or newline"),
+        alt((symbol(";"), tag("\n"), eof)),
+    )(s)
+}
+
+/// Parses `open { item ";" } close`, like a struct body or a grouped declaration.
+/// The ";" may be omitted before `close`.
+pub(crate) fn list_of<'a, T, F>(
+    open: &'static str,
+    item: F,
+    close: &'static str,
+) -> impl Fn(&'a str) -> IResult<&'a str, Vec<T>, ParseError<'a>>
+where
+    F: Fn(&'a str) -> IResult<&'a str, T, ParseError<'a>>,
+{
+    move |s: &'a str| {
+        let (mut s, _) = symbol(open)(s)?;
+        let mut items = vec![];
+        loop {
+            s = multispace0(s)?.0;
+            if let Ok((s, _)) = symbol(close)(s) {
+                return Ok((s, items));
+            }
+            let (rest, parsed) = item(s).map_err(|e| {
+                e.map(|e| nom::error::ParseError::or(e, ParseError::new(s, Expected::Token(close))))
+            })?;
+            let (rest, _) = alt((terminator, peek(symbol(close))))(rest)?;
+            items.push(parsed);
+            s = rest;
+        }
     }
 }
 
-pub fn identifier(s: &str) -> IResult<&str, &str> {
-    let (s, ident) = alphanumeric1(s)?;
+/// identifier = letter { letter | unicode_digit } .
+/// letter     = unicode_letter | "_" .
+/// Keywords are not identifiers.
+pub fn identifier(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    let letter = |c: char| c.is_alphabetic() || c == '_';
+    let word = recognize(pair(satisfy(letter), take_while(is_identifier_char)));
+    let not_keyword = verify(word, |word: &str| !KEYWORDS.contains(&word));
+    let (s, ident) = expected(Expected::Kind("identifier"), not_keyword)(s)?;
     let (s, _) = space0(s)?;
     Ok((s, ident))
 }
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Gives the previous value back to a thread-local when dropped, even when unwinding.
+pub(crate) struct Restore<T: Copy + 'static> {
+    key: &'static LocalKey<Cell<T>>,
+    previous: T,
+}
+
+impl<T: Copy + 'static> Restore<T> {
+    pub(crate) fn set(key: &'static LocalKey<Cell<T>>, value: T) -> Self {
+        let previous = key.with(|cell| cell.replace(value));
+        Restore { key, previous }
+    }
+}
+
+impl<T: Copy + 'static> Drop for Restore<T> {
+    fn drop(&mut self) {
+        let previous = self.previous;
+        self.key.with(|cell| cell.set(previous));
+    }
+}
+
+#[test]
+fn test_space0() {
+    assert_eq!(space0("  \tx"), Ok(("x", "  \t")));
+    assert_eq!(space0(" // line\nx"), Ok(("\nx", " // line")));
+    assert_eq!(space0("/* a\nb */ x"), Ok(("x", "/* a\nb */ ")));
+    assert_eq!(space0("\nx"), Ok(("\nx", "")));
+}
+
+#[test]
+fn test_terminator() {
+    assert_eq!(terminator("; x"), Ok(("x", ";")));
+    assert_eq!(terminator(" // done\nx"), Ok(("x", "\n")));
+    assert_eq!(terminator(""), Ok(("", "")));
+    assert_eq!(
+        terminator("x"),
+        Err(nom::Err::Error(ParseError::new(
+            "x",
+            Expected::Kind("`;` or newline")
+        )))
+    );
+}
+
+#[test]
+fn test_symbol_reads_longest_operator() {
+    assert_eq!(symbol("+")("+ 1"), Ok(("1", "+")));
+    assert!(symbol("+")("+= 1").is_err());
+    assert!(symbol("+")("++").is_err());
+    assert!(symbol("&")("&&x").is_err());
+    assert!(symbol("<")("<-ch").is_err());
+    assert_eq!(symbol(".")(".x"), Ok(("x", ".")));
+}
+
+#[test]
+fn test_newlines_after_tokens() {
+    assert_eq!(symbol("+")("+\n\t1"), Ok(("1", "+")));
+    assert_eq!(symbol(")")(")\n1"), Ok(("\n1", ")")));
+    assert_eq!(reserved("func")("func\nf"), Ok(("f", "func")));
+    assert_eq!(reserved("return")("return\nf"), Ok(("\nf", "return")));
+    assert_eq!(identifier("x // c\ny"), Ok(("\ny", "x")));
+}
+
+#[test]
+fn test_list_of() {
+    let list = list_of("(", identifier, ")");
+    assert_eq!(list("(a; b\n\n c)"), Ok(("", vec!["a", "b", "c"])));
+    assert_eq!(list("(\n)\n"), Ok(("\n", vec![])));
+    let e = match list("(a\n") {
+        Err(nom::Err::Error(e)) => e,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(
+        e.message(),
+        "expected one of `)`, identifier, found end of file"
+    );
+}
+
+#[test]
+fn test_identifier() {
+    assert_eq!(identifier("_x1 "), Ok(("", "_x1")));
+    assert_eq!(identifier("名前"), Ok(("", "名前")));
+    assert!(identifier("func").is_err());
+    assert_eq!(identifier("funcs"), Ok(("", "funcs")));
+    assert!(identifier("1x").is_err());
+}
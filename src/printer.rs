@@ -0,0 +1,1940 @@
+//! Printing of syntax trees as Go source, following the layout of `gofmt`.
+//!
+//! Lines are indented with tabs and the operators of a binary expression are spaced by their
+//! precedence, so `a*b + c` keeps the tighter binding visible. Struct fields and the specs of a
+//! grouped declaration are aligned in columns, and imports are printed as one block, sorted within
+//! the groups separated by empty lines.
+//!
+//! The tree keeps no positions, so the printer decides the line breaks itself: top-level
+//! declarations are separated by an empty line, blocks are split over lines, and composite
+//! literals are printed on a single line. Only doc comments are part of the tree, so [`print`]
+//! leaves the other comments out; [`print_with_comments`] reads them from the source of a file.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, expression::Expression, printer::print};
+//! let (_, expr) = Expression::parse("a*b+c*(d+e)").unwrap();
+//! assert_eq!(print(&expr), "a*b + c*(d+e)");
+//! ```
+
+use crate::{
+    comment::CommentGroup,
+    declaration::{ConstDecl, Declaration, TypeDecl, TypeSpec, VarDecl},
+    expression::{
+        operand::{Element, KeyedElement, LiteralValue, Operand, OperandName},
+        Expression, PrimaryExpr, UnaryExpr,
+    },
+    identifier::QualifiedIdent,
+    literals::{integer::IntLit, Literal},
+    statement::{
+        control::{CaseClause, Else, ForClause, ForStmt, IfStmt, RangeVars},
+        simple::SimpleStmt,
+        Block, Statement,
+    },
+    tokenize::{tokenizer::tokenize_lossless, Token},
+    typ::{
+        ChanDir, FieldDecl, GoType, InterfaceElem, ParameterDecl, Signature, TypeElem, TypeLit,
+        TypeName, TypeParamDecl,
+    },
+    visit::{walk_operand, Visitor},
+    Function, ImportDeclaration, Receiver, SourceFile, TopLevel,
+};
+
+/// Returns the Go source of `node`.
+pub fn print<N: Print + ?Sized>(node: &N) -> String {
+    let mut printer = Printer::default();
+    node.print(&mut printer);
+    printer.finish()
+}
+
+/// Returns the Go source of `file` with the comments of `source`, the text `file` was parsed from.
+///
+/// A comment on a line of its own is printed on its own line, before the token that follows it.
+/// A comment after a token on the same line stays at the end of the line the token is printed on,
+/// and such comments are aligned in a column over consecutive lines.
+///
+/// ```
+/// use go_parser_rs::{astable::ASTable, printer::print_with_comments, SourceFile};
+/// let src = "package p\ntype T struct {\n\tA int // the a\n\tBc string // the bc\n}\n";
+/// let (_, file) = SourceFile::parse(src).unwrap();
+/// assert_eq!(
+///     print_with_comments(&file, src),
+///     "package p\n\ntype T struct {\n\tA  int    // the a\n\tBc string // the bc\n}\n"
+/// );
+/// ```
+pub fn print_with_comments(file: &SourceFile, source: &str) -> String {
+    let mut printer = Printer {
+        source: Some(Source::new(source)),
+        ..Printer::default()
+    };
+    file.print(&mut printer);
+    printer.comments_before(source.len(), 0);
+    printer.finish()
+}
+
+/// A node that can be printed as Go source, see [`print`].
+pub trait Print {
+    fn print(&self, printer: &mut Printer);
+}
+
+/// Collects the printed source as lines of cells, the way `text/tabwriter` does for `gofmt`.
+///
+/// A cell ends with an indentation tab or with an alignment tab. The cells of one column are
+/// aligned over the consecutive lines that have it, and a new section, started by
+/// [`Printer::formfeed`], ends all columns.
+#[derive(Default)]
+pub struct Printer<'s> {
+    lines: Vec<Line>,
+    cells: Vec<Cell>,
+    cell: String,
+    line_started: bool,
+    indent: usize,
+    /// The last printed operator, if printing a token right after it could merge the two.
+    last_operator: Option<char>,
+    /// The source being printed, when its comments are printed too.
+    source: Option<Source<'s>>,
+    /// The comments to print at the end of the current line.
+    trailing: Vec<&'s str>,
+    /// The number of lines holding only comments.
+    comment_lines: usize,
+}
+
+/// The tokens and comments of a source, read along with the printed text.
+struct Source<'s> {
+    text: &'s str,
+    tokens: Vec<(usize, &'s str)>,
+    comments: Vec<(usize, &'s str)>,
+    next_token: usize,
+    next_comment: usize,
+    /// The comments printed out of order, with the import they follow.
+    taken: Vec<usize>,
+    /// The end of the last token or comment read, and the index of the line it is printed on.
+    last: Option<(usize, usize)>,
+}
+
+impl<'s> Source<'s> {
+    fn new(text: &'s str) -> Self {
+        let mut tokens = vec![];
+        let mut comments = vec![];
+        let mut offset = 0;
+        for token in tokenize_lossless(text) {
+            let token_text = token.text();
+            match token {
+                Token::Whitespace(_) => {}
+                Token::Comment(_) => comments.push((offset, token_text)),
+                _ => tokens.push((offset, token_text)),
+            }
+            offset += token_text.len();
+        }
+        Source {
+            text,
+            tokens,
+            comments,
+            next_token: 0,
+            next_comment: 0,
+            taken: vec![],
+            last: None,
+        }
+    }
+
+    fn offset_of(&self, text: &str) -> Option<usize> {
+        let offset = (text.as_ptr() as usize).checked_sub(self.text.as_ptr() as usize)?;
+        if offset + text.len() <= self.text.len() {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    /// The next token if `text` starts with it, past the separators `,` and `;` unless `text`
+    /// starts with one of them.
+    fn peek(&self, text: &str) -> Option<(usize, usize, &'s str)> {
+        let mut i = self.next_token;
+        while let Some(&(offset, token)) = self.tokens.get(i) {
+            if text.starts_with(token) {
+                return Some((i, offset, token));
+            }
+            if token != "," && token != ";" {
+                break;
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Takes the comments on the line of the source after `text`, a slice of the source.
+    fn line_comments(&mut self, text: &str) -> Vec<&'s str> {
+        let mut comments = vec![];
+        if let Some(offset) = self.offset_of(text) {
+            let end = offset + text.len();
+            let line_end = self.text[end..]
+                .find('\n')
+                .map_or(self.text.len(), |i| end + i);
+            for &(start, comment) in &self.comments {
+                if start >= end && start < line_end && !self.taken.contains(&start) {
+                    self.taken.push(start);
+                    comments.push(comment);
+                }
+            }
+        }
+        comments
+    }
+
+    /// Whether an empty line separates `a` and `b`, slices of the source with `a` first.
+    fn empty_line_between(&self, a: &str, b: &str) -> bool {
+        match (self.offset_of(a), self.offset_of(b)) {
+            (Some(a_offset), Some(b_offset)) if a_offset + a.len() <= b_offset => {
+                let lines: Vec<_> = self.text[a_offset + a.len()..b_offset]
+                    .split('\n')
+                    .collect();
+                // The first and the last line hold `a` and `b`.
+                lines.len() > 2
+                    && lines[1..lines.len() - 1]
+                        .iter()
+                        .any(|line| line.trim().is_empty())
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves past the tokens and comments that start before `end`.
+    fn skip_to(&mut self, end: usize) {
+        let next_token = self.tokens.partition_point(|&(offset, _)| offset < end);
+        self.next_token = self.next_token.max(next_token);
+        let next_comment = self.comments.partition_point(|&(offset, _)| offset < end);
+        self.next_comment = self.next_comment.max(next_comment);
+    }
+}
+
+struct Line {
+    cells: Vec<Cell>,
+    /// Whether a new section starts after this line.
+    section_end: bool,
+}
+
+struct Cell {
+    text: String,
+    /// Whether the cell is ended by an indentation tab rather than an alignment tab.
+    indentation: bool,
+}
+
+impl Cell {
+    fn width(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+const TAB_WIDTH: usize = 8;
+
+impl<'s> Printer<'s> {
+    fn text(&mut self, text: &str) {
+        self.read_source(text);
+        if !self.line_started {
+            self.line_started = true;
+            for _ in 0..self.indent {
+                self.cells.push(Cell {
+                    text: String::new(),
+                    indentation: true,
+                });
+            }
+        }
+        if let (Some(prev), Some(next)) = (self.last_operator, text.chars().next()) {
+            // `- -x` must not become `--x`, and `a / *p` must not start a comment.
+            if matches!(
+                (prev, next),
+                ('+', '+')
+                    | ('-', '-')
+                    | ('/', '*')
+                    | ('<', '-')
+                    | ('<', '<')
+                    | ('&', '&')
+                    | ('&', '^')
+            ) {
+                self.cell.push(' ');
+            }
+        }
+        self.last_operator = None;
+        self.cell.push_str(text);
+    }
+
+    /// Prints an operator, remembering it for the next token.
+    fn operator(&mut self, op: &str) {
+        self.text(op);
+        if let [c] = op.as_bytes() {
+            self.last_operator = Some(*c as char);
+        }
+    }
+
+    fn blank(&mut self) {
+        self.cell.push(' ');
+        self.last_operator = None;
+    }
+
+    /// Ends the current cell so that the next one is aligned with the cells of the lines around.
+    fn vtab(&mut self) {
+        let text = std::mem::take(&mut self.cell);
+        self.cells.push(Cell {
+            text,
+            indentation: false,
+        });
+    }
+
+    fn newline(&mut self) {
+        self.line_break(false);
+    }
+
+    /// Ends the line and the current section, so that the following lines are aligned on their own.
+    fn formfeed(&mut self) {
+        self.line_break(true);
+    }
+
+    fn line_break(&mut self, section_end: bool) {
+        self.vtab();
+        if !self.trailing.is_empty() {
+            self.cells.push(Cell {
+                text: self.trailing.join(" "),
+                indentation: false,
+            });
+            self.trailing.clear();
+        }
+        let cells = std::mem::take(&mut self.cells);
+        self.lines.push(Line { cells, section_end });
+        self.line_started = false;
+        self.last_operator = None;
+    }
+
+    fn finish(mut self) -> String {
+        self.vtab();
+        let last = Line {
+            cells: std::mem::take(&mut self.cells),
+            section_end: true,
+        };
+        let mut out = String::new();
+        let mut section = vec![];
+        for line in self.lines.into_iter() {
+            let section_end = line.section_end;
+            section.push(line);
+            if section_end {
+                format_section(&mut out, &section);
+                section.clear();
+            }
+        }
+        section.push(last);
+        format_section(&mut out, &section);
+        out.pop();
+        out
+    }
+
+    /// Prints `items` separated by `", "`.
+    fn list<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, &T)) {
+        for (i, x) in items.iter().enumerate() {
+            if i > 0 {
+                self.text(",");
+                self.blank();
+            }
+            item(self, x);
+        }
+    }
+
+    /// Prints `items` one per line. A new section starts after an item that spans several lines.
+    fn lines<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, &T)) {
+        let mut multi_line = true;
+        for x in items {
+            self.line_break(multi_line);
+            // Lines holding only comments don't make an item span several lines.
+            let start = self.lines.len() - self.comment_lines;
+            item(self, x);
+            multi_line = self.lines.len() - self.comment_lines > start;
+        }
+    }
+
+    fn doc(&mut self, doc: &Option<CommentGroup>) {
+        if let Some(doc) = doc {
+            for comment in &doc.list {
+                self.text(comment.0);
+                self.newline();
+            }
+        }
+    }
+
+    fn names(&mut self, names: &[&str]) {
+        self.list(names, |p, name| p.text(name));
+    }
+
+    /// Reads the tokens of the source that `text` is made of, printing the comments before them.
+    /// A slice of the source is read up to its end, and another text as far as its tokens match.
+    fn read_source(&mut self, text: &str) {
+        let source = match &mut self.source {
+            Some(source) => source,
+            None => return,
+        };
+        if let Some(offset) = source.offset_of(text) {
+            self.comments_before(offset, 0);
+            let line = self.lines.len();
+            let source = self.source.as_mut().unwrap();
+            source.skip_to(offset + text.len());
+            source.last = Some((offset + text.len(), line));
+            return;
+        }
+        let mut rest = text.trim_start();
+        while let Some((i, offset, token)) = self.source.as_ref().and_then(|s| s.peek(rest)) {
+            // The comments before a closing token belong to the lines inside.
+            let indent = matches!(token, "}" | ")") as usize;
+            self.comments_before(offset, indent);
+            let line = self.lines.len();
+            let source = self.source.as_mut().unwrap();
+            source.next_token = i + 1;
+            source.last = Some((offset + token.len(), line));
+            rest = rest[token.len()..].trim_start();
+        }
+    }
+
+    /// Prints the comments of the source that start before `offset` and are not printed yet. The
+    /// comments on a line of their own are indented `indent` levels deeper than the current line.
+    fn comments_before(&mut self, offset: usize, indent: usize) {
+        let source = match &mut self.source {
+            Some(source) => source,
+            None => return,
+        };
+        while let Some(&(start, comment)) = source.comments.get(source.next_comment) {
+            if start >= offset {
+                break;
+            }
+            source.next_comment += 1;
+            if source.taken.contains(&start) {
+                continue;
+            }
+            let end = start + comment.len();
+            let same_line = match source.last {
+                Some((last_end, line)) if !source.text[last_end..start].contains('\n') => {
+                    Some(line)
+                }
+                _ => None,
+            };
+            let line = match same_line {
+                Some(line) if line < self.lines.len() => {
+                    let cells = &mut self.lines[line].cells;
+                    match cells.last_mut() {
+                        Some(cell)
+                            if cell.text.starts_with("//") || cell.text.starts_with("/*") =>
+                        {
+                            cell.text.push(' ');
+                            cell.text.push_str(comment);
+                        }
+                        _ => cells.push(Cell {
+                            text: comment.to_string(),
+                            indentation: false,
+                        }),
+                    }
+                    line
+                }
+                // A comment can't start a line in the middle of the current one, so it ends it.
+                _ if same_line.is_some() || self.line_started => {
+                    if comment.starts_with("/*") && !comment.contains('\n') {
+                        // The comment keeps the spaces around it in the source.
+                        if source.text[..start].ends_with(char::is_whitespace) {
+                            self.cell.push(' ');
+                        }
+                        self.cell.push_str(comment);
+                        if source.text[end..].starts_with(char::is_whitespace) {
+                            self.cell.push(' ');
+                        }
+                    } else {
+                        self.trailing.push(comment);
+                    }
+                    self.lines.len()
+                }
+                _ => {
+                    let before = &source.text[..start];
+                    let blank_before = before[before.trim_end().len()..].matches('\n').count() > 1;
+                    let after_blank = self
+                        .lines
+                        .last()
+                        .is_none_or(|line| line.cells.iter().all(|cell| cell.text.is_empty()));
+                    if blank_before && !after_blank {
+                        self.lines.push(Line {
+                            cells: vec![],
+                            section_end: true,
+                        });
+                        self.comment_lines += 1;
+                    }
+                    let mut cells: Vec<_> = (0..self.indent + indent)
+                        .map(|_| Cell {
+                            text: String::new(),
+                            indentation: true,
+                        })
+                        .collect();
+                    cells.push(Cell {
+                        text: comment.to_string(),
+                        indentation: false,
+                    });
+                    self.lines.push(Line {
+                        cells,
+                        section_end: false,
+                    });
+                    self.comment_lines += 1;
+                    let space = &source.text[end..];
+                    let space = &space[..space.len() - space.trim_start().len()];
+                    if space.matches('\n').count() > 1 {
+                        self.lines.push(Line {
+                            cells: vec![],
+                            section_end: true,
+                        });
+                        self.comment_lines += 1;
+                    }
+                    self.lines.len() - 1
+                }
+            };
+            source.last = Some((end, line));
+        }
+    }
+}
+
+/// Formats the lines of a section like `text/tabwriter` with the flags used by `gofmt`:
+/// a padding of one space, and leading empty cells printed as tabs.
+fn format_section(out: &mut String, lines: &[Line]) {
+    format_columns(out, lines, &mut vec![]);
+}
+
+fn format_columns(out: &mut String, lines: &[Line], widths: &mut Vec<usize>) {
+    let column = widths.len();
+    let has_column = |line: &Line| column + 1 < line.cells.len();
+    let mut start = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        if !has_column(&lines[i]) {
+            i += 1;
+            continue;
+        }
+        write_lines(out, &lines[start..i], widths);
+        start = i;
+        let mut width = 0;
+        let mut discardable = true;
+        while i < lines.len() && has_column(&lines[i]) {
+            let cell = &lines[i].cells[column];
+            width = width.max(cell.width() + 1);
+            discardable &= cell.width() == 0 && !cell.indentation;
+            i += 1;
+        }
+        widths.push(if discardable { 0 } else { width });
+        format_columns(out, &lines[start..i], widths);
+        widths.pop();
+        start = i;
+    }
+    write_lines(out, &lines[start..], widths);
+}
+
+fn write_lines(out: &mut String, lines: &[Line], widths: &[usize]) {
+    for line in lines {
+        let mut indenting = true;
+        for (j, cell) in line.cells.iter().enumerate() {
+            indenting &= cell.text.is_empty();
+            out.push_str(&cell.text);
+            if let Some(&width) = widths.get(j) {
+                if indenting {
+                    let tabs = width.div_ceil(TAB_WIDTH);
+                    out.extend(std::iter::repeat_n('\t', tabs));
+                } else {
+                    out.extend(std::iter::repeat_n(' ', width - cell.width()));
+                }
+            }
+        }
+        out.push('\n');
+    }
+}
+
+impl Print for SourceFile<'_> {
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, printer::print, SourceFile};
+    /// let src = "package main\nimport \"os\"\nimport \"fmt\"\nfunc main() { fmt.Println(os.Args) }\n";
+    /// let (_, file) = SourceFile::parse(src).unwrap();
+    /// assert_eq!(
+    ///     print(&file),
+    ///     "package main\n\nimport (\n\t\"fmt\"\n\t\"os\"\n)\n\nfunc main() {\n\tfmt.Println(os.Args)\n}\n"
+    /// );
+    /// ```
+    fn print(&self, p: &mut Printer) {
+        p.doc(&self.doc);
+        p.text("package");
+        p.blank();
+        p.text(self.package);
+        if !self.imports.is_empty() {
+            p.formfeed();
+            p.formfeed();
+            p.imports(&self.imports);
+        }
+        for decl in &self.decls {
+            p.formfeed();
+            p.formfeed();
+            decl.print(p);
+        }
+        p.newline();
+    }
+}
+
+impl Printer<'_> {
+    /// Prints the imports as one block. The groups separated by an empty line in the source are
+    /// kept, each sorted by path; without the source, the imports form a single group.
+    fn imports(&mut self, imports: &[ImportDeclaration]) {
+        self.text("import");
+        self.blank();
+        if let [import] = imports {
+            import.print(self);
+            return;
+        }
+        let mut groups: Vec<Vec<_>> = vec![];
+        for (i, import) in imports.iter().enumerate() {
+            let separated = match (&self.source, i.checked_sub(1)) {
+                (Some(source), Some(prev)) => {
+                    source.empty_line_between(imports[prev].path, import.path)
+                }
+                _ => false,
+            };
+            match groups.last_mut() {
+                Some(group) if !separated => group.push(import),
+                _ => groups.push(vec![import]),
+            }
+        }
+        self.text("(");
+        self.indent += 1;
+        for (i, mut group) in groups.into_iter().enumerate() {
+            group.sort_by_key(|import| (import.path, import.pkg_name_opt));
+            let group: Vec<_> = group
+                .into_iter()
+                .map(|import| {
+                    let comments = match &mut self.source {
+                        Some(source) => source.line_comments(import.path),
+                        None => vec![],
+                    };
+                    (import, comments)
+                })
+                .collect();
+            if i > 0 {
+                self.formfeed();
+            }
+            self.lines(&group, |p, (import, comments)| {
+                import.print(p);
+                p.trailing.extend(comments);
+            });
+        }
+        self.indent -= 1;
+        self.formfeed();
+        self.text(")");
+    }
+}
+
+impl Print for ImportDeclaration<'_> {
+    fn print(&self, p: &mut Printer) {
+        if let Some(name) = self.pkg_name_opt {
+            p.text(name);
+            p.blank();
+        }
+        p.read_source(self.path);
+        p.text(&format!("\"{}\"", self.path));
+    }
+}
+
+impl Print for TopLevel<'_> {
+    fn print(&self, p: &mut Printer) {
+        match self {
+            TopLevel::Pkg(name) => {
+                p.text("package");
+                p.blank();
+                p.text(name);
+            }
+            TopLevel::Import(imports) => p.imports(imports),
+            TopLevel::Function(function) => function.print(p),
+            TopLevel::Declaration(decl) => decl.print(p),
+            TopLevel::BadDecl(text) => p.text(text),
+        }
+    }
+}
+
+impl Print for Function<'_> {
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, printer::print, SourceFile, TopLevel};
+    /// let (_, file) = SourceFile::parse("package m\nfunc (l *List[T]) Len()(n int){return len( l.items )}").unwrap();
+    /// let function = match &file.decls[0] {
+    ///     TopLevel::Function(function) => function,
+    ///     _ => unreachable!(),
+    /// };
+    /// assert_eq!(print(function), "func (l *List[T]) Len() (n int) {\n\treturn len(l.items)\n}");
+    /// ```
+    fn print(&self, p: &mut Printer) {
+        p.doc(&self.doc);
+        p.text("func");
+        p.blank();
+        if let Some(receiver) = &self.receiver {
+            receiver.print(p);
+            p.blank();
+        }
+        p.text(self.name);
+        p.type_params(&self.type_params);
+        p.signature(&self.signature);
+        if let Some(body) = &self.body {
+            p.blank();
+            p.body(body);
+        }
+    }
+}
+
+impl Print for Receiver<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.text("(");
+        if let Some(name) = self.name {
+            p.text(name);
+            p.blank();
+        }
+        if self.pointer {
+            p.text("*");
+        }
+        p.text(self.base_type);
+        if !self.type_params.is_empty() {
+            p.text("[");
+            p.names(&self.type_params);
+            p.text("]");
+        }
+        p.text(")");
+    }
+}
+
+impl Print for Declaration<'_> {
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, declaration::Declaration, printer::print};
+    /// let (_, decl) = Declaration::parse("const (\nA int = iota\nBig = 2\nC = 1<<10\n)").unwrap();
+    /// assert_eq!(print(&decl), "const (\n\tA   int = iota\n\tBig     = 2\n\tC       = 1 << 10\n)");
+    /// ```
+    fn print(&self, p: &mut Printer) {
+        match self {
+            Declaration::ConstDecl(decl) => decl.print(p),
+            Declaration::TypeDecl(decl) => decl.print(p),
+            Declaration::VarDecl(decl) => decl.print(p),
+        }
+    }
+}
+
+impl Print for ConstDecl<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.doc(&self.doc);
+        p.value_specs("const", &self.specs, |spec| {
+            (&spec.identifiers, &spec.typ, &spec.values)
+        });
+    }
+}
+
+impl Print for VarDecl<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.doc(&self.doc);
+        p.value_specs("var", &self.specs, |spec| {
+            (&spec.identifiers, &spec.typ, &spec.values)
+        });
+    }
+}
+
+/// The names, type and values of a [`ConstSpec`] or [`VarSpec`].
+type ValueSpec<'s, 'a> = (&'s [&'a str], &'s Option<GoType<'a>>, &'s [Expression<'a>]);
+
+impl Printer<'_> {
+    fn value_specs<'s, 'a: 's, S>(
+        &mut self,
+        keyword: &str,
+        specs: &'s [S],
+        parts: impl Fn(&'s S) -> ValueSpec<'s, 'a>,
+    ) {
+        self.text(keyword);
+        self.blank();
+        if let [spec] = specs {
+            let (names, typ, values) = parts(spec);
+            self.names(names);
+            if let Some(typ) = typ {
+                self.blank();
+                self.typ(typ);
+            }
+            if !values.is_empty() {
+                self.blank();
+                self.operator("=");
+                self.blank();
+                self.list(values, |p, value| p.expr(value));
+            }
+            return;
+        }
+        let specs: Vec<_> = specs.iter().map(parts).collect();
+        let keep_type = keep_type_column(&specs);
+        self.group(&specs, |p, (i, (names, typ, values))| {
+            p.names(names);
+            if typ.is_some() || keep_type[i] {
+                p.vtab();
+            }
+            if let Some(typ) = typ {
+                p.typ(typ);
+            }
+            if !values.is_empty() {
+                p.vtab();
+                p.operator("=");
+                p.blank();
+                p.list(values, |p, value| p.expr(value));
+            }
+        });
+    }
+
+    /// Prints the specs of a grouped declaration, one per line between parentheses.
+    fn group<T>(&mut self, specs: &[T], mut spec: impl FnMut(&mut Self, (usize, &T))) {
+        self.text("(");
+        if !specs.is_empty() {
+            self.indent += 1;
+            let specs: Vec<_> = specs.iter().enumerate().collect();
+            self.lines(&specs, |p, &(i, s)| spec(p, (i, s)));
+            self.indent -= 1;
+            self.formfeed();
+        }
+        self.text(")");
+    }
+}
+
+/// Whether each spec of a group keeps an empty type column, so that the values of a run of specs
+/// with values stay aligned when some of them have a type, as `gofmt` does.
+fn keep_type_column(specs: &[ValueSpec]) -> Vec<bool> {
+    let mut keep = vec![false; specs.len()];
+    let mut run_start = None;
+    let mut keep_type = false;
+    for (i, (_, typ, values)) in specs.iter().enumerate() {
+        if !values.is_empty() {
+            if run_start.is_none() {
+                run_start = Some(i);
+                keep_type = false;
+            }
+        } else if let Some(start) = run_start.take() {
+            keep[start..i].iter_mut().for_each(|k| *k = keep_type);
+        }
+        keep_type |= typ.is_some();
+    }
+    if let Some(start) = run_start {
+        keep[start..].iter_mut().for_each(|k| *k = keep_type);
+    }
+    keep
+}
+
+impl Print for TypeDecl<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.doc(&self.doc);
+        p.text("type");
+        p.blank();
+        if let [spec] = self.specs.as_slice() {
+            p.type_spec(spec, false);
+        } else {
+            p.group(&self.specs, |p, (_, spec)| p.type_spec(spec, true));
+        }
+    }
+}
+
+impl Printer<'_> {
+    fn type_spec(&mut self, spec: &TypeSpec, aligned: bool) {
+        let (name, type_params, typ, alias) = match spec {
+            TypeSpec::AliasDecl {
+                name,
+                type_params,
+                typ,
+            } => (name, type_params, typ, true),
+            TypeSpec::TypeDef {
+                name,
+                type_params,
+                typ,
+            } => (name, type_params, typ, false),
+        };
+        self.text(name);
+        self.type_params(type_params);
+        // `type A[P *C] T` would read as an array type, which a trailing comma rules out.
+        if let [TypeParamDecl {
+            identifiers,
+            constraint: TypeElem(terms),
+        }] = type_params.as_slice()
+        {
+            if let ([_], [term]) = (identifiers.as_slice(), terms.as_slice()) {
+                if !term.tilde && matches!(term.go_type, GoType::TypeLit(TypeLit::PointerType(_))) {
+                    self.cell.insert(self.cell.len() - 1, ',');
+                }
+            }
+        }
+        if aligned {
+            self.vtab();
+        } else {
+            self.blank();
+        }
+        if alias {
+            self.operator("=");
+            self.blank();
+        }
+        self.typ(typ);
+    }
+
+    fn type_params(&mut self, type_params: &[TypeParamDecl]) {
+        if type_params.is_empty() {
+            return;
+        }
+        self.text("[");
+        self.list(type_params, |p, param| {
+            p.names(&param.identifiers);
+            p.blank();
+            p.type_elem(&param.constraint);
+        });
+        self.text("]");
+    }
+
+    fn type_elem(&mut self, elem: &TypeElem) {
+        for (i, term) in elem.0.iter().enumerate() {
+            if i > 0 {
+                self.blank();
+                self.operator("|");
+                self.blank();
+            }
+            if term.tilde {
+                self.text("~");
+            }
+            self.typ(&term.go_type);
+        }
+    }
+
+    fn signature(&mut self, signature: &Signature) {
+        self.parameters(&signature.params);
+        match signature.results.as_slice() {
+            [] => {}
+            [ParameterDecl {
+                identifiers: None,
+                is_variadic: false,
+                go_type,
+            }] => {
+                self.blank();
+                self.typ(go_type);
+            }
+            results => {
+                self.blank();
+                self.parameters(results);
+            }
+        }
+    }
+
+    fn parameters(&mut self, params: &[ParameterDecl]) {
+        self.text("(");
+        self.list(params, |p, param| {
+            if let Some(names) = &param.identifiers {
+                p.names(names);
+                p.blank();
+            }
+            if param.is_variadic {
+                p.text("...");
+            }
+            p.typ(&param.go_type);
+        });
+        self.text(")");
+    }
+}
+
+impl Print for GoType<'_> {
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, printer::print, typ::GoType};
+    /// let (_, typ) = GoType::parse("map[ string ] * pkg.List[ int ]").unwrap();
+    /// assert_eq!(print(&typ), "map[string]*pkg.List[int]");
+    /// let (_, typ) = GoType::parse("struct {\nName string `json:\"name\"`\nAge, Height int\nio.Reader\n}").unwrap();
+    /// assert_eq!(
+    ///     print(&typ),
+    ///     "struct {\n\tName        string `json:\"name\"`\n\tAge, Height int\n\tio.Reader\n}"
+    /// );
+    /// ```
+    fn print(&self, p: &mut Printer) {
+        p.typ(self)
+    }
+}
+
+impl Printer<'_> {
+    fn typ(&mut self, typ: &GoType) {
+        match typ {
+            GoType::TypeName(name) => self.type_name(name),
+            GoType::Instantiation { name, type_args } => {
+                self.type_name(name);
+                self.text("[");
+                self.list(type_args, |p, arg| p.typ(arg));
+                self.text("]");
+            }
+            GoType::TypeLit(lit) => self.type_lit(lit),
+        }
+    }
+
+    fn type_name(&mut self, name: &TypeName) {
+        match name {
+            TypeName::Identifier(name) => self.text(name),
+            TypeName::QualifiedIdent(name) => self.qualified_ident(name),
+        }
+    }
+
+    fn qualified_ident(&mut self, name: &QualifiedIdent) {
+        self.text(name.package_name);
+        self.text(".");
+        self.text(name.identifier);
+    }
+
+    fn type_lit(&mut self, lit: &TypeLit) {
+        match lit {
+            TypeLit::ArrayType { len, elem } => {
+                self.text("[");
+                match len {
+                    Some(len) => self.expr(len),
+                    None => self.text("..."),
+                }
+                self.text("]");
+                self.typ(elem);
+            }
+            TypeLit::SliceType(elem) => {
+                self.text("[]");
+                self.typ(elem);
+            }
+            TypeLit::StructType(fields) => {
+                self.text("struct");
+                self.field_list(fields, |p, field| p.field_decl(field));
+            }
+            TypeLit::PointerType(base) => {
+                self.operator("*");
+                self.typ(base);
+            }
+            TypeLit::FunctionType(signature) => {
+                self.text("func");
+                self.signature(signature);
+            }
+            TypeLit::InterfaceType(elems) => {
+                self.text("interface");
+                self.field_list(elems, |p, elem| match elem {
+                    InterfaceElem::Method { name, signature } => {
+                        p.text(name);
+                        p.signature(signature);
+                    }
+                    InterfaceElem::Type(elem) => p.type_elem(elem),
+                });
+            }
+            TypeLit::MapType { key, value } => {
+                self.text("map[");
+                self.typ(key);
+                self.text("]");
+                self.typ(value);
+            }
+            TypeLit::ChannelType { dir, elem } => {
+                self.text(match dir {
+                    ChanDir::Both => "chan",
+                    ChanDir::Send => "chan<-",
+                    ChanDir::Recv => "<-chan",
+                });
+                self.blank();
+                self.typ(elem);
+            }
+        }
+    }
+
+    /// Prints the body of a struct or interface type: `{}` when empty, or one item per line.
+    fn field_list<T>(&mut self, items: &[T], item: impl FnMut(&mut Self, &T)) {
+        if items.is_empty() {
+            self.text("{}");
+            return;
+        }
+        self.blank();
+        self.text("{");
+        self.indent += 1;
+        self.lines(items, item);
+        self.indent -= 1;
+        self.formfeed();
+        self.text("}");
+    }
+
+    fn field_decl(&mut self, field: &FieldDecl) {
+        if !field.identifiers.is_empty() {
+            self.names(&field.identifiers);
+            self.vtab();
+        }
+        self.typ(&field.go_type);
+        if let Some(tag) = field.tag {
+            self.vtab();
+            self.text(tag);
+        }
+    }
+}
+
+impl Print for Expression<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.expr(self)
+    }
+}
+
+impl Print for PrimaryExpr<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.primary_expr(self, 1)
+    }
+}
+
+/// binary_op precedence, from `||` with 1 to the multiplication operators with 5.
+fn precedence(op: &str) -> usize {
+    match op {
+        "||" => 1,
+        "&&" => 2,
+        "==" | "!=" | "<" | "<=" | ">" | ">=" => 3,
+        "+" | "-" | "|" | "^" => 4,
+        _ => 5,
+    }
+}
+
+/// Binary expressions at a `depth` below this precedence get blanks around their operator.
+/// This is `gofmt`'s rule: an expression mixing additions and multiplications only spaces out
+/// the additions at the top level, and nested expressions are spaced only below precedence 4.
+fn cutoff(expr: &Expression, depth: usize) -> usize {
+    let (has4, has5, max_problem) = walk_binary(expr);
+    if max_problem > 0 {
+        max_problem + 1
+    } else if has4 && has5 {
+        if depth == 1 {
+            5
+        } else {
+            4
+        }
+    } else if depth == 1 {
+        6
+    } else {
+        4
+    }
+}
+
+/// Finds the precedences used in a binary expression, and whether an operator next to a unary
+/// operator would merge with it without a blank, like the `/*` in `a / *p`.
+fn walk_binary(expr: &Expression) -> (bool, bool, usize) {
+    let (left, op, right) = match expr {
+        Expression::BinExpr { left, op, right } => (left, op, right),
+        _ => return (false, false, 0),
+    };
+    let prec = precedence(op);
+    let (mut has4, mut has5, mut max_problem) = (prec == 4, prec == 5, 0);
+    let mut merge = |(h4, h5, problem): (bool, bool, usize)| {
+        has4 |= h4;
+        has5 |= h5;
+        max_problem = max_problem.max(problem);
+    };
+    if let Expression::BinExpr { op: left_op, .. } = &**left {
+        if precedence(left_op) >= prec {
+            merge(walk_binary(left));
+        }
+    }
+    match &**right {
+        Expression::BinExpr { op: right_op, .. } if precedence(right_op) > prec => {
+            merge(walk_binary(right))
+        }
+        Expression::UnaryExpr(UnaryExpr::Unary(unary)) => {
+            match format!("{}{}", op, unary.op).as_str() {
+                "/*" | "&&" | "&^" => max_problem = 5,
+                "++" | "--" => max_problem = max_problem.max(4),
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+    (has4, has5, max_problem)
+}
+
+/// Parentheses undo one level of depth.
+fn reduce_depth(depth: usize) -> usize {
+    depth.saturating_sub(1).max(1)
+}
+
+impl Printer<'_> {
+    fn expr(&mut self, expr: &Expression) {
+        self.expr1(expr, 0, 1);
+    }
+
+    fn expr0(&mut self, expr: &Expression, depth: usize) {
+        self.expr1(expr, 0, depth);
+    }
+
+    fn expr1(&mut self, expr: &Expression, prec1: usize, depth: usize) {
+        match expr {
+            Expression::BinExpr { left, op, right } => {
+                let depth = depth.max(1);
+                let prec = precedence(op);
+                if prec < prec1 {
+                    self.text("(");
+                    self.expr0(expr, reduce_depth(depth));
+                    self.text(")");
+                    return;
+                }
+                let blanks = prec < cutoff(expr, depth);
+                let left_depth = match &**left {
+                    Expression::BinExpr { op, .. } if precedence(op) == prec => depth,
+                    _ => depth + 1,
+                };
+                self.expr1(left, prec, left_depth);
+                if blanks {
+                    self.blank();
+                }
+                self.operator(op);
+                if blanks {
+                    self.blank();
+                }
+                self.expr1(right, prec + 1, depth + 1);
+            }
+            Expression::UnaryExpr(expr) => self.unary_expr(expr, depth),
+            Expression::BadExpr(text) => self.text(text),
+        }
+    }
+
+    fn unary_expr(&mut self, expr: &UnaryExpr, depth: usize) {
+        match expr {
+            UnaryExpr::PrimaryExpr(expr) => self.primary_expr(expr, depth),
+            UnaryExpr::Unary(unary) => {
+                self.operator(unary.op);
+                self.unary_expr(&unary.expr, depth);
+            }
+        }
+    }
+
+    fn primary_expr(&mut self, expr: &PrimaryExpr, depth: usize) {
+        match expr {
+            PrimaryExpr::Operand(operand) => self.operand(operand, depth),
+            PrimaryExpr::Selector { expr, selector } => {
+                self.primary_expr(expr, depth);
+                self.text(".");
+                self.text(selector);
+            }
+            PrimaryExpr::Index { expr, index } => {
+                self.primary_expr(expr, 1);
+                self.text("[");
+                self.expr0(index, depth + 1);
+                self.text("]");
+            }
+            PrimaryExpr::Slice {
+                expr,
+                low,
+                high,
+                max,
+            } => {
+                self.primary_expr(expr, 1);
+                self.text("[");
+                let indices = [low, high, max];
+                let is_binary = |index: &&Option<Box<Expression>>| {
+                    matches!(index.as_deref(), Some(Expression::BinExpr { .. }))
+                };
+                let count = indices.iter().filter(|index| index.is_some()).count();
+                let blanks = depth <= 1 && count > 1 && indices.iter().any(is_binary);
+                let indices = if max.is_some() {
+                    &indices[..]
+                } else {
+                    &indices[..2]
+                };
+                for (i, index) in indices.iter().enumerate() {
+                    if i > 0 {
+                        if indices[i - 1].is_some() && blanks {
+                            self.blank();
+                        }
+                        self.text(":");
+                        if index.is_some() && blanks {
+                            self.blank();
+                        }
+                    }
+                    if let Some(index) = index {
+                        self.expr0(index, depth + 1);
+                    }
+                }
+                self.text("]");
+            }
+            PrimaryExpr::TypeAssertion { expr, typ } => {
+                self.primary_expr(expr, depth);
+                self.text(".(");
+                self.typ(typ);
+                self.text(")");
+            }
+            PrimaryExpr::Instantiation { expr, type_args } => {
+                self.primary_expr(expr, 1);
+                self.text("[");
+                self.list(type_args, |p, arg| p.typ(arg));
+                self.text("]");
+            }
+            PrimaryExpr::Call {
+                func,
+                args,
+                ellipsis,
+            } => {
+                let depth = if args.len() > 1 { depth + 1 } else { depth };
+                self.primary_expr(func, depth);
+                self.text("(");
+                self.list(args, |p, arg| p.expr0(arg, depth));
+                if *ellipsis {
+                    self.text("...");
+                }
+                self.text(")");
+            }
+        }
+    }
+
+    fn operand(&mut self, operand: &Operand, depth: usize) {
+        match operand {
+            Operand::Literal(literal) => self.literal(literal),
+            Operand::OperandName(OperandName::Identifier(name)) => self.text(name),
+            Operand::OperandName(OperandName::QualifiedIdent(name)) => self.qualified_ident(name),
+            Operand::Parenthesized(expr) => {
+                if as_parenthesized(expr).is_some() {
+                    // `((x))` is printed as `(x)`.
+                    self.expr0(expr, depth);
+                } else {
+                    self.text("(");
+                    self.expr0(expr, reduce_depth(depth));
+                    self.text(")");
+                }
+            }
+            Operand::FunctionLit(lit) => {
+                self.text("func");
+                self.signature(&lit.signature);
+                self.blank();
+                self.body(&lit.body);
+            }
+            Operand::TypeLit(lit) => self.type_lit(lit),
+            Operand::CompositeLit { typ, value } => {
+                self.typ(typ);
+                self.literal_value(value);
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match literal {
+            Literal::IntLit(
+                IntLit::DecimalLit(lit)
+                | IntLit::BinaryLit(lit)
+                | IntLit::OctalLit(lit)
+                | IntLit::HexLit(lit),
+            ) => self.text(lit),
+            Literal::FloatLit(lit) | Literal::ImaginaryLit(lit) => self.text(lit),
+            Literal::RuneLit(text) => self.text(text),
+            Literal::StringLit(lit) => self.text(lit),
+        }
+    }
+
+    fn literal_value(&mut self, value: &LiteralValue) {
+        self.text("{");
+        self.list(&value.0, |p, KeyedElement { key, value }| {
+            if let Some(key) = key {
+                p.element(key);
+                p.text(":");
+                p.blank();
+            }
+            p.element(value);
+        });
+        self.text("}");
+    }
+
+    fn element(&mut self, element: &Element) {
+        match element {
+            Element::Expression(expr) => self.expr(expr),
+            Element::LiteralValue(value) => self.literal_value(value),
+        }
+    }
+}
+
+/// The expression in parentheses, if `expr` is a parenthesized expression.
+fn as_parenthesized<'e, 'a>(expr: &'e Expression<'a>) -> Option<&'e Expression<'a>> {
+    match expr {
+        Expression::UnaryExpr(UnaryExpr::PrimaryExpr(PrimaryExpr::Operand(
+            Operand::Parenthesized(expr),
+        ))) => Some(expr),
+        _ => None,
+    }
+}
+
+/// Removes the parentheses around the expression of a statement header, unless they are needed
+/// for a composite literal of a named type in it, which would otherwise read as the block.
+fn strip_parens<'e, 'a>(mut expr: &'e Expression<'a>) -> &'e Expression<'a> {
+    #[derive(Default)]
+    struct NamedCompositeLit(bool);
+
+    impl<'v> Visitor<'v> for NamedCompositeLit {
+        fn visit_operand(&mut self, node: &'v Operand<'v>) {
+            match node {
+                Operand::Parenthesized(_) | Operand::FunctionLit(_) => {}
+                Operand::CompositeLit { typ, .. } => {
+                    self.0 |= matches!(**typ, GoType::TypeName(_) | GoType::Instantiation { .. });
+                }
+                _ => walk_operand(self, node),
+            }
+        }
+    }
+
+    while let Some(inner) = as_parenthesized(expr) {
+        let mut finder = NamedCompositeLit::default();
+        finder.visit_expression(inner);
+        if finder.0 {
+            break;
+        }
+        expr = inner;
+    }
+    expr
+}
+
+impl Print for Statement<'_> {
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, printer::print, statement::Statement};
+    /// let (_, stmt) = Statement::parse("for i:=0;i<n;i++ {\nif (x[i]>0) { break } else { x[i]=-x[i] }\n}").unwrap();
+    /// assert_eq!(
+    ///     print(&stmt),
+    ///     "for i := 0; i < n; i++ {\n\tif x[i] > 0 {\n\t\tbreak\n\t} else {\n\t\tx[i] = -x[i]\n\t}\n}"
+    /// );
+    /// ```
+    fn print(&self, p: &mut Printer) {
+        p.stmt(self, false)
+    }
+}
+
+impl Print for Block<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.text("{");
+        p.stmt_list(&self.0, 1);
+        p.formfeed();
+        p.text("}");
+    }
+}
+
+impl Print for SimpleStmt<'_> {
+    fn print(&self, p: &mut Printer) {
+        p.simple_stmt(self)
+    }
+}
+
+impl Printer<'_> {
+    /// Prints the body of a function, `{}` when it is empty.
+    fn body(&mut self, body: &Block) {
+        if body.0.is_empty() {
+            self.text("{}");
+        } else {
+            body.print(self);
+        }
+    }
+
+    /// Prints the statements of a block or a case clause, `indent` levels deeper than the line
+    /// before. Empty statements are left out.
+    fn stmt_list(&mut self, stmts: &[Statement], indent: usize) {
+        self.indent += indent;
+        let stmts: Vec<_> = stmts
+            .iter()
+            .filter(|stmt| **stmt != Statement::SimpleStmt(SimpleStmt::EmptyStmt))
+            .enumerate()
+            .collect();
+        let count = stmts.len();
+        self.lines(&stmts, |p, &(i, stmt)| p.stmt(stmt, i + 1 == count));
+        self.indent -= indent;
+    }
+
+    fn stmt(&mut self, stmt: &Statement, next_is_rbrace: bool) {
+        match stmt {
+            Statement::DeclStmt(decl) => decl.print(self),
+            Statement::SimpleStmt(stmt) => self.simple_stmt(stmt),
+            Statement::Block(block) => block.print(self),
+            Statement::IfStmt(stmt) => self.if_stmt(stmt),
+            Statement::SwitchStmt(stmt) => {
+                self.text("switch");
+                self.control_clause(stmt.init.as_ref(), stmt.tag.as_ref());
+                self.case_clauses(&stmt.clauses, |p, exprs| p.list(exprs, |p, e| p.expr(e)));
+            }
+            Statement::TypeSwitchStmt(stmt) => {
+                self.text("switch");
+                self.blank();
+                if let Some(init) = &stmt.init {
+                    self.simple_stmt(init);
+                    self.text(";");
+                    self.blank();
+                }
+                if let Some(binding) = stmt.binding {
+                    self.text(binding);
+                    self.blank();
+                    self.operator(":=");
+                    self.blank();
+                }
+                self.primary_expr(&stmt.expr, 1);
+                self.text(".(type)");
+                self.blank();
+                self.case_clauses(&stmt.clauses, |p, types| p.list(types, |p, t| p.typ(t)));
+            }
+            Statement::SelectStmt(stmt) => {
+                self.text("select");
+                self.blank();
+                self.case_clauses(&stmt.clauses, |p, comm| p.simple_stmt(comm));
+            }
+            Statement::ForStmt(stmt) => self.for_stmt(stmt),
+            Statement::GoStmt(call) => {
+                self.text("go");
+                self.blank();
+                self.primary_expr(call, 1);
+            }
+            Statement::DeferStmt(call) => {
+                self.text("defer");
+                self.blank();
+                self.primary_expr(call, 1);
+            }
+            Statement::ReturnStmt(results) => {
+                self.text("return");
+                if !results.is_empty() {
+                    self.blank();
+                    self.list(results, |p, result| p.expr(result));
+                }
+            }
+            Statement::BreakStmt(label) => self.branch("break", *label),
+            Statement::ContinueStmt(label) => self.branch("continue", *label),
+            Statement::GotoStmt(label) => self.branch("goto", Some(label)),
+            Statement::FallthroughStmt => self.text("fallthrough"),
+            Statement::LabeledStmt { label, stmt } => {
+                // Labels stand one level to the left of the statements around them.
+                self.indent -= 1;
+                self.text(label);
+                self.text(":");
+                self.indent += 1;
+                match &**stmt {
+                    Statement::SimpleStmt(SimpleStmt::EmptyStmt) if next_is_rbrace => {}
+                    Statement::SimpleStmt(SimpleStmt::EmptyStmt) => {
+                        self.newline();
+                        self.text(";");
+                    }
+                    stmt => {
+                        self.formfeed();
+                        self.stmt(stmt, next_is_rbrace);
+                    }
+                }
+            }
+            Statement::BadStmt(text) => self.text(text),
+        }
+    }
+
+    fn branch(&mut self, keyword: &str, label: Option<&str>) {
+        self.text(keyword);
+        if let Some(label) = label {
+            self.blank();
+            self.text(label);
+        }
+    }
+
+    fn simple_stmt(&mut self, stmt: &SimpleStmt) {
+        match stmt {
+            SimpleStmt::EmptyStmt => {}
+            SimpleStmt::ExpressionStmt(expr) => self.expr(expr),
+            SimpleStmt::SendStmt { channel, value } => {
+                self.expr(channel);
+                self.blank();
+                self.operator("<-");
+                self.blank();
+                self.expr(value);
+            }
+            SimpleStmt::IncDecStmt { expr, op } => {
+                self.expr(expr);
+                self.operator(op);
+            }
+            SimpleStmt::Assignment { lhs, op, rhs } => {
+                let depth = if lhs.len() > 1 && rhs.len() > 1 { 2 } else { 1 };
+                self.list(lhs, |p, e| p.expr0(e, depth));
+                self.blank();
+                self.operator(op);
+                self.blank();
+                self.list(rhs, |p, e| p.expr0(e, depth));
+            }
+            SimpleStmt::ShortVarDecl {
+                identifiers,
+                values,
+            } => {
+                let depth = if identifiers.len() > 1 && values.len() > 1 {
+                    2
+                } else {
+                    1
+                };
+                self.names(identifiers);
+                self.blank();
+                self.operator(":=");
+                self.blank();
+                self.list(values, |p, e| p.expr0(e, depth));
+            }
+        }
+    }
+
+    /// Prints the header of an if or switch statement, and the blank before its block.
+    fn control_clause(&mut self, init: Option<&SimpleStmt>, expr: Option<&Expression>) {
+        self.blank();
+        let init = init.filter(|init| **init != SimpleStmt::EmptyStmt);
+        if let Some(init) = init {
+            self.simple_stmt(init);
+            self.text(";");
+            self.blank();
+        }
+        if let Some(expr) = expr {
+            self.expr(strip_parens(expr));
+            self.blank();
+        }
+    }
+
+    fn if_stmt(&mut self, stmt: &IfStmt) {
+        self.text("if");
+        self.control_clause(stmt.init.as_ref(), Some(&stmt.cond));
+        stmt.then.print(self);
+        match &stmt.otherwise {
+            None => {}
+            Some(Else::If(stmt)) => {
+                self.text(" else ");
+                self.if_stmt(stmt);
+            }
+            Some(Else::Block(block)) => {
+                self.text(" else ");
+                block.print(self);
+            }
+        }
+    }
+
+    fn for_stmt(&mut self, stmt: &ForStmt) {
+        self.text("for");
+        self.blank();
+        match &stmt.clause {
+            None => {}
+            Some(ForClause::Condition(cond)) => {
+                self.expr(strip_parens(cond));
+                self.blank();
+            }
+            Some(ForClause::ForClause { init, cond, post }) => {
+                let init = init.as_ref().filter(|s| **s != SimpleStmt::EmptyStmt);
+                let post = post.as_ref().filter(|s| **s != SimpleStmt::EmptyStmt);
+                if init.is_none() && post.is_none() {
+                    if let Some(cond) = cond {
+                        self.expr(strip_parens(cond));
+                        self.blank();
+                    }
+                } else {
+                    if let Some(init) = init {
+                        self.simple_stmt(init);
+                    }
+                    self.text(";");
+                    self.blank();
+                    if let Some(cond) = cond {
+                        self.expr(strip_parens(cond));
+                    }
+                    self.text(";");
+                    self.blank();
+                    if let Some(post) = post {
+                        self.simple_stmt(post);
+                        self.blank();
+                    }
+                }
+            }
+            Some(ForClause::RangeClause { vars, expr }) => {
+                match vars {
+                    None => {}
+                    Some(RangeVars::Declared(names)) => {
+                        self.names(names);
+                        self.blank();
+                        self.operator(":=");
+                        self.blank();
+                    }
+                    Some(RangeVars::Assigned(exprs)) => {
+                        self.list(exprs, |p, e| p.expr(e));
+                        self.blank();
+                        self.operator("=");
+                        self.blank();
+                    }
+                }
+                self.text("range");
+                self.blank();
+                self.expr(strip_parens(expr));
+                self.blank();
+            }
+        }
+        stmt.body.print(self);
+    }
+
+    /// Prints the body of a switch or select statement. The clauses line up with the statement
+    /// and their statements are indented.
+    fn case_clauses<C>(&mut self, clauses: &[CaseClause<C>], mut case: impl FnMut(&mut Self, &C)) {
+        self.text("{");
+        for clause in clauses {
+            self.formfeed();
+            match &clause.case {
+                Some(c) => {
+                    self.text("case");
+                    self.blank();
+                    case(self, c);
+                }
+                None => self.text("default"),
+            }
+            self.text(":");
+            self.stmt_list(&clause.body, 1);
+        }
+        self.formfeed();
+        self.text("}");
+    }
+}
+
+#[test]
+fn test_binary_expression_spacing() {
+    use crate::astable::ASTable;
+    let cases = [
+        ("a+b", "a + b"),
+        ("a+b*c", "a + b*c"),
+        ("a*b+c*d", "a*b + c*d"),
+        ("(a+b)*c", "(a + b) * c"),
+        ("x==y||z", "x == y || z"),
+        ("a+b<c&&d", "a+b < c && d"),
+        ("f(a+b)", "f(a + b)"),
+        ("f(a+b,c)", "f(a+b, c)"),
+        ("x[i+1]", "x[i+1]"),
+        ("s[a+1:b]", "s[a+1 : b]"),
+        ("s[:n]", "s[:n]"),
+        ("a - -b", "a - -b"),
+        ("a / *p", "a / *p"),
+        ("- -x", "- -x"),
+        ("((x))", "(x)"),
+        ("[]int{1,2}", "[]int{1, 2}"),
+        ("T{X:1,Y:p.q}", "T{X: 1, Y: p.q}"),
+    ];
+    for (src, want) in cases.iter() {
+        let (rest, expr) = Expression::parse(src).unwrap();
+        assert_eq!(rest, "", "{}", src);
+        assert_eq!(print(&expr), *want, "{}", src);
+    }
+}
+
+#[test]
+fn test_print_source_file() {
+    use crate::astable::ASTable;
+    let src = "// Package m is a test.
+package m
+import \"strings\"
+import b \"bytes\"
+import \"strings\"
+// Point is a point.
+type Point struct {
+	X, Y int `json:\"xy\"`
+	Label string
+	*strings.Builder
+}
+type (
+	ID int
+	Names = []string
+)
+var (
+	a int
+	bb = 1
+	c string = \"c\"
+)
+func (p *Point) Move(dx, dy int) {
+	p.X+=dx
+	outer:
+	for _, s := range []string{\"a\"} {
+		switch {
+		case s == \"\":
+			continue outer
+		default:
+			p.Label = s
+		}
+	}
+	defer func() {}()
+	go func(n int) { p.X = n }(dx)
+	ch := make( chan int, dx )
+	_ = []byte(p.Label)
+	_ = (<-chan int)(ch)
+}
+";
+    let want = "// Package m is a test.
+package m
+
+import (
+	b \"bytes\"
+	\"strings\"
+	\"strings\"
+)
+
+// Point is a point.
+type Point struct {
+	X, Y  int `json:\"xy\"`
+	Label string
+	*strings.Builder
+}
+
+type (
+	ID    int
+	Names = []string
+)
+
+var (
+	a  int
+	bb        = 1
+	c  string = \"c\"
+)
+
+func (p *Point) Move(dx, dy int) {
+	p.X += dx
+outer:
+	for _, s := range []string{\"a\"} {
+		switch {
+		case s == \"\":
+			continue outer
+		default:
+			p.Label = s
+		}
+	}
+	defer func() {}()
+	go func(n int) {
+		p.X = n
+	}(dx)
+	ch := make(chan int, dx)
+	_ = []byte(p.Label)
+	_ = (<-chan int)(ch)
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    assert_eq!(print(&file), want);
+    let (_, reparsed) = SourceFile::parse(want).unwrap();
+    assert_eq!(print(&reparsed), want);
+}
+
+#[test]
+fn test_print_comments() {
+    use crate::astable::ASTable;
+    let src = "// Copyright header.
+
+// Package p does things.
+package p
+
+import (
+	\"os\" // for Args
+	\"fmt\"
+)
+
+// T is a type.
+type T struct {
+	A int // the a
+	// Bc comes next.
+	Bc string // the bc
+	Def bool `json:\"def\"`
+}
+
+const (
+	x = 1 // one
+	yy = 22 // twenty-two
+)
+
+func f(a int /* first */, b int) int {
+	// Start.
+	n := a + b // sum
+	longer := n // longer
+	if n > 0 { // positive
+		n-- // down
+		/* block */
+	}
+	m := []int{
+		1, // one
+		2,
+	}
+	_ = m
+	switch n {
+	// Before the case.
+	case 1:
+		fmt.Println(os.Args)
+		// Last in the case.
+	}
+	return longer
+	// Last in the body.
+} // end of f
+
+// Trailing file comment.
+";
+    let want = "// Copyright header.
+
+// Package p does things.
+package p
+
+import (
+	\"fmt\"
+	\"os\" // for Args
+)
+
+// T is a type.
+type T struct {
+	A int // the a
+	// Bc comes next.
+	Bc  string // the bc
+	Def bool   `json:\"def\"`
+}
+
+const (
+	x  = 1  // one
+	yy = 22 // twenty-two
+)
+
+func f(a int /* first */, b int) int {
+	// Start.
+	n := a + b  // sum
+	longer := n // longer
+	if n > 0 {  // positive
+		n-- // down
+		/* block */
+	}
+	m := []int{1, 2} // one
+	_ = m
+	switch n {
+	// Before the case.
+	case 1:
+		fmt.Println(os.Args)
+		// Last in the case.
+	}
+	return longer
+	// Last in the body.
+} // end of f
+
+// Trailing file comment.
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    assert_eq!(print_with_comments(&file, src), want);
+    let (_, reparsed) = SourceFile::parse(want).unwrap();
+    assert_eq!(print_with_comments(&reparsed, want), want);
+    assert!(!print(&file).contains("// the a"));
+}
+
+#[test]
+fn test_print_import_groups() {
+    use crate::astable::ASTable;
+    let src = "package p
+
+import (
+	\"os\"
+	\"fmt\" // for Println
+	\"fmt\"
+
+	// Local packages.
+	\"example.com/z\"
+	a \"example.com/a\"
+)
+
+import \"bytes\"
+";
+    let want = "package p
+
+import (
+	\"fmt\" // for Println
+	\"fmt\"
+	\"os\"
+
+	// Local packages.
+	a \"example.com/a\"
+	\"example.com/z\"
+
+	\"bytes\"
+)
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    assert_eq!(print_with_comments(&file, src), want);
+    let (_, reparsed) = SourceFile::parse(want).unwrap();
+    assert_eq!(print_with_comments(&reparsed, want), want);
+    assert_eq!(
+        print(&file),
+        "package p
+
+import (
+	\"bytes\"
+	a \"example.com/a\"
+	\"example.com/z\"
+	\"fmt\"
+	\"fmt\"
+	\"os\"
+)
+"
+    );
+}
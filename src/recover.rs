@@ -0,0 +1,486 @@
+//! Parsers that keep going after a syntax error, for editors and other tools that want
+//! to see as much of a broken file as possible.
+//!
+//! A broken construct is skipped up to the next synchronisation point and replaced by a
+//! placeholder holding its source text, like `go/ast`'s `BadDecl`, `BadStmt` and `BadExpr`.
+
+use nom::{
+    branch::alt,
+    combinator::{map, peek},
+    sequence::terminated,
+    IResult,
+};
+
+use crate::{
+    astable::ASTable,
+    comment::parse_doc,
+    error::{Expected, ParseError},
+    expression::Expression,
+    package_clause, parse_function_head, parse_import_decl, parse_top_level_decl,
+    parse_util::{comment, identifier, multispace0, reserved, symbol, terminator},
+    statement::{
+        at_statement_list_end,
+        control::{ForStmt, IfStmt, SelectStmt, SwitchStmt, TypeSwitchStmt},
+        simple::SimpleStmt,
+        Block, Statement, StatementList,
+    },
+    tokenize::tokenizer::next_token,
+    Function, SourceFile, TopLevel,
+};
+
+const TOP_LEVEL_KEYWORDS: [&str; 5] = ["const", "func", "import", "type", "var"];
+
+/// Parses a whole file like [`SourceFile::parse`], but records errors instead of stopping at the first one.
+/// Broken declarations end up as [`TopLevel::BadDecl`]; a broken package clause leaves the package name empty.
+/// A function whose signature parses keeps it, and only the broken statements of its body are replaced.
+/// ```
+/// use go_parser_rs::recover::parse_source_file;
+/// use go_parser_rs::TopLevel;
+/// let (file, errors) = parse_source_file("package main\nfunc f(x int y) int\nfunc g() int\n");
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(file.decls[0], TopLevel::BadDecl("func f(x int y) int"));
+/// assert!(matches!(file.decls[1], TopLevel::Function(_)));
+/// ```
+pub fn parse_source_file(s: &str) -> (SourceFile<'_>, Vec<ParseError<'_>>) {
+    let mut errors = vec![];
+    let (s, doc) = skip_comments(s);
+    let (mut s, package) = match terminated(package_clause, terminator)(s) {
+        Ok(ok) => ok,
+        Err(e) if starts_top_level_decl(s) => {
+            errors.push(into_error(e, s));
+            (s, "")
+        }
+        Err(e) => {
+            errors.push(into_error(e, s));
+            let (rest, _) = bad_decl(s);
+            (rest, "")
+        }
+    };
+
+    let mut imports = vec![];
+    let mut decls = vec![];
+    let mut in_imports = true;
+    loop {
+        let (rest, _) = skip_comments(s);
+        if rest.is_empty() {
+            break;
+        }
+        in_imports = in_imports && reserved("import")(rest).is_ok();
+        let result = if in_imports {
+            terminated(parse_import_decl, terminator)(rest)
+        } else {
+            terminated(parse_top_level_decl, terminator)(s)
+        };
+        s = match result {
+            Ok((s, TopLevel::Import(import))) => {
+                imports.extend(import);
+                s
+            }
+            Ok((s, decl)) => {
+                decls.push(decl);
+                s
+            }
+            Err(e) => match parse_function(s, &mut errors) {
+                Some((s, function)) => {
+                    decls.push(TopLevel::Function(function));
+                    s
+                }
+                None => {
+                    errors.push(into_error(e, rest));
+                    let (s, bad) = bad_decl(rest);
+                    decls.push(bad);
+                    s
+                }
+            },
+        };
+    }
+    let file = SourceFile {
+        doc,
+        package,
+        imports,
+        decls,
+    };
+    (file, errors)
+}
+
+/// Parses an expression, or skips to its end and returns [`Expression::BadExpr`] when it is broken.
+/// An expression ends before a `;`, `,`, newline or closing bracket outside of any brackets of its own.
+/// A broken expression takes at least the next token, so that the caller always moves on.
+/// ```
+/// use go_parser_rs::recover::parse_expression;
+/// use go_parser_rs::expression::Expression;
+/// let mut errors = vec![];
+/// assert_eq!(parse_expression("1 + (2 3), 4", &mut errors), (", 4", Expression::BadExpr("1 + (2 3)")));
+/// assert_eq!(parse_expression(") + 1", &mut errors), (" + 1", Expression::BadExpr(")")));
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub fn parse_expression<'a>(
+    s: &'a str,
+    errors: &mut Vec<ParseError<'a>>,
+) -> (&'a str, Expression<'a>) {
+    match Expression::parse(s) {
+        Ok(ok) => ok,
+        Err(e) => {
+            errors.push(into_error(e, s));
+            let mut end = scan(s, Stop::Expression);
+            if s[..end].trim().is_empty() {
+                let start = s.len() - s.trim_start().len();
+                let token = next_token(&s[start..]).map_or(0, |token| token.text().len());
+                let unknown = s[start..].chars().next().map_or(0, char::len_utf8);
+                end = start + token.max(unknown);
+            }
+            (&s[end..], Expression::BadExpr(s[..end].trim()))
+        }
+    }
+}
+
+/// Parses a block like [`Block::parse`], replacing broken statements with [`Statement::BadStmt`],
+/// also in the blocks and case clauses nested in it. A statement ends after a `;`, or before a
+/// newline or the `}` closing the block, outside of any brackets of its own. Fails only when the
+/// block does not start with `{`; a missing `}` is recorded in `errors`.
+/// ```
+/// use go_parser_rs::recover::parse_block;
+/// use go_parser_rs::statement::Statement;
+/// let mut errors = vec![];
+/// let (_, block) = parse_block("{\n\tx := )\n\tx++\n}", &mut errors).unwrap();
+/// assert_eq!(block.0[0], Statement::BadStmt("x := )"));
+/// assert!(matches!(block.0[1], Statement::SimpleStmt(_)));
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn parse_block<'a>(
+    s: &'a str,
+    errors: &mut Vec<ParseError<'a>>,
+) -> IResult<&'a str, Block<'a>, ParseError<'a>> {
+    let (mut s, _) = symbol("{")(s)?;
+    let mut statements = vec![];
+    loop {
+        let (rest, list) = parse_statement_list(s, errors)?;
+        statements.extend(list);
+        s = rest;
+        if let Ok((rest, _)) = symbol("}")(s) {
+            return Ok((rest, Block(statements)));
+        }
+        errors.push(ParseError::new(s, Expected::Token("}")));
+        if s.is_empty() {
+            return Ok((s, Block(statements)));
+        }
+        // A `case` or `default` outside of a switch.
+        let end = scan(s, Stop::Statement);
+        statements.push(Statement::BadStmt(s[..end].trim_end()));
+        s = &s[end..];
+    }
+}
+
+/// Parses statements up to the end of a block or a case clause, like
+/// [`statement::parse_statement_list`](crate::statement::parse_statement_list), replacing the broken ones.
+fn parse_statement_list<'a>(
+    mut s: &'a str,
+    errors: &mut Vec<ParseError<'a>>,
+) -> IResult<&'a str, Vec<Statement<'a>>, ParseError<'a>> {
+    let mut statements = vec![];
+    loop {
+        s = multispace0(s)?.0;
+        if at_statement_list_end(s) {
+            return Ok((s, statements));
+        }
+        if let Ok((rest, _)) = symbol(";")(s) {
+            statements.push(Statement::SimpleStmt(SimpleStmt::EmptyStmt));
+            s = rest;
+            continue;
+        }
+        let mut nested = vec![];
+        let end_of_statement = alt((terminator, peek(symbol("}"))));
+        let result = terminated(|s| parse_statement(s, &mut nested), end_of_statement)(s);
+        s = match result {
+            Ok((rest, statement)) => {
+                errors.extend(nested);
+                statements.push(statement);
+                rest
+            }
+            Err(e) => {
+                errors.push(into_error(e, s));
+                let end = scan(s, Stop::Statement);
+                statements.push(Statement::BadStmt(s[..end].trim_end()));
+                &s[end..]
+            }
+        };
+    }
+}
+
+/// A parser of a statement holding blocks or case clauses, whose statements are read by the list.
+type Compound<'a> =
+    fn(&'a str, &mut StatementList<'_, 'a>) -> IResult<&'a str, Statement<'a>, ParseError<'a>>;
+
+/// Parses a statement like [`Statement::parse`], recovering in the blocks and case clauses it holds.
+fn parse_statement<'a>(
+    s: &'a str,
+    errors: &mut Vec<ParseError<'a>>,
+) -> IResult<&'a str, Statement<'a>, ParseError<'a>> {
+    if let Ok((rest, label)) = terminated(identifier, symbol(":"))(s) {
+        if symbol("}")(rest).is_err() {
+            let (rest, stmt) = parse_statement(rest, errors)?;
+            let stmt = Box::new(stmt);
+            return Ok((rest, Statement::LabeledStmt { label, stmt }));
+        }
+    }
+    let compound: [Compound<'a>; 6] = [
+        |s, list| map(|s| Block::parse_with(s, list), Statement::Block)(s),
+        |s, list| map(|s| IfStmt::parse_with(s, list), Statement::IfStmt)(s),
+        // A type switch guard also starts like an expression, so it is tried first.
+        |s, list| {
+            let stmt = |s| TypeSwitchStmt::parse_with(s, list);
+            map(stmt, Statement::TypeSwitchStmt)(s)
+        },
+        |s, list| map(|s| SwitchStmt::parse_with(s, list), Statement::SwitchStmt)(s),
+        |s, list| map(|s| SelectStmt::parse_with(s, list), Statement::SelectStmt)(s),
+        |s, list| map(|s| ForStmt::parse_with(s, list), Statement::ForStmt)(s),
+    ];
+    for parse in compound.iter() {
+        let mut nested = vec![];
+        match parse(s, &mut |s| parse_statement_list(s, &mut nested)) {
+            Ok(ok) => {
+                errors.extend(nested);
+                return Ok(ok);
+            }
+            Err(nom::Err::Error(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Statement::parse(s)
+}
+
+/// Parses a function declaration whose signature is fine, recovering from errors in its body.
+fn parse_function<'a>(
+    s: &'a str,
+    errors: &mut Vec<ParseError<'a>>,
+) -> Option<(&'a str, Function<'a>)> {
+    let (s, doc) = skip_comments(s);
+    let (s, function) = parse_function_head(s).ok()?;
+    let mut body_errors = vec![];
+    let (s, body) = parse_block(s, &mut body_errors).ok()?;
+    let (s, _) = terminator(s).ok()?;
+    errors.extend(body_errors);
+    let body = Some(body);
+    Some((
+        s,
+        Function {
+            doc,
+            body,
+            ..function
+        },
+    ))
+}
+
+fn skip_comments(s: &str) -> (&str, Option<crate::comment::CommentGroup<'_>>) {
+    parse_doc(s).unwrap_or((s, None))
+}
+
+fn into_error<'a>(e: nom::Err<ParseError<'a>>, s: &'a str) -> ParseError<'a> {
+    match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => e,
+        nom::Err::Incomplete(_) => ParseError::new(s, Expected::Kind("more input")),
+    }
+}
+
+/// Skips a broken declaration: up to a `;` or the `}` closing its body,
+/// or to the next line starting with a top level keyword.
+fn bad_decl(s: &str) -> (&str, TopLevel<'_>) {
+    let end = scan(s, Stop::Declaration);
+    (&s[end..], TopLevel::BadDecl(s[..end].trim_end()))
+}
+
+#[derive(PartialEq)]
+enum Stop {
+    Declaration,
+    Statement,
+    Expression,
+}
+
+/// Returns the length of the text up to the synchronisation point, skipping over strings,
+/// comments and bracketed text.
+fn scan(s: &str, stop: Stop) -> usize {
+    let mut depth = 0usize;
+    let mut rest = s;
+    let mut line_start = false;
+    while let Some(c) = rest.chars().next() {
+        let i = s.len() - rest.len();
+        if stop == Stop::Declaration && line_start && starts_top_level_decl(rest) {
+            return i;
+        }
+        line_start = c == '\n';
+        if let Ok((after, _)) = comment(rest) {
+            rest = after;
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                rest = skip_quoted(rest, c);
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 && c == '}' && stop == Stop::Declaration {
+                    return i + 1;
+                }
+            }
+            ';' if depth == 0 && stop != Stop::Expression => return i + 1,
+            '\n' | '}' if depth == 0 && stop == Stop::Statement => return i,
+            '}' if stop == Stop::Declaration => return i + 1,
+            ';' | ',' | '\n' | ')' | ']' | '}' if depth == 0 && stop == Stop::Expression => {
+                return i
+            }
+            _ => {}
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    s.len()
+}
+
+fn starts_top_level_decl(s: &str) -> bool {
+    TOP_LEVEL_KEYWORDS
+        .iter()
+        .any(|keyword| reserved(keyword)(s).is_ok())
+}
+
+/// Skips a string or rune literal starting at `s`. An unterminated one ends at the line end,
+/// or at the end of the input for raw strings.
+fn skip_quoted(s: &str, quote: char) -> &str {
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote != '`' => {
+                chars.next();
+            }
+            '\n' if quote != '`' => return &s[i..],
+            c if c == quote => return &s[i + 1..],
+            _ => {}
+        }
+    }
+    ""
+}
+
+#[test]
+fn test_recovers_after_each_broken_declaration() {
+    let src = "package main
+
+import \"fmt\"
+import 42
+
+func f(x int) int {
+    x := )
+    x++
+}
+
+func g() int
+func h(y int z) int
+func i() int
+";
+    let (file, errors) = parse_source_file(src);
+    assert_eq!(file.package, "main");
+    assert_eq!(file.imports.len(), 1);
+    assert_eq!(errors.len(), 3);
+    match &file.decls[1] {
+        TopLevel::Function(f) => {
+            let body = &f.body.as_ref().unwrap().0;
+            assert_eq!(body[0], Statement::BadStmt("x := )"));
+            assert_eq!(body.len(), 2);
+        }
+        decl => panic!("{:?}", decl),
+    }
+    let bad: Vec<_> = file
+        .decls
+        .iter()
+        .filter_map(|decl| match decl {
+            TopLevel::BadDecl(text) => Some(*text),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(bad, vec!["import 42", "func h(y int z) int"]);
+    assert_eq!(file.decls.len(), 5);
+}
+
+#[test]
+fn test_recovers_in_nested_blocks() {
+    use crate::statement::control::{CaseClause, Else};
+
+    let src = "{
+	if x {
+		y := )
+		y++
+	} else {
+		for {
+			z = ]
+		}
+	}
+	switch x {
+	case 1:
+		f g
+		g()
+	default:
+	}
+	outer:
+	select {
+	case <-ch:
+		return 1 +
+	}
+	case 2:
+}";
+    let mut errors = vec![];
+    let (s, block) = parse_block(src, &mut errors).unwrap();
+    assert_eq!(s, "");
+    let messages: Vec<_> = errors.iter().map(ParseError::message).collect();
+    assert_eq!(messages.len(), 5, "{:?}", messages);
+    assert_eq!(block.0.len(), 4);
+    let stmt = match &block.0[0] {
+        Statement::IfStmt(stmt) => stmt,
+        stmt => panic!("{:?}", stmt),
+    };
+    assert_eq!(stmt.then.0[0], Statement::BadStmt("y := )"));
+    assert!(matches!(stmt.then.0[1], Statement::SimpleStmt(_)));
+    match &stmt.otherwise {
+        Some(Else::Block(block)) => match &block.0[0] {
+            Statement::ForStmt(stmt) => assert_eq!(stmt.body.0, [Statement::BadStmt("z = ]")]),
+            stmt => panic!("{:?}", stmt),
+        },
+        otherwise => panic!("{:?}", otherwise),
+    }
+    match &block.0[1] {
+        Statement::SwitchStmt(stmt) => match stmt.clauses.as_slice() {
+            [CaseClause { body, .. }, CaseClause { case: None, .. }] => {
+                assert_eq!(body[0], Statement::BadStmt("f g"));
+                assert!(matches!(body[1], Statement::SimpleStmt(_)));
+            }
+            clauses => panic!("{:?}", clauses),
+        },
+        stmt => panic!("{:?}", stmt),
+    }
+    match &block.0[2] {
+        Statement::LabeledStmt {
+            label: "outer",
+            stmt,
+        } => match &**stmt {
+            Statement::SelectStmt(stmt) => {
+                assert_eq!(stmt.clauses[0].body, [Statement::BadStmt("return 1 +")])
+            }
+            stmt => panic!("{:?}", stmt),
+        },
+        stmt => panic!("{:?}", stmt),
+    }
+    assert_eq!(block.0[3], Statement::BadStmt("case 2:"));
+}
+
+#[test]
+fn test_recovers_from_missing_package_clause() {
+    let (file, errors) = parse_source_file("func f() int\n");
+    assert_eq!(file.package, "");
+    assert_eq!(errors[0].message(), "expected `package`, found `func`");
+    assert!(matches!(file.decls[0], TopLevel::Function(_)));
+}
+
+#[test]
+fn test_skip_quoted() {
+    assert_eq!(skip_quoted("\"a\\\"}\" x", '"'), " x");
+    assert_eq!(skip_quoted("`a\n}` x", '`'), " x");
+    assert_eq!(skip_quoted("\"a\nx", '"'), "\nx");
+}
@@ -0,0 +1,1175 @@
+//! Name resolution: binds each identifier of a package to the declaration it refers to.
+//!
+//! [`resolve`] goes through the files of a package with the scopes of the specification: the
+//! universe scope of the predeclared identifiers, the package scope of the top-level declarations
+//! of all files, the file scope of the imports of each file, the scope of each function, with its
+//! receiver, type parameters and parameters, and the scope of each block, explicit or implicit like
+//! those of `if`, `for`, `switch` and `select` statements and of their clauses. The labels of a
+//! function body are in a scope of their own, where they are visible before their declaration.
+//!
+//! An identifier declared in a block is visible from the end of its declaration, or from the
+//! identifier itself for types, and hides the declarations of the same name in the enclosing
+//! scopes. `:=` declares the identifiers of its left hand side that are not declared in the same
+//! scope yet, and assigns to the others, but must declare at least one.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, resolve::{resolve, Decl, ObjKind}, SourceFile};
+//! let src = "package m\n\nvar x = 1\n\nfunc f() int {\n\tx := x + 1\n\treturn x\n}\n";
+//! let (_, file) = SourceFile::parse(src).unwrap();
+//! let resolution = resolve([&file]);
+//! let x: Vec<_> = src.match_indices('x').map(|(i, _)| &src[i..i + 1]).collect();
+//! let declaration = |name| resolution.binding(name).unwrap().object().unwrap().name.as_ptr();
+//! // `x + 1` is the variable of the package, not the one being declared.
+//! assert_eq!(declaration(x[2]), x[0].as_ptr());
+//! assert_eq!(declaration(x[3]), x[1].as_ptr());
+//! let int = &src[src.find("int").unwrap()..][..3];
+//! let object = resolution.binding(int).unwrap().object().copied().unwrap();
+//! assert_eq!((object.kind, object.decl), (ObjKind::Type, Decl::Universe));
+//! ```
+//!
+//! An identifier is looked up with its span in the text of its file, like with
+//! [`Resolution::binding_at`], or with the identifier itself, the slice of that text held by the
+//! tree. Each identifier of the tree must then be a slice of its own, like in the trees parsed
+//! from a source or [copied](crate::owned::Owned::new) from one: the same slice used for several
+//! identifiers, like a `&'static str` in a tree built by hand, is reported as
+//! [`ErrorKind::SharedText`]. The names after the `.` of
+//! selectors, struct fields, methods and the keys of composite literals that are not in scope,
+//! which are field names, are not resolved.
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    fmt,
+};
+
+use crate::{
+    declaration::{ConstDecl, Declaration, TypeSpec, VarSpec},
+    error::Span,
+    expression::{
+        operand::{Element, FunctionLit, KeyedElement, Operand, OperandName},
+        Expression, PrimaryExpr, UnaryExpr,
+    },
+    statement::{
+        control::{
+            CommClause, Else, ExprCaseClause, ForClause, ForStmt, IfStmt, RangeVars, SwitchStmt,
+            TypeCaseClause, TypeSwitchStmt,
+        },
+        simple::SimpleStmt,
+        Block, Statement,
+    },
+    typ::{ParameterDecl, TypeName, TypeParamDecl},
+    visit::{self, Visitor},
+    Function, ImportDeclaration, Receiver, SourceFile, TopLevel,
+};
+
+/// The predeclared identifiers of the universe scope.
+pub const UNIVERSE: &[(&str, ObjKind)] = &[
+    ("any", ObjKind::Type),
+    ("bool", ObjKind::Type),
+    ("byte", ObjKind::Type),
+    ("comparable", ObjKind::Type),
+    ("complex64", ObjKind::Type),
+    ("complex128", ObjKind::Type),
+    ("error", ObjKind::Type),
+    ("float32", ObjKind::Type),
+    ("float64", ObjKind::Type),
+    ("int", ObjKind::Type),
+    ("int8", ObjKind::Type),
+    ("int16", ObjKind::Type),
+    ("int32", ObjKind::Type),
+    ("int64", ObjKind::Type),
+    ("rune", ObjKind::Type),
+    ("string", ObjKind::Type),
+    ("uint", ObjKind::Type),
+    ("uint8", ObjKind::Type),
+    ("uint16", ObjKind::Type),
+    ("uint32", ObjKind::Type),
+    ("uint64", ObjKind::Type),
+    ("uintptr", ObjKind::Type),
+    ("true", ObjKind::Const),
+    ("false", ObjKind::Const),
+    ("iota", ObjKind::Const),
+    ("nil", ObjKind::Nil),
+    ("append", ObjKind::Func),
+    ("cap", ObjKind::Func),
+    ("clear", ObjKind::Func),
+    ("close", ObjKind::Func),
+    ("complex", ObjKind::Func),
+    ("copy", ObjKind::Func),
+    ("delete", ObjKind::Func),
+    ("imag", ObjKind::Func),
+    ("len", ObjKind::Func),
+    ("make", ObjKind::Func),
+    ("max", ObjKind::Func),
+    ("min", ObjKind::Func),
+    ("new", ObjKind::Func),
+    ("panic", ObjKind::Func),
+    ("print", ObjKind::Func),
+    ("println", ObjKind::Func),
+    ("real", ObjKind::Func),
+    ("recover", ObjKind::Func),
+];
+
+/// What a name denotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjKind {
+    /// An imported package.
+    Package,
+    Const,
+    Type,
+    Var,
+    Func,
+    Label,
+    /// The predeclared `nil`.
+    Nil,
+}
+
+/// The node declaring a name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decl<'a> {
+    /// A predeclared identifier of the universe scope.
+    Universe,
+    Import(&'a ImportDeclaration<'a>),
+    /// A constant, which is the one of [`ConstDecl::constants`] with the same name.
+    Const(&'a ConstDecl<'a>),
+    Var(&'a VarSpec<'a>),
+    Type(&'a TypeSpec<'a>),
+    Func(&'a Function<'a>),
+    /// A type parameter of a function or of a type.
+    TypeParam(&'a TypeParamDecl<'a>),
+    /// The receiver of a method, or one of its type parameters.
+    Receiver(&'a Receiver<'a>),
+    /// A parameter or a named result.
+    Param(&'a ParameterDecl<'a>),
+    /// A variable declared by a [`SimpleStmt::ShortVarDecl`].
+    ShortVarDecl(&'a SimpleStmt<'a>),
+    /// A variable declared by a range clause.
+    Range(&'a ForStmt<'a>),
+    /// The variable declared by the guard of a type switch, in one of its clauses.
+    TypeSwitch(&'a TypeSwitchStmt<'a>, &'a TypeCaseClause<'a>),
+    /// A label, declared by a [`Statement::LabeledStmt`].
+    Label(&'a Statement<'a>),
+}
+
+/// A declared entity: a constant, a type, a variable, a function, a package or a label.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Object<'a> {
+    /// The identifier of the declaration, or the last element of the path of an import without
+    /// a name.
+    pub name: &'a str,
+    pub kind: ObjKind,
+    pub decl: Decl<'a>,
+}
+
+/// What an identifier refers to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding<'a> {
+    Object(Object<'a>),
+    /// Not declared in any scope, like a name declared in a file that was not resolved or in a
+    /// package imported with a dot import.
+    Unresolved,
+}
+
+impl<'a> Binding<'a> {
+    pub fn object(&self) -> Option<&Object<'a>> {
+        match self {
+            Binding::Object(object) => Some(object),
+            Binding::Unresolved => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Universe,
+    Package,
+    File,
+    Function,
+    Block,
+}
+
+/// The names declared in a scope.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope<'a> {
+    pub kind: ScopeKind,
+    objects: BTreeMap<&'a str, Object<'a>>,
+    dot_imports: Vec<&'a ImportDeclaration<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    fn new(kind: ScopeKind) -> Self {
+        Scope {
+            kind,
+            objects: BTreeMap::new(),
+            dot_imports: vec![],
+        }
+    }
+
+    /// The scope of the predeclared identifiers of [`UNIVERSE`].
+    pub fn universe() -> Scope<'static> {
+        let objects = UNIVERSE.iter().map(|&(name, kind)| {
+            let object = Object {
+                name,
+                kind,
+                decl: Decl::Universe,
+            };
+            (name, object)
+        });
+        Scope {
+            kind: ScopeKind::Universe,
+            objects: objects.collect(),
+            dot_imports: vec![],
+        }
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<&Object<'a>> {
+        self.objects.get(name)
+    }
+
+    /// Adds `object` unless its name is blank or taken, in which case the object of that name is
+    /// returned.
+    fn insert(&mut self, object: Object<'a>) -> Option<Object<'a>> {
+        if object.name == "_" {
+            return None;
+        }
+        match self.objects.entry(object.name) {
+            Entry::Occupied(entry) => Some(*entry.get()),
+            Entry::Vacant(entry) => {
+                entry.insert(object);
+                None
+            }
+        }
+    }
+
+    /// The objects of the scope, sorted by name.
+    pub fn objects(&self) -> impl Iterator<Item = &Object<'a>> {
+        self.objects.values()
+    }
+
+    /// The imports of a file scope that declare the exported names of their package in it, like
+    /// `import . "fmt"`. Those names are not known, so they stay unresolved.
+    pub fn dot_imports(&self) -> &[&'a ImportDeclaration<'a>] {
+        &self.dot_imports
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A name declared twice in the same scope.
+    Redeclared,
+    /// A `:=` without any new variable on its left hand side.
+    NoNewVariables,
+    UndefinedLabel,
+    UnusedLabel,
+    /// The same slice of text used for several identifiers of the tree, which cannot be told
+    /// apart then.
+    SharedText,
+}
+
+/// An error found while resolving names, at the identifier `name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error<'a> {
+    pub kind: ErrorKind,
+    pub name: &'a str,
+    /// The previous declaration of a redeclared name.
+    pub previous: Option<&'a str>,
+}
+
+impl Error<'_> {
+    /// Locates the error in `source`, the text of the file the identifier is from, or `None` if
+    /// the identifier is not a slice of `source`.
+    pub fn span(&self, source: &str) -> Option<Span> {
+        span_in(source, self.name)
+    }
+
+    pub fn message(&self) -> String {
+        match self.kind {
+            ErrorKind::Redeclared => format!("`{}` redeclared in this block", self.name),
+            ErrorKind::NoNewVariables => "no new variables on left side of `:=`".to_string(),
+            ErrorKind::UndefinedLabel => format!("label `{}` not defined", self.name),
+            ErrorKind::UnusedLabel => format!("label `{}` defined and not used", self.name),
+            ErrorKind::SharedText => {
+                format!(
+                    "identifier `{}` appears at several places of the tree",
+                    self.name
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for Error<'_> {}
+
+/// The span of `text` if it is a slice of `source`.
+pub(crate) fn span_in(source: &str, text: &str) -> Option<Span> {
+    let start = (text.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    if start + text.len() > source.len() {
+        return None;
+    }
+    Some(Span {
+        start,
+        end: start + text.len(),
+    })
+}
+
+/// The address of the text at `span` in `source`, which identifies the identifier there.
+pub(crate) fn address_in(source: &str, span: Span) -> Option<usize> {
+    let text = source.get(span.start..span.end)?;
+    Some(text.as_ptr() as usize)
+}
+
+/// The result of [`resolve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution<'a> {
+    pub package: Scope<'a>,
+    /// The scope of the imports of each file, in the order of the files.
+    pub files: Vec<Scope<'a>>,
+    uses: Vec<(&'a str, Binding<'a>)>,
+    /// The index in `uses` of each identifier, by address.
+    use_indices: HashMap<usize, usize>,
+    definitions: HashMap<usize, Object<'a>>,
+    pub errors: Vec<Error<'a>>,
+}
+
+impl<'a> Resolution<'a> {
+    /// What the identifier `name` of the resolved files refers to, or `None` if `name` is not one
+    /// that is resolved, like a declared name or a field name. `name` must be the identifier
+    /// itself, a slice of its file: another string with the same text gives `None`.
+    pub fn binding(&self, name: &str) -> Option<Binding<'a>> {
+        let index = self.use_indices.get(&(name.as_ptr() as usize))?;
+        Some(self.uses[*index].1)
+    }
+
+    /// The object declared by the identifier `name` of the resolved files, which like for
+    /// [`Resolution::binding`] must be the identifier itself.
+    pub fn definition(&self, name: &str) -> Option<&Object<'a>> {
+        self.definitions.get(&(name.as_ptr() as usize))
+    }
+
+    /// What the identifier at `span` in `source`, the text of its file, refers to, like
+    /// [`Resolution::binding`].
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, error::Span, resolve::{resolve, ObjKind}, SourceFile};
+    /// let src = "package m\n\nfunc f(n int) int { return n }\n";
+    /// let (_, file) = SourceFile::parse(src).unwrap();
+    /// let resolution = resolve([&file]);
+    /// let n = src.rfind('n').unwrap();
+    /// let binding = resolution.binding_at(src, Span { start: n, end: n + 1 }).unwrap();
+    /// assert_eq!(binding.object().unwrap().kind, ObjKind::Var);
+    /// let f = src.find("f(").unwrap();
+    /// let definition = resolution.definition_at(src, Span { start: f, end: f + 1 });
+    /// assert_eq!(definition.unwrap().kind, ObjKind::Func);
+    /// ```
+    pub fn binding_at(&self, source: &str, span: Span) -> Option<Binding<'a>> {
+        let index = self.use_indices.get(&address_in(source, span)?)?;
+        Some(self.uses[*index].1)
+    }
+
+    /// The object declared by the identifier at `span` in `source`, like
+    /// [`Resolution::definition`].
+    pub fn definition_at(&self, source: &str, span: Span) -> Option<&Object<'a>> {
+        self.definitions.get(&address_in(source, span)?)
+    }
+
+    /// Every resolved identifier with its binding, in the order of the files.
+    pub fn uses(&self) -> &[(&'a str, Binding<'a>)] {
+        &self.uses
+    }
+
+    /// The identifiers that are not declared in any scope.
+    pub fn unresolved(&self) -> impl Iterator<Item = &'a str> + '_ {
+        let uses = self.uses.iter();
+        uses.filter(|(_, binding)| *binding == Binding::Unresolved)
+            .map(|(name, _)| *name)
+    }
+}
+
+/// Resolves the identifiers of `files`, which make up a package.
+pub fn resolve<'a>(files: impl IntoIterator<Item = &'a SourceFile<'a>>) -> Resolution<'a> {
+    let files: Vec<_> = files.into_iter().collect();
+    let mut resolver = Resolver {
+        universe: Scope::universe(),
+        scopes: vec![],
+        labels: vec![],
+        resolution: Resolution {
+            package: Scope::new(ScopeKind::Package),
+            files: vec![],
+            uses: vec![],
+            use_indices: HashMap::new(),
+            definitions: HashMap::new(),
+            errors: vec![],
+        },
+    };
+    for file in &files {
+        resolver.declare_package_objects(file);
+    }
+    for file in files {
+        resolver.scopes.push(Scope::new(ScopeKind::File));
+        for import in &file.imports {
+            if import.is_dot() {
+                let scope = resolver.scopes.last_mut().unwrap();
+                scope.dot_imports.push(import);
+                continue;
+            }
+            let object = Object {
+                name: import.name(),
+                kind: ObjKind::Package,
+                decl: Decl::Import(import),
+            };
+            if let Some(previous) = resolver.resolution.package.lookup(object.name) {
+                resolver.resolution.errors.push(Error {
+                    kind: ErrorKind::Redeclared,
+                    name: object.name,
+                    previous: Some(previous.name),
+                });
+            }
+            resolver.declare(object);
+        }
+        resolver.visit_source_file(file);
+        let scope = resolver.scopes.pop().unwrap();
+        resolver.resolution.files.push(scope);
+    }
+    resolver.resolution
+}
+
+struct Resolver<'a> {
+    universe: Scope<'static>,
+    /// The file scope, followed by the scopes of the function and blocks being resolved.
+    scopes: Vec<Scope<'a>>,
+    /// The labels of the function body being resolved, with whether they are used.
+    labels: Vec<(Object<'a>, bool)>,
+    resolution: Resolution<'a>,
+}
+
+impl<'a> Resolver<'a> {
+    fn declare_package_objects(&mut self, file: &'a SourceFile<'a>) {
+        for decl in &file.decls {
+            match decl {
+                TopLevel::Function(function) => {
+                    let object = Object {
+                        name: function.name,
+                        kind: ObjKind::Func,
+                        decl: Decl::Func(function),
+                    };
+                    // Methods and `init` functions cannot be referred to by name.
+                    if function.receiver.is_some() || function.name == "init" {
+                        self.define(object);
+                    } else {
+                        self.declare_in_package(object);
+                    }
+                }
+                TopLevel::Declaration(decl) => {
+                    for object in declared_objects(decl) {
+                        self.declare_in_package(object);
+                    }
+                }
+                TopLevel::Pkg(_) | TopLevel::Import(_) | TopLevel::BadDecl(_) => {}
+            }
+        }
+    }
+
+    fn declare_in_package(&mut self, object: Object<'a>) {
+        let previous = self.resolution.package.insert(object);
+        self.declared(object, previous);
+    }
+
+    /// Declares `object` in the innermost scope.
+    fn declare(&mut self, object: Object<'a>) {
+        let scope = self.scopes.last_mut().expect("no scope to declare in");
+        let previous = scope.insert(object);
+        self.declared(object, previous);
+    }
+
+    fn declared(&mut self, object: Object<'a>, previous: Option<Object<'a>>) {
+        if object.name == "_" {
+            return;
+        }
+        self.define(object);
+        if let Some(previous) = previous {
+            self.resolution.errors.push(Error {
+                kind: ErrorKind::Redeclared,
+                name: object.name,
+                previous: Some(previous.name),
+            });
+        }
+    }
+
+    fn define(&mut self, object: Object<'a>) {
+        let definitions = &mut self.resolution.definitions;
+        if let Some(previous) = definitions.insert(object.name.as_ptr() as usize, object) {
+            // The variable of a type switch guard is declared again in each clause.
+            let same = match (previous.decl, object.decl) {
+                (Decl::TypeSwitch(a, _), Decl::TypeSwitch(b, _)) => std::ptr::eq(a, b),
+                (a, b) => a == b,
+            };
+            if !same {
+                self.shared_text(object.name);
+            }
+        }
+    }
+
+    fn shared_text(&mut self, name: &'a str) {
+        self.resolution.errors.push(Error {
+            kind: ErrorKind::SharedText,
+            name,
+            previous: None,
+        });
+    }
+
+    fn lookup(&self, name: &str) -> Option<Object<'a>> {
+        let scopes = self
+            .scopes
+            .iter()
+            .rev()
+            .chain([&self.resolution.package, &self.universe]);
+        scopes
+            .filter_map(|scope| scope.lookup(name))
+            .next()
+            .copied()
+    }
+
+    fn bind(&mut self, name: &'a str, binding: Binding<'a>) {
+        let resolution = &mut self.resolution;
+        let index = resolution.uses.len();
+        resolution.uses.push((name, binding));
+        if resolution
+            .use_indices
+            .insert(name.as_ptr() as usize, index)
+            .is_some()
+        {
+            self.shared_text(name);
+        }
+    }
+
+    fn resolve(&mut self, name: &'a str) {
+        if name == "_" {
+            return;
+        }
+        let binding = self
+            .lookup(name)
+            .map_or(Binding::Unresolved, Binding::Object);
+        self.bind(name, binding);
+    }
+
+    fn with_scope(&mut self, kind: ScopeKind, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(Scope::new(kind));
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn is_local(&self) -> bool {
+        matches!(
+            self.scopes.last(),
+            Some(Scope {
+                kind: ScopeKind::Function | ScopeKind::Block,
+                ..
+            })
+        )
+    }
+
+    /// Declares all the type parameters before resolving their constraints, which may refer to
+    /// each other.
+    fn declare_type_params(&mut self, params: &'a [TypeParamDecl<'a>]) {
+        for param in params {
+            for name in &param.identifiers {
+                self.declare(Object {
+                    name,
+                    kind: ObjKind::Type,
+                    decl: Decl::TypeParam(param),
+                });
+            }
+        }
+        for param in params {
+            self.visit_type_param_decl(param);
+        }
+    }
+
+    fn declare_params(&mut self, params: &'a [ParameterDecl<'a>]) {
+        for param in params {
+            for name in param.identifiers.iter().flatten() {
+                self.declare(Object {
+                    name,
+                    kind: ObjKind::Var,
+                    decl: Decl::Param(param),
+                });
+            }
+        }
+    }
+
+    fn short_var_decl(&mut self, identifiers: &'a [&'a str], stmt: &'a SimpleStmt<'a>) {
+        let mut new = false;
+        for (i, name) in identifiers.iter().enumerate() {
+            if *name == "_" {
+                continue;
+            }
+            if let Some(previous) = identifiers[..i].iter().find(|previous| *previous == name) {
+                self.resolution.errors.push(Error {
+                    kind: ErrorKind::Redeclared,
+                    name,
+                    previous: Some(previous),
+                });
+                continue;
+            }
+            let scope = self.scopes.last().unwrap();
+            match scope.lookup(name) {
+                // Assigned to rather than declared.
+                Some(object) => {
+                    let object = *object;
+                    self.bind(name, Binding::Object(object));
+                }
+                None => {
+                    new = true;
+                    self.declare(Object {
+                        name,
+                        kind: ObjKind::Var,
+                        decl: Decl::ShortVarDecl(stmt),
+                    });
+                }
+            }
+        }
+        if !new {
+            self.resolution.errors.push(Error {
+                kind: ErrorKind::NoNewVariables,
+                name: identifiers[0],
+                previous: None,
+            });
+        }
+    }
+
+    /// Declares the labels of `stmts` and of the statements nested in them.
+    fn declare_labels(&mut self, stmts: &'a [Statement<'a>]) {
+        for stmt in stmts {
+            self.declare_labels_of(stmt);
+        }
+    }
+
+    fn declare_labels_of(&mut self, stmt: &'a Statement<'a>) {
+        match stmt {
+            Statement::LabeledStmt { label, stmt: inner } => {
+                match self.labels.iter().find(|(object, _)| object.name == *label) {
+                    Some((previous, _)) => self.resolution.errors.push(Error {
+                        kind: ErrorKind::Redeclared,
+                        name: label,
+                        previous: Some(previous.name),
+                    }),
+                    None => {
+                        let object = Object {
+                            name: label,
+                            kind: ObjKind::Label,
+                            decl: Decl::Label(stmt),
+                        };
+                        self.define(object);
+                        self.labels.push((object, false));
+                    }
+                }
+                self.declare_labels_of(inner);
+            }
+            Statement::Block(block) => self.declare_labels(&block.0),
+            Statement::IfStmt(stmt) => {
+                let mut stmt = stmt;
+                loop {
+                    self.declare_labels(&stmt.then.0);
+                    match &stmt.otherwise {
+                        Some(Else::If(otherwise)) => stmt = otherwise,
+                        Some(Else::Block(block)) => break self.declare_labels(&block.0),
+                        None => break,
+                    }
+                }
+            }
+            Statement::SwitchStmt(stmt) => {
+                for clause in &stmt.clauses {
+                    self.declare_labels(&clause.body);
+                }
+            }
+            Statement::TypeSwitchStmt(stmt) => {
+                for clause in &stmt.clauses {
+                    self.declare_labels(&clause.body);
+                }
+            }
+            Statement::SelectStmt(stmt) => {
+                for clause in &stmt.clauses {
+                    self.declare_labels(&clause.body);
+                }
+            }
+            Statement::ForStmt(stmt) => self.declare_labels(&stmt.body.0),
+            _ => {}
+        }
+    }
+
+    /// Resolves a body in the scope of the parameters, with its own labels.
+    fn function_body(&mut self, body: &'a Block<'a>) {
+        let labels = std::mem::take(&mut self.labels);
+        self.declare_labels(&body.0);
+        for stmt in &body.0 {
+            self.visit_statement(stmt);
+        }
+        let labels = std::mem::replace(&mut self.labels, labels);
+        for (label, used) in labels {
+            if !used {
+                self.resolution.errors.push(Error {
+                    kind: ErrorKind::UnusedLabel,
+                    name: label.name,
+                    previous: None,
+                });
+            }
+        }
+    }
+
+    fn resolve_label(&mut self, label: &'a str) {
+        match self
+            .labels
+            .iter_mut()
+            .find(|(object, _)| object.name == label)
+        {
+            Some((object, used)) => {
+                *used = true;
+                let object = *object;
+                self.bind(label, Binding::Object(object));
+            }
+            None => {
+                self.bind(label, Binding::Unresolved);
+                self.resolution.errors.push(Error {
+                    kind: ErrorKind::UndefinedLabel,
+                    name: label,
+                    previous: None,
+                });
+            }
+        }
+    }
+
+    /// The identifier of an element that is nothing else, which may be a field name.
+    fn field_name(element: &'a Element<'a>) -> Option<&'a str> {
+        match element {
+            Element::Expression(Expression::UnaryExpr(UnaryExpr::PrimaryExpr(
+                PrimaryExpr::Operand(Operand::OperandName(OperandName::Identifier(name))),
+            ))) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// The objects declared by a declaration.
+fn declared_objects<'a>(decl: &'a Declaration<'a>) -> Vec<Object<'a>> {
+    match decl {
+        Declaration::ConstDecl(decl) => {
+            let specs = decl.specs.iter();
+            specs
+                .flat_map(|spec| &spec.identifiers)
+                .map(|name| Object {
+                    name,
+                    kind: ObjKind::Const,
+                    decl: Decl::Const(decl),
+                })
+                .collect()
+        }
+        Declaration::VarDecl(decl) => {
+            let specs = decl.specs.iter();
+            specs
+                .flat_map(|spec| spec.identifiers.iter().map(move |name| (name, spec)))
+                .map(|(name, spec)| Object {
+                    name,
+                    kind: ObjKind::Var,
+                    decl: Decl::Var(spec),
+                })
+                .collect()
+        }
+        Declaration::TypeDecl(decl) => decl.specs.iter().map(type_object).collect(),
+    }
+}
+
+fn type_object<'a>(spec: &'a TypeSpec<'a>) -> Object<'a> {
+    let (TypeSpec::AliasDecl { name, .. } | TypeSpec::TypeDef { name, .. }) = spec;
+    Object {
+        name,
+        kind: ObjKind::Type,
+        decl: Decl::Type(spec),
+    }
+}
+
+impl<'a> Visitor<'a> for Resolver<'a> {
+    fn visit_function(&mut self, node: &'a Function<'a>) {
+        self.with_scope(ScopeKind::Function, |r| {
+            if let Some(receiver) = &node.receiver {
+                r.resolve(receiver.base_type);
+                let names = receiver
+                    .type_params
+                    .iter()
+                    .map(|name| (name, ObjKind::Type));
+                for (name, kind) in
+                    names.chain(receiver.name.iter().map(|name| (name, ObjKind::Var)))
+                {
+                    r.declare(Object {
+                        name,
+                        kind,
+                        decl: Decl::Receiver(receiver),
+                    });
+                }
+            }
+            r.declare_type_params(&node.type_params);
+            // The types of the parameters are resolved before their names are declared.
+            r.visit_signature(&node.signature);
+            r.declare_params(&node.signature.params);
+            r.declare_params(&node.signature.results);
+            if let Some(body) = &node.body {
+                r.function_body(body);
+            }
+        });
+    }
+
+    fn visit_function_lit(&mut self, node: &'a FunctionLit<'a>) {
+        self.with_scope(ScopeKind::Function, |r| {
+            r.visit_signature(&node.signature);
+            r.declare_params(&node.signature.params);
+            r.declare_params(&node.signature.results);
+            r.function_body(&node.body);
+        });
+    }
+
+    fn visit_const_decl(&mut self, node: &'a ConstDecl<'a>) {
+        for spec in &node.specs {
+            self.visit_const_spec(spec);
+            if self.is_local() {
+                for name in &spec.identifiers {
+                    self.declare(Object {
+                        name,
+                        kind: ObjKind::Const,
+                        decl: Decl::Const(node),
+                    });
+                }
+            }
+        }
+    }
+
+    fn visit_var_spec(&mut self, node: &'a VarSpec<'a>) {
+        visit::walk_var_spec(self, node);
+        if self.is_local() {
+            for name in &node.identifiers {
+                self.declare(Object {
+                    name,
+                    kind: ObjKind::Var,
+                    decl: Decl::Var(node),
+                });
+            }
+        }
+    }
+
+    fn visit_type_spec(&mut self, node: &'a TypeSpec<'a>) {
+        // In scope in its own definition.
+        if self.is_local() {
+            self.declare(type_object(node));
+        }
+        let (TypeSpec::AliasDecl {
+            type_params, typ, ..
+        }
+        | TypeSpec::TypeDef {
+            type_params, typ, ..
+        }) = node;
+        self.with_scope(ScopeKind::Block, |r| {
+            r.declare_type_params(type_params);
+            r.visit_type(typ);
+        });
+    }
+
+    fn visit_type_name(&mut self, node: &'a TypeName<'a>) {
+        match node {
+            TypeName::Identifier(name) => self.resolve(name),
+            TypeName::QualifiedIdent(ident) => self.resolve(ident.package_name),
+        }
+    }
+
+    fn visit_operand_name(&mut self, node: &'a OperandName<'a>) {
+        match node {
+            OperandName::Identifier(name) => self.resolve(name),
+            // Either a package and one of its members, or a selector.
+            OperandName::QualifiedIdent(ident) => self.resolve(ident.package_name),
+        }
+    }
+
+    fn visit_keyed_element(&mut self, node: &'a KeyedElement<'a>) {
+        match node.key.as_ref().map(|key| (key, Self::field_name(key))) {
+            Some((_, Some(name))) => {
+                if let Some(object) = self.lookup(name) {
+                    self.bind(name, Binding::Object(object));
+                }
+            }
+            Some((key, None)) => self.visit_element(key),
+            None => {}
+        }
+        self.visit_element(&node.value);
+    }
+
+    fn visit_statement(&mut self, node: &'a Statement<'a>) {
+        match node {
+            Statement::BreakStmt(Some(label)) | Statement::ContinueStmt(Some(label)) => {
+                self.resolve_label(label)
+            }
+            Statement::GotoStmt(label) => self.resolve_label(label),
+            _ => visit::walk_statement(self, node),
+        }
+    }
+
+    fn visit_block(&mut self, node: &'a Block<'a>) {
+        self.with_scope(ScopeKind::Block, |r| visit::walk_block(r, node));
+    }
+
+    fn visit_simple_stmt(&mut self, node: &'a SimpleStmt<'a>) {
+        visit::walk_simple_stmt(self, node);
+        if let SimpleStmt::ShortVarDecl { identifiers, .. } = node {
+            self.short_var_decl(identifiers, node);
+        }
+    }
+
+    fn visit_if_stmt(&mut self, node: &'a IfStmt<'a>) {
+        self.with_scope(ScopeKind::Block, |r| visit::walk_if_stmt(r, node));
+    }
+
+    fn visit_for_stmt(&mut self, node: &'a ForStmt<'a>) {
+        self.with_scope(ScopeKind::Block, |r| {
+            match &node.clause {
+                Some(ForClause::RangeClause { vars, expr }) => {
+                    r.visit_expression(expr);
+                    match vars {
+                        Some(RangeVars::Declared(names)) => {
+                            for name in names {
+                                r.declare(Object {
+                                    name,
+                                    kind: ObjKind::Var,
+                                    decl: Decl::Range(node),
+                                });
+                            }
+                        }
+                        Some(vars) => r.visit_range_vars(vars),
+                        None => {}
+                    }
+                }
+                Some(clause) => r.visit_for_clause(clause),
+                None => {}
+            }
+            r.visit_block(&node.body);
+        });
+    }
+
+    fn visit_switch_stmt(&mut self, node: &'a SwitchStmt<'a>) {
+        self.with_scope(ScopeKind::Block, |r| visit::walk_switch_stmt(r, node));
+    }
+
+    fn visit_expr_case_clause(&mut self, node: &'a ExprCaseClause<'a>) {
+        self.with_scope(ScopeKind::Block, |r| visit::walk_expr_case_clause(r, node));
+    }
+
+    fn visit_type_switch_stmt(&mut self, node: &'a TypeSwitchStmt<'a>) {
+        self.with_scope(ScopeKind::Block, |r| {
+            if let Some(init) = &node.init {
+                r.visit_simple_stmt(init);
+            }
+            r.visit_primary_expr(&node.expr);
+            for clause in &node.clauses {
+                r.with_scope(ScopeKind::Block, |r| {
+                    for typ in clause.case.iter().flatten() {
+                        r.visit_type(typ);
+                    }
+                    if let Some(name) = node.binding {
+                        r.declare(Object {
+                            name,
+                            kind: ObjKind::Var,
+                            decl: Decl::TypeSwitch(node, clause),
+                        });
+                    }
+                    for stmt in &clause.body {
+                        r.visit_statement(stmt);
+                    }
+                });
+            }
+        });
+    }
+
+    fn visit_comm_clause(&mut self, node: &'a CommClause<'a>) {
+        self.with_scope(ScopeKind::Block, |r| visit::walk_comm_clause(r, node));
+    }
+}
+
+#[test]
+fn test_resolve_scopes_and_labels() {
+    use crate::{astable::ASTable, diagnostic::Diagnostic};
+
+    let src = "package m
+
+import \"fmt\"
+
+type T struct{ next *T }
+
+var T = 0
+
+func (t *T) Len() (n int) {
+	for p := t; p != nil; p = p.next {
+		n++
+	}
+	return n
+}
+
+func f(xs []int, t T) int {
+	a, b := 1, 2
+	a, c := 3, b
+	a, b := 5, 6
+	if x := a; x > 0 {
+		x := x + c
+		fmt.Println(x, missing)
+	}
+outer:
+	for _, x := range xs {
+		switch v := t.(type) {
+		case int:
+			continue outer
+		case string:
+			return len(v)
+		}
+	}
+unused:
+	goto nowhere
+	return T{next: &t}.Len()
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let resolution = resolve([&file]);
+
+    // The occurrences of the identifier `name`.
+    let idents = |name: &str| -> Vec<&str> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        src.match_indices(name)
+            .filter(|(i, _)| {
+                !src[..*i].ends_with(is_word) && !src[i + name.len()..].starts_with(is_word)
+            })
+            .map(|(i, _)| &src[i..i + name.len()])
+            .collect()
+    };
+    let object = |name: &str| *resolution.binding(name).unwrap().object().unwrap();
+    let declared_by = |name: &str, declaration: &str| {
+        assert!(std::ptr::eq(object(name).name, declaration), "{}", name);
+    };
+
+    let x = idents("x");
+    declared_by(x[1], x[0]);
+    // The new `x` is in scope after its declaration only.
+    declared_by(x[3], x[0]);
+    declared_by(x[4], x[2]);
+    let a = idents("a");
+    // Assigned to by the second and third `:=`.
+    for assigned in &a[1..] {
+        declared_by(assigned, a[0]);
+    }
+    let (b, c, n) = (idents("b"), idents("c"), idents("n"));
+    declared_by(b[1], b[0]);
+    declared_by(c[1], c[0]);
+    declared_by(n[1], n[0]);
+    declared_by(n[2], n[0]);
+
+    let t = idents("t");
+    assert!(matches!(object(t[1]).decl, Decl::Receiver(_)));
+    assert!(matches!(object(t[3]).decl, Decl::Param(_)));
+    assert!(matches!(object(t[4]).decl, Decl::Param(_)));
+    let v = idents("v");
+    match object(v[1]).decl {
+        Decl::TypeSwitch(stmt, clause) => assert!(std::ptr::eq(clause, &stmt.clauses[1])),
+        decl => panic!("{:?}", decl),
+    }
+    // The type, not the variable redeclaring it.
+    let types = idents("T");
+    assert!(matches!(object(types[3]).decl, Decl::Type(_)));
+    assert_eq!(object(idents("fmt")[1]).kind, ObjKind::Package);
+    assert_eq!(object(idents("len")[0]).decl, Decl::Universe);
+    assert_eq!(object(idents("outer")[1]).kind, ObjKind::Label);
+    // A field name.
+    assert_eq!(resolution.binding(idents("next")[2]), None);
+    assert_eq!(
+        resolution.unresolved().collect::<Vec<_>>(),
+        ["missing", "nowhere"]
+    );
+
+    let errors: Vec<_> = resolution.errors.iter().map(|e| (e.kind, e.name)).collect();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::Redeclared, "T"),
+            (ErrorKind::NoNewVariables, "a"),
+            (ErrorKind::UndefinedLabel, "nowhere"),
+            (ErrorKind::UnusedLabel, "unused"),
+        ]
+    );
+    let diagnostic = Diagnostic::from_resolve_error(&resolution.errors[0], src);
+    assert_eq!(diagnostic.message, "`T` redeclared in this block");
+    assert_eq!(diagnostic.labels[1].span, span_in(src, types[0]).unwrap());
+
+    let package: Vec<_> = resolution.package.objects().map(|o| o.name).collect();
+    assert_eq!(package, ["T", "f"]);
+    assert_eq!(
+        resolution.files[0].lookup("fmt").unwrap().kind,
+        ObjKind::Package
+    );
+}
+
+#[test]
+fn test_resolve_dot_imports_and_shared_text() {
+    use crate::{astable::ASTable, types::check, visit_mut::VisitorMut};
+
+    let src = "package m
+
+import . \"fmt\"
+
+func f() {
+	x := 1
+	Println(x)
+}
+
+func g() {
+	x := \"s\"
+	Println(x + \"t\")
+}
+";
+    let (_, mut file) = SourceFile::parse(src).unwrap();
+    let resolution = resolve([&file]);
+    assert_eq!(resolution.errors, []);
+    assert_eq!(resolution.files[0].dot_imports()[0].path(), "fmt");
+    assert_eq!(resolution.files[0].objects().count(), 0);
+    assert_eq!(
+        resolution.unresolved().collect::<Vec<_>>(),
+        ["Println", "Println"]
+    );
+
+    // Every `x` becomes the same text, so the two variables can no longer be told apart.
+    struct Share;
+    impl<'a> VisitorMut<'a> for Share {
+        fn visit_operand_name(&mut self, node: &mut OperandName<'a>) {
+            if *node == OperandName::Identifier("x") {
+                *node = OperandName::Identifier("x");
+            }
+        }
+
+        fn visit_simple_stmt(&mut self, node: &mut SimpleStmt<'a>) {
+            if let SimpleStmt::ShortVarDecl { identifiers, .. } = node {
+                identifiers[0] = "x";
+            }
+            crate::visit_mut::walk_simple_stmt(self, node)
+        }
+    }
+    Share.visit_source_file(&mut file);
+    let resolution = resolve([&file]);
+    let errors: Vec<_> = resolution.errors.iter().map(|e| (e.kind, e.name)).collect();
+    assert_eq!(
+        errors,
+        [(ErrorKind::SharedText, "x"), (ErrorKind::SharedText, "x")]
+    );
+    assert_eq!(
+        resolution.errors[0].message(),
+        "identifier `x` appears at several places of the tree"
+    );
+    // The shared text is not from the source.
+    assert_eq!(resolution.errors[0].span(src), None);
+    let diagnostic = crate::diagnostic::Diagnostic::from_resolve_error(&resolution.errors[0], src);
+    assert_eq!(diagnostic.labels, []);
+    assert_eq!(check([&file], &resolution).errors, []);
+}
@@ -0,0 +1,290 @@
+//! The JSON shape of syntax trees, enabled by the `serde` feature.
+//!
+//! Every node implements `Serialize` and `Deserialize`, following serde's defaults:
+//!
+//! - a struct is an object with a member per field, named like the field, e.g.
+//!   `{"doc": null, "package": …, "imports": […], "decls": […]}` for a [`SourceFile`];
+//! - a tuple struct is an array of its fields, except one with a single field, which is that field;
+//! - an enum variant is `"Name"` when it has no fields, and `{"Name": …}` otherwise, with the
+//!   fields like those of a struct, e.g. `{"BinExpr": {"left": …, "op": …, "right": …}}`;
+//! - `None` is `null`, a `Vec` is an array and a `Box` is its content; a rune is a one
+//!   character string.
+//!
+//! Text taken from the source, like an identifier, an operator or a literal, is
+//! `{"text": "main", "span": {"start": 8, "end": 12}}`. The span is the byte range of the text in the
+//! source given to [`with_source`], and `null` outside of it or when no source is given.
+//!
+//! A whole tree is exchanged as a [`Document`], `{"version": 2, "root": …}`. [`VERSION`] changes
+//! whenever the shape of any node does, and deserializing a document of another version fails.
+//! ```
+//! use go_parser_rs::{astable::ASTable, schema::{with_source, Document}, SourceFile};
+//! let src = "package main\n";
+//! let (_, file) = SourceFile::parse(src).unwrap();
+//! let json = with_source(src, || serde_json::to_string(&Document::new(&file))).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"version":2,"root":{"doc":null,"package":{"text":"main","span":{"start":8,"end":12}},"imports":[],"decls":[]}}"#
+//! );
+//! let document: Document<SourceFile> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(document.root, file);
+//! ```
+//!
+//! Deserialized nodes borrow their text from the input, so they are read from a string or a
+//! `&serde_json::Value`, and text with escapes, like most string literals, cannot be read from a
+//! string. An [`Owned`](crate::owned::Owned) node copies its text instead, and is read from
+//! anything, with `DeserializeOwned` functions like `serde_json::from_reader` too:
+//! ```
+//! use go_parser_rs::{astable::ASTable, owned::Owned, schema::Document, SourceFile};
+//! let (_, file) = SourceFile::parse("package main\nconst s = \"s\"\n").unwrap();
+//! let json = serde_json::to_string(&Document::new(&file)).unwrap();
+//! assert!(serde_json::from_str::<Document<SourceFile>>(&json).is_err());
+//! let document: Document<Owned<SourceFile<'static>>> =
+//!     serde_json::from_reader(json.as_bytes()).unwrap();
+//! assert_eq!(*document.root.get(), file);
+//! ```
+
+use std::{borrow::Cow, cell::Cell, ptr::NonNull};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(doc)]
+use crate::SourceFile;
+use crate::{error::Span, owned::Strings, parse_util::Restore};
+
+/// The version of the JSON shape described in the [module documentation](self).
+pub const VERSION: u32 = 2;
+
+/// A syntax tree with the version of its JSON shape.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Document<T> {
+    #[serde(deserialize_with = "version")]
+    pub version: u32,
+    pub root: T,
+}
+
+impl<T> Document<T> {
+    pub fn new(root: T) -> Self {
+        Document {
+            version: VERSION,
+            root,
+        }
+    }
+}
+
+fn version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+    if version != VERSION {
+        return Err(D::Error::custom(format!(
+            "unsupported version {}, expected {}",
+            version, VERSION
+        )));
+    }
+    Ok(version)
+}
+
+thread_local! {
+    /// The address and the length of the source given to `with_source`.
+    static SOURCE: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, in which text serialized from `source` gets its span.
+pub fn with_source<R>(source: &str, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore::set(&SOURCE, Some((source.as_ptr() as usize, source.len())));
+    f()
+}
+
+thread_local! {
+    /// The storage given to `with_strings`.
+    static STRINGS: Cell<Option<NonNull<Strings>>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, in which deserialized text is copied into `strings` rather than borrowed.
+pub(crate) fn with_strings<R>(strings: &Strings, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore::set(&STRINGS, Some(NonNull::from(strings)));
+    f()
+}
+
+fn span_of(text: &str) -> Option<Span> {
+    let (source, len) = SOURCE.with(Cell::get)?;
+    let start = (text.as_ptr() as usize).checked_sub(source)?;
+    if start + text.len() > len {
+        return None;
+    }
+    Some(Span {
+        start,
+        end: start + text.len(),
+    })
+}
+
+#[derive(Serialize)]
+struct Text<'a> {
+    text: &'a str,
+    span: Option<Span>,
+}
+
+#[derive(Deserialize)]
+struct TextDe<'a> {
+    #[serde(borrow)]
+    text: Cow<'a, str>,
+    #[allow(dead_code)]
+    span: Option<Span>,
+}
+
+/// Fields holding text from the source: `&str`, and options and vectors of them.
+pub(crate) trait Texts: Sized {
+    fn serialize_texts<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// The [`Texts`] that can be deserialized from an input living for `'de`.
+pub(crate) trait DeTexts<'de>: Texts {
+    fn deserialize_texts<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl Texts for &str {
+    fn serialize_texts<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = Text {
+            text: self,
+            span: span_of(self),
+        };
+        text.serialize(serializer)
+    }
+}
+
+impl<'de: 'a, 'a> DeTexts<'de> for &'a str {
+    fn deserialize_texts<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = TextDe::deserialize(deserializer)?.text;
+        if let Some(strings) = STRINGS.with(Cell::get) {
+            // SAFETY: `with_strings` is only given storage that outlives the node deserialized
+            // in it.
+            return Ok(unsafe { strings.as_ref() }.alloc(&text));
+        }
+        match text {
+            Cow::Borrowed(text) => Ok(text),
+            Cow::Owned(text) => Err(D::Error::custom(format!(
+                "cannot borrow {:?} from the input, read an owned tree instead",
+                text
+            ))),
+        }
+    }
+}
+
+impl<T: Texts> Texts for Option<T> {
+    fn serialize_texts<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_ref().map(Ser).serialize(serializer)
+    }
+}
+
+impl<'de, T: DeTexts<'de>> DeTexts<'de> for Option<T> {
+    fn deserialize_texts<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<De<T>>::deserialize(deserializer).map(|texts| texts.map(|De(texts)| texts))
+    }
+}
+
+impl<T: Texts> Texts for Vec<T> {
+    fn serialize_texts<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Ser))
+    }
+}
+
+impl<'de, T: DeTexts<'de>> DeTexts<'de> for Vec<T> {
+    fn deserialize_texts<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<De<T>>::deserialize(deserializer)
+            .map(|texts| texts.into_iter().map(|De(texts)| texts).collect())
+    }
+}
+
+struct Ser<'t, T>(&'t T);
+
+impl<T: Texts> Serialize for Ser<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_texts(serializer)
+    }
+}
+
+struct De<T>(T);
+
+impl<'de, T: DeTexts<'de>> Deserialize<'de> for De<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_texts(deserializer).map(De)
+    }
+}
+
+/// The `serde(with = "crate::schema::text")` of fields implementing [`Texts`].
+pub(crate) mod text {
+    use serde::{Deserializer, Serializer};
+
+    use super::{DeTexts, Texts};
+
+    pub fn serialize<T: Texts, S: Serializer>(texts: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        texts.serialize_texts(serializer)
+    }
+
+    pub fn deserialize<'de, T: DeTexts<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize_texts(deserializer)
+    }
+}
+
+#[test]
+fn test_round_trip_with_spans() {
+    use crate::recover::parse_source_file;
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/hello.go");
+    let src = std::fs::read_to_string(path).unwrap();
+    let (file, errors) = parse_source_file(&src);
+    assert!(errors.is_empty());
+    let json = with_source(&src, || serde_json::to_value(Document::new(&file))).unwrap();
+
+    fn check_spans(value: &serde_json::Value, src: &str, texts: &mut usize) {
+        match value {
+            serde_json::Value::Object(object) if object.contains_key("span") => {
+                let span = &object["span"];
+                let range = span["start"].as_u64().unwrap() as usize
+                    ..span["end"].as_u64().unwrap() as usize;
+                assert_eq!(object["text"], src[range]);
+                *texts += 1;
+            }
+            serde_json::Value::Object(object) => {
+                object.values().for_each(|v| check_spans(v, src, texts))
+            }
+            serde_json::Value::Array(array) => {
+                array.iter().for_each(|v| check_spans(v, src, texts))
+            }
+            _ => {}
+        }
+    }
+    let mut texts = 0;
+    check_spans(&json["root"], &src, &mut texts);
+    assert_eq!(texts, 7);
+
+    let document: Document<crate::SourceFile> = Document::deserialize(&json).unwrap();
+    assert_eq!(document.root, file);
+
+    let mut json = json;
+    json["version"] = (VERSION + 1).into();
+    let error = Document::<crate::SourceFile>::deserialize(&json).unwrap_err();
+    assert!(error.to_string().starts_with("unsupported version"));
+}
+
+#[test]
+fn test_storage_reset_after_panic() {
+    use crate::{astable::ASTable, SourceFile};
+
+    let panicked = std::panic::catch_unwind(|| {
+        let strings = Strings::default();
+        with_strings(&strings, || panic!("in deserialize"))
+    });
+    assert!(panicked.is_err());
+    assert!(STRINGS.with(Cell::get).is_none());
+    let panicked =
+        std::panic::catch_unwind(|| with_source("package m\n", || panic!("in serialize")));
+    assert!(panicked.is_err());
+    assert!(SOURCE.with(Cell::get).is_none());
+
+    let (_, file) = SourceFile::parse("package main\n").unwrap();
+    let json = serde_json::to_string(&Document::new(&file)).unwrap();
+    // Text is borrowed again, not copied into the storage dropped by the panic.
+    let document: Document<SourceFile> = serde_json::from_str(&json).unwrap();
+    assert_eq!(document.root, file);
+}
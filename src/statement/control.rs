@@ -0,0 +1,473 @@
+use nom::{
+    branch::alt,
+    combinator::{cut, map, opt, verify},
+    multi::separated_list1,
+    sequence::{pair, preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::{
+    astable::ASTable,
+    error::{expected, Expected, ParseError},
+    expression::{control_clause, Expression, PrimaryExpr, Unary, UnaryExpr},
+    parse_identifier_list,
+    parse_util::{identifier, reserved, symbol},
+    typ::GoType,
+};
+
+use super::{parse_statement_list, simple::SimpleStmt, Block, Statement, StatementList};
+
+/// IfStmt = "if" [ SimpleStmt ";" ] Expression Block [ "else" ( IfStmt | Block ) ] .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IfStmt<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub init: Option<SimpleStmt<'a>>,
+    pub cond: Expression<'a>,
+    pub then: Block<'a>,
+    pub otherwise: Option<Else<'a>>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Else<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    If(Box<IfStmt<'a>>),
+    Block(Block<'a>),
+}
+
+impl<'a> ASTable<'a> for IfStmt<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::control::{Else, IfStmt};
+    /// let (s, stmt) = IfStmt::parse("if err := f(); err != nil {\n} else if x {\n} else {\n}").unwrap();
+    /// assert_eq!(s, "");
+    /// assert!(stmt.init.is_some());
+    /// match stmt.otherwise {
+    ///     Some(Else::If(stmt)) => assert!(matches!(stmt.otherwise, Some(Else::Block(_)))),
+    ///     otherwise => panic!("{:?}", otherwise),
+    /// }
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_with(s, &mut parse_statement_list)
+    }
+}
+
+impl<'a> IfStmt<'a> {
+    /// Parses an if statement whose blocks are read by `list`, see [`Block::parse_with`].
+    pub(crate) fn parse_with(
+        s: &'a str,
+        list: &mut StatementList<'_, 'a>,
+    ) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("if")(s)?;
+        let (s, (init, cond)) = control_clause(pair(
+            opt(terminated(SimpleStmt::parse, symbol(";"))),
+            Expression::parse,
+        ))(s)?;
+        let (s, then) = Block::parse_with(s, list)?;
+        let (s, otherwise) = match reserved("else")(s) {
+            Ok((rest, _)) if reserved("if")(rest).is_ok() => {
+                let (rest, stmt) = Self::parse_with(rest, list)?;
+                (rest, Some(Else::If(Box::new(stmt))))
+            }
+            Ok((rest, _)) => {
+                let (rest, block) = Block::parse_with(rest, list)?;
+                (rest, Some(Else::Block(block)))
+            }
+            Err(_) => (s, None),
+        };
+        Ok((
+            s,
+            IfStmt {
+                init,
+                cond,
+                then,
+                otherwise,
+            },
+        ))
+    }
+}
+
+/// ForStmt = "for" [ Condition | ForClause | RangeClause ] Block .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForStmt<'a> {
+    /// `None` for an infinite loop.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub clause: Option<ForClause<'a>>,
+    pub body: Block<'a>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForClause<'a> {
+    /// Condition = Expression .
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Condition(Expression<'a>),
+    /// ForClause = [ InitStmt ] ";" [ Condition ] ";" [ PostStmt ] .
+    ForClause {
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        init: Option<SimpleStmt<'a>>,
+        cond: Option<Expression<'a>>,
+        post: Option<SimpleStmt<'a>>,
+    },
+    /// RangeClause = [ ExpressionList "=" | IdentifierList ":=" ] "range" Expression .
+    RangeClause {
+        /// `None` for `for range x`.
+        vars: Option<RangeVars<'a>>,
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        expr: Expression<'a>,
+    },
+}
+
+/// The iteration variables of a range clause.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RangeVars<'a> {
+    /// IdentifierList ":="
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    Declared(Vec<&'a str>),
+    /// ExpressionList "="
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Assigned(Vec<Expression<'a>>),
+}
+
+impl<'a> ASTable<'a> for ForStmt<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::control::{ForClause, ForStmt, RangeVars};
+    /// assert!(matches!(ForStmt::parse("for {}"), Ok(("", ForStmt { clause: None, .. }))));
+    /// assert!(matches!(ForStmt::parse("for x < 10 {}"), Ok(("", ForStmt { clause: Some(ForClause::Condition(_)), .. }))));
+    /// assert!(matches!(
+    ///     ForStmt::parse("for i := 0; i < n; i++ {}"),
+    ///     Ok(("", ForStmt { clause: Some(ForClause::ForClause { init: Some(_), cond: Some(_), post: Some(_) }), .. }))
+    /// ));
+    /// assert!(matches!(
+    ///     ForStmt::parse("for ;; {}"),
+    ///     Ok(("", ForStmt { clause: Some(ForClause::ForClause { init: None, cond: None, post: None }), .. }))
+    /// ));
+    /// assert!(matches!(
+    ///     ForStmt::parse("for k, v := range m {}"),
+    ///     Ok(("", ForStmt { clause: Some(ForClause::RangeClause { vars: Some(RangeVars::Declared(_)), .. }), .. }))
+    /// ));
+    /// assert!(matches!(
+    ///     ForStmt::parse("for range 10 {}"),
+    ///     Ok(("", ForStmt { clause: Some(ForClause::RangeClause { vars: None, .. }), .. }))
+    /// ));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_with(s, &mut parse_statement_list)
+    }
+}
+
+impl<'a> ForStmt<'a> {
+    /// Parses a for statement whose body is read by `list`, see [`Block::parse_with`].
+    pub(crate) fn parse_with(
+        s: &'a str,
+        list: &mut StatementList<'_, 'a>,
+    ) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("for")(s)?;
+        let (s, clause) = control_clause(opt(alt((
+            Self::parse_range_clause,
+            Self::parse_for_clause,
+            map(Expression::parse, ForClause::Condition),
+        ))))(s)?;
+        let (s, body) = Block::parse_with(s, list)?;
+        Ok((s, ForStmt { clause, body }))
+    }
+
+    fn parse_range_clause(s: &'a str) -> IResult<&'a str, ForClause<'a>, ParseError<'a>> {
+        let (s, vars) = opt(alt((
+            map(
+                terminated(parse_identifier_list, symbol(":=")),
+                RangeVars::Declared,
+            ),
+            map(
+                terminated(Expression::parse_list, symbol("=")),
+                RangeVars::Assigned,
+            ),
+        )))(s)?;
+        let (s, expr) = preceded(reserved("range"), Expression::parse)(s)?;
+        Ok((s, ForClause::RangeClause { vars, expr }))
+    }
+
+    fn parse_for_clause(s: &'a str) -> IResult<&'a str, ForClause<'a>, ParseError<'a>> {
+        let (s, (init, _, cond, _, post)) = tuple((
+            opt(SimpleStmt::parse),
+            symbol(";"),
+            opt(Expression::parse),
+            symbol(";"),
+            opt(SimpleStmt::parse),
+        ))(s)?;
+        Ok((s, ForClause::ForClause { init, cond, post }))
+    }
+}
+
+/// A clause of a switch or select statement.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaseClause<'a, C> {
+    /// What follows `case`, `None` for the `default` clause.
+    pub case: Option<C>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub body: Vec<Statement<'a>>,
+}
+
+/// ExprCaseClause = ExprSwitchCase ":" StatementList .
+/// ExprSwitchCase = "case" ExpressionList | "default" .
+pub type ExprCaseClause<'a> = CaseClause<'a, Vec<Expression<'a>>>;
+
+/// TypeCaseClause = TypeSwitchCase ":" StatementList .
+/// TypeSwitchCase = "case" TypeList | "default" .
+pub type TypeCaseClause<'a> = CaseClause<'a, Vec<GoType<'a>>>;
+
+/// CommClause = CommCase ":" StatementList .
+/// CommCase   = "case" ( SendStmt | RecvStmt ) | "default" .
+pub type CommClause<'a> = CaseClause<'a, SimpleStmt<'a>>;
+
+/// "{" { clause } "}", with the statements of each clause read by `list`.
+fn parse_clauses<'a, C>(
+    s: &'a str,
+    case: impl Fn(&'a str) -> IResult<&'a str, C, ParseError<'a>>,
+    list: &mut StatementList<'_, 'a>,
+) -> IResult<&'a str, Vec<CaseClause<'a, C>>, ParseError<'a>> {
+    let (mut s, _) = symbol("{")(s)?;
+    let mut clauses = vec![];
+    loop {
+        // Past `case` or `default`, an error is the clause's own rather than the end of the list.
+        let (rest, case) = match alt((
+            map(preceded(reserved("case"), cut(&case)), Some),
+            map(reserved("default"), |_| None),
+        ))(s)
+        {
+            Ok(ok) => ok,
+            Err(nom::Err::Error(_)) => break,
+            Err(e) => return Err(e),
+        };
+        let (rest, _) = cut(symbol(":"))(rest)?;
+        let (rest, body) = cut(&mut *list)(rest)?;
+        clauses.push(CaseClause { case, body });
+        s = rest;
+    }
+    let (s, _) = symbol("}")(s)?;
+    Ok((s, clauses))
+}
+
+/// ExprSwitchStmt = "switch" [ SimpleStmt ";" ] [ Expression ] "{" { ExprCaseClause } "}" .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwitchStmt<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub init: Option<SimpleStmt<'a>>,
+    pub tag: Option<Expression<'a>>,
+    pub clauses: Vec<ExprCaseClause<'a>>,
+}
+
+impl<'a> ASTable<'a> for SwitchStmt<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::control::SwitchStmt;
+    /// let (s, stmt) = SwitchStmt::parse("switch x := f(); x {\ncase 1, 2:\n\tg()\n\th()\ndefault:\n}").unwrap();
+    /// assert_eq!(s, "");
+    /// assert!(stmt.init.is_some() && stmt.tag.is_some());
+    /// assert_eq!(stmt.clauses.len(), 2);
+    /// assert_eq!(stmt.clauses[0].case.as_ref().map(Vec::len), Some(2));
+    /// assert_eq!(stmt.clauses[0].body.len(), 2);
+    /// assert!(stmt.clauses[1].case.is_none());
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_with(s, &mut parse_statement_list)
+    }
+}
+
+impl<'a> SwitchStmt<'a> {
+    /// Parses a switch statement whose clauses are read by `list`.
+    pub(crate) fn parse_with(
+        s: &'a str,
+        list: &mut StatementList<'_, 'a>,
+    ) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("switch")(s)?;
+        let (s, (init, tag)) = control_clause(pair(
+            opt(terminated(SimpleStmt::parse, symbol(";"))),
+            opt(Expression::parse),
+        ))(s)?;
+        let (s, clauses) = parse_clauses(s, Expression::parse_list, list)?;
+        Ok((s, SwitchStmt { init, tag, clauses }))
+    }
+}
+
+/// TypeSwitchStmt  = "switch" [ SimpleStmt ";" ] TypeSwitchGuard "{" { TypeCaseClause } "}" .
+/// TypeSwitchGuard = [ identifier ":=" ] PrimaryExpr "." "(" "type" ")" .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeSwitchStmt<'a> {
+    pub init: Option<SimpleStmt<'a>>,
+    /// The identifier declared by the guard, `x` in `x := y.(type)`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub binding: Option<&'a str>,
+    pub expr: PrimaryExpr<'a>,
+    pub clauses: Vec<TypeCaseClause<'a>>,
+}
+
+impl<'a> ASTable<'a> for TypeSwitchStmt<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::control::TypeSwitchStmt;
+    /// let (s, stmt) = TypeSwitchStmt::parse("switch v := x.(type) {\ncase int, io.Reader:\ncase nil:\n}").unwrap();
+    /// assert_eq!((s, stmt.binding), ("", Some("v")));
+    /// assert_eq!(stmt.clauses[0].case.as_ref().map(Vec::len), Some(2));
+    /// assert!(TypeSwitchStmt::parse("switch x {}").is_err());
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_with(s, &mut parse_statement_list)
+    }
+}
+
+impl<'a> TypeSwitchStmt<'a> {
+    /// Parses a type switch statement whose clauses are read by `list`.
+    pub(crate) fn parse_with(
+        s: &'a str,
+        list: &mut StatementList<'_, 'a>,
+    ) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("switch")(s)?;
+        let (s, (init, binding, expr, _)) = control_clause(tuple((
+            opt(terminated(SimpleStmt::parse, symbol(";"))),
+            opt(terminated(identifier, symbol(":="))),
+            PrimaryExpr::parse,
+            tuple((symbol("."), symbol("("), reserved("type"), symbol(")"))),
+        )))(s)?;
+        let type_list = |s| separated_list1(symbol(","), GoType::parse)(s);
+        let (s, clauses) = parse_clauses(s, type_list, list)?;
+        Ok((
+            s,
+            TypeSwitchStmt {
+                init,
+                binding,
+                expr,
+                clauses,
+            },
+        ))
+    }
+}
+
+/// SelectStmt = "select" "{" { CommClause } "}" .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectStmt<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub clauses: Vec<CommClause<'a>>,
+}
+
+impl<'a> ASTable<'a> for SelectStmt<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::control::SelectStmt;
+    /// let src = "select {\ncase v, ok := <-ch:\ncase out <- v:\ncase <-done:\ncase x = <-ch:\ndefault:\n}";
+    /// let (s, stmt) = SelectStmt::parse(src).unwrap();
+    /// assert_eq!((s, stmt.clauses.len()), ("", 5));
+    /// assert!(SelectStmt::parse("select {\ncase f():\n}").is_err());
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_with(s, &mut parse_statement_list)
+    }
+}
+
+impl<'a> SelectStmt<'a> {
+    /// Parses a select statement whose clauses are read by `list`.
+    pub(crate) fn parse_with(
+        s: &'a str,
+        list: &mut StatementList<'_, 'a>,
+    ) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = reserved("select")(s)?;
+        let comm = |s| {
+            let send_or_receive = verify(SimpleStmt::parse, is_comm);
+            expected(Expected::Kind("send or receive"), send_or_receive)(s)
+        };
+        let (s, clauses) = parse_clauses(s, comm, list)?;
+        Ok((s, SelectStmt { clauses }))
+    }
+}
+
+/// SendStmt | RecvStmt
+/// RecvStmt = [ ExpressionList "=" | IdentifierList ":=" ] RecvExpr .
+fn is_comm(stmt: &SimpleStmt) -> bool {
+    match stmt {
+        SimpleStmt::SendStmt { .. } => true,
+        SimpleStmt::ExpressionStmt(expr) => is_receive(expr),
+        SimpleStmt::Assignment { lhs, op, rhs } => {
+            *op == "=" && lhs.len() <= 2 && matches!(rhs.as_slice(), [expr] if is_receive(expr))
+        }
+        SimpleStmt::ShortVarDecl {
+            identifiers,
+            values,
+        } => identifiers.len() <= 2 && matches!(values.as_slice(), [expr] if is_receive(expr)),
+        _ => false,
+    }
+}
+
+/// RecvExpr = Expression . It must be a receive operation.
+fn is_receive(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::UnaryExpr(UnaryExpr::Unary(Unary { op: "<-", .. }))
+    )
+}
+
+#[test]
+fn test_if_needs_same_line_else() {
+    let (s, stmt) = IfStmt::parse("if x {\n}\nelse {\n}").unwrap();
+    assert_eq!(s, "\nelse {\n}");
+    assert!(stmt.otherwise.is_none());
+}
+
+#[test]
+fn test_switch_statements() {
+    let src = "switch x := y.(type) {\ndefault:\n}";
+    assert!(matches!(
+        Statement::parse(src),
+        Ok(("", Statement::TypeSwitchStmt(_)))
+    ));
+    let src = "switch x := y; x.(int) {\n}";
+    assert!(matches!(
+        Statement::parse(src),
+        Ok(("", Statement::SwitchStmt(_)))
+    ));
+    let src = "switch {\ncase x > 1:\n\tf()\n}";
+    let stmt = match Statement::parse(src) {
+        Ok(("", Statement::SwitchStmt(stmt))) => stmt,
+        other => panic!("{:?}", other),
+    };
+    assert!(stmt.tag.is_none());
+}
+
+#[test]
+fn test_composite_literals_in_control_clauses() {
+    // The `{` after a type name opens the block, unless the literal is parenthesized.
+    let (s, stmt) = IfStmt::parse("if x == T {\n}").unwrap();
+    assert_eq!((s, stmt.then.0.len()), ("", 0));
+    assert!(IfStmt::parse("if x == (T{}) {\n}").is_ok());
+    assert!(IfStmt::parse("if f(T{1}) {\n}").is_ok());
+    let src = "for _, x := range []int{1, 2} {\n\tsum += x\n}";
+    let (s, stmt) = ForStmt::parse(src).unwrap();
+    assert_eq!((s, stmt.body.0.len()), ("", 1));
+}
+
+#[test]
+fn test_errors_inside_case_clauses() {
+    // The error is reported where the clause goes wrong, not at the `case` that could not be read.
+    let error = |src| match Statement::parse(src) {
+        Err(nom::Err::Failure(error)) => (error.found(), error.message()),
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(error("switch x {\ncase 1:\n\tif {\n\t}\n}").0, "{");
+    let (found, message) = error("switch x {\ndefault\n\tf()\n}");
+    assert_eq!((found, message.as_str()), ("f", "expected `:`, found `f`"));
+    let (_, message) = error("select {\ncase <-ch\n\tf()\n}");
+    assert_eq!(message, "expected `:`, found newline");
+    let (_, message) = error("switch x.(type) {\ncase int, :\n}");
+    assert_eq!(message, "expected `:`, found `,`");
+    assert_eq!(
+        error("switch x.(type) {\ncase int:\n\treturn 1 +\n}").0,
+        "1"
+    );
+}
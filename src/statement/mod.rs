@@ -0,0 +1,248 @@
+use nom::{
+    branch::alt,
+    combinator::{cut, eof, map, opt, peek, verify},
+    sequence::{preceded, terminated},
+    IResult,
+};
+
+use crate::{
+    astable::ASTable,
+    declaration::Declaration,
+    error::{expected, Expected, ParseError},
+    expression::{Expression, PrimaryExpr},
+    parse_util::{identifier, multispace0, reserved, symbol, terminator},
+};
+
+use self::{
+    control::{ForStmt, IfStmt, SelectStmt, SwitchStmt, TypeSwitchStmt},
+    simple::SimpleStmt,
+};
+
+pub mod control;
+pub mod simple;
+
+/// Statement = Declaration | LabeledStmt | SimpleStmt | GoStmt | ReturnStmt | BreakStmt | ContinueStmt | GotoStmt |
+///             FallthroughStmt | Block | IfStmt | SwitchStmt | SelectStmt | ForStmt | DeferStmt .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Statement<'a> {
+    /// Declaration = ConstDecl | TypeDecl | VarDecl .
+    DeclStmt(Declaration<'a>),
+    SimpleStmt(SimpleStmt<'a>),
+    Block(Block<'a>),
+    IfStmt(IfStmt<'a>),
+    SwitchStmt(SwitchStmt<'a>),
+    TypeSwitchStmt(TypeSwitchStmt<'a>),
+    SelectStmt(SelectStmt<'a>),
+    ForStmt(ForStmt<'a>),
+    /// GoStmt = "go" Expression . The expression is always a call.
+    GoStmt(PrimaryExpr<'a>),
+    /// DeferStmt = "defer" Expression . The expression is always a call.
+    DeferStmt(PrimaryExpr<'a>),
+    /// ReturnStmt = "return" [ ExpressionList ] .
+    ReturnStmt(Vec<Expression<'a>>),
+    /// BreakStmt = "break" [ Label ] .
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    BreakStmt(Option<&'a str>),
+    /// ContinueStmt = "continue" [ Label ] .
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    ContinueStmt(Option<&'a str>),
+    /// GotoStmt = "goto" Label .
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    GotoStmt(&'a str),
+    /// FallthroughStmt = "fallthrough" .
+    FallthroughStmt,
+    /// LabeledStmt = Label ":" Statement .
+    LabeledStmt {
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        label: &'a str,
+        stmt: Box<Statement<'a>>,
+    },
+    /// The source text of a statement that failed to parse, see [`crate::recover`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    BadStmt(&'a str),
+}
+
+impl<'a> ASTable<'a> for Statement<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::{Statement, simple::SimpleStmt};
+    /// assert!(matches!(Statement::parse("x = 1\ny = 2"), Ok(("\ny = 2", Statement::SimpleStmt(SimpleStmt::Assignment { .. })))));
+    /// assert!(matches!(Statement::parse("{ x++ }"), Ok(("", Statement::Block(_)))));
+    /// assert!(matches!(Statement::parse("var x, y = 1, 2"), Ok(("", Statement::DeclStmt(_)))));
+    /// assert!(matches!(Statement::parse("type Celsius = float64"), Ok(("", Statement::DeclStmt(_)))));
+    /// assert!(matches!(Statement::parse("go worker(jobs)"), Ok(("", Statement::GoStmt(_)))));
+    /// assert!(matches!(Statement::parse("defer mu.Unlock()"), Ok(("", Statement::DeferStmt(_)))));
+    /// assert!(matches!(Statement::parse("go func() { ch <- 1 }()"), Ok(("", Statement::GoStmt(_)))));
+    /// assert!(matches!(Statement::parse("defer func() {\n\tmu.Unlock()\n}()"), Ok(("", Statement::DeferStmt(_)))));
+    /// assert!(Statement::parse("go worker").is_err());
+    /// assert!(matches!(Statement::parse("return a, b"), Ok(("", Statement::ReturnStmt(results))) if results.len() == 2));
+    /// assert_eq!(Statement::parse("return\n}"), Ok(("\n}", Statement::ReturnStmt(vec![]))));
+    /// assert_eq!(Statement::parse("continue outer"), Ok(("", Statement::ContinueStmt(Some("outer")))));
+    /// assert_eq!(Statement::parse("break\nx"), Ok(("\nx", Statement::BreakStmt(None))));
+    /// assert_eq!(Statement::parse("goto done"), Ok(("", Statement::GotoStmt("done"))));
+    /// assert_eq!(Statement::parse("fallthrough"), Ok(("", Statement::FallthroughStmt)));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        alt((
+            map(Declaration::parse, Statement::DeclStmt),
+            map(preceded(reserved("go"), cut(parse_call)), Statement::GoStmt),
+            map(
+                preceded(reserved("defer"), cut(parse_call)),
+                Statement::DeferStmt,
+            ),
+            map(
+                preceded(reserved("return"), opt(Expression::parse_list)),
+                |results| Statement::ReturnStmt(results.unwrap_or_default()),
+            ),
+            map(
+                preceded(reserved("break"), opt(identifier)),
+                Statement::BreakStmt,
+            ),
+            map(
+                preceded(reserved("continue"), opt(identifier)),
+                Statement::ContinueStmt,
+            ),
+            map(
+                preceded(reserved("goto"), cut(identifier)),
+                Statement::GotoStmt,
+            ),
+            map(reserved("fallthrough"), |_| Statement::FallthroughStmt),
+            Self::parse_labeled_stmt,
+            map(Block::parse, Statement::Block),
+            map(IfStmt::parse, Statement::IfStmt),
+            // A type switch guard also starts like an expression, so it is tried first.
+            map(TypeSwitchStmt::parse, Statement::TypeSwitchStmt),
+            map(SwitchStmt::parse, Statement::SwitchStmt),
+            map(SelectStmt::parse, Statement::SelectStmt),
+            map(ForStmt::parse, Statement::ForStmt),
+            map(SimpleStmt::parse, Statement::SimpleStmt),
+        ))(s)
+    }
+}
+
+/// Block = "{" StatementList "}" .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Block<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub Vec<Statement<'a>>);
+
+impl<'a> ASTable<'a> for Block<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::Block;
+    /// let (s, block) = Block::parse("{\n\ta := 1 // one\n\n\ta++; f(a)\n}").unwrap();
+    /// assert_eq!((s, block.0.len()), ("", 3));
+    /// assert_eq!(Block::parse("{}"), Ok(("", Block(vec![]))));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        Self::parse_with(s, &mut parse_statement_list)
+    }
+}
+
+impl<'a> Block<'a> {
+    /// Parses a block whose statements are read by `list`.
+    pub(crate) fn parse_with(
+        s: &'a str,
+        list: &mut StatementList<'_, 'a>,
+    ) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, _) = symbol("{")(s)?;
+        let (s, statements) = list(s)?;
+        let (s, _) = symbol("}")(s)?;
+        Ok((s, Block(statements)))
+    }
+}
+
+/// Reads the statements of a block or a case clause: [`parse_statement_list`], or the parser of
+/// [`crate::recover`] that replaces broken statements.
+pub(crate) type StatementList<'p, 'a> =
+    dyn FnMut(&'a str) -> IResult<&'a str, Vec<Statement<'a>>, ParseError<'a>> + 'p;
+
+/// StatementList = { Statement ";" } .
+///
+/// The list ends before a `}`, `case` or `default`, where the last ";" may be omitted.
+/// A ";" with no statement in front of it is an empty statement.
+pub fn parse_statement_list(s: &str) -> IResult<&str, Vec<Statement<'_>>, ParseError<'_>> {
+    let mut statements = vec![];
+    let (mut s, _) = multispace0(s)?;
+    while !at_statement_list_end(s) {
+        if let Ok((rest, _)) = symbol(";")(s) {
+            statements.push(Statement::SimpleStmt(SimpleStmt::EmptyStmt));
+            s = rest;
+            continue;
+        }
+        let (rest, statement) = Statement::parse(s)?;
+        let (rest, _) = alt((terminator, peek(symbol("}"))))(rest)?;
+        statements.push(statement);
+        s = multispace0(rest)?.0;
+    }
+    Ok((s, statements))
+}
+
+impl<'a> Statement<'a> {
+    /// A label may also stand right before the `}` closing a block, labelling an empty statement.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::{Statement, simple::SimpleStmt};
+    /// let (s, stmt) = Statement::parse("outer:\n\tfor {}").unwrap();
+    /// assert!(matches!(stmt, Statement::LabeledStmt { label: "outer", stmt } if matches!(*stmt, Statement::ForStmt(_))));
+    /// let (s, stmt) = Statement::parse("end:\n}").unwrap();
+    /// assert_eq!(s, "}");
+    /// assert!(matches!(stmt, Statement::LabeledStmt { stmt, .. } if *stmt == Statement::SimpleStmt(SimpleStmt::EmptyStmt)));
+    /// ```
+    fn parse_labeled_stmt(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, label) = terminated(identifier, symbol(":"))(s)?;
+        let empty = map(peek(symbol("}")), |_| {
+            Statement::SimpleStmt(SimpleStmt::EmptyStmt)
+        });
+        let (s, stmt) = alt((Statement::parse, empty))(s)?;
+        let stmt = Box::new(stmt);
+        Ok((s, Statement::LabeledStmt { label, stmt }))
+    }
+}
+
+/// The expression of a go or defer statement, which must be a function call.
+fn parse_call(s: &str) -> IResult<&str, PrimaryExpr<'_>, ParseError<'_>> {
+    let call = verify(PrimaryExpr::parse, |expr| {
+        matches!(expr, PrimaryExpr::Call { .. })
+    });
+    expected(Expected::Kind("function call"), call)(s)
+}
+
+pub(crate) fn at_statement_list_end(s: &str) -> bool {
+    let mut end = alt((symbol("}"), reserved("case"), reserved("default"), eof));
+    end(s).is_ok()
+}
+
+#[test]
+fn test_statement_list() {
+    let src = "{\n\tch <- v; i++\n\ta, b = b, a\n\n\tc := a &^ b // mask\n\t;\n}";
+    let (s, Block(statements)) = Block::parse(src).unwrap();
+    assert_eq!(s, "");
+    assert!(matches!(
+        statements.as_slice(),
+        [
+            Statement::SimpleStmt(SimpleStmt::SendStmt { .. }),
+            Statement::SimpleStmt(SimpleStmt::IncDecStmt { .. }),
+            Statement::SimpleStmt(SimpleStmt::Assignment { .. }),
+            Statement::SimpleStmt(SimpleStmt::ShortVarDecl { .. }),
+            Statement::SimpleStmt(SimpleStmt::EmptyStmt),
+        ]
+    ));
+}
+
+#[test]
+fn test_statement_list_errors() {
+    let error = match Block::parse("{ a b }") {
+        Err(nom::Err::Error(e)) => e,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(
+        error.message(),
+        "expected one of `}`, `;` or newline, found `b`"
+    );
+    let error = match Block::parse("{ a, b }") {
+        Err(nom::Err::Error(e)) => e,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(error.found(), "}");
+}
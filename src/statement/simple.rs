@@ -0,0 +1,138 @@
+use nom::{
+    branch::alt,
+    combinator::{map, opt},
+    sequence::{preceded, terminated},
+    IResult,
+};
+
+use crate::{
+    astable::ASTable,
+    error::{Expected, ParseError},
+    expression::Expression,
+    parse_identifier_list,
+    parse_util::symbol,
+};
+
+/// SimpleStmt = EmptyStmt | ExpressionStmt | SendStmt | IncDecStmt | Assignment | ShortVarDecl .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SimpleStmt<'a> {
+    /// EmptyStmt = .
+    EmptyStmt,
+    /// ExpressionStmt = Expression .
+    ExpressionStmt(Expression<'a>),
+    /// SendStmt = Channel "<-" Expression .
+    SendStmt {
+        channel: Expression<'a>,
+        value: Expression<'a>,
+    },
+    /// IncDecStmt = Expression ( "++" | "--" ) .
+    IncDecStmt {
+        expr: Expression<'a>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        op: &'a str,
+    },
+    /// Assignment = ExpressionList assign_op ExpressionList .
+    Assignment {
+        lhs: Vec<Expression<'a>>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        op: &'a str,
+        rhs: Vec<Expression<'a>>,
+    },
+    /// ShortVarDecl = IdentifierList ":=" ExpressionList .
+    ShortVarDecl {
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        identifiers: Vec<&'a str>,
+        values: Vec<Expression<'a>>,
+    },
+}
+
+impl<'a> ASTable<'a> for SimpleStmt<'a> {
+    /// The left hand side of `:=` must be a list of identifiers,
+    /// while the one of an assignment can be any list of expressions.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::statement::simple::SimpleStmt;
+    /// assert!(matches!(SimpleStmt::parse("a, b := f()"), Ok(("", SimpleStmt::ShortVarDecl { .. }))));
+    /// assert!(matches!(SimpleStmt::parse("a[i], b.c = 1, 2"), Ok(("", SimpleStmt::Assignment { op: "=", .. }))));
+    /// assert!(matches!(SimpleStmt::parse("x &^= mask"), Ok(("", SimpleStmt::Assignment { op: "&^=", .. }))));
+    /// assert!(matches!(SimpleStmt::parse("ch <- v"), Ok(("", SimpleStmt::SendStmt { .. }))));
+    /// assert!(matches!(SimpleStmt::parse("i++"), Ok(("", SimpleStmt::IncDecStmt { op: "++", .. }))));
+    /// assert!(matches!(SimpleStmt::parse("f(x)"), Ok(("", SimpleStmt::ExpressionStmt(_)))));
+    /// // Only identifiers can be declared, so this stops before `:=`.
+    /// assert!(matches!(SimpleStmt::parse("a.b := 1"), Ok((":= 1", SimpleStmt::ExpressionStmt(_)))));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        alt((Self::parse_short_var_decl, Self::parse_expression_stmt))(s)
+    }
+}
+
+impl<'a> SimpleStmt<'a> {
+    fn parse_short_var_decl(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, identifiers) = terminated(parse_identifier_list, symbol(":="))(s)?;
+        let (s, values) = Expression::parse_list(s)?;
+        Ok((
+            s,
+            SimpleStmt::ShortVarDecl {
+                identifiers,
+                values,
+            },
+        ))
+    }
+
+    /// Parses every simple statement starting with an expression list,
+    /// and tells them apart by the token following the list.
+    fn parse_expression_stmt(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, mut lhs) = Expression::parse_list(s)?;
+        if lhs.len() == 1 {
+            let (s, value) = opt(preceded(symbol("<-"), Expression::parse))(s)?;
+            if let Some(value) = value {
+                let channel = lhs.remove(0);
+                return Ok((s, SimpleStmt::SendStmt { channel, value }));
+            }
+            let (s, op) = opt(alt((symbol("++"), symbol("--"))))(s)?;
+            if let Some(op) = op {
+                let expr = lhs.remove(0);
+                return Ok((s, SimpleStmt::IncDecStmt { expr, op }));
+            }
+        }
+        match opt(assign_op)(s)? {
+            (s, Some(op)) => map(Expression::parse_list, |rhs| SimpleStmt::Assignment {
+                lhs: std::mem::take(&mut lhs),
+                op,
+                rhs,
+            })(s),
+            (s, None) if lhs.len() == 1 => Ok((s, SimpleStmt::ExpressionStmt(lhs.remove(0)))),
+            (s, None) => Err(nom::Err::Error(ParseError::new(
+                s,
+                Expected::Kind("assignment operator"),
+            ))),
+        }
+    }
+}
+
+/// assign_op = [ add_op | mul_op ] "=" .
+/// ```
+/// use go_parser_rs::statement::simple::assign_op;
+/// assert_eq!(assign_op("= 1"), Ok(("1", "=")));
+/// assert_eq!(assign_op("+= 1"), Ok(("1", "+=")));
+/// assert_eq!(assign_op("<<= 1"), Ok(("1", "<<=")));
+/// assert_eq!(assign_op("&^= 1"), Ok(("1", "&^=")));
+/// assert!(assign_op("== 1").is_err());
+/// ```
+pub fn assign_op(s: &str) -> IResult<&str, &str, ParseError<'_>> {
+    alt((
+        symbol("="),
+        symbol("+="),
+        symbol("-="),
+        symbol("|="),
+        symbol("^="),
+        symbol("*="),
+        symbol("/="),
+        symbol("%="),
+        symbol("<<="),
+        symbol(">>="),
+        symbol("&="),
+        symbol("&^="),
+    ))(s)
+}
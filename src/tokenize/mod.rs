@@ -6,7 +6,7 @@ pub mod tokenizer;
 
 pub(crate) type Tokens<'a> = VecDeque<Token<'a>>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Delimiter {
     // '(' ')'
     Paren,
@@ -16,30 +16,171 @@ pub enum Delimiter {
     Cur,
 }
 
+impl Delimiter {
+    pub fn open(&self) -> &'static str {
+        match self {
+            Delimiter::Paren => "(",
+            Delimiter::Bra => "[",
+            Delimiter::Cur => "{",
+        }
+    }
+
+    pub fn close(&self) -> &'static str {
+        match self {
+            Delimiter::Paren => ")",
+            Delimiter::Bra => "]",
+            Delimiter::Cur => "}",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Token<'a> {
+    Comment(&'a str),
+    Ident(&'a str),
     IntLit(IntLit<'a>),
+    FloatLit(&'a str),
+    ImaginaryLit(&'a str),
+    RuneLit(&'a str),
+    StringLit(&'a str),
     Keyword(&'static str),
     LDel(Delimiter),
     RDel(Delimiter),
     Symbol(Symbol),
+    /// Spaces, tabs and newlines, only kept by [`tokenizer::tokenize_lossless`].
+    Whitespace(&'a str),
+    /// Text no token starts with, only kept by [`tokenizer::tokenize_lossless`].
+    Unknown(&'a str),
 }
 
 impl<'a> Token<'a> {
     fn len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the source text of the token.
+    pub fn text(&self) -> &'a str {
         match self {
-            Self::IntLit(i) => i.len(),
-            Token::Keyword(s) => s.len(),
-            Token::LDel(_) | Token::RDel(_) => 1,
-            Token::Symbol(s) => s.len(),
+            Token::Comment(s)
+            | Token::Ident(s)
+            | Token::FloatLit(s)
+            | Token::ImaginaryLit(s)
+            | Token::RuneLit(s)
+            | Token::StringLit(s)
+            | Token::Whitespace(s)
+            | Token::Unknown(s) => s,
+            Token::IntLit(
+                IntLit::DecimalLit(s)
+                | IntLit::BinaryLit(s)
+                | IntLit::OctalLit(s)
+                | IntLit::HexLit(s),
+            ) => s,
+            Token::Keyword(s) => s,
+            Token::LDel(d) => d.open(),
+            Token::RDel(d) => d.close(),
+            Token::Symbol(s) => s.as_str(),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Symbol {
     Plus,
     Minus,
     Aster,
     Slash,
+    Percent,
+    Amp,
+    Pipe,
+    Caret,
+    Shl,
+    Shr,
+    AmpCaret,
+    PlusEq,
+    MinusEq,
+    AsterEq,
+    SlashEq,
+    PercentEq,
+    AmpEq,
+    PipeEq,
+    CaretEq,
+    ShlEq,
+    ShrEq,
+    AmpCaretEq,
+    AmpAmp,
+    PipePipe,
+    Arrow,
+    PlusPlus,
+    MinusMinus,
+    EqEq,
+    Lt,
+    Gt,
+    Eq,
+    Bang,
+    Tilde,
+    BangEq,
+    LtEq,
+    GtEq,
+    ColonEq,
+    Ellipsis,
+    Comma,
+    Semi,
+    Dot,
+    Colon,
+}
+
+/// The text of every symbol, longest first so that the first match is the one Go's scanner reads.
+pub(crate) const SYMBOLS: [(&str, Symbol); 42] = [
+    ("&^=", Symbol::AmpCaretEq),
+    ("<<=", Symbol::ShlEq),
+    (">>=", Symbol::ShrEq),
+    ("...", Symbol::Ellipsis),
+    ("&&", Symbol::AmpAmp),
+    ("||", Symbol::PipePipe),
+    ("<-", Symbol::Arrow),
+    ("++", Symbol::PlusPlus),
+    ("--", Symbol::MinusMinus),
+    ("==", Symbol::EqEq),
+    ("!=", Symbol::BangEq),
+    ("<=", Symbol::LtEq),
+    (">=", Symbol::GtEq),
+    (":=", Symbol::ColonEq),
+    ("+=", Symbol::PlusEq),
+    ("-=", Symbol::MinusEq),
+    ("*=", Symbol::AsterEq),
+    ("/=", Symbol::SlashEq),
+    ("%=", Symbol::PercentEq),
+    ("&=", Symbol::AmpEq),
+    ("|=", Symbol::PipeEq),
+    ("^=", Symbol::CaretEq),
+    ("<<", Symbol::Shl),
+    (">>", Symbol::Shr),
+    ("&^", Symbol::AmpCaret),
+    ("+", Symbol::Plus),
+    ("-", Symbol::Minus),
+    ("*", Symbol::Aster),
+    ("/", Symbol::Slash),
+    ("%", Symbol::Percent),
+    ("&", Symbol::Amp),
+    ("|", Symbol::Pipe),
+    ("^", Symbol::Caret),
+    ("<", Symbol::Lt),
+    (">", Symbol::Gt),
+    ("=", Symbol::Eq),
+    ("!", Symbol::Bang),
+    ("~", Symbol::Tilde),
+    (",", Symbol::Comma),
+    (";", Symbol::Semi),
+    (".", Symbol::Dot),
+    (":", Symbol::Colon),
+];
+
+impl Symbol {
+    pub fn as_str(&self) -> &'static str {
+        SYMBOLS
+            .iter()
+            .find(|(_, symbol)| symbol == self)
+            .map(|(text, _)| *text)
+            .unwrap()
+    }
 }
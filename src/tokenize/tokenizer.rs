@@ -0,0 +1,214 @@
+use nom::bytes::complete::take_while1;
+
+use crate::{
+    literals::{integer::int_lit, string::string_lit},
+    parse_util::{comment, KEYWORDS},
+};
+
+use super::{Delimiter, Token, Tokens, SYMBOLS};
+
+/// Splits the source into tokens, skipping white space.
+/// On a character no token starts with, the rest of the input is returned as the error.
+/// ```
+/// use go_parser_rs::tokenize::{tokenizer::tokenize, Delimiter, Symbol, Token};
+/// use go_parser_rs::literals::integer::IntLit;
+/// let tokens = tokenize("func f() // f\n{ x := 1 }").unwrap();
+/// assert_eq!(
+///     Vec::from(tokens),
+///     vec![
+///         Token::Keyword("func"),
+///         Token::Ident("f"),
+///         Token::LDel(Delimiter::Paren),
+///         Token::RDel(Delimiter::Paren),
+///         Token::Comment("// f"),
+///         Token::LDel(Delimiter::Cur),
+///         Token::Ident("x"),
+///         Token::Symbol(Symbol::ColonEq),
+///         Token::IntLit(IntLit::DecimalLit("1")),
+///         Token::RDel(Delimiter::Cur),
+///     ]
+/// );
+/// assert_eq!(tokenize("x ? y : z"), Err("? y : z"));
+/// ```
+pub fn tokenize(s: &str) -> Result<Tokens<'_>, &str> {
+    let mut tokens = Tokens::new();
+    let mut s = s.trim_start();
+    while !s.is_empty() {
+        let token = next_token(s).ok_or(s)?;
+        s = s[token.len()..].trim_start();
+        tokens.push_back(token);
+    }
+    Ok(tokens)
+}
+
+/// Splits the source into tokens, keeping white space and text no token starts with,
+/// so that the texts of the tokens put together are the source again.
+/// ```
+/// use go_parser_rs::tokenize::{tokenizer::tokenize_lossless, Token};
+/// let src = "x = 'a' ? 1.5i\n";
+/// let tokens = tokenize_lossless(src);
+/// assert_eq!(tokens[4], Token::RuneLit("'a'"));
+/// assert_eq!(tokens[6], Token::Unknown("?"));
+/// assert_eq!(tokens[8], Token::ImaginaryLit("1.5i"));
+/// assert_eq!(tokens.iter().map(Token::text).collect::<String>(), src);
+/// ```
+pub fn tokenize_lossless(mut s: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    while let Some(c) = s.chars().next() {
+        let token = match take_while1::<_, _, ()>(char::is_whitespace)(s) {
+            Ok((_, space)) => Token::Whitespace(space),
+            Err(_) => next_token(s).unwrap_or_else(|| Token::Unknown(&s[..c.len_utf8()])),
+        };
+        s = &s[token.len()..];
+        tokens.push(token);
+    }
+    tokens
+}
+
+pub(crate) fn next_token(s: &str) -> Option<Token<'_>> {
+    if let Ok((_, comment)) = comment(s) {
+        return Some(Token::Comment(comment));
+    }
+    if let Some(number) = number(s) {
+        return Some(number);
+    }
+    if let Ok((_, word)) = word(s) {
+        let token = match KEYWORDS.iter().find(|keyword| **keyword == word) {
+            Some(keyword) => Token::Keyword(keyword),
+            None => Token::Ident(word),
+        };
+        return Some(token);
+    }
+    if let Ok((_, string)) = string_lit(s) {
+        return Some(Token::StringLit(string));
+    }
+    if let Some(rune) = rune(s) {
+        return Some(Token::RuneLit(rune));
+    }
+    let token = match s.chars().next()? {
+        '(' => Token::LDel(Delimiter::Paren),
+        ')' => Token::RDel(Delimiter::Paren),
+        '[' => Token::LDel(Delimiter::Bra),
+        ']' => Token::RDel(Delimiter::Bra),
+        '{' => Token::LDel(Delimiter::Cur),
+        '}' => Token::RDel(Delimiter::Cur),
+        _ => {
+            let (_, symbol) = SYMBOLS.iter().find(|(text, _)| s.starts_with(text))?;
+            Token::Symbol(*symbol)
+        }
+    };
+    Some(token)
+}
+
+fn word(s: &str) -> nom::IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(s)
+}
+
+/// Reads an integer, floating-point or imaginary literal the way Go's scanner does:
+/// the longest run of digits, letters, `_` and `.`, and signs right after an exponent.
+/// A run that is not a valid literal, like `0x`, is an unknown token.
+fn number(s: &str) -> Option<Token<'_>> {
+    let bytes = s.as_bytes();
+    let starts_number = match bytes {
+        [b'.', digit, ..] => digit.is_ascii_digit(),
+        [digit, ..] => digit.is_ascii_digit(),
+        [] => false,
+    };
+    if !starts_number {
+        return None;
+    }
+    let hex = s.starts_with("0x") || s.starts_with("0X");
+    let exponent: &[u8] = if hex { b"pP" } else { b"eE" };
+    let mut len = 0;
+    while let Some(&b) = bytes.get(len) {
+        let sign = (b == b'+' || b == b'-') && len > 0 && exponent.contains(&bytes[len - 1]);
+        if !(b.is_ascii_alphanumeric() || b == b'_' || b == b'.' || sign) {
+            break;
+        }
+        len += 1;
+    }
+    let text = &s[..len];
+    let token = match int_lit(text) {
+        Ok((_, int)) if int.len() == len => Token::IntLit(int),
+        _ if text.ends_with('i') => Token::ImaginaryLit(text),
+        _ if text.contains('.') || text.bytes().any(|b| exponent.contains(&b)) => {
+            Token::FloatLit(text)
+        }
+        _ => Token::Unknown(text),
+    };
+    Some(token)
+}
+
+/// rune_lit = "'" ( unicode_value | byte_value ) "'" .
+/// Only the quotes and escapes are checked, the value is left to the parser.
+fn rune(s: &str) -> Option<&str> {
+    let mut chars = s.char_indices();
+    chars.next().filter(|(_, c)| *c == '\'')?;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' if i > 1 => return Some(&s[..=i]),
+            '\\' => {
+                chars.next();
+            }
+            '\n' | '\'' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[test]
+fn test_keywords_and_identifiers() {
+    assert_eq!(
+        Vec::from(tokenize("package main_2 /* x */").unwrap()),
+        vec![
+            Token::Keyword("package"),
+            Token::Ident("main_2"),
+            Token::Comment("/* x */")
+        ]
+    );
+    assert_eq!(
+        Vec::from(tokenize("funcs - 0x1F").unwrap()),
+        vec![
+            Token::Ident("funcs"),
+            Token::Symbol(super::Symbol::Minus),
+            Token::IntLit(crate::literals::integer::IntLit::HexLit("0x1F"))
+        ]
+    );
+}
+
+#[test]
+fn test_literals_and_operators() {
+    use super::Symbol;
+    let texts = |src| {
+        Vec::from(tokenize(src).unwrap())
+            .iter()
+            .map(|token| format!("{:?}", token))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        texts("1e-3 0x1p+2 .5 6i 1_000 '\\'' `raw\"` \"s\\\"\""),
+        vec![
+            "FloatLit(\"1e-3\")",
+            "FloatLit(\"0x1p+2\")",
+            "FloatLit(\".5\")",
+            "ImaginaryLit(\"6i\")",
+            "IntLit(DecimalLit(\"1_000\"))",
+            "RuneLit(\"'\\\\''\")",
+            "StringLit(\"`raw\\\"`\")",
+            "StringLit(\"\\\"s\\\\\\\"\\\"\")",
+        ]
+    );
+    assert_eq!(
+        Vec::from(tokenize("a&^=b<-c...").unwrap())
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Symbol(symbol) => Some(symbol),
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        vec![Symbol::AmpCaretEq, Symbol::Arrow, Symbol::Ellipsis]
+    );
+    assert_eq!(tokenize_lossless("0x '\n")[0], Token::Unknown("0x"));
+    assert_eq!(tokenize_lossless("0x '\n")[2], Token::Unknown("'"));
+}
@@ -1,20 +1,105 @@
-use crate::identifier::QualifiedIdent;
+use nom::{
+    branch::alt,
+    combinator::{map, opt},
+    multi::{separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
 
-// Type = TypeName | TypeLit | "(" Type ")" .
+use crate::{
+    astable::ASTable,
+    error::{expected, Expected, ParseError},
+    expression::{nested, Expression},
+    identifier::QualifiedIdent,
+    literals::string::string_lit,
+    parse_identifier_list,
+    parse_util::{identifier, list_of, reserved, symbol},
+};
+
+// Type = TypeName [ TypeArgs ] | TypeLit | "(" Type ")" .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GoType<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     TypeName(TypeName<'a>),
-    TypeLit(TypeLit),
+    /// An instantiated generic type, e.g. `List[int]`.
+    Instantiation {
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        name: TypeName<'a>,
+        type_args: Vec<GoType<'a>>,
+    },
+    TypeLit(TypeLit<'a>),
+}
+
+impl<'a> ASTable<'a> for GoType<'a> {
+    /// A parenthesized type is returned as the type inside the parentheses.
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::typ::{GoType, TypeLit, TypeName};
+    /// assert_eq!(GoType::parse("(int)"), Ok(("", GoType::TypeName(TypeName::Identifier("int")))));
+    /// assert!(matches!(GoType::parse("map[string][]*T"), Ok(("", GoType::TypeLit(TypeLit::MapType { .. })))));
+    /// assert!(matches!(GoType::parse("pkg.Map[string, []int]"), Ok(("", GoType::Instantiation { type_args, .. })) if type_args.len() == 2));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let go_type = alt((
+            parse_type_name_with_args,
+            map(TypeLit::parse, GoType::TypeLit),
+            delimited(symbol("("), GoType::parse, symbol(")")),
+        ));
+        expected(Expected::Kind("type"), go_type)(s)
+    }
 }
 
 // TypeName  = identifier | QualifiedIdent .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeName<'a> {
     // e.g) int, string ... and user-defined types. because the primitives aren't reserved-keyword.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
     Identifier(&'a str),
 
     //
     QualifiedIdent(QualifiedIdent<'a>),
 }
 
+impl<'a> ASTable<'a> for TypeName<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::identifier::QualifiedIdent;
+    /// use go_parser_rs::typ::TypeName;
+    /// assert_eq!(TypeName::parse("error"), Ok(("", TypeName::Identifier("error"))));
+    /// assert_eq!(
+    ///     TypeName::parse("io.Reader"),
+    ///     Ok(("", TypeName::QualifiedIdent(QualifiedIdent { package_name: "io", identifier: "Reader" })))
+    /// );
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        alt((
+            map(QualifiedIdent::parse, TypeName::QualifiedIdent),
+            map(identifier, TypeName::Identifier),
+        ))(s)
+    }
+}
+
+/// TypeName [ TypeArgs ]
+pub(crate) fn parse_type_name_with_args(s: &str) -> IResult<&str, GoType<'_>, ParseError<'_>> {
+    let (s, name) = TypeName::parse(s)?;
+    match opt(parse_type_args)(s)? {
+        (s, Some(type_args)) => Ok((s, GoType::Instantiation { name, type_args })),
+        (s, None) => Ok((s, GoType::TypeName(name))),
+    }
+}
+
+/// TypeArgs = "[" TypeList [ "," ] "]" .
+/// TypeList = Type { "," Type } .
+pub(crate) fn parse_type_args(s: &str) -> IResult<&str, Vec<GoType<'_>>, ParseError<'_>> {
+    let type_list = terminated(
+        separated_list1(symbol(","), GoType::parse),
+        opt(symbol(",")),
+    );
+    delimited(symbol("["), nested(type_list), symbol("]"))(s)
+}
+
 impl<'a> From<&'a str> for TypeName<'a> {
     fn from(v: &'a str) -> Self {
         Self::Identifier(v)
@@ -28,4 +113,473 @@ impl<'a> From<QualifiedIdent<'a>> for TypeName<'a> {
 }
 // TypeLit = ArrayType | StructType | PointerType | FunctionType | InterfaceType |
 // 	         SliceType | MapType | ChannelType .
-pub enum TypeLit {}
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypeLit<'a> {
+    /// ArrayType = "[" ArrayLength "]" ElementType .
+    ArrayType {
+        /// `None` for the `[...]T` of a composite literal.
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        len: Option<Box<Expression<'a>>>,
+        elem: Box<GoType<'a>>,
+    },
+    /// SliceType = "[" "]" ElementType .
+    SliceType(Box<GoType<'a>>),
+    /// StructType = "struct" "{" { FieldDecl ";" } "}" .
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    StructType(Vec<FieldDecl<'a>>),
+    /// PointerType = "*" BaseType .
+    PointerType(Box<GoType<'a>>),
+    /// FunctionType = "func" Signature .
+    FunctionType(Signature<'a>),
+    /// InterfaceType = "interface" "{" { InterfaceElem ";" } "}" .
+    InterfaceType(Vec<InterfaceElem<'a>>),
+    /// MapType = "map" "[" KeyType "]" ElementType .
+    MapType {
+        key: Box<GoType<'a>>,
+        value: Box<GoType<'a>>,
+    },
+    /// ChannelType = ( "chan" | "chan" "<-" | "<-" "chan" ) ElementType .
+    ChannelType { dir: ChanDir, elem: Box<GoType<'a>> },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChanDir {
+    Both,
+    /// `chan<- T`
+    Send,
+    /// `<-chan T`
+    Recv,
+}
+
+impl<'a> ASTable<'a> for TypeLit<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::typ::{ChanDir, TypeLit};
+    /// assert!(matches!(TypeLit::parse("[4]byte"), Ok(("", TypeLit::ArrayType { .. }))));
+    /// assert!(matches!(TypeLit::parse("[]string"), Ok(("", TypeLit::SliceType(_)))));
+    /// assert!(matches!(TypeLit::parse("*T"), Ok(("", TypeLit::PointerType(_)))));
+    /// assert!(matches!(TypeLit::parse("func(int) error"), Ok(("", TypeLit::FunctionType(_)))));
+    /// assert!(matches!(TypeLit::parse("<-chan int"), Ok(("", TypeLit::ChannelType { dir: ChanDir::Recv, .. }))));
+    /// assert!(matches!(TypeLit::parse("chan<- int"), Ok(("", TypeLit::ChannelType { dir: ChanDir::Send, .. }))));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let boxed = |s| map(GoType::parse, Box::new)(s);
+        alt((
+            map(
+                pair(
+                    delimited(symbol("["), Expression::parse, symbol("]")),
+                    boxed,
+                ),
+                |(len, elem)| TypeLit::ArrayType {
+                    len: Some(Box::new(len)),
+                    elem,
+                },
+            ),
+            map(
+                preceded(pair(symbol("["), symbol("]")), boxed),
+                TypeLit::SliceType,
+            ),
+            map(
+                preceded(reserved("struct"), list_of("{", FieldDecl::parse, "}")),
+                TypeLit::StructType,
+            ),
+            map(preceded(symbol("*"), boxed), TypeLit::PointerType),
+            map(
+                preceded(reserved("func"), Signature::parse),
+                TypeLit::FunctionType,
+            ),
+            map(
+                preceded(
+                    reserved("interface"),
+                    list_of("{", InterfaceElem::parse, "}"),
+                ),
+                TypeLit::InterfaceType,
+            ),
+            map(
+                tuple((
+                    reserved("map"),
+                    delimited(symbol("["), boxed, symbol("]")),
+                    boxed,
+                )),
+                |(_, key, value)| TypeLit::MapType { key, value },
+            ),
+            map(pair(chan_dir, boxed), |(dir, elem)| TypeLit::ChannelType {
+                dir,
+                elem,
+            }),
+        ))(s)
+    }
+}
+
+fn chan_dir(s: &str) -> IResult<&str, ChanDir, ParseError<'_>> {
+    alt((
+        map(pair(symbol("<-"), reserved("chan")), |_| ChanDir::Recv),
+        map(pair(reserved("chan"), symbol("<-")), |_| ChanDir::Send),
+        map(reserved("chan"), |_| ChanDir::Both),
+    ))(s)
+}
+
+/// FieldDecl     = ( IdentifierList Type | EmbeddedField ) [ Tag ] .
+/// EmbeddedField = [ "*" ] TypeName [ TypeArgs ] .
+/// Tag           = string_lit .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldDecl<'a> {
+    /// Empty for an embedded field.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub identifiers: Vec<&'a str>,
+    pub go_type: GoType<'a>,
+    /// The tag as written, with its quotes.
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub tag: Option<&'a str>,
+}
+
+impl<'a> ASTable<'a> for FieldDecl<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::typ::FieldDecl;
+    /// let (_, field) = FieldDecl::parse("X, Y int `json:\"x\"`").unwrap();
+    /// assert_eq!((field.identifiers, field.tag), (vec!["X", "Y"], Some("`json:\"x\"`")));
+    /// let (_, field) = FieldDecl::parse("*sync.Mutex").unwrap();
+    /// assert!(field.identifiers.is_empty());
+    /// let (_, field) = FieldDecl::parse("List[int]").unwrap();
+    /// assert!(field.identifiers.is_empty());
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let embedded = alt((
+            map(
+                preceded(symbol("*"), parse_type_name_with_args),
+                |go_type| GoType::TypeLit(TypeLit::PointerType(Box::new(go_type))),
+            ),
+            parse_type_name_with_args,
+        ));
+        let (s, (identifiers, go_type)) = alt((
+            pair(parse_identifier_list, GoType::parse),
+            map(embedded, |go_type| (vec![], go_type)),
+        ))(s)?;
+        let (s, tag) = opt(string_lit)(s)?;
+        Ok((
+            s,
+            FieldDecl {
+                identifiers,
+                go_type,
+                tag,
+            },
+        ))
+    }
+}
+
+/// InterfaceElem = MethodElem | TypeElem .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InterfaceElem<'a> {
+    /// MethodElem = MethodName Signature .
+    Method {
+        #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+        name: &'a str,
+        signature: Signature<'a>,
+    },
+    Type(TypeElem<'a>),
+}
+
+impl<'a> ASTable<'a> for InterfaceElem<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::typ::InterfaceElem;
+    /// assert!(matches!(InterfaceElem::parse("Read(p []byte) (n int, err error)"), Ok(("", InterfaceElem::Method { name: "Read", .. }))));
+    /// assert!(matches!(InterfaceElem::parse("io.Closer"), Ok(("", InterfaceElem::Type(_)))));
+    /// let (_, elem) = InterfaceElem::parse("~int | ~string | float64").unwrap();
+    /// match elem {
+    ///     InterfaceElem::Type(elem) => {
+    ///         let tildes: Vec<_> = elem.0.iter().map(|term| term.tilde).collect();
+    ///         assert_eq!(tildes, vec![true, true, false]);
+    ///     }
+    ///     elem => panic!("{:?}", elem),
+    /// }
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        alt((
+            map(pair(identifier, Signature::parse), |(name, signature)| {
+                InterfaceElem::Method { name, signature }
+            }),
+            map(TypeElem::parse, InterfaceElem::Type),
+        ))(s)
+    }
+}
+
+/// TypeElem = TypeTerm { "|" TypeTerm } .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeElem<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub Vec<TypeTerm<'a>>);
+
+impl<'a> ASTable<'a> for TypeElem<'a> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        map(separated_list1(symbol("|"), TypeTerm::parse), TypeElem)(s)
+    }
+}
+
+/// TypeTerm       = Type | UnderlyingType .
+/// UnderlyingType = "~" Type .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeTerm<'a> {
+    /// Whether the term stands for all types whose underlying type is `go_type`.
+    pub tilde: bool,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub go_type: GoType<'a>,
+}
+
+impl<'a> ASTable<'a> for TypeTerm<'a> {
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, tilde) = opt(symbol("~"))(s)?;
+        let (s, go_type) = GoType::parse(s)?;
+        let tilde = tilde.is_some();
+        Ok((s, TypeTerm { tilde, go_type }))
+    }
+}
+
+/// TypeParamDecl  = IdentifierList TypeConstraint .
+/// TypeConstraint = TypeElem .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeParamDecl<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub identifiers: Vec<&'a str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub constraint: TypeElem<'a>,
+}
+
+/// TypeParameters = "[" TypeParamList [ "," ] "]" .
+/// TypeParamList  = TypeParamDecl { "," TypeParamDecl } .
+/// ```
+/// use go_parser_rs::typ::parse_type_parameters;
+/// let (s, params) = parse_type_parameters("[K comparable, V any, S ~[]V,](").unwrap();
+/// assert_eq!(s, "(");
+/// let names: Vec<_> = params.iter().map(|param| param.identifiers.clone()).collect();
+/// assert_eq!(names, vec![vec!["K"], vec!["V"], vec!["S"]]);
+/// assert!(parse_type_parameters("[T]").is_err());
+/// ```
+pub fn parse_type_parameters(s: &str) -> IResult<&str, Vec<TypeParamDecl<'_>>, ParseError<'_>> {
+    let decl = map(
+        pair(parse_identifier_list, TypeElem::parse),
+        |(identifiers, constraint)| TypeParamDecl {
+            identifiers,
+            constraint,
+        },
+    );
+    delimited(
+        symbol("["),
+        terminated(separated_list1(symbol(","), decl), opt(symbol(","))),
+        symbol("]"),
+    )(s)
+}
+
+/// Signature = Parameters [ Result ] .
+/// Result    = Parameters | Type .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Signature<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub params: Vec<ParameterDecl<'a>>,
+    /// A result written as a bare type is a single unnamed parameter.
+    pub results: Vec<ParameterDecl<'a>>,
+}
+
+impl<'a> ASTable<'a> for Signature<'a> {
+    /// ```
+    /// use go_parser_rs::astable::ASTable;
+    /// use go_parser_rs::typ::Signature;
+    /// let (_, signature) = Signature::parse("(a, b int, opts ...Option) (*T, error)").unwrap();
+    /// assert_eq!(signature.params.len(), 2);
+    /// assert_eq!(signature.params[0].identifiers, Some(vec!["a", "b"]));
+    /// assert!(signature.params[1].is_variadic);
+    /// assert_eq!(signature.results.len(), 2);
+    /// let (s, signature) = Signature::parse("()\nx").unwrap();
+    /// assert_eq!((s, signature.results.len()), ("\nx", 0));
+    /// ```
+    fn parse(s: &'a str) -> IResult<&'a str, Self, ParseError<'a>> {
+        let (s, params) = parse_parameters(s)?;
+        let (s, results) = parse_result(s)?;
+        Ok((s, Signature { params, results }))
+    }
+}
+
+/// [ Result ], as a list of parameters.
+pub(crate) fn parse_result(s: &str) -> IResult<&str, Vec<ParameterDecl<'_>>, ParseError<'_>> {
+    let single_result = map(GoType::parse, |go_type| {
+        vec![ParameterDecl {
+            identifiers: None,
+            is_variadic: false,
+            go_type,
+        }]
+    });
+    map(
+        opt(alt((parse_parameters, single_result))),
+        Option::unwrap_or_default,
+    )(s)
+}
+
+/// ParameterDecl = [ IdentifierList ] [ "..." ] Type .
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParameterDecl<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::text"))]
+    pub identifiers: Option<Vec<&'a str>>,
+    pub is_variadic: bool,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub go_type: GoType<'a>,
+}
+
+/// Parameters    = "(" [ ParameterList [ "," ] ] ")" .
+/// ParameterList = ParameterDecl { "," ParameterDecl } .
+///
+/// Like Go's parser, it reads a list of types that may be preceded by a name, then decides:
+/// either no parameter is named, or the unnamed ones are names sharing the type of the next named one.
+pub(crate) fn parse_parameters(s: &str) -> IResult<&str, Vec<ParameterDecl<'_>>, ParseError<'_>> {
+    let variadic = |s| map(opt(symbol("...")), |ellipsis| ellipsis.is_some())(s);
+    let entry = |s| {
+        alt((
+            map(
+                tuple((identifier, variadic, GoType::parse)),
+                |(name, v, t)| (Some(name), v, t),
+            ),
+            map(pair(variadic, GoType::parse), |(v, t)| (None, v, t)),
+        ))(s)
+    };
+    let (rest, entries) = delimited(
+        symbol("("),
+        terminated(separated_list0(symbol(","), entry), opt(symbol(","))),
+        symbol(")"),
+    )(s)?;
+    if entries.iter().all(|(name, _, _)| name.is_none()) {
+        let params = entries
+            .into_iter()
+            .map(|(_, is_variadic, go_type)| ParameterDecl {
+                identifiers: None,
+                is_variadic,
+                go_type,
+            })
+            .collect();
+        return Ok((rest, params));
+    }
+    let mut params = vec![];
+    let mut names = vec![];
+    for entry in entries {
+        match entry {
+            (Some(name), is_variadic, go_type) => {
+                names.push(name);
+                params.push(ParameterDecl {
+                    identifiers: Some(std::mem::take(&mut names)),
+                    is_variadic,
+                    go_type,
+                });
+            }
+            (None, false, GoType::TypeName(TypeName::Identifier(name))) => names.push(name),
+            _ => return Err(mixed_parameters(s)),
+        }
+    }
+    if !names.is_empty() {
+        return Err(mixed_parameters(s));
+    }
+    Ok((rest, params))
+}
+
+fn mixed_parameters(s: &str) -> nom::Err<ParseError<'_>> {
+    nom::Err::Failure(ParseError::new(
+        s,
+        Expected::Kind("parameters all named or all unnamed"),
+    ))
+}
+
+#[test]
+fn test_parameters() {
+    let (_, params) = parse_parameters("(int, string)").unwrap();
+    assert_eq!(params.len(), 2);
+    assert!(params.iter().all(|param| param.identifiers.is_none()));
+    let (_, params) = parse_parameters("(x, y int, z ...io.Reader,)").unwrap();
+    assert_eq!(params[1].identifiers, Some(vec!["z"]));
+    let (_, params) = parse_parameters("(f func(int) bool)").unwrap();
+    assert_eq!(params[0].identifiers, Some(vec!["f"]));
+    assert!(parse_parameters("(x int, string)").is_err());
+    assert!(parse_parameters("(x, y int, []byte)").is_err());
+}
+
+#[test]
+fn test_parameter_decls() {
+    let named = |name| GoType::TypeName(TypeName::Identifier(name));
+    assert_eq!(
+        parse_parameters("(x int)"),
+        Ok((
+            "",
+            vec![ParameterDecl {
+                identifiers: Some(vec!["x"]),
+                is_variadic: false,
+                go_type: named("int")
+            }]
+        ))
+    );
+    assert_eq!(
+        parse_parameters("(x, y int, z string)"),
+        Ok((
+            "",
+            vec![
+                ParameterDecl {
+                    identifiers: Some(vec!["x", "y"]),
+                    is_variadic: false,
+                    go_type: named("int")
+                },
+                ParameterDecl {
+                    identifiers: Some(vec!["z"]),
+                    is_variadic: false,
+                    go_type: named("string")
+                }
+            ]
+        ))
+    );
+    assert_eq!(
+        parse_parameters("(x, y ... int)"),
+        Ok((
+            "",
+            vec![ParameterDecl {
+                identifiers: Some(vec!["x", "y"]),
+                is_variadic: true,
+                go_type: named("int")
+            }]
+        ))
+    );
+}
+
+#[test]
+fn test_struct_type() {
+    let src = "struct {\n\tio.Reader\n\tname, email string `json:\"name\"`\n\n\tnext *Node // the next node\n}";
+    let (s, go_type) = GoType::parse(src).unwrap();
+    assert_eq!(s, "");
+    let fields = match go_type {
+        GoType::TypeLit(TypeLit::StructType(fields)) => fields,
+        go_type => panic!("{:?}", go_type),
+    };
+    let names: Vec<_> = fields.iter().map(|f| f.identifiers.clone()).collect();
+    assert_eq!(names, vec![vec![], vec!["name", "email"], vec!["next"]]);
+}
+
+#[test]
+fn test_type_args() {
+    let (s, go_type) = GoType::parse("List[int]\n").unwrap();
+    assert_eq!(s, "\n");
+    assert_eq!(
+        go_type,
+        GoType::Instantiation {
+            name: TypeName::Identifier("List"),
+            type_args: vec![GoType::TypeName(TypeName::Identifier("int"))],
+        }
+    );
+    let (s, go_type) = GoType::parse("*Tree[Pair[K, V],]").unwrap();
+    assert_eq!(s, "");
+    assert!(matches!(go_type, GoType::TypeLit(TypeLit::PointerType(_))));
+    assert!(matches!(
+        GoType::parse("List[]"),
+        Ok(("[]", GoType::TypeName(_)))
+    ));
+}
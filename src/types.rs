@@ -0,0 +1,4315 @@
+//! Type checking: computes the type of the expressions of a resolved package and reports the
+//! operations their operands do not allow.
+//!
+//! [`check`] goes through the files after [`resolve`](crate::resolve::resolve), computing the
+//! types of declared objects when they are first used, and checks assignments, conversions, calls,
+//! the operands of unary and binary operators, conditions, and return statements. Constant
+//! expressions keep their untyped type, which becomes the type of the context they are used in,
+//! and their values are computed by [`constant`](crate::constant) to report the ones that do not
+//! fit that type and the divisions by zero.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, resolve::resolve, types::{check, ErrorKind}, SourceFile};
+//! let src = "package m\n\nfunc f(n int) string {\n\treturn n + \"s\"\n}\n";
+//! let (_, file) = SourceFile::parse(src).unwrap();
+//! let info = check([&file], &resolve([&file]));
+//! assert_eq!(info.errors.len(), 1);
+//! assert_eq!(info.errors[0].kind, ErrorKind::MismatchedTypes);
+//! assert_eq!(
+//!     info.errors[0].message,
+//!     "invalid operation: n + \"s\" (mismatched types int and untyped string)"
+//! );
+//! assert_eq!(&src[info.errors[0].span(src).unwrap().start..][..1], "+");
+//! ```
+//!
+//! Imported packages are not loaded, so their members are of the [`Type::Invalid`] type, which
+//! is accepted everywhere. So are the operands whose type is a type parameter, for which only
+//! identical types are checked.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use num_traits::{Signed, ToPrimitive};
+
+use crate::{
+    constant::{self, Constant, Name},
+    declaration::{ConstDecl, TypeSpec, VarSpec},
+    error::Span,
+    expression::{
+        operand::{Element, LiteralValue, Operand, OperandName},
+        Expression, PrimaryExpr, UnaryExpr,
+    },
+    literals::Literal,
+    methods::{self, MethodSet, MissingMethods},
+    printer::print,
+    resolve::{self, Binding, Decl, ObjKind, Object, Resolution},
+    statement::{
+        control::{ForClause, IfStmt, RangeVars, SwitchStmt, TypeSwitchStmt},
+        simple::SimpleStmt,
+        Block, Statement,
+    },
+    typ::{self, ChanDir, InterfaceElem, ParameterDecl, Signature, TypeLit, TypeName},
+    visit::{self, Visitor},
+    Function, GoType, ImportDeclaration, SourceFile, TopLevel,
+};
+
+/// The kind of an untyped constant, or of `nil`.
+///
+/// The numeric kinds are ordered, an operation on two of them being of the greater one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Untyped {
+    Bool,
+    Int,
+    Rune,
+    Float,
+    Complex,
+    String,
+    Nil,
+}
+
+impl Untyped {
+    /// The type the constant gets where no type is expected, `None` for `nil`.
+    pub fn default_type(self) -> Option<GoType> {
+        let typ = match self {
+            Untyped::Bool => GoType::Bool,
+            Untyped::Int => GoType::Int,
+            Untyped::Rune => GoType::Int32,
+            Untyped::Float => GoType::Float64,
+            Untyped::Complex => GoType::Complex128,
+            Untyped::String => GoType::String,
+            Untyped::Nil => return None,
+        };
+        Some(typ)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Untyped::Bool => "untyped bool",
+            Untyped::Int => "untyped int",
+            Untyped::Rune => "untyped rune",
+            Untyped::Float => "untyped float",
+            Untyped::Complex => "untyped complex",
+            Untyped::String => "untyped string",
+            Untyped::Nil => "untyped nil",
+        }
+    }
+
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Untyped::Int | Untyped::Rune | Untyped::Float | Untyped::Complex
+        )
+    }
+}
+
+/// The type of an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type<'a> {
+    /// A predeclared type, never an alias like `byte`.
+    Basic(GoType),
+    Untyped(Untyped),
+    /// A defined type, or `error` or `comparable`.
+    Named(Named<'a>),
+    /// A type parameter, by the identifier declaring it.
+    TypeParam(&'a str),
+    Pointer(Box<Type<'a>>),
+    /// `len` is `None` when it is not known, like when it is a constant expression.
+    Array {
+        len: Option<u64>,
+        elem: Box<Type<'a>>,
+    },
+    Slice(Box<Type<'a>>),
+    Map {
+        key: Box<Type<'a>>,
+        value: Box<Type<'a>>,
+    },
+    Chan {
+        dir: ChanDir,
+        elem: Box<Type<'a>>,
+    },
+    Struct(Vec<Field<'a>>),
+    Func(Func<'a>),
+    /// The methods of an interface, sorted by name. Type elements are not kept.
+    Interface(Vec<Method<'a>>),
+    /// The results of a call returning several values.
+    Tuple(Vec<Type<'a>>),
+    /// The type of an expression that could not be typed, which is accepted everywhere.
+    Invalid,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Named<'a> {
+    pub name: &'a str,
+    /// `None` for the predeclared `error` and `comparable`.
+    pub spec: Option<&'a TypeSpec<'a>>,
+    /// The type arguments of an instantiated generic type.
+    pub type_args: Vec<Type<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field<'a> {
+    /// The name of the type for an embedded field.
+    pub name: &'a str,
+    pub typ: Type<'a>,
+    pub embedded: bool,
+}
+
+/// A function signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Func<'a> {
+    pub type_params: Vec<&'a str>,
+    /// The last parameter of a variadic function is a slice.
+    pub params: Vec<Type<'a>>,
+    pub results: Vec<Type<'a>>,
+    pub variadic: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Method<'a> {
+    pub name: &'a str,
+    pub func: Func<'a>,
+}
+
+impl fmt::Display for Type<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Basic(typ) => f.write_str(typ.name()),
+            Type::Untyped(untyped) => f.write_str(untyped.name()),
+            Type::Named(named) => {
+                f.write_str(named.name)?;
+                if !named.type_args.is_empty() {
+                    write!(f, "[{}]", list(&named.type_args))?;
+                }
+                Ok(())
+            }
+            Type::TypeParam(name) => f.write_str(name),
+            Type::Pointer(base) => write!(f, "*{}", base),
+            Type::Array {
+                len: Some(len),
+                elem,
+            } => write!(f, "[{}]{}", len, elem),
+            Type::Array { len: None, elem } => write!(f, "[...]{}", elem),
+            Type::Slice(elem) => write!(f, "[]{}", elem),
+            Type::Map { key, value } => write!(f, "map[{}]{}", key, value),
+            Type::Chan { dir, elem } => match dir {
+                ChanDir::Both => write!(f, "chan {}", elem),
+                ChanDir::Send => write!(f, "chan<- {}", elem),
+                ChanDir::Recv => write!(f, "<-chan {}", elem),
+            },
+            Type::Struct(fields) => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|field| match field.embedded {
+                        true => field.typ.to_string(),
+                        false => format!("{} {}", field.name, field.typ),
+                    })
+                    .collect();
+                write!(f, "struct{{{}}}", fields.join("; "))
+            }
+            Type::Func(func) => write!(f, "func{}", func),
+            Type::Interface(methods) => {
+                let methods: Vec<_> = methods
+                    .iter()
+                    .map(|method| format!("{}{}", method.name, method.func))
+                    .collect();
+                write!(f, "interface{{{}}}", methods.join("; "))
+            }
+            Type::Tuple(types) => write!(f, "({})", list(types)),
+            Type::Invalid => f.write_str("invalid type"),
+        }
+    }
+}
+
+/// The signature without `func`, like `(a int, b ...string) (int, error)`.
+impl fmt::Display for Func<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut params: Vec<_> = self.params.iter().map(Type::to_string).collect();
+        if let (true, Some(Type::Slice(elem))) = (self.variadic, self.params.last()) {
+            *params.last_mut().unwrap() = format!("...{}", elem);
+        }
+        write!(f, "({})", params.join(", "))?;
+        match self.results.as_slice() {
+            [] => Ok(()),
+            [result] => write!(f, " {}", result),
+            results => write!(f, " ({})", list(results)),
+        }
+    }
+}
+
+fn list(types: &[Type<'_>]) -> String {
+    let types: Vec<_> = types.iter().map(Type::to_string).collect();
+    types.join(", ")
+}
+
+/// Whether `a` and `b` are the same type. The invalid type is identical to any other.
+pub fn identical(a: &Type<'_>, b: &Type<'_>) -> bool {
+    let all = |a: &[Type<'_>], b: &[Type<'_>]| {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| identical(a, b))
+    };
+    match (a, b) {
+        (Type::Invalid, _) | (_, Type::Invalid) => true,
+        (Type::Basic(a), Type::Basic(b)) => a == b,
+        (Type::Untyped(a), Type::Untyped(b)) => a == b,
+        (Type::Named(a), Type::Named(b)) => {
+            let same = match (a.spec, b.spec) {
+                (Some(a), Some(b)) => std::ptr::eq(a, b),
+                (None, None) => a.name == b.name,
+                _ => false,
+            };
+            same && all(&a.type_args, &b.type_args)
+        }
+        (Type::TypeParam(a), Type::TypeParam(b)) => a.as_ptr() == b.as_ptr(),
+        (Type::Pointer(a), Type::Pointer(b)) | (Type::Slice(a), Type::Slice(b)) => identical(a, b),
+        (Type::Array { len: a, elem: x }, Type::Array { len: b, elem: y }) => {
+            (a.is_none() || b.is_none() || a == b) && identical(x, y)
+        }
+        (Type::Map { key: a, value: x }, Type::Map { key: b, value: y }) => {
+            identical(a, b) && identical(x, y)
+        }
+        (Type::Chan { dir: a, elem: x }, Type::Chan { dir: b, elem: y }) => {
+            a == b && identical(x, y)
+        }
+        (Type::Struct(a), Type::Struct(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| {
+                    a.name == b.name && a.embedded == b.embedded && identical(&a.typ, &b.typ)
+                })
+        }
+        (Type::Func(a), Type::Func(b)) => identical_funcs(a, b),
+        (Type::Interface(a), Type::Interface(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| a.name == b.name && identical_funcs(&a.func, &b.func))
+        }
+        (Type::Tuple(a), Type::Tuple(b)) => all(a, b),
+        _ => false,
+    }
+}
+
+pub(crate) fn identical_funcs(a: &Func<'_>, b: &Func<'_>) -> bool {
+    let all = |a: &[Type<'_>], b: &[Type<'_>]| {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| identical(a, b))
+    };
+    a.variadic == b.variadic && all(&a.params, &b.params) && all(&a.results, &b.results)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The operands of a binary operator have different types.
+    MismatchedTypes,
+    /// An operator applied to an operand of a type it is not defined on.
+    InvalidOperation,
+    NotAssignable,
+    InvalidConversion,
+    WrongArgumentCount,
+    WrongResultCount,
+    /// Different numbers of values on each side of an assignment.
+    AssignmentMismatch,
+    NotCallable,
+    /// A type, a package or a builtin function used as a value.
+    NotAnExpression,
+    NotAType,
+    MissingFieldOrMethod,
+    NonBooleanCondition,
+    /// A call returning several values where one is expected.
+    MultipleValues,
+    /// A call returning nothing used as a value.
+    NoValue,
+    InvalidCompositeLit,
+    /// `nil` used where there is no type to give it.
+    UntypedNil,
+    DivisionByZero,
+    /// A constant too big for its type.
+    Overflow,
+    /// A constant with a fractional or imaginary part given a type that cannot have it.
+    Truncated,
+    /// A name declared in no scope.
+    Undefined,
+    /// A constant index that is negative, or past the end of an array.
+    IndexOutOfRange,
+}
+
+/// A type error, at the token `at`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error<'a> {
+    pub kind: ErrorKind,
+    pub at: &'a str,
+    pub message: String,
+}
+
+impl Error<'_> {
+    /// Locates the error in `source`, the text of the file the token is from, or `None` if the
+    /// token is not a slice of `source`.
+    pub fn span(&self, source: &str) -> Option<Span> {
+        resolve::span_in(source, self.at)
+    }
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error<'_> {}
+
+/// The result of [`check`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Info<'a> {
+    /// The type of each expression, by its address.
+    types: HashMap<usize, Type<'a>>,
+    /// The type of each declared variable, constant and function, by the address of its name.
+    objects: HashMap<usize, Type<'a>>,
+    /// The value of each declared constant, by the address of its name.
+    constants: HashMap<usize, Constant>,
+    /// The underlying type of each defined type, by the address of its spec.
+    underlying: HashMap<usize, Type<'a>>,
+    /// The methods declared for each defined type, by the address of its spec, with whether
+    /// their receiver is a pointer and the names given to the type parameters of the receiver.
+    methods: HashMap<usize, Vec<DeclaredMethod<'a>>>,
+    pub errors: Vec<Error<'a>>,
+}
+
+type DeclaredMethod<'a> = (Method<'a>, bool, Vec<&'a str>);
+
+impl<'a> Info<'a> {
+    /// The type of `expr`, an empty tuple for a call returning nothing, or `None` if it is not a
+    /// checked expression of the files or it is not a value, like a type.
+    pub fn type_of(&self, expr: &Expression<'_>) -> Option<&Type<'a>> {
+        self.types.get(&address(expr))
+    }
+
+    /// Like [`Info::type_of`], for a primary expression like the callee of a call.
+    pub fn type_of_primary(&self, expr: &PrimaryExpr<'_>) -> Option<&Type<'a>> {
+        self.types.get(&address(expr))
+    }
+
+    /// The type of the variable, constant or function declared by the identifier `name`, or the
+    /// type declared by it. Like for [`Resolution::binding`], `name` must be the identifier
+    /// itself.
+    pub fn object_type(&self, name: &str) -> Option<&Type<'a>> {
+        self.objects.get(&(name.as_ptr() as usize))
+    }
+
+    /// Like [`Info::object_type`], for the identifier at `span` in `source`, the text of its file.
+    /// ```
+    /// use go_parser_rs::{astable::ASTable, error::Span, resolve::resolve, types::check, SourceFile};
+    /// let src = "package m\n\nvar s = \"s\"\n";
+    /// let (_, file) = SourceFile::parse(src).unwrap();
+    /// let info = check([&file], &resolve([&file]));
+    /// let s = src.find("s =").unwrap();
+    /// let typ = info.object_type_at(src, Span { start: s, end: s + 1 }).unwrap();
+    /// assert_eq!(typ.to_string(), "string");
+    /// ```
+    pub fn object_type_at(&self, source: &str, span: Span) -> Option<&Type<'a>> {
+        self.objects.get(&resolve::address_in(source, span)?)
+    }
+
+    /// The value of the constant declared by the identifier `name`, if it could be computed.
+    pub fn constant(&self, name: &str) -> Option<&Constant> {
+        self.constants.get(&(name.as_ptr() as usize))
+    }
+
+    /// The method set of `typ`, a type of the checked package, see [`methods`](crate::methods).
+    pub fn method_set(&self, typ: &Type<'a>) -> MethodSet<'a> {
+        methods::method_set(&mut &*self, typ)
+    }
+
+    /// Whether the method set of `typ` holds the methods of the interface `iface`.
+    pub fn implements(&self, typ: &Type<'a>, iface: &Type<'a>) -> Result<(), MissingMethods<'a>> {
+        methods::implements(&mut &*self, typ, iface)
+    }
+}
+
+impl<'a> methods::Types<'a> for &Info<'a> {
+    fn underlying(&mut self, typ: &Type<'a>) -> Type<'a> {
+        let named = match typ {
+            Type::Named(named) => named,
+            Type::TypeParam(_) => return Type::Invalid,
+            typ => return typ.clone(),
+        };
+        let spec = match named.spec {
+            Some(spec) => spec,
+            None => return predeclared(named.name),
+        };
+        match self.underlying.get(&address(spec)) {
+            Some(underlying) => substitute(underlying, &spec_type_params(spec), &named.type_args),
+            None => Type::Invalid,
+        }
+    }
+
+    fn declared(&mut self, named: &Named<'a>) -> Vec<(Method<'a>, bool)> {
+        let methods = named.spec.and_then(|spec| self.methods.get(&address(spec)));
+        let methods = methods.map(Vec::as_slice).unwrap_or_default();
+        let instantiate = |(method, pointer, type_params): &DeclaredMethod<'a>| {
+            let func = substitute(
+                &Type::Func(method.func.clone()),
+                type_params,
+                &named.type_args,
+            );
+            let func = match func {
+                Type::Func(func) => func,
+                _ => unreachable!("a substituted function is a function"),
+            };
+            (
+                Method {
+                    name: method.name,
+                    func,
+                },
+                *pointer,
+            )
+        };
+        methods.iter().map(instantiate).collect()
+    }
+}
+
+fn address<T>(node: &T) -> usize {
+    node as *const T as usize
+}
+
+/// Checks the types of `files`, which make up a package resolved as `resolution`.
+///
+/// Nothing is checked when `resolution` has [`SharedText`](resolve::ErrorKind::SharedText)
+/// errors, as the types of identifiers that cannot be told apart would be mixed up.
+pub fn check<'a>(
+    files: impl IntoIterator<Item = &'a SourceFile<'a>>,
+    resolution: &Resolution<'a>,
+) -> Info<'a> {
+    let shared_text = |error: &resolve::Error| error.kind == resolve::ErrorKind::SharedText;
+    if resolution.errors.iter().any(shared_text) {
+        return Info::default();
+    }
+    let files: Vec<_> = files.into_iter().collect();
+    let mut checker = Checker {
+        resolution,
+        methods: HashMap::new(),
+        modes: HashMap::new(),
+        objects: HashMap::new(),
+        constants: HashMap::new(),
+        pending: HashSet::new(),
+        underlying: HashMap::new(),
+        results: vec![],
+        anchor: "",
+        dot_imports: false,
+        errors: vec![],
+        reported: HashSet::new(),
+    };
+    for file in &files {
+        for decl in &file.decls {
+            let receiver = match decl {
+                TopLevel::Function(function) => function.receiver.as_ref().map(|r| (function, r)),
+                _ => None,
+            };
+            if let Some((function, receiver)) = receiver {
+                let object = resolution.binding(receiver.base_type);
+                if let Some(Decl::Type(spec)) =
+                    object.as_ref().and_then(|b| b.object()).map(|o| o.decl)
+                {
+                    checker
+                        .methods
+                        .entry(address(spec))
+                        .or_default()
+                        .push(function);
+                }
+            }
+        }
+    }
+    for file in files {
+        checker.anchor = file.package;
+        checker.dot_imports = file.imports.iter().any(ImportDeclaration::is_dot);
+        checker.visit_source_file(file);
+    }
+    let mut methods = HashMap::new();
+    for (spec, functions) in checker.methods.clone() {
+        let functions = functions.into_iter().filter_map(|function| {
+            let receiver = function.receiver.as_ref()?;
+            let method = Method {
+                name: function.name,
+                func: checker.function_type(function),
+            };
+            Some((method, receiver.pointer, receiver.type_params.clone()))
+        });
+        methods.insert(spec, functions.collect());
+    }
+    let mut types = HashMap::new();
+    for (node, mode) in checker.modes {
+        match mode {
+            Mode::Value(typ) => types.insert(node, typ),
+            Mode::NoValue => types.insert(node, Type::Tuple(vec![])),
+            Mode::Type(_) | Mode::Builtin(_) | Mode::Package => None,
+        };
+    }
+    Info {
+        types,
+        objects: checker.objects,
+        constants: checker
+            .constants
+            .into_iter()
+            .filter_map(|(name, constant)| Some((name, constant?)))
+            .collect(),
+        underlying: checker.underlying,
+        methods,
+        errors: checker.errors,
+    }
+}
+
+/// What an expression denotes.
+#[derive(Debug, Clone)]
+enum Mode<'a> {
+    Value(Type<'a>),
+    Type(Type<'a>),
+    Builtin(&'a str),
+    Package,
+    /// A call of a function without results.
+    NoValue,
+}
+
+/// The kind of operands an operator accepts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Bool,
+    Int,
+    Float,
+    Complex,
+    String,
+    Other,
+}
+
+struct Checker<'r, 'a> {
+    resolution: &'r Resolution<'a>,
+    /// The methods declared for each defined type, by the address of its spec.
+    methods: HashMap<usize, Vec<&'a Function<'a>>>,
+    /// What each checked expression denotes, by its address.
+    modes: HashMap<usize, Mode<'a>>,
+    objects: HashMap<usize, Type<'a>>,
+    /// The values of the declared constants, `None` while being computed or when not constant.
+    constants: HashMap<usize, Option<Constant>>,
+    /// The objects and type specs whose type is being computed, to stop at cycles.
+    pending: HashSet<usize>,
+    /// The underlying type of each defined type, by the address of its spec.
+    underlying: HashMap<usize, Type<'a>>,
+    /// The result types of the functions being checked, with whether the results are named.
+    results: Vec<(Vec<Type<'a>>, bool)>,
+    /// Where to report the errors of an expression without a token, like a function literal.
+    anchor: &'a str,
+    /// Whether the file being checked has dot imports, which may declare its unresolved names.
+    dot_imports: bool,
+    errors: Vec<Error<'a>>,
+    reported: HashSet<(usize, String)>,
+}
+
+impl<'r, 'a> Checker<'r, 'a> {
+    fn error(&mut self, kind: ErrorKind, at: Option<&'a str>, message: String) {
+        let at = at.unwrap_or(self.anchor);
+        if self
+            .reported
+            .insert((at.as_ptr() as usize, message.clone()))
+        {
+            self.errors.push(Error { kind, at, message });
+        }
+    }
+
+    fn object(&self, name: &str) -> Option<Object<'a>> {
+        self.resolution.binding(name)?.object().copied()
+    }
+
+    /// Reports `name` if it was resolved to no declaration.
+    fn undefined(&mut self, name: &'a str) {
+        if self.resolution.binding(name) == Some(Binding::Unresolved) && !self.dot_imports {
+            self.error(
+                ErrorKind::Undefined,
+                Some(name),
+                format!("undefined: {}", name),
+            );
+        }
+    }
+
+    fn typ(&mut self, node: &'a typ::GoType<'a>) -> Type<'a> {
+        match node {
+            typ::GoType::TypeName(name) => self.type_name(name),
+            typ::GoType::Instantiation { name, type_args } => {
+                let args = type_args.iter().map(|arg| self.typ(arg)).collect();
+                match self.type_name(name) {
+                    Type::Named(named) => Type::Named(Named {
+                        type_args: args,
+                        ..named
+                    }),
+                    _ => Type::Invalid,
+                }
+            }
+            typ::GoType::TypeLit(lit) => self.type_lit(lit),
+        }
+    }
+
+    fn type_name(&mut self, name: &'a TypeName<'a>) -> Type<'a> {
+        let name = match name {
+            TypeName::Identifier(name) => name,
+            // The members of imported packages are not known.
+            TypeName::QualifiedIdent(_) => return Type::Invalid,
+        };
+        match self.object(name) {
+            Some(object) if object.kind == ObjKind::Type => self.type_object(object),
+            Some(_) => {
+                self.error(
+                    ErrorKind::NotAType,
+                    Some(name),
+                    format!("{} is not a type", name),
+                );
+                Type::Invalid
+            }
+            None => {
+                self.undefined(name);
+                Type::Invalid
+            }
+        }
+    }
+
+    /// The type declared by `object`.
+    fn type_object(&mut self, object: Object<'a>) -> Type<'a> {
+        match object.decl {
+            Decl::Universe => match GoType::from_name(object.name) {
+                Some(typ) => Type::Basic(typ.unaliased()),
+                None if object.name == "any" => Type::Interface(vec![]),
+                None => Type::Named(Named {
+                    name: object.name,
+                    spec: None,
+                    type_args: vec![],
+                }),
+            },
+            Decl::Type(spec @ TypeSpec::TypeDef { name, .. }) => Type::Named(Named {
+                name,
+                spec: Some(spec),
+                type_args: vec![],
+            }),
+            Decl::Type(spec @ TypeSpec::AliasDecl { typ, .. }) => {
+                if !self.pending.insert(address(spec)) {
+                    return Type::Invalid;
+                }
+                let typ = self.typ(typ);
+                self.pending.remove(&address(spec));
+                typ
+            }
+            Decl::TypeParam(_) | Decl::Receiver(_) => Type::TypeParam(object.name),
+            _ => Type::Invalid,
+        }
+    }
+
+    fn type_lit(&mut self, node: &'a TypeLit<'a>) -> Type<'a> {
+        match node {
+            TypeLit::ArrayType { len, elem } => {
+                let len = len.as_ref().and_then(|len| {
+                    let typ = self.value(len);
+                    self.integer(&typ, first_token(len), "array length");
+                    self.constant(len, None)?.value.to_int()?.to_u64()
+                });
+                Type::Array {
+                    len,
+                    elem: Box::new(self.typ(elem)),
+                }
+            }
+            TypeLit::SliceType(elem) => Type::Slice(Box::new(self.typ(elem))),
+            TypeLit::StructType(fields) => {
+                let mut types = vec![];
+                for field in fields {
+                    let typ = self.typ(&field.go_type);
+                    if field.identifiers.is_empty() {
+                        types.push(Field {
+                            name: embedded_name(&field.go_type),
+                            typ: typ.clone(),
+                            embedded: true,
+                        });
+                    }
+                    for name in &field.identifiers {
+                        types.push(Field {
+                            name,
+                            typ: typ.clone(),
+                            embedded: false,
+                        });
+                    }
+                }
+                Type::Struct(types)
+            }
+            TypeLit::PointerType(base) => Type::Pointer(Box::new(self.typ(base))),
+            TypeLit::FunctionType(signature) => Type::Func(self.signature(signature, vec![])),
+            TypeLit::InterfaceType(elems) => self.interface(elems),
+            TypeLit::MapType { key, value } => Type::Map {
+                key: Box::new(self.typ(key)),
+                value: Box::new(self.typ(value)),
+            },
+            TypeLit::ChannelType { dir, elem } => Type::Chan {
+                dir: *dir,
+                elem: Box::new(self.typ(elem)),
+            },
+        }
+    }
+
+    fn signature(&mut self, node: &'a Signature<'a>, type_params: Vec<&'a str>) -> Func<'a> {
+        let mut params = self.params(&node.params);
+        let variadic = node.params.last().is_some_and(|param| param.is_variadic);
+        if let (true, Some(last)) = (variadic, params.last_mut()) {
+            *last = Type::Slice(Box::new(std::mem::replace(last, Type::Invalid)));
+        }
+        Func {
+            type_params,
+            params,
+            results: self.params(&node.results),
+            variadic,
+        }
+    }
+
+    /// The type of each parameter, repeated for each of its names.
+    fn params(&mut self, params: &'a [ParameterDecl<'a>]) -> Vec<Type<'a>> {
+        let mut types = vec![];
+        for param in params {
+            let typ = self.typ(&param.go_type);
+            let count = param.identifiers.as_ref().map_or(1, Vec::len).max(1);
+            types.extend(std::iter::repeat_n(typ, count));
+        }
+        types
+    }
+
+    fn interface(&mut self, elems: &'a [InterfaceElem<'a>]) -> Type<'a> {
+        let mut methods = vec![];
+        for elem in elems {
+            match elem {
+                InterfaceElem::Method { name, signature } => methods.push(Method {
+                    name,
+                    func: self.signature(signature, vec![]),
+                }),
+                // An embedded interface, unlike a union or an approximation of a constraint.
+                InterfaceElem::Type(typ::TypeElem(terms))
+                    if terms.len() == 1 && !terms[0].tilde =>
+                {
+                    let embedded = self.typ(&terms[0].go_type);
+                    if let Type::Interface(embedded) = self.underlying(&embedded) {
+                        methods.extend(embedded);
+                    }
+                }
+                InterfaceElem::Type(_) => {}
+            }
+        }
+        methods.sort_by(|a, b| a.name.cmp(b.name));
+        methods.dedup_by(|a, b| a.name == b.name);
+        Type::Interface(methods)
+    }
+
+    /// The type a defined type is defined as, with its type arguments in place of its type
+    /// parameters. The underlying type of a type parameter is not known, so it is invalid.
+    fn underlying(&mut self, typ: &Type<'a>) -> Type<'a> {
+        let named = match typ {
+            Type::Named(named) => named,
+            Type::TypeParam(_) => return Type::Invalid,
+            typ => return typ.clone(),
+        };
+        let spec = match named.spec {
+            Some(spec) => spec,
+            None => return predeclared(named.name),
+        };
+        let underlying = match self.underlying.get(&address(spec)) {
+            Some(underlying) => underlying.clone(),
+            None if !self.pending.insert(address(spec)) => return Type::Invalid,
+            None => {
+                let (TypeSpec::TypeDef { typ, .. } | TypeSpec::AliasDecl { typ, .. }) = spec;
+                let typ = self.typ(typ);
+                let underlying = self.underlying(&typ);
+                self.pending.remove(&address(spec));
+                self.underlying.insert(address(spec), underlying.clone());
+                underlying
+            }
+        };
+        let params = spec_type_params(spec);
+        substitute(&underlying, &params, &named.type_args)
+    }
+}
+
+/// The underlying type of the predeclared `error` or `comparable`.
+fn predeclared<'a>(name: &str) -> Type<'a> {
+    match name {
+        "error" => {
+            let error = Func {
+                type_params: vec![],
+                params: vec![],
+                results: vec![Type::Basic(GoType::String)],
+                variadic: false,
+            };
+            Type::Interface(vec![Method {
+                name: "Error",
+                func: error,
+            }])
+        }
+        _ => Type::Interface(vec![]),
+    }
+}
+
+/// The names of the type parameters of `spec`.
+fn spec_type_params<'a>(spec: &'a TypeSpec<'a>) -> Vec<&'a str> {
+    let (TypeSpec::TypeDef { type_params, .. } | TypeSpec::AliasDecl { type_params, .. }) = spec;
+    type_params
+        .iter()
+        .flat_map(|decl| decl.identifiers.iter().copied())
+        .collect()
+}
+
+/// `typ` with `args` in place of the type parameters `params`, told apart by address.
+fn substitute<'a>(typ: &Type<'a>, params: &[&'a str], args: &[Type<'a>]) -> Type<'a> {
+    if args.is_empty() {
+        return typ.clone();
+    }
+    let each = |types: &[Type<'a>]| types.iter().map(|t| substitute(t, params, args)).collect();
+    let boxed = |typ: &Type<'a>| Box::new(substitute(typ, params, args));
+    let func = |func: &Func<'a>| Func {
+        type_params: func.type_params.clone(),
+        params: each(&func.params),
+        results: each(&func.results),
+        variadic: func.variadic,
+    };
+    match typ {
+        Type::TypeParam(name) => params
+            .iter()
+            .position(|param| param.as_ptr() == name.as_ptr())
+            .and_then(|i| args.get(i))
+            .unwrap_or(typ)
+            .clone(),
+        Type::Named(named) => Type::Named(Named {
+            type_args: each(&named.type_args),
+            ..named.clone()
+        }),
+        Type::Pointer(base) => Type::Pointer(boxed(base)),
+        Type::Array { len, elem } => Type::Array {
+            len: *len,
+            elem: boxed(elem),
+        },
+        Type::Slice(elem) => Type::Slice(boxed(elem)),
+        Type::Map { key, value } => Type::Map {
+            key: boxed(key),
+            value: boxed(value),
+        },
+        Type::Chan { dir, elem } => Type::Chan {
+            dir: *dir,
+            elem: boxed(elem),
+        },
+        Type::Struct(fields) => Type::Struct(
+            fields
+                .iter()
+                .map(|field| Field {
+                    typ: substitute(&field.typ, params, args),
+                    ..field.clone()
+                })
+                .collect(),
+        ),
+        Type::Func(f) => Type::Func(func(f)),
+        Type::Interface(methods) => Type::Interface(
+            methods
+                .iter()
+                .map(|method| Method {
+                    name: method.name,
+                    func: func(&method.func),
+                })
+                .collect(),
+        ),
+        Type::Tuple(types) => Type::Tuple(each(types)),
+        Type::Basic(_) | Type::Untyped(_) | Type::Invalid => typ.clone(),
+    }
+}
+
+/// The name of the field of an embedded type: `T` for `T`, `*T`, `pkg.T` and `T[int]`.
+fn embedded_name<'a>(typ: &'a typ::GoType<'a>) -> &'a str {
+    match typ {
+        typ::GoType::TypeName(name) | typ::GoType::Instantiation { name, .. } => match name {
+            TypeName::Identifier(name) => name,
+            TypeName::QualifiedIdent(ident) => ident.identifier,
+        },
+        typ::GoType::TypeLit(TypeLit::PointerType(base)) => embedded_name(base),
+        typ::GoType::TypeLit(_) => "_",
+    }
+}
+
+const NUMERIC: &[Kind] = &[Kind::Int, Kind::Float, Kind::Complex];
+const ORDERED: &[Kind] = &[Kind::Int, Kind::Float, Kind::String];
+
+impl<'r, 'a> Checker<'r, 'a> {
+    /// The type of the variable, constant or function `object`.
+    fn object_type(&mut self, object: Object<'a>) -> Type<'a> {
+        // The variable of a type switch has a type in each clause.
+        let key = match object.decl {
+            Decl::TypeSwitch(_, clause) => address(clause),
+            _ => object.name.as_ptr() as usize,
+        };
+        if let Some(typ) = self.objects.get(&key) {
+            return typ.clone();
+        }
+        if !self.pending.insert(key) {
+            return Type::Invalid;
+        }
+        let typ = self.declared_type(object);
+        self.pending.remove(&key);
+        self.objects.insert(key, typ.clone());
+        typ
+    }
+
+    fn declared_type(&mut self, object: Object<'a>) -> Type<'a> {
+        let position = |names: &[&str]| {
+            names
+                .iter()
+                .position(|name| name.as_ptr() == object.name.as_ptr())
+        };
+        match object.decl {
+            Decl::Const(decl) => {
+                let constants = decl.constants();
+                let constant = constants
+                    .iter()
+                    .find(|constant| constant.name.as_ptr() == object.name.as_ptr());
+                match constant.map(|constant| (constant.typ, constant.value)) {
+                    Some((Some(typ), _)) => self.typ(typ),
+                    Some((None, Some(value))) => self.value(value),
+                    _ => Type::Invalid,
+                }
+            }
+            Decl::Var(spec) => match &spec.typ {
+                Some(typ) => self.typ(typ),
+                None => {
+                    let i = position(&spec.identifiers);
+                    self.initialized(&spec.values, spec.identifiers.len(), i)
+                }
+            },
+            Decl::ShortVarDecl(SimpleStmt::ShortVarDecl {
+                identifiers,
+                values,
+            }) => self.initialized(values, identifiers.len(), position(identifiers)),
+            Decl::Func(function) => Type::Func(self.function_type(function)),
+            Decl::Receiver(receiver) => {
+                let base = self.object(receiver.base_type);
+                let base = match base.map(|base| self.type_object(base)) {
+                    Some(Type::Named(named)) => Type::Named(Named {
+                        type_args: receiver
+                            .type_params
+                            .iter()
+                            .map(|param| Type::TypeParam(param))
+                            .collect(),
+                        ..named
+                    }),
+                    _ => Type::Invalid,
+                };
+                match receiver.pointer {
+                    true => Type::Pointer(Box::new(base)),
+                    false => base,
+                }
+            }
+            Decl::Param(param) => match self.typ(&param.go_type) {
+                typ if param.is_variadic => Type::Slice(Box::new(typ)),
+                typ => typ,
+            },
+            Decl::Range(stmt) => match &stmt.clause {
+                Some(ForClause::RangeClause {
+                    vars: Some(RangeVars::Declared(names)),
+                    expr,
+                }) => {
+                    let (key, value) = self.range(expr);
+                    match position(names) {
+                        Some(0) => key,
+                        Some(1) => value,
+                        _ => Type::Invalid,
+                    }
+                }
+                _ => Type::Invalid,
+            },
+            Decl::TypeSwitch(stmt, clause) => match clause.case.as_deref() {
+                Some([typ]) if !self.is_nil(typ) => self.typ(typ),
+                _ => self.primary_value(&stmt.expr),
+            },
+            _ => Type::Invalid,
+        }
+    }
+
+    /// The type of the `i`th of `count` variables initialized with `values`.
+    fn initialized(
+        &mut self,
+        values: &'a [Expression<'a>],
+        count: usize,
+        i: Option<usize>,
+    ) -> Type<'a> {
+        let types = self.values(values, count).unwrap_or_default();
+        match i.and_then(|i| types.get(i)).map(default) {
+            // Reported with the declaration.
+            Some(Type::Untyped(Untyped::Nil)) | None => Type::Invalid,
+            Some(typ) => typ,
+        }
+    }
+
+    /// The value of the constant expression `expr`, where `iota` is the index of its constant spec,
+    /// reporting the operations whose results do not fit their type. The other errors are left to
+    /// the checking of its types.
+    fn constant(&mut self, expr: &'a Expression<'a>, iota: Option<usize>) -> Option<Constant> {
+        let error = match constant::eval_with(expr, iota, &mut |name| self.constant_name(name)) {
+            Ok(value) => return Some(value),
+            Err(error) => error,
+        };
+        let kind = match error.kind {
+            constant::ErrorKind::DivisionByZero => ErrorKind::DivisionByZero,
+            constant::ErrorKind::Overflow => ErrorKind::Overflow,
+            constant::ErrorKind::Truncated => ErrorKind::Truncated,
+            _ => return None,
+        };
+        self.error(kind, error.at, error.message);
+        None
+    }
+
+    /// What `name` denotes in a constant expression.
+    fn constant_name(&mut self, name: &'a str) -> Option<Name> {
+        let object = self.object(name)?;
+        match object.decl {
+            Decl::Universe => constant::universe(name),
+            Decl::Const(decl) => {
+                let value = self.declared_constant(object.name, decl)?;
+                Some(Name::Constant(Box::new(value)))
+            }
+            Decl::Type(_) => {
+                let typ = self.type_object(object);
+                match self.underlying(&typ) {
+                    Type::Basic(basic) => Some(Name::Type(basic)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The value of the constant declared by `name` in `decl`, given its declared type.
+    fn declared_constant(&mut self, name: &'a str, decl: &'a ConstDecl<'a>) -> Option<Constant> {
+        let key = name.as_ptr() as usize;
+        if let Some(value) = self.constants.get(&key) {
+            return value.clone();
+        }
+        // Stops at a constant defined with itself.
+        self.constants.insert(key, None);
+        let spec = decl
+            .constants()
+            .into_iter()
+            .find(|constant| constant.name.as_ptr() == name.as_ptr())?;
+        let value = spec
+            .value
+            .and_then(|value| self.constant(value, Some(spec.iota)));
+        let value = match (value, spec.typ) {
+            (Some(value), Some(typ)) => {
+                let typ = self.typ(typ);
+                match self.underlying(&typ) {
+                    Type::Basic(basic) => value.convert(basic).ok(),
+                    _ => None,
+                }
+            }
+            (value, _) => value,
+        };
+        self.constants.insert(key, value.clone());
+        value
+    }
+
+    fn function_type(&mut self, function: &'a Function<'a>) -> Func<'a> {
+        let type_params = function
+            .type_params
+            .iter()
+            .flat_map(|decl| decl.identifiers.iter().copied())
+            .collect();
+        self.signature(&function.signature, type_params)
+    }
+
+    fn is_nil(&self, typ: &typ::GoType<'_>) -> bool {
+        match typ {
+            typ::GoType::TypeName(TypeName::Identifier(name)) => {
+                self.object(name).map(|object| object.kind) == Some(ObjKind::Nil)
+            }
+            _ => false,
+        }
+    }
+
+    /// The types of `exprs` assigned to `count` variables: the results of a call returning
+    /// `count` values, a value and whether it is valid for a comma-ok expression when `count` is
+    /// 2, or the value of each expression. The types are the error when there are not `count`.
+    fn values(
+        &mut self,
+        exprs: &'a [Expression<'a>],
+        count: usize,
+    ) -> Result<Vec<Type<'a>>, Vec<Type<'a>>> {
+        let types = match exprs {
+            [expr] => match self.expr(expr) {
+                Mode::Value(Type::Tuple(types)) => types,
+                Mode::NoValue => vec![],
+                mode => {
+                    let typ = self.value_of(mode, first_token(expr), || print(expr));
+                    if count == 2 && self.comma_ok(expr) {
+                        return Ok(vec![typ, Type::Untyped(Untyped::Bool)]);
+                    }
+                    vec![typ]
+                }
+            },
+            exprs => exprs.iter().map(|expr| self.value(expr)).collect(),
+        };
+        match types.len() == count {
+            true => Ok(types),
+            false => Err(types),
+        }
+    }
+
+    /// Whether `expr` is a map index, a type assertion or a receive, which can be assigned to a
+    /// second variable telling whether it succeeded.
+    fn comma_ok(&mut self, expr: &'a Expression<'a>) -> bool {
+        match expr {
+            Expression::UnaryExpr(UnaryExpr::Unary(unary)) => unary.op == "<-",
+            Expression::UnaryExpr(UnaryExpr::PrimaryExpr(primary)) => match primary {
+                PrimaryExpr::TypeAssertion { .. } => true,
+                PrimaryExpr::Index { expr, .. } => {
+                    let typ = self.primary_value(expr);
+                    matches!(self.underlying(&typ), Type::Map { .. })
+                }
+                PrimaryExpr::Operand(Operand::Parenthesized(expr)) => self.comma_ok(expr),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn value(&mut self, expr: &'a Expression<'a>) -> Type<'a> {
+        let mode = self.expr(expr);
+        self.value_of(mode, first_token(expr), || print(expr))
+    }
+
+    fn primary_value(&mut self, expr: &'a PrimaryExpr<'a>) -> Type<'a> {
+        let mode = self.primary(expr);
+        self.value_of(mode, primary_token(expr), || print(expr))
+    }
+
+    /// The type of a single value denoted by `mode`, reporting anything else.
+    fn value_of(
+        &mut self,
+        mode: Mode<'a>,
+        at: Option<&'a str>,
+        text: impl FnOnce() -> String,
+    ) -> Type<'a> {
+        let (kind, message) = match mode {
+            Mode::Value(Type::Tuple(types)) => (
+                ErrorKind::MultipleValues,
+                format!(
+                    "multiple-value {} (value of type {}) in single-value context",
+                    text(),
+                    Type::Tuple(types)
+                ),
+            ),
+            Mode::Value(typ) => return typ,
+            Mode::Type(_) => (
+                ErrorKind::NotAnExpression,
+                format!("{} (type) is not an expression", text()),
+            ),
+            Mode::Builtin(_) => (
+                ErrorKind::NotAnExpression,
+                format!("{} (built-in function) must be called", text()),
+            ),
+            Mode::Package => (
+                ErrorKind::NotAnExpression,
+                format!("use of package {} without selector", text()),
+            ),
+            Mode::NoValue => (
+                ErrorKind::NoValue,
+                format!("{} (no value) used as value", text()),
+            ),
+        };
+        self.error(kind, at, message);
+        Type::Invalid
+    }
+
+    fn expr(&mut self, expr: &'a Expression<'a>) -> Mode<'a> {
+        if let Some(mode) = self.modes.get(&address(expr)) {
+            return mode.clone();
+        }
+        let mode = match expr {
+            Expression::UnaryExpr(unary) => self.unary_expr(unary),
+            Expression::BinExpr { left, op, right } => {
+                let (x, y) = (self.value(left), self.value(right));
+                if *op == "/" || *op == "%" {
+                    self.division(left, &x, op, right);
+                }
+                self.operands(left, &x, op, right, &y);
+                Mode::Value(match *op {
+                    "<<" | ">>" => self.shift(x, y, Some(op), || print(&**left)),
+                    _ => self.binary(x, op, y, Some(op), || print(expr)),
+                })
+            }
+            Expression::BadExpr(_) => Mode::Value(Type::Invalid),
+        };
+        self.modes.insert(address(expr), mode.clone());
+        mode
+    }
+
+    fn unary_expr(&mut self, expr: &'a UnaryExpr<'a>) -> Mode<'a> {
+        let (op, operand) = match expr {
+            UnaryExpr::PrimaryExpr(primary) => return self.primary(primary),
+            UnaryExpr::Unary(unary) => (unary.op, &*unary.expr),
+        };
+        let mode = self.unary_expr(operand);
+        if let (Mode::Type(base), "*") = (&mode, op) {
+            return Mode::Type(Type::Pointer(Box::new(base.clone())));
+        }
+        let typ = self.value_of(mode, unary_token(operand), || print_unary(operand));
+        let invalid = |message: String| (Type::Invalid, Some(message));
+        let (typ, message) = match (op, self.underlying(&typ)) {
+            ("&", _) => (Type::Pointer(Box::new(typ)), None),
+            (_, Type::Invalid) => (Type::Invalid, None),
+            ("*", Type::Pointer(base)) => (*base, None),
+            ("*", _) => invalid(format!(
+                "invalid operation: cannot indirect {} (value of type {})",
+                print_unary(operand),
+                typ
+            )),
+            ("<-", Type::Chan { dir, elem }) if dir != ChanDir::Send => (*elem, None),
+            ("<-", Type::Chan { .. }) => invalid(format!(
+                "invalid operation: cannot receive from send-only channel {} (value of type {})",
+                print_unary(operand),
+                typ
+            )),
+            ("<-", _) => invalid(format!(
+                "invalid operation: cannot receive from {} (value of type {})",
+                print_unary(operand),
+                typ
+            )),
+            (op, _) => {
+                let kinds = match op {
+                    "!" => &[Kind::Bool][..],
+                    "^" => &[Kind::Int][..],
+                    _ => NUMERIC,
+                };
+                match self.allows(&typ, kinds) {
+                    true => (typ, None),
+                    false => invalid(format!(
+                        "invalid operation: operator {} not defined on {} (value of type {})",
+                        op,
+                        print_unary(operand),
+                        typ
+                    )),
+                }
+            }
+        };
+        if let Some(message) = message {
+            self.error(ErrorKind::InvalidOperation, Some(op), message);
+        }
+        Mode::Value(typ)
+    }
+
+    /// Reports a division of `left`, of type `x`, by a constant zero, unless it is of a
+    /// floating-point variable.
+    fn division(
+        &mut self,
+        left: &'a Expression<'a>,
+        x: &Type<'a>,
+        op: &'a str,
+        right: &'a Expression<'a>,
+    ) {
+        let zero = self.constant(right, None).and_then(|y| y.value.to_int());
+        if zero.is_some_and(|y| y == 0.into())
+            && (self.kind(x) == Some(Kind::Int) || self.constant(left, None).is_some())
+        {
+            let message = "invalid operation: division by zero".to_string();
+            self.error(ErrorKind::DivisionByZero, Some(op), message);
+        }
+    }
+
+    /// Reports the untyped constant operands of `left op right`, of types `x` and `y`, that are
+    /// not integers when shifted, or that the type of the other operand cannot represent.
+    fn operands(
+        &mut self,
+        left: &'a Expression<'a>,
+        x: &Type<'a>,
+        op: &'a str,
+        right: &'a Expression<'a>,
+        y: &Type<'a>,
+    ) {
+        if op == "<<" || op == ">>" {
+            let value = match x {
+                Type::Untyped(_) => self.constant(left, None),
+                _ => None,
+            };
+            if let Some(value) = value.filter(|value| value.value.to_int().is_none()) {
+                let message = format!(
+                    "invalid operation: shifted operand {} must be integer",
+                    value.describe(&print(left))
+                );
+                self.error(ErrorKind::InvalidOperation, Some(op), message);
+            }
+            return;
+        }
+        for (expr, from, to) in [(left, x, y), (right, y, x)] {
+            if matches!(from, Type::Untyped(_))
+                && !matches!(to, Type::Untyped(_))
+                && self.assignable(from, to)
+            {
+                self.fits(expr, None, to, None);
+            }
+        }
+    }
+
+    /// The type of `x op y`, where `text` is the printed operation and `op` is not a shift.
+    fn binary(
+        &mut self,
+        x: Type<'a>,
+        op: &str,
+        y: Type<'a>,
+        at: Option<&'a str>,
+        text: impl Fn() -> String,
+    ) -> Type<'a> {
+        let comparison = matches!(op, "==" | "!=" | "<" | "<=" | ">" | ">=");
+        let nil = [&x, &y].contains(&&Type::Untyped(Untyped::Nil));
+        let typ = match self.unify(x, y, comparison, at, &text) {
+            Some(typ) => typ,
+            None if comparison => return Type::Untyped(Untyped::Bool),
+            None => return Type::Invalid,
+        };
+        let kinds = match op {
+            "&&" | "||" => &[Kind::Bool][..],
+            "+" => &[Kind::Int, Kind::Float, Kind::Complex, Kind::String][..],
+            "-" | "*" | "/" => NUMERIC,
+            "<" | "<=" | ">" | ">=" => ORDERED,
+            "==" | "!=" => return self.equality(typ, nil, at, text),
+            _ => &[Kind::Int][..],
+        };
+        if !self.allows(&typ, kinds) {
+            let message = format!(
+                "invalid operation: operator {} not defined on {} (value of type {})",
+                op,
+                text(),
+                typ
+            );
+            self.error(ErrorKind::InvalidOperation, at, message);
+            return Type::Invalid;
+        }
+        match comparison {
+            true => Type::Untyped(Untyped::Bool),
+            false => typ,
+        }
+    }
+
+    /// The type both operands of a binary operator are converted to, which for a comparison may
+    /// be the type of either one as long as the other is assignable to it.
+    fn unify(
+        &mut self,
+        x: Type<'a>,
+        y: Type<'a>,
+        comparison: bool,
+        at: Option<&'a str>,
+        text: impl Fn() -> String,
+    ) -> Option<Type<'a>> {
+        match (&x, &y) {
+            (Type::Untyped(a), Type::Untyped(b)) if a.is_numeric() && b.is_numeric() => {
+                return Some(Type::Untyped(*a.max(b)))
+            }
+            (Type::Untyped(a), Type::Untyped(b)) if a == b => return Some(x),
+            (Type::Untyped(_), Type::Untyped(_)) => {}
+            (Type::Untyped(_), _) if self.assignable(&x, &y) => return Some(y),
+            (_, Type::Untyped(_)) if self.assignable(&y, &x) => return Some(x),
+            _ if identical(&x, &y) => return Some(x),
+            (Type::Untyped(_), _) | (_, Type::Untyped(_)) => {}
+            _ if comparison && self.assignable(&x, &y) => return Some(y),
+            _ if comparison && self.assignable(&y, &x) => return Some(x),
+            _ => {}
+        }
+        let message = format!(
+            "invalid operation: {} (mismatched types {} and {})",
+            text(),
+            x,
+            y
+        );
+        self.error(ErrorKind::MismatchedTypes, at, message);
+        None
+    }
+
+    /// The type of a comparison for equality of values of `typ`, one of them being `nil` if `nil`.
+    fn equality(
+        &mut self,
+        typ: Type<'a>,
+        nil: bool,
+        at: Option<&'a str>,
+        text: impl Fn() -> String,
+    ) -> Type<'a> {
+        // Values of these types can only be compared to `nil`, which gets their type.
+        let what = match self.underlying(&typ) {
+            Type::Untyped(Untyped::Nil) => Some("nil"),
+            Type::Slice(_) => Some("slice"),
+            Type::Map { .. } => Some("map"),
+            Type::Func(_) => Some("func"),
+            _ => None,
+        };
+        let message = match what {
+            Some("nil") => format!(
+                "invalid operation: {} (operator == not defined on nil)",
+                text()
+            ),
+            Some(what) if !nil => format!(
+                "invalid operation: {} ({} can only be compared to nil)",
+                text(),
+                what
+            ),
+            _ => return Type::Untyped(Untyped::Bool),
+        };
+        self.error(ErrorKind::InvalidOperation, at, message);
+        Type::Untyped(Untyped::Bool)
+    }
+
+    /// The type of a shift of `x` by `y`, where `text` is the printed shifted operand.
+    fn shift(
+        &mut self,
+        x: Type<'a>,
+        y: Type<'a>,
+        at: Option<&'a str>,
+        text: impl Fn() -> String,
+    ) -> Type<'a> {
+        let count = match &y {
+            Type::Untyped(untyped) => untyped.is_numeric(),
+            y => self.allows(y, &[Kind::Int]),
+        };
+        if !count {
+            let message = format!("invalid operation: shift count type {}, must be integer", y);
+            self.error(ErrorKind::InvalidOperation, at, message);
+        }
+        match x {
+            Type::Untyped(Untyped::Rune) => x,
+            Type::Untyped(untyped) if untyped.is_numeric() => Type::Untyped(Untyped::Int),
+            x if self.allows(&x, &[Kind::Int]) => x,
+            x => {
+                let message = format!(
+                    "invalid operation: shifted operand {} (value of type {}) must be integer",
+                    text(),
+                    x
+                );
+                self.error(ErrorKind::InvalidOperation, at, message);
+                Type::Invalid
+            }
+        }
+    }
+
+    /// The kind of the values of `typ`, `None` if it is not known.
+    fn kind(&mut self, typ: &Type<'a>) -> Option<Kind> {
+        let kind = match self.underlying(typ) {
+            Type::Untyped(Untyped::Bool) | Type::Basic(GoType::Bool) => Kind::Bool,
+            Type::Untyped(Untyped::Int | Untyped::Rune) => Kind::Int,
+            Type::Untyped(Untyped::Float) => Kind::Float,
+            Type::Untyped(Untyped::Complex) => Kind::Complex,
+            Type::Untyped(Untyped::String) | Type::Basic(GoType::String) => Kind::String,
+            Type::Basic(typ) if typ.is_integer() => Kind::Int,
+            Type::Basic(typ) if typ.is_float() => Kind::Float,
+            Type::Basic(typ) if typ.is_complex() => Kind::Complex,
+            Type::Invalid => return None,
+            _ => Kind::Other,
+        };
+        Some(kind)
+    }
+
+    /// Whether the values of `typ` are of one of `kinds`, which they may be if it is not known.
+    fn allows(&mut self, typ: &Type<'a>, kinds: &[Kind]) -> bool {
+        self.kind(typ).is_none_or(|kind| kinds.contains(&kind))
+    }
+
+    /// Reports `typ` unless its values are integers, for the `what` of an expression.
+    fn integer(&mut self, typ: &Type<'a>, at: Option<&'a str>, what: &str) {
+        if !self.allows(typ, &[Kind::Int]) {
+            let message = format!("invalid argument: {} of type {} must be integer", what, typ);
+            self.error(ErrorKind::InvalidOperation, at, message);
+        }
+    }
+
+    /// Reports `nil` where it is not given a type, and the untyped constant `expr` of type `typ`
+    /// when it does not fit its default type.
+    fn typed(
+        &mut self,
+        expr: Option<&'a Expression<'a>>,
+        typ: &Type<'a>,
+        at: Option<&'a str>,
+        context: &str,
+    ) {
+        if *typ == Type::Untyped(Untyped::Nil) {
+            let message = format!("use of untyped nil in {}", context);
+            self.error(ErrorKind::UntypedNil, at, message);
+        } else if let Some(expr) = expr {
+            self.fits(expr, None, &default(typ), Some(context));
+        }
+    }
+}
+
+/// `typ`, or the default type of an untyped constant.
+fn default<'a>(typ: &Type<'a>) -> Type<'a> {
+    match typ {
+        Type::Untyped(untyped) => untyped
+            .default_type()
+            .map_or_else(|| typ.clone(), Type::Basic),
+        typ => typ.clone(),
+    }
+}
+
+impl<'r, 'a> Checker<'r, 'a> {
+    fn primary(&mut self, expr: &'a PrimaryExpr<'a>) -> Mode<'a> {
+        if let Some(mode) = self.modes.get(&address(expr)) {
+            return mode.clone();
+        }
+        let mode = match expr {
+            PrimaryExpr::Operand(operand) => self.operand(operand),
+            PrimaryExpr::Selector {
+                expr: operand,
+                selector,
+            } => {
+                let mode = self.primary(operand);
+                self.selector(mode, selector, || print(&**operand))
+            }
+            PrimaryExpr::Index {
+                expr: operand,
+                index,
+            } => self.index(operand, index),
+            PrimaryExpr::Slice {
+                expr: operand,
+                low,
+                high,
+                max,
+            } => {
+                for index in [low, high, max].iter().copied().flatten() {
+                    let typ = self.value(index);
+                    self.integer(&typ, first_token(index), "slice index");
+                }
+                Mode::Value(self.slice(operand))
+            }
+            PrimaryExpr::TypeAssertion { expr: operand, typ } => {
+                let interface = self.primary_value(operand);
+                if !matches!(
+                    self.underlying(&interface),
+                    Type::Interface(_) | Type::Invalid
+                ) {
+                    let message = format!(
+                        "invalid operation: {} (value of type {}) is not an interface",
+                        print(&**operand),
+                        interface
+                    );
+                    self.error(ErrorKind::InvalidOperation, primary_token(operand), message);
+                }
+                Mode::Value(self.typ(typ))
+            }
+            PrimaryExpr::Instantiation {
+                expr: operand,
+                type_args,
+            } => {
+                let mode = self.primary(operand);
+                let args = type_args.iter().map(|arg| self.typ(arg)).collect();
+                self.instantiate(mode, args, primary_token(operand), || print(&**operand))
+            }
+            PrimaryExpr::Call {
+                func,
+                args,
+                ellipsis,
+            } => self.call(expr, func, args, *ellipsis),
+        };
+        self.modes.insert(address(expr), mode.clone());
+        mode
+    }
+
+    fn operand(&mut self, operand: &'a Operand<'a>) -> Mode<'a> {
+        match operand {
+            Operand::Literal(literal) => Mode::Value(Type::Untyped(match literal {
+                Literal::IntLit(_) => Untyped::Int,
+                Literal::FloatLit(_) => Untyped::Float,
+                Literal::ImaginaryLit(_) => Untyped::Complex,
+                Literal::RuneLit(_) => Untyped::Rune,
+                Literal::StringLit(_) => Untyped::String,
+            })),
+            Operand::OperandName(OperandName::Identifier(name)) => self.name(name),
+            Operand::OperandName(OperandName::QualifiedIdent(ident)) => {
+                match self.name(ident.package_name) {
+                    Mode::Package => Mode::Value(Type::Invalid),
+                    mode => {
+                        self.selector(mode, ident.identifier, || ident.package_name.to_string())
+                    }
+                }
+            }
+            Operand::Parenthesized(expr) => self.expr(expr),
+            Operand::CompositeLit { typ, value } => {
+                let literal = self.typ(typ);
+                Mode::Value(self.composite(literal, value, type_token(typ)))
+            }
+            Operand::TypeLit(lit) => Mode::Type(self.type_lit(lit)),
+            Operand::FunctionLit(lit) => {
+                let func = self.signature(&lit.signature, vec![]);
+                self.body(&lit.signature, func.results.clone(), &lit.body);
+                Mode::Value(Type::Func(func))
+            }
+        }
+    }
+
+    fn name(&mut self, name: &'a str) -> Mode<'a> {
+        let object = match self.object(name) {
+            Some(object) => object,
+            None => {
+                self.undefined(name);
+                return Mode::Value(Type::Invalid);
+            }
+        };
+        match (object.kind, object.decl) {
+            (ObjKind::Type, _) => Mode::Type(self.type_object(object)),
+            (ObjKind::Package, _) => Mode::Package,
+            (ObjKind::Nil, _) => Mode::Value(Type::Untyped(Untyped::Nil)),
+            (ObjKind::Func, Decl::Universe) => Mode::Builtin(object.name),
+            (ObjKind::Const, Decl::Universe) if object.name == "iota" => {
+                Mode::Value(Type::Untyped(Untyped::Int))
+            }
+            (ObjKind::Const, Decl::Universe) => Mode::Value(Type::Untyped(Untyped::Bool)),
+            (ObjKind::Label, _) => Mode::Value(Type::Invalid),
+            _ => Mode::Value(self.object_type(object)),
+        }
+    }
+
+    /// What `x.selector` denotes, `x` being `mode`: a field or a method of a value, or a
+    /// method expression of a type.
+    fn selector(
+        &mut self,
+        mode: Mode<'a>,
+        selector: &'a str,
+        text: impl Fn() -> String,
+    ) -> Mode<'a> {
+        let (typ, what) = match mode {
+            Mode::Type(typ) => (typ, "method"),
+            mode => (self.value_of(mode, None, &text), "field or method"),
+        };
+        let found = self.lookup(&typ, selector);
+        match (found, what) {
+            (Some(Type::Func(func)), "method") => {
+                let params = std::iter::once(typ).chain(func.params).collect();
+                Mode::Value(Type::Func(Func { params, ..func }))
+            }
+            (Some(Type::Invalid), _) => Mode::Value(Type::Invalid),
+            (Some(found), "field or method") => Mode::Value(found),
+            _ => {
+                let message = format!(
+                    "{}.{} undefined (type {} has no {} {})",
+                    text(),
+                    selector,
+                    typ,
+                    what,
+                    selector
+                );
+                self.error(ErrorKind::MissingFieldOrMethod, Some(selector), message);
+                Mode::Value(Type::Invalid)
+            }
+        }
+    }
+
+    /// The type of the field or method `name` of `typ`, looked up in `typ` or the type it points
+    /// to, then in its embedded fields, breadth first. The invalid type when it is not known.
+    fn lookup(&mut self, typ: &Type<'a>, name: &str) -> Option<Type<'a>> {
+        let mut types = vec![typ.clone()];
+        let mut seen = HashSet::new();
+        while !types.is_empty() {
+            let mut embedded = vec![];
+            for typ in types {
+                let typ = match typ {
+                    Type::Pointer(base) => *base,
+                    typ => typ,
+                };
+                if let Type::Named(Named {
+                    spec: Some(spec),
+                    type_args,
+                    ..
+                }) = &typ
+                {
+                    if !seen.insert(address(*spec)) {
+                        continue;
+                    }
+                    let methods = self.methods.get(&address(*spec)).cloned();
+                    let method = methods
+                        .unwrap_or_default()
+                        .into_iter()
+                        .find(|method| method.name == name);
+                    if let Some(method) = method {
+                        return Some(self.method_type(method, type_args));
+                    }
+                }
+                match self.underlying(&typ) {
+                    Type::Struct(fields) => {
+                        for field in fields {
+                            if field.name == name {
+                                return Some(field.typ);
+                            }
+                            if field.embedded {
+                                embedded.push(field.typ);
+                            }
+                        }
+                    }
+                    Type::Interface(methods) => {
+                        let method = methods.into_iter().find(|method| method.name == name);
+                        if let Some(method) = method {
+                            return Some(Type::Func(method.func));
+                        }
+                    }
+                    Type::Invalid => return Some(Type::Invalid),
+                    _ => {}
+                }
+            }
+            types = embedded;
+        }
+        None
+    }
+
+    /// The type of `method` of the type instantiated with `type_args`.
+    fn method_type(&mut self, method: &'a Function<'a>, type_args: &[Type<'a>]) -> Type<'a> {
+        let func = Type::Func(self.function_type(method));
+        match &method.receiver {
+            Some(receiver) => substitute(&func, &receiver.type_params, type_args),
+            None => func,
+        }
+    }
+
+    fn index(&mut self, operand: &'a PrimaryExpr<'a>, index: &'a Expression<'a>) -> Mode<'a> {
+        let mode = self.primary(operand);
+        let generic = match &mode {
+            Mode::Type(_) => true,
+            Mode::Value(Type::Func(func)) => !func.type_params.is_empty(),
+            _ => false,
+        };
+        if generic {
+            // A single type argument that could be an expression.
+            let arg = match self.expr(index) {
+                Mode::Type(arg) => arg,
+                _ => {
+                    let message = format!("{} is not a type", print(index));
+                    self.error(ErrorKind::NotAType, first_token(index), message);
+                    Type::Invalid
+                }
+            };
+            return self.instantiate(mode, vec![arg], primary_token(operand), || print(operand));
+        }
+        let typ = self.value_of(mode, primary_token(operand), || print(operand));
+        let key = self.value(index);
+        let at = first_token(index);
+        let len = match self.underlying(&typ) {
+            Type::Array { len, .. } => len,
+            Type::Pointer(base) => match *base {
+                Type::Array { len, .. } => len,
+                _ => None,
+            },
+            _ => None,
+        };
+        let elem = match self.underlying(&typ) {
+            Type::Basic(GoType::String) | Type::Untyped(Untyped::String) => {
+                Type::Basic(GoType::Uint8)
+            }
+            Type::Map {
+                key: expected,
+                value,
+            } => {
+                self.assign(&key, &expected, at, "map index");
+                return Mode::Value(*value);
+            }
+            Type::Invalid => Type::Invalid,
+            underlying => match array_elem(underlying) {
+                Some(elem) => elem,
+                None => {
+                    let message = format!(
+                        "invalid operation: cannot index {} (value of type {})",
+                        print(operand),
+                        typ
+                    );
+                    self.error(ErrorKind::InvalidOperation, at, message);
+                    return Mode::Value(Type::Invalid);
+                }
+            },
+        };
+        self.integer(&key, at, "index");
+        self.index_bounds(index, len, at);
+        Mode::Value(elem)
+    }
+
+    /// Reports a constant `index` that is negative, or not less than `len`.
+    fn index_bounds(&mut self, index: &'a Expression<'a>, len: Option<u64>, at: Option<&'a str>) {
+        let value = match self.constant(index, None).and_then(|c| c.value.to_int()) {
+            Some(value) => value,
+            None => return,
+        };
+        let message = if value.is_negative() {
+            format!("invalid argument: index {} must not be negative", value)
+        } else if len.is_some_and(|len| value >= len.into()) {
+            format!(
+                "invalid argument: index {} out of bounds [0:{}]",
+                value,
+                len.unwrap()
+            )
+        } else {
+            return;
+        };
+        self.error(ErrorKind::IndexOutOfRange, at, message);
+    }
+
+    fn slice(&mut self, operand: &'a PrimaryExpr<'a>) -> Type<'a> {
+        let typ = self.primary_value(operand);
+        match self.underlying(&typ) {
+            Type::Untyped(Untyped::String) => Type::Basic(GoType::String),
+            Type::Basic(GoType::String) | Type::Slice(_) | Type::Invalid => typ,
+            underlying => match array_elem(underlying) {
+                Some(elem) => Type::Slice(Box::new(elem)),
+                None => {
+                    let message =
+                        format!("cannot slice {} (value of type {})", print(operand), typ);
+                    self.error(ErrorKind::InvalidOperation, primary_token(operand), message);
+                    Type::Invalid
+                }
+            },
+        }
+    }
+
+    /// A generic type or function, `mode`, instantiated with `args`.
+    fn instantiate(
+        &mut self,
+        mode: Mode<'a>,
+        args: Vec<Type<'a>>,
+        at: Option<&'a str>,
+        text: impl Fn() -> String,
+    ) -> Mode<'a> {
+        match mode {
+            Mode::Type(Type::Named(named)) => Mode::Type(Type::Named(Named {
+                type_args: args,
+                ..named
+            })),
+            Mode::Type(Type::Invalid) => Mode::Type(Type::Invalid),
+            Mode::Value(Type::Func(func)) if !func.type_params.is_empty() => {
+                let rest = func.type_params[args.len().min(func.type_params.len())..].to_vec();
+                let params = func.type_params.clone();
+                match substitute(&Type::Func(func), &params, &args) {
+                    Type::Func(func) => Mode::Value(Type::Func(Func {
+                        type_params: rest,
+                        ..func
+                    })),
+                    _ => unreachable!(),
+                }
+            }
+            Mode::Value(Type::Invalid) => Mode::Value(Type::Invalid),
+            _ => {
+                let message = format!("{} is not a generic type or function", text());
+                self.error(ErrorKind::InvalidOperation, at, message);
+                Mode::Value(Type::Invalid)
+            }
+        }
+    }
+}
+
+/// The element type of an array or of a pointer to an array, or of a slice.
+fn array_elem<'a>(typ: Type<'a>) -> Option<Type<'a>> {
+    match typ {
+        Type::Array { elem, .. } | Type::Slice(elem) => Some(*elem),
+        Type::Pointer(base) => match *base {
+            Type::Array { elem, .. } => Some(*elem),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl<'r, 'a> Checker<'r, 'a> {
+    fn call(
+        &mut self,
+        call: &'a PrimaryExpr<'a>,
+        func: &'a PrimaryExpr<'a>,
+        args: &'a [Expression<'a>],
+        ellipsis: bool,
+    ) -> Mode<'a> {
+        let (callee, at) = (func, primary_token(func));
+        let typ = match self.primary(func) {
+            Mode::Type(typ) => return Mode::Value(self.conversion(call, typ, args, ellipsis)),
+            Mode::Builtin(name) => return self.builtin(call, name, args, ellipsis, at),
+            mode => self.value_of(mode, at, || print(func)),
+        };
+        let func = match self.underlying(&typ) {
+            Type::Func(func) => func,
+            underlying => {
+                if underlying != Type::Invalid {
+                    let message = format!(
+                        "invalid operation: cannot call non-function {} (value of type {})",
+                        print(func),
+                        typ
+                    );
+                    self.error(ErrorKind::NotCallable, at, message);
+                }
+                for arg in args {
+                    self.expr(arg);
+                }
+                return Mode::Value(Type::Invalid);
+            }
+        };
+        let mut results = self.arguments(callee, func, args, ellipsis, at);
+        match results.len() {
+            0 => Mode::NoValue,
+            1 => Mode::Value(results.remove(0)),
+            _ => Mode::Value(Type::Tuple(results)),
+        }
+    }
+
+    fn conversion(
+        &mut self,
+        call: &'a PrimaryExpr<'a>,
+        typ: Type<'a>,
+        args: &'a [Expression<'a>],
+        ellipsis: bool,
+    ) -> Type<'a> {
+        let arg = match args {
+            [arg] if !ellipsis => arg,
+            _ => {
+                let message = format!("{} converts to {} a single value", print(call), typ);
+                self.error(ErrorKind::WrongArgumentCount, primary_token(call), message);
+                for arg in args {
+                    self.expr(arg);
+                }
+                return typ;
+            }
+        };
+        let from = self.value(arg);
+        if !self.convertible(&from, &typ) {
+            let operand = match self.constant(arg, None) {
+                Some(value) => value.describe(&print(arg)),
+                None => format!("{} (value of type {})", print(arg), from),
+            };
+            let message = format!("cannot convert {} to type {}", operand, typ);
+            self.error(ErrorKind::InvalidConversion, first_token(arg), message);
+        } else if let (Type::Basic(basic), Some(value)) =
+            (self.underlying(&typ), self.constant(arg, None))
+        {
+            let at = primary_token(call);
+            if let Err(error) = constant::conversion(value, basic, || print(arg), at) {
+                let kind = match error.kind {
+                    constant::ErrorKind::Overflow => ErrorKind::Overflow,
+                    constant::ErrorKind::Truncated => ErrorKind::Truncated,
+                    _ => ErrorKind::InvalidConversion,
+                };
+                self.error(kind, error.at, error.message);
+            }
+        }
+        typ
+    }
+
+    /// Checks the arguments of a call of `func`, returning the types of its results.
+    fn arguments(
+        &mut self,
+        callee: &'a PrimaryExpr<'a>,
+        func: Func<'a>,
+        args: &'a [Expression<'a>],
+        ellipsis: bool,
+        at: Option<&'a str>,
+    ) -> Vec<Type<'a>> {
+        // The results of a call can be the arguments of another one.
+        let have = match args {
+            [arg] => match self.expr(arg) {
+                Mode::Value(Type::Tuple(types)) => types,
+                mode => vec![self.value_of(mode, first_token(arg), || print(arg))],
+            },
+            args => args.iter().map(|arg| self.value(arg)).collect(),
+        };
+        let func = match func.type_params.is_empty() {
+            true => func,
+            false => self.infer(func, &have),
+        };
+        let (params, results) = (func.params.len(), func.results.clone());
+        let spread = func.variadic && !ellipsis;
+        let count = match spread {
+            true => have.len() + 1 >= params,
+            false => have.len() == params,
+        };
+        if ellipsis && !func.variadic {
+            let message = format!("cannot use ... in call to non-variadic {}", print(callee));
+            self.error(ErrorKind::WrongArgumentCount, at, message);
+            return results;
+        }
+        if !count {
+            let message = format!(
+                "{} arguments in call to {}\n\thave ({})\n\twant {}",
+                if have.len() < params {
+                    "not enough"
+                } else {
+                    "too many"
+                },
+                print(callee),
+                list(&have),
+                Func {
+                    results: vec![],
+                    ..func
+                }
+            );
+            self.error(ErrorKind::WrongArgumentCount, at, message);
+            return results;
+        }
+        for (i, typ) in have.iter().enumerate() {
+            let param = match (&func.params[i.min(params - 1)], spread && i + 1 >= params) {
+                (Type::Slice(elem), true) => &**elem,
+                (param, _) => param,
+            };
+            let at = args.get(i).or_else(|| args.first()).and_then(first_token);
+            self.assign_value(args.get(i), typ, param, at, "argument");
+        }
+        results
+    }
+
+    /// `func` with the types of the arguments `have` in place of its type parameters, the typed
+    /// ones first. The type parameters that are not inferred become invalid.
+    fn infer(&mut self, func: Func<'a>, have: &[Type<'a>]) -> Func<'a> {
+        let mut inferred = vec![None; func.type_params.len()];
+        for untyped in [false, true].iter().copied() {
+            for (i, arg) in have.iter().enumerate() {
+                let param = match (func.variadic, func.params.get(i), func.params.last()) {
+                    (_, Some(param), _) if !func.variadic || i + 1 < func.params.len() => param,
+                    (true, _, Some(Type::Slice(elem))) => elem,
+                    _ => continue,
+                };
+                if untyped == matches!(arg, Type::Untyped(_)) {
+                    infer(&func.type_params, &mut inferred, param, &default(arg));
+                }
+            }
+        }
+        let args: Vec<_> = inferred
+            .into_iter()
+            .map(|typ| typ.unwrap_or(Type::Invalid))
+            .collect();
+        match substitute(&Type::Func(func.clone()), &func.type_params, &args) {
+            Type::Func(func) => Func {
+                type_params: vec![],
+                ..func
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn builtin(
+        &mut self,
+        call: &'a PrimaryExpr<'a>,
+        name: &'a str,
+        args: &'a [Expression<'a>],
+        ellipsis: bool,
+        at: Option<&'a str>,
+    ) -> Mode<'a> {
+        let (min, max) = match name {
+            "recover" => (0, 0),
+            "print" | "println" => (0, usize::MAX),
+            "complex" | "copy" | "delete" => (2, 2),
+            "make" => (1, 3),
+            "append" | "max" | "min" => (1, usize::MAX),
+            _ => (1, 1),
+        };
+        if args.len() < min || args.len() > max || (ellipsis && name != "append") {
+            let message = format!(
+                "{} arguments for {} (expected {}, found {})",
+                if args.len() < min {
+                    "not enough"
+                } else {
+                    "too many"
+                },
+                print(call),
+                min,
+                args.len()
+            );
+            self.error(ErrorKind::WrongArgumentCount, at, message);
+            for arg in args {
+                self.expr(arg);
+            }
+            return Mode::Value(Type::Invalid);
+        }
+        if name == "make" || name == "new" {
+            return Mode::Value(self.allocation(call, name, args, at));
+        }
+        let types: Vec<_> = args.iter().map(|arg| self.value(arg)).collect();
+        let invalid = |message: String| (Type::Invalid, Some(message));
+        let (typ, message) = match name {
+            "recover" => (Type::Interface(vec![]), None),
+            "print" | "println" | "panic" => {
+                for (arg, typ) in args.iter().zip(&types) {
+                    self.typed(Some(arg), typ, first_token(arg), "argument to built-in");
+                }
+                return Mode::NoValue;
+            }
+            "complex" => match self.unify(types[0].clone(), types[1].clone(), false, at, || {
+                print(call)
+            }) {
+                Some(Type::Untyped(untyped)) if untyped.is_numeric() => {
+                    (Type::Untyped(Untyped::Complex), None)
+                }
+                Some(typ) => match self.underlying(&typ) {
+                    Type::Basic(GoType::Float32) => (Type::Basic(GoType::Complex64), None),
+                    Type::Basic(GoType::Float64) => (Type::Basic(GoType::Complex128), None),
+                    Type::Invalid => (Type::Invalid, None),
+                    _ => invalid(format!(
+                        "invalid operation: {} (arguments have type {}, expected floating-point)",
+                        print(call),
+                        typ
+                    )),
+                },
+                None => (Type::Invalid, None),
+            },
+            "max" | "min" => {
+                let mut typ = types[0].clone();
+                for other in &types[1..] {
+                    typ = self
+                        .unify(typ, other.clone(), false, at, || print(call))
+                        .unwrap_or(Type::Invalid);
+                }
+                match self.allows(&typ, ORDERED) {
+                    true => (typ, None),
+                    false => invalid(format!(
+                        "invalid argument: {} cannot be ordered",
+                        print(call)
+                    )),
+                }
+            }
+            _ => {
+                let (typ, underlying) = (&types[0], self.underlying(&types[0]));
+                let result = match (name, underlying) {
+                    (_, Type::Invalid) => Some(Type::Invalid),
+                    ("len", Type::Basic(GoType::String))
+                    | ("len", Type::Untyped(Untyped::String))
+                    | ("len", Type::Map { .. })
+                    | ("len" | "cap", Type::Slice(_) | Type::Chan { .. }) => {
+                        Some(Type::Basic(GoType::Int))
+                    }
+                    ("len" | "cap", underlying) => {
+                        array_elem(underlying).map(|_| Type::Basic(GoType::Int))
+                    }
+                    ("append", Type::Slice(elem)) => {
+                        self.append(typ, &elem, &args[1..], &types[1..], ellipsis);
+                        Some(typ.clone())
+                    }
+                    ("copy", Type::Slice(_)) => Some(Type::Basic(GoType::Int)),
+                    ("delete", Type::Map { key, .. }) => {
+                        self.assign(&types[1], &key, first_token(&args[1]), "argument to delete");
+                        Some(Type::Tuple(vec![]))
+                    }
+                    ("close", Type::Chan { dir, .. }) if dir != ChanDir::Recv => {
+                        Some(Type::Tuple(vec![]))
+                    }
+                    ("clear", Type::Map { .. } | Type::Slice(_)) => Some(Type::Tuple(vec![])),
+                    ("real" | "imag", Type::Untyped(untyped)) if untyped.is_numeric() => {
+                        Some(Type::Untyped(Untyped::Float))
+                    }
+                    ("real" | "imag", Type::Basic(GoType::Complex64)) => {
+                        Some(Type::Basic(GoType::Float32))
+                    }
+                    ("real" | "imag", Type::Basic(GoType::Complex128)) => {
+                        Some(Type::Basic(GoType::Float64))
+                    }
+                    _ => None,
+                };
+                match result {
+                    Some(Type::Tuple(_)) => return Mode::NoValue,
+                    Some(typ) => (typ, None),
+                    None => invalid(format!(
+                        "invalid argument: {} (value of type {}) for built-in {}",
+                        print(&args[0]),
+                        typ,
+                        name
+                    )),
+                }
+            }
+        };
+        if let Some(message) = message {
+            self.error(ErrorKind::InvalidOperation, first_token(&args[0]), message);
+        }
+        Mode::Value(typ)
+    }
+
+    /// Checks the elements appended to `slice`, of type `elem`.
+    fn append(
+        &mut self,
+        slice: &Type<'a>,
+        elem: &Type<'a>,
+        args: &'a [Expression<'a>],
+        types: &[Type<'a>],
+        ellipsis: bool,
+    ) {
+        for (arg, typ) in args.iter().zip(types) {
+            let at = first_token(arg);
+            match ellipsis {
+                // Appending the bytes of a string to a byte slice.
+                true if *elem == Type::Basic(GoType::Uint8)
+                    && self.kind(typ) == Some(Kind::String) => {}
+                true => self.assign(typ, slice, at, "argument to append"),
+                false => self.assign(typ, elem, at, "argument to append"),
+            }
+        }
+    }
+
+    /// The type of a call of `make` or `new`.
+    fn allocation(
+        &mut self,
+        call: &'a PrimaryExpr<'a>,
+        name: &str,
+        args: &'a [Expression<'a>],
+        at: Option<&'a str>,
+    ) -> Type<'a> {
+        let typ = match self.expr(&args[0]) {
+            Mode::Type(typ) => typ,
+            _ => {
+                let message = format!("{} is not a type", print(&args[0]));
+                self.error(ErrorKind::NotAType, first_token(&args[0]), message);
+                Type::Invalid
+            }
+        };
+        if name == "new" {
+            return Type::Pointer(Box::new(typ));
+        }
+        let (min, max) = match self.underlying(&typ) {
+            Type::Slice(_) => (2, 3),
+            Type::Map { .. } | Type::Chan { .. } => (1, 2),
+            Type::Invalid => (1, 3),
+            _ => {
+                let message = format!(
+                    "invalid argument: cannot make {}; type must be slice, map, or channel",
+                    typ
+                );
+                self.error(ErrorKind::InvalidOperation, first_token(&args[0]), message);
+                (1, 3)
+            }
+        };
+        if args.len() < min || args.len() > max {
+            let message = format!(
+                "invalid operation: {} expects {} or {} arguments; found {}",
+                print(call),
+                min,
+                max,
+                args.len()
+            );
+            self.error(ErrorKind::WrongArgumentCount, at, message);
+        }
+        for size in &args[1..] {
+            let typ = self.value(size);
+            self.integer(&typ, first_token(size), "size");
+        }
+        typ
+    }
+}
+
+/// Infers the type parameters `params` of a function by matching the type `param` of one of its
+/// parameters with the type `arg` of an argument.
+fn infer<'a>(
+    params: &[&'a str],
+    inferred: &mut [Option<Type<'a>>],
+    param: &Type<'a>,
+    arg: &Type<'a>,
+) {
+    let mut pairs = |a: &[Type<'a>], b: &[Type<'a>]| {
+        for (a, b) in a.iter().zip(b) {
+            infer(params, inferred, a, b);
+        }
+    };
+    match (param, arg) {
+        (Type::TypeParam(name), arg) => {
+            let i = params.iter().position(|p| p.as_ptr() == name.as_ptr());
+            if let Some(slot @ None) = i.map(|i| &mut inferred[i]) {
+                if *arg != Type::Untyped(Untyped::Nil) {
+                    *slot = Some(arg.clone());
+                }
+            }
+        }
+        (Type::Pointer(a), Type::Pointer(b))
+        | (Type::Slice(a), Type::Slice(b))
+        | (Type::Array { elem: a, .. }, Type::Array { elem: b, .. })
+        | (Type::Chan { elem: a, .. }, Type::Chan { elem: b, .. }) => infer(params, inferred, a, b),
+        (Type::Map { key: a, value: x }, Type::Map { key: b, value: y }) => {
+            infer(params, inferred, a, b);
+            infer(params, inferred, x, y);
+        }
+        (Type::Named(a), Type::Named(b)) => pairs(&a.type_args, &b.type_args),
+        (Type::Func(a), Type::Func(b)) => {
+            pairs(&a.params, &b.params);
+            pairs(&a.results, &b.results);
+        }
+        _ => {}
+    }
+}
+
+impl<'r, 'a> Checker<'r, 'a> {
+    /// The type of a composite literal of type `typ`, which gets the length of an array written
+    /// `[...]T`.
+    fn composite(
+        &mut self,
+        typ: Type<'a>,
+        value: &'a LiteralValue<'a>,
+        at: Option<&'a str>,
+    ) -> Type<'a> {
+        let elements = &value.0;
+        match self.underlying(&typ) {
+            Type::Struct(fields) => {
+                let keyed = elements.iter().filter(|e| e.key.is_some()).count();
+                if keyed > 0 && keyed < elements.len() {
+                    let message =
+                        "mixture of field:value and value elements in struct literal".to_string();
+                    self.error(ErrorKind::InvalidCompositeLit, at, message);
+                }
+                if keyed == 0 && !elements.is_empty() && elements.len() != fields.len() {
+                    let message = format!(
+                        "too {} values in struct literal of type {}",
+                        if elements.len() < fields.len() {
+                            "few"
+                        } else {
+                            "many"
+                        },
+                        typ
+                    );
+                    self.error(ErrorKind::InvalidCompositeLit, at, message);
+                }
+                for (i, element) in elements.iter().enumerate() {
+                    let field = match &element.key {
+                        Some(key) => {
+                            let name = field_name(key);
+                            let field = fields.iter().find(|field| Some(field.name) == name);
+                            if field.is_none() {
+                                let message = format!(
+                                    "unknown field {} in struct literal of type {}",
+                                    name.unwrap_or("_"),
+                                    typ
+                                );
+                                self.error(ErrorKind::MissingFieldOrMethod, name.or(at), message);
+                            }
+                            field
+                        }
+                        None if keyed == 0 => fields.get(i),
+                        None => None,
+                    };
+                    let field = field.map_or(Type::Invalid, |field| field.typ.clone());
+                    self.element(&element.value, &field, "struct literal");
+                }
+                typ
+            }
+            Type::Array { .. } | Type::Slice(_) => {
+                let (len, elem) = match self.underlying(&typ) {
+                    Type::Array { len, elem } => (len, *elem),
+                    Type::Slice(elem) => (None, *elem),
+                    _ => unreachable!(),
+                };
+                let mut index = 0;
+                let mut count = 0;
+                for element in elements {
+                    if let Some(key) = &element.key {
+                        index = match key {
+                            Element::Expression(key) => {
+                                let typ = self.value(key);
+                                self.integer(&typ, first_token(key), "index");
+                                self.constant(key, None)
+                                    .and_then(|key| key.value.to_int()?.to_u64())
+                                    .unwrap_or(index)
+                            }
+                            Element::LiteralValue(_) => index,
+                        };
+                    }
+                    self.element(&element.value, &elem, "array or slice literal");
+                    index += 1;
+                    count = count.max(index);
+                }
+                match typ {
+                    Type::Array { len: None, elem } => Type::Array {
+                        len: len.or(Some(count)),
+                        elem,
+                    },
+                    typ => typ,
+                }
+            }
+            Type::Map { key, value } => {
+                for element in elements {
+                    match &element.key {
+                        Some(element_key) => self.element(element_key, &key, "map literal"),
+                        None => {
+                            let message = "missing key in map literal".to_string();
+                            self.error(ErrorKind::InvalidCompositeLit, at, message);
+                        }
+                    }
+                    self.element(&element.value, &value, "map literal");
+                }
+                typ
+            }
+            underlying => {
+                if underlying != Type::Invalid {
+                    let message = format!("invalid composite literal type {}", typ);
+                    self.error(ErrorKind::InvalidCompositeLit, at, message);
+                }
+                for element in elements {
+                    if let Some(Element::Expression(key)) = &element.key {
+                        if field_name(element.key.as_ref().unwrap()).is_none() {
+                            self.expr(key);
+                        }
+                    }
+                    self.element(&element.value, &Type::Invalid, "composite literal");
+                }
+                Type::Invalid
+            }
+        }
+    }
+
+    /// Checks an element of a composite literal of type `typ`, whose own type may be elided.
+    fn element(&mut self, element: &'a Element<'a>, typ: &Type<'a>, context: &str) {
+        match element {
+            Element::Expression(expr) => {
+                let value = self.value(expr);
+                self.assign_value(Some(expr), &value, typ, first_token(expr), context);
+            }
+            Element::LiteralValue(value) => {
+                // `&T` can be elided too.
+                let typ = match self.underlying(typ) {
+                    Type::Pointer(base) => *base,
+                    _ => typ.clone(),
+                };
+                self.composite(typ, value, None);
+            }
+        }
+    }
+
+    fn assignable(&mut self, from: &Type<'a>, to: &Type<'a>) -> bool {
+        if identical(from, to) {
+            return true;
+        }
+        let (underlying_from, underlying_to) = (self.underlying(from), self.underlying(to));
+        match (from, to) {
+            (Type::Untyped(untyped), _) => return self.representable(*untyped, to),
+            (_, Type::TypeParam(_)) => return false,
+            (Type::TypeParam(_), _) => return matches!(underlying_to, Type::Interface(_)),
+            _ => {}
+        }
+        // The predeclared types are named too.
+        let named = |typ: &Type<'_>| matches!(typ, Type::Named(_) | Type::Basic(_));
+        let unnamed = !named(from) || !named(to);
+        if let Type::Interface(_) = &underlying_to {
+            return methods::implements(self, from, to).is_ok();
+        }
+        match (&underlying_from, &underlying_to) {
+            (
+                Type::Chan {
+                    dir: ChanDir::Both,
+                    elem: a,
+                },
+                Type::Chan { elem: b, .. },
+            ) => unnamed && identical(a, b),
+            (a, b) => unnamed && identical(a, b),
+        }
+    }
+
+    /// Whether an untyped constant, or `nil`, can be given the type `typ`.
+    fn representable(&mut self, untyped: Untyped, typ: &Type<'a>) -> bool {
+        if let Type::TypeParam(_) = typ {
+            return true;
+        }
+        match (untyped, self.underlying(typ)) {
+            (_, Type::Invalid) => true,
+            (Untyped::Nil, typ) => matches!(
+                typ,
+                Type::Pointer(_)
+                    | Type::Slice(_)
+                    | Type::Map { .. }
+                    | Type::Chan { .. }
+                    | Type::Func(_)
+                    | Type::Interface(_)
+            ),
+            // Given its default type.
+            (_, Type::Interface(methods)) => methods.is_empty(),
+            (Untyped::Bool, Type::Basic(basic)) => basic == GoType::Bool,
+            (Untyped::String, Type::Basic(basic)) => basic == GoType::String,
+            (untyped, Type::Basic(basic)) => untyped.is_numeric() && basic.is_numeric(),
+            (untyped, Type::Untyped(other)) => {
+                untyped == other || (untyped.is_numeric() && other.is_numeric())
+            }
+            _ => false,
+        }
+    }
+
+    fn convertible(&mut self, from: &Type<'a>, to: &Type<'a>) -> bool {
+        if self.assignable(from, to) {
+            return true;
+        }
+        let lenient = |typ: &Type<'_>| matches!(typ, Type::TypeParam(_) | Type::Invalid);
+        if lenient(from) || lenient(to) {
+            return true;
+        }
+        let (from, to) = (self.underlying(from), self.underlying(to));
+        let kinds = (self.kind(&from), self.kind(&to));
+        let bytes_or_runes = |checker: &mut Self, typ: &Type<'a>| match typ {
+            Type::Slice(elem) => matches!(
+                checker.underlying(elem),
+                Type::Basic(GoType::Uint8 | GoType::Int32)
+            ),
+            _ => false,
+        };
+        match (&from, &to) {
+            (Type::Pointer(a), Type::Pointer(b)) => {
+                let (a, b) = (self.underlying(a), self.underlying(b));
+                identical(&a, &b)
+            }
+            (Type::Slice(a), to) => match array_elem(to.clone()) {
+                Some(b) if !matches!(to, Type::Slice(_)) => identical(a, &b),
+                _ => kinds.1 == Some(Kind::String) && bytes_or_runes(self, &from),
+            },
+            (_, Type::Slice(_)) => kinds.0 == Some(Kind::String) && bytes_or_runes(self, &to),
+            _ => match kinds {
+                (Some(Kind::Int | Kind::Float), Some(Kind::Int | Kind::Float))
+                | (Some(Kind::Complex), Some(Kind::Complex))
+                | (Some(Kind::Int), Some(Kind::String)) => true,
+                _ => identical(&from, &to),
+            },
+        }
+    }
+
+    /// Like [`Self::assign`] for the value of `expr`, which must also fit `to` when it is an
+    /// untyped constant.
+    fn assign_value(
+        &mut self,
+        expr: Option<&'a Expression<'a>>,
+        from: &Type<'a>,
+        to: &Type<'a>,
+        at: Option<&'a str>,
+        context: &str,
+    ) {
+        let value = match (expr, from) {
+            (Some(expr), Type::Untyped(_)) => self.constant(expr, None).map(|value| (expr, value)),
+            _ => None,
+        };
+        match value {
+            Some((expr, value)) if !self.assignable(from, to) => {
+                let message = format!(
+                    "cannot use {} as {} value in {}",
+                    value.describe(&print(expr)),
+                    to,
+                    context
+                );
+                self.error(ErrorKind::NotAssignable, at, message);
+            }
+            Some((expr, _)) => self.fits(expr, None, to, Some(context)),
+            None => self.assign(from, to, at, context),
+        }
+    }
+
+    /// Reports the value of the untyped constant `expr`, evaluated with `iota`, when it is too
+    /// big for the type `to`, or not an integer for an integer type. `context` is the assignment
+    /// giving it the type, if it is not an operand converted to the type of the other one.
+    fn fits(
+        &mut self,
+        expr: &'a Expression<'a>,
+        iota: Option<usize>,
+        to: &Type<'a>,
+        context: Option<&str>,
+    ) {
+        let value = match self.constant(expr, iota) {
+            Some(value) if matches!(value.typ, Type::Untyped(_)) => value,
+            _ => return,
+        };
+        // An interface holds the value with its default type.
+        let to = match self.underlying(to) {
+            Type::Interface(_) => default(&value.typ),
+            _ => to.clone(),
+        };
+        let basic = match self.underlying(&to) {
+            Type::Basic(basic) => basic,
+            _ => return,
+        };
+        let (kind, reason) = match value.convert(basic) {
+            Err(constant::ErrorKind::Overflow) => (ErrorKind::Overflow, "overflows"),
+            Err(constant::ErrorKind::Truncated) => (ErrorKind::Truncated, "truncated"),
+            _ => return,
+        };
+        let value = value.describe(&print(expr));
+        let message = match (context, kind) {
+            (Some(context), _) => {
+                format!(
+                    "cannot use {} as {} value in {} ({})",
+                    value, to, context, reason
+                )
+            }
+            (None, ErrorKind::Truncated) => format!("{} truncated to {}", value, to),
+            (None, _) => format!("{} overflows {}", value, to),
+        };
+        self.error(kind, first_token(expr), message);
+    }
+
+    /// Reports a value of type `from` that cannot be assigned to a variable of type `to`.
+    fn assign(&mut self, from: &Type<'a>, to: &Type<'a>, at: Option<&'a str>, context: &str) {
+        if self.assignable(from, to) {
+            return;
+        }
+        let value = match from {
+            Type::Untyped(Untyped::Nil) => "nil".to_string(),
+            from => format!("value of type {}", from),
+        };
+        let mut message = format!("cannot use {} as {} value in {}", value, to, context);
+        if let (Type::Interface(_), false) = (self.underlying(to), matches!(from, Type::Untyped(_)))
+        {
+            if let Err(missing) = methods::implements(self, from, to) {
+                message = format!("{}: {}", message, missing);
+            }
+        }
+        self.error(ErrorKind::NotAssignable, at, message);
+    }
+}
+
+/// The name of a field as the key of an element of a struct literal.
+fn field_name<'a>(key: &'a Element<'a>) -> Option<&'a str> {
+    match key {
+        Element::Expression(Expression::UnaryExpr(UnaryExpr::PrimaryExpr(
+            PrimaryExpr::Operand(Operand::OperandName(OperandName::Identifier(name))),
+        ))) => Some(name),
+        _ => None,
+    }
+}
+
+impl<'r, 'a> Checker<'r, 'a> {
+    /// Computes the type of the object declared by the identifier `name`.
+    fn declare(&mut self, name: &'a str) {
+        if let Some(object) = self.resolution.definition(name).copied() {
+            if matches!(object.kind, ObjKind::Var | ObjKind::Const | ObjKind::Func) {
+                self.object_type(object);
+            }
+        }
+    }
+
+    /// Checks the body of a function or of a function literal returning `results`.
+    fn body(&mut self, signature: &'a Signature<'a>, results: Vec<Type<'a>>, body: &'a Block<'a>) {
+        let names = signature.params.iter().chain(&signature.results);
+        for name in names.flat_map(|param| param.identifiers.iter().flatten()) {
+            self.declare(name);
+        }
+        let named = signature.results.iter().any(|r| r.identifiers.is_some());
+        self.results.push((results, named));
+        self.visit_block(body);
+        self.results.pop();
+    }
+
+    fn condition(&mut self, cond: &'a Expression<'a>, statement: &str) {
+        let typ = self.value(cond);
+        if !self.allows(&typ, &[Kind::Bool]) {
+            let message = format!("non-boolean condition in {}", statement);
+            self.error(ErrorKind::NonBooleanCondition, first_token(cond), message);
+        }
+    }
+
+    /// Reports the values assigned to a different number of variables.
+    fn mismatch(&mut self, variables: usize, values: usize, at: Option<&'a str>) {
+        let plural = |count: usize, what: &str| match count {
+            1 => format!("1 {}", what),
+            count => format!("{} {}s", count, what),
+        };
+        let message = format!(
+            "assignment mismatch: {} but {}",
+            plural(variables, "variable"),
+            plural(values, "value")
+        );
+        self.error(ErrorKind::AssignmentMismatch, at, message);
+    }
+
+    fn return_stmt(&mut self, exprs: &'a [Expression<'a>]) {
+        let (want, named) = match self.results.last() {
+            Some(results) => results.clone(),
+            None => return,
+        };
+        let (have, at) = match self.values(exprs, want.len()) {
+            Ok(types) => {
+                for (i, (typ, result)) in types.iter().zip(&want).enumerate() {
+                    let at = exprs.get(i).or_else(|| exprs.first()).and_then(first_token);
+                    self.assign_value(exprs.get(i), typ, result, at, "return statement");
+                }
+                return;
+            }
+            // A bare return of named results.
+            Err(_) if exprs.is_empty() && named => return,
+            Err(have) => (have, exprs.first().and_then(first_token)),
+        };
+        let message = format!(
+            "{} return values\n\thave ({})\n\twant ({})",
+            if have.len() < want.len() {
+                "not enough"
+            } else {
+                "too many"
+            },
+            list(&have),
+            list(&want)
+        );
+        self.error(ErrorKind::WrongResultCount, at, message);
+    }
+
+    /// The types of the iteration variables of a range clause over `expr`.
+    fn range(&mut self, expr: &'a Expression<'a>) -> (Type<'a>, Type<'a>) {
+        let typ = self.value(expr);
+        let int = Type::Basic(GoType::Int);
+        match self.underlying(&typ) {
+            Type::Basic(GoType::String) | Type::Untyped(Untyped::String) => {
+                (int, Type::Basic(GoType::Int32))
+            }
+            Type::Map { key, value } => (*key, *value),
+            Type::Chan { dir, elem } if dir != ChanDir::Send => (*elem, Type::Invalid),
+            Type::Basic(basic) if basic.is_integer() => (typ, Type::Invalid),
+            Type::Untyped(Untyped::Int | Untyped::Rune) => (int, Type::Invalid),
+            // Iterator functions are not checked.
+            Type::Func(_) | Type::Invalid => (Type::Invalid, Type::Invalid),
+            underlying => match array_elem(underlying) {
+                Some(elem) => (int, elem),
+                None => {
+                    let message =
+                        format!("cannot range over {} (value of type {})", print(expr), typ);
+                    self.error(ErrorKind::InvalidOperation, first_token(expr), message);
+                    (Type::Invalid, Type::Invalid)
+                }
+            },
+        }
+    }
+}
+
+impl<'r, 'a> methods::Types<'a> for Checker<'r, 'a> {
+    fn underlying(&mut self, typ: &Type<'a>) -> Type<'a> {
+        Checker::underlying(self, typ)
+    }
+
+    fn declared(&mut self, named: &Named<'a>) -> Vec<(Method<'a>, bool)> {
+        let functions = named
+            .spec
+            .and_then(|spec| self.methods.get(&address(spec)).cloned());
+        let mut methods = vec![];
+        for function in functions.unwrap_or_default() {
+            if let Type::Func(func) = self.method_type(function, &named.type_args) {
+                let pointer = function.receiver.as_ref().is_some_and(|r| r.pointer);
+                methods.push((
+                    Method {
+                        name: function.name,
+                        func,
+                    },
+                    pointer,
+                ));
+            }
+        }
+        methods
+    }
+}
+
+impl<'r, 'a> Visitor<'a> for Checker<'r, 'a> {
+    fn visit_function(&mut self, node: &'a Function<'a>) {
+        let anchor = std::mem::replace(&mut self.anchor, node.name);
+        let func = self.function_type(node);
+        self.objects
+            .insert(node.name.as_ptr() as usize, Type::Func(func.clone()));
+        for name in node.receiver.iter().flat_map(|r| &r.name) {
+            self.declare(name);
+        }
+        if let Some(body) = &node.body {
+            self.body(&node.signature, func.results, body);
+        }
+        self.anchor = anchor;
+    }
+
+    fn visit_const_decl(&mut self, node: &'a ConstDecl<'a>) {
+        for constant in node.constants() {
+            let typ = self.object_type(Object {
+                name: constant.name,
+                kind: ObjKind::Const,
+                decl: Decl::Const(node),
+            });
+            if let (Some(_), Some(value)) = (constant.typ, constant.value) {
+                let value_type = self.value(value);
+                self.assign(
+                    &value_type,
+                    &typ,
+                    first_token(value),
+                    "constant declaration",
+                );
+                self.fits(
+                    value,
+                    Some(constant.iota),
+                    &typ,
+                    Some("constant declaration"),
+                );
+            }
+            self.declared_constant(constant.name, node);
+        }
+    }
+
+    fn visit_var_spec(&mut self, node: &'a VarSpec<'a>) {
+        let declared = node.typ.as_ref().map(|typ| self.typ(typ));
+        let count = node.identifiers.len();
+        if !node.values.is_empty() {
+            match self.values(&node.values, count) {
+                Ok(types) => {
+                    for (i, typ) in types.iter().enumerate() {
+                        let at = node.values.get(i).or_else(|| node.values.first());
+                        let at = at.and_then(first_token);
+                        match &declared {
+                            Some(declared) => self.assign_value(
+                                node.values.get(i),
+                                typ,
+                                declared,
+                                at,
+                                "variable declaration",
+                            ),
+                            None => self.typed(node.values.get(i), typ, at, "variable declaration"),
+                        }
+                    }
+                }
+                Err(types) => self.mismatch(count, types.len(), Some(node.identifiers[0])),
+            }
+        }
+        for name in &node.identifiers {
+            self.declare(name);
+        }
+    }
+
+    fn visit_type_spec(&mut self, node: &'a TypeSpec<'a>) {
+        let (TypeSpec::TypeDef { name, .. } | TypeSpec::AliasDecl { name, .. }) = node;
+        let object = Object {
+            name,
+            kind: ObjKind::Type,
+            decl: Decl::Type(node),
+        };
+        let typ = self.type_object(object);
+        self.underlying(&typ);
+        self.objects.insert(name.as_ptr() as usize, typ);
+        visit::walk_type_spec(self, node);
+    }
+
+    fn visit_type(&mut self, node: &'a typ::GoType<'a>) {
+        self.typ(node);
+    }
+
+    fn visit_statement(&mut self, node: &'a Statement<'a>) {
+        match node {
+            Statement::ReturnStmt(exprs) => self.return_stmt(exprs),
+            Statement::GoStmt(call) | Statement::DeferStmt(call) => {
+                self.primary(call);
+            }
+            _ => visit::walk_statement(self, node),
+        }
+    }
+
+    fn visit_simple_stmt(&mut self, node: &'a SimpleStmt<'a>) {
+        match node {
+            SimpleStmt::EmptyStmt => {}
+            SimpleStmt::ExpressionStmt(expr) => match self.expr(expr) {
+                Mode::NoValue => {}
+                mode => {
+                    self.value_of(mode, first_token(expr), || print(expr));
+                }
+            },
+            SimpleStmt::SendStmt { channel, value } => {
+                let (typ, value_type) = (self.value(channel), self.value(value));
+                match self.underlying(&typ) {
+                    Type::Chan { dir, elem } if dir != ChanDir::Recv => {
+                        self.assign(&value_type, &elem, first_token(value), "send")
+                    }
+                    Type::Invalid => {}
+                    _ => {
+                        let message = format!(
+                            "invalid operation: cannot send to {} (value of type {})",
+                            print(channel),
+                            typ
+                        );
+                        self.error(ErrorKind::InvalidOperation, first_token(channel), message);
+                    }
+                }
+            }
+            SimpleStmt::IncDecStmt { expr, op } => {
+                let typ = self.value(expr);
+                if !self.allows(&typ, NUMERIC) {
+                    let message = format!(
+                        "invalid operation: {}{} (non-numeric type {})",
+                        print(expr),
+                        op,
+                        typ
+                    );
+                    self.error(ErrorKind::InvalidOperation, Some(op), message);
+                }
+            }
+            SimpleStmt::Assignment { lhs, op, rhs } if *op == "=" => {
+                let targets: Vec<_> = lhs
+                    .iter()
+                    .map(|expr| match is_blank(expr) {
+                        true => None,
+                        false => Some(self.value(expr)),
+                    })
+                    .collect();
+                match self.values(rhs, lhs.len()) {
+                    Ok(types) => {
+                        for (i, (typ, target)) in types.iter().zip(&targets).enumerate() {
+                            let at = rhs.get(i).or_else(|| rhs.first()).and_then(first_token);
+                            match target {
+                                Some(target) => {
+                                    self.assign_value(rhs.get(i), typ, target, at, "assignment")
+                                }
+                                None => self.typed(rhs.get(i), typ, at, "assignment"),
+                            }
+                        }
+                    }
+                    Err(types) => self.mismatch(lhs.len(), types.len(), Some(op)),
+                }
+            }
+            SimpleStmt::Assignment { lhs, op, rhs } => match (lhs.as_slice(), rhs.as_slice()) {
+                ([target], [value]) => {
+                    let (x, y) = (self.value(target), self.value(value));
+                    let operator = &op[..op.len() - 1];
+                    self.operands(target, &x, operator, value, &y);
+                    match operator {
+                        "<<" | ">>" => self.shift(x, y, Some(op), || print(target)),
+                        _ => self.binary(x, operator, y, Some(op), || {
+                            format!("{} {} {}", print(target), op, print(value))
+                        }),
+                    };
+                }
+                _ => {
+                    let message = format!(
+                        "assignment operation {} requires single-valued expressions",
+                        op
+                    );
+                    self.error(ErrorKind::AssignmentMismatch, Some(op), message);
+                }
+            },
+            SimpleStmt::ShortVarDecl {
+                identifiers,
+                values,
+            } => {
+                match self.values(values, identifiers.len()) {
+                    Ok(types) => {
+                        for (i, (typ, name)) in types.iter().zip(identifiers).enumerate() {
+                            let at = values.get(i).or_else(|| values.first());
+                            let at = at.and_then(first_token);
+                            // The variables that are not new are assigned to.
+                            match self.resolution.definition(name) {
+                                Some(_) => self.typed(values.get(i), typ, at, "assignment"),
+                                None => match self.object(name) {
+                                    Some(object) => {
+                                        let variable = self.object_type(object);
+                                        self.assign_value(
+                                            values.get(i),
+                                            typ,
+                                            &variable,
+                                            at,
+                                            "assignment",
+                                        );
+                                    }
+                                    None => self.typed(values.get(i), typ, at, "assignment"),
+                                },
+                            }
+                        }
+                    }
+                    Err(types) => {
+                        self.mismatch(identifiers.len(), types.len(), Some(identifiers[0]))
+                    }
+                }
+                for name in identifiers {
+                    self.declare(name);
+                }
+            }
+        }
+    }
+
+    fn visit_if_stmt(&mut self, node: &'a IfStmt<'a>) {
+        if let Some(init) = &node.init {
+            self.visit_simple_stmt(init);
+        }
+        self.condition(&node.cond, "if statement");
+        self.visit_block(&node.then);
+        if let Some(otherwise) = &node.otherwise {
+            self.visit_else(otherwise);
+        }
+    }
+
+    fn visit_for_clause(&mut self, node: &'a ForClause<'a>) {
+        match node {
+            ForClause::Condition(cond) => self.condition(cond, "for statement"),
+            ForClause::ForClause { init, cond, post } => {
+                if let Some(init) = init {
+                    self.visit_simple_stmt(init);
+                }
+                if let Some(cond) = cond {
+                    self.condition(cond, "for statement");
+                }
+                if let Some(post) = post {
+                    self.visit_simple_stmt(post);
+                }
+            }
+            ForClause::RangeClause { vars, expr } => {
+                let (key, value) = self.range(expr);
+                match vars {
+                    Some(RangeVars::Declared(names)) => {
+                        for name in names {
+                            self.declare(name);
+                        }
+                    }
+                    Some(RangeVars::Assigned(targets)) => {
+                        for (target, typ) in targets.iter().zip([key, value].iter()) {
+                            if !is_blank(target) {
+                                let target_type = self.value(target);
+                                self.assign(typ, &target_type, first_token(target), "range");
+                            }
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    fn visit_switch_stmt(&mut self, node: &'a SwitchStmt<'a>) {
+        if let Some(init) = &node.init {
+            self.visit_simple_stmt(init);
+        }
+        let tag = node
+            .tag
+            .as_ref()
+            .map(|tag| (tag, default(&self.value(tag))));
+        for clause in &node.clauses {
+            for expr in clause.case.iter().flatten() {
+                match &tag {
+                    Some((tag, typ)) => {
+                        let value = self.value(expr);
+                        self.binary(value, "==", typ.clone(), first_token(expr), || {
+                            format!("{} == {}", print(expr), print(*tag))
+                        });
+                    }
+                    None => self.condition(expr, "switch case"),
+                }
+            }
+            for stmt in &clause.body {
+                self.visit_statement(stmt);
+            }
+        }
+    }
+
+    fn visit_type_switch_stmt(&mut self, node: &'a TypeSwitchStmt<'a>) {
+        if let Some(init) = &node.init {
+            self.visit_simple_stmt(init);
+        }
+        let typ = self.primary_value(&node.expr);
+        if !matches!(self.underlying(&typ), Type::Interface(_) | Type::Invalid) {
+            let message = format!(
+                "{} (value of type {}) is not an interface",
+                print(&node.expr),
+                typ
+            );
+            self.error(
+                ErrorKind::InvalidOperation,
+                primary_token(&node.expr),
+                message,
+            );
+        }
+        for clause in &node.clauses {
+            for typ in clause.case.iter().flatten() {
+                if !self.is_nil(typ) {
+                    self.typ(typ);
+                }
+            }
+            if let Some(name) = node.binding {
+                self.object_type(Object {
+                    name,
+                    kind: ObjKind::Var,
+                    decl: Decl::TypeSwitch(node, clause),
+                });
+            }
+            for stmt in &clause.body {
+                self.visit_statement(stmt);
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, node: &'a Expression<'a>) {
+        self.expr(node);
+    }
+
+    fn visit_primary_expr(&mut self, node: &'a PrimaryExpr<'a>) {
+        self.primary(node);
+    }
+}
+
+fn is_blank(expr: &Expression<'_>) -> bool {
+    matches!(
+        expr,
+        Expression::UnaryExpr(UnaryExpr::PrimaryExpr(PrimaryExpr::Operand(
+            Operand::OperandName(OperandName::Identifier("_"))
+        )))
+    )
+}
+
+pub(crate) fn print_unary(expr: &UnaryExpr<'_>) -> String {
+    match expr {
+        UnaryExpr::PrimaryExpr(primary) => print(primary),
+        UnaryExpr::Unary(unary) => format!("{}{}", unary.op, print_unary(&unary.expr)),
+    }
+}
+
+/// The first token of `expr`, where the errors about it are reported. Function literals do not
+/// keep theirs.
+fn first_token<'a>(expr: &'a Expression<'a>) -> Option<&'a str> {
+    match expr {
+        Expression::UnaryExpr(unary) => unary_token(unary),
+        Expression::BinExpr { left, .. } => first_token(left),
+        Expression::BadExpr(text) => Some(text),
+    }
+}
+
+fn unary_token<'a>(expr: &'a UnaryExpr<'a>) -> Option<&'a str> {
+    match expr {
+        UnaryExpr::PrimaryExpr(primary) => primary_first_token(primary),
+        UnaryExpr::Unary(unary) => Some(unary.op),
+    }
+}
+
+pub(crate) fn primary_first_token<'a>(expr: &'a PrimaryExpr<'a>) -> Option<&'a str> {
+    use crate::literals::integer::IntLit;
+    match expr {
+        PrimaryExpr::Operand(operand) => match operand {
+            Operand::Literal(Literal::IntLit(
+                IntLit::DecimalLit(text)
+                | IntLit::BinaryLit(text)
+                | IntLit::OctalLit(text)
+                | IntLit::HexLit(text),
+            ))
+            | Operand::Literal(
+                Literal::FloatLit(text)
+                | Literal::ImaginaryLit(text)
+                | Literal::RuneLit(text)
+                | Literal::StringLit(text),
+            ) => Some(text),
+            Operand::OperandName(OperandName::Identifier(name)) => Some(name),
+            Operand::OperandName(OperandName::QualifiedIdent(ident)) => Some(ident.package_name),
+            Operand::Parenthesized(expr) => first_token(expr),
+            Operand::CompositeLit { typ, .. } => type_token(typ),
+            Operand::TypeLit(lit) => type_lit_token(lit),
+            Operand::FunctionLit(_) => None,
+        },
+        PrimaryExpr::Selector { expr, .. }
+        | PrimaryExpr::Index { expr, .. }
+        | PrimaryExpr::Slice { expr, .. }
+        | PrimaryExpr::TypeAssertion { expr, .. }
+        | PrimaryExpr::Instantiation { expr, .. }
+        | PrimaryExpr::Call { func: expr, .. } => primary_first_token(expr),
+    }
+}
+
+/// The token naming what `expr` denotes: the selector of a selector expression, or the function
+/// of a call.
+fn primary_token<'a>(expr: &'a PrimaryExpr<'a>) -> Option<&'a str> {
+    match expr {
+        PrimaryExpr::Selector { selector, .. } => Some(selector),
+        PrimaryExpr::Operand(Operand::OperandName(OperandName::QualifiedIdent(ident))) => {
+            Some(ident.identifier)
+        }
+        PrimaryExpr::Call { func: expr, .. }
+        | PrimaryExpr::Index { expr, .. }
+        | PrimaryExpr::Instantiation { expr, .. } => primary_token(expr),
+        expr => primary_first_token(expr),
+    }
+}
+
+fn type_token<'a>(typ: &'a typ::GoType<'a>) -> Option<&'a str> {
+    match typ {
+        typ::GoType::TypeName(name) | typ::GoType::Instantiation { name, .. } => match name {
+            TypeName::Identifier(name) => Some(name),
+            TypeName::QualifiedIdent(ident) => Some(ident.package_name),
+        },
+        typ::GoType::TypeLit(lit) => type_lit_token(lit),
+    }
+}
+
+fn type_lit_token<'a>(lit: &'a TypeLit<'a>) -> Option<&'a str> {
+    match lit {
+        TypeLit::ArrayType { elem, .. }
+        | TypeLit::SliceType(elem)
+        | TypeLit::PointerType(elem)
+        | TypeLit::ChannelType { elem, .. }
+        | TypeLit::MapType { key: elem, .. } => type_token(elem),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_check_expressions_and_statements() {
+    use crate::{astable::ASTable, diagnostic::Diagnostic, resolve::resolve};
+
+    let src = "package m
+
+import \"strings\"
+
+type Shape interface{ Area() int }
+
+type Point struct{ X, Y int }
+
+type Square struct {
+	Point
+	Side int
+}
+
+func (s *Square) Area() int { return s.Side * s.Side }
+
+type List[T any] struct{ items []T }
+
+func (l *List[U]) Get(i int) (U, bool) {
+	var zero U
+	if i < len(l.items) {
+		return l.items[i], true
+	}
+	return zero, false
+}
+
+func Keys[K comparable, V any](m map[K]V) []K {
+	var keys []K
+	for k := range m {
+		keys = append(keys, k)
+	}
+	return keys
+}
+
+func div(a, b int) (int, error) {
+	return a / b, nil
+}
+
+func f(n int, s string) {
+	var shape Shape = &Square{Point{1, 2}, 3}
+	sq := Square{Side: 2}
+	l := &List[string]{}
+	v, ok := l.Get(0)
+	keys := Keys(map[string]bool{\"a\": true})
+	q, err := div(n, 2)
+	_ = strings.ToUpper(s)
+	_ = n + s
+	_ = -s
+	if n {
+	}
+	var shape2 Shape = sq.Point
+	_ = sq.Z
+	_ = string(n) + s
+	_ = int(s)
+	q = div(4, 2)
+	f(1)
+	return 1
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+
+    // The type of `name` declared by the first occurrence of `decl`.
+    let object_type = |decl: &str, name: &str| {
+        let offset = src.find(decl).unwrap() + decl.find(name).unwrap();
+        let typ = info.object_type(&src[offset..][..name.len()]);
+        typ.map(Type::to_string).unwrap_or_default()
+    };
+    assert_eq!(object_type("sq :=", "sq"), "Square");
+    assert_eq!(object_type("l :=", "l"), "*List[string]");
+    assert_eq!(object_type("v, ok :=", "v"), "string");
+    assert_eq!(object_type("v, ok :=", "ok"), "bool");
+    assert_eq!(object_type("q, err :=", "err"), "error");
+    assert_eq!(object_type("zero U", "zero"), "U");
+    assert_eq!(
+        object_type("func div", "div"),
+        "func(int, int) (int, error)"
+    );
+    let body = match &file.decls[8] {
+        TopLevel::Function(function) => &function.body.as_ref().unwrap().0,
+        decl => panic!("{:?}", decl),
+    };
+    match &body[4] {
+        Statement::SimpleStmt(SimpleStmt::ShortVarDecl { values, .. }) => {
+            let typ = info.type_of(&values[0]).map(Type::to_string);
+            assert_eq!(typ.as_deref(), Some("[]string"));
+        }
+        stmt => panic!("{:?}", stmt),
+    }
+
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|e| {
+            let span = e.span(src).unwrap();
+            (e.kind, &src[span.start..span.end])
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::MismatchedTypes, "+"),
+            (ErrorKind::InvalidOperation, "-"),
+            (ErrorKind::NonBooleanCondition, "n"),
+            (ErrorKind::NotAssignable, "sq"),
+            (ErrorKind::MissingFieldOrMethod, "Z"),
+            (ErrorKind::InvalidConversion, "s"),
+            (ErrorKind::AssignmentMismatch, "="),
+            (ErrorKind::WrongArgumentCount, "f"),
+            (ErrorKind::WrongResultCount, "1"),
+        ]
+    );
+    assert_eq!(
+        info.errors[3].message,
+        "cannot use value of type Point as Shape value in variable declaration: Point does not implement Shape (missing method Area)"
+    );
+    assert_eq!(
+        info.errors[4].message,
+        "sq.Z undefined (type Square has no field or method Z)"
+    );
+    let diagnostic = Diagnostic::from_type_error(&info.errors[7], src);
+    assert_eq!(diagnostic.message, "not enough arguments in call to f");
+    assert_eq!(
+        diagnostic.notes,
+        ["have (untyped int)", "want (int, string)"]
+    );
+}
+
+#[test]
+fn test_check_function_literals() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+func f(ch chan int) {
+	go func() { ch <- 1 }()
+	defer func() {}()
+	g := func(i int) string {
+		if i > 0 {
+			return \"positive\"
+		}
+		return i
+	}
+	var s string = g(<-ch)
+	h := func(x int) (y int) {
+		y = x + s
+		return
+	}
+	_ = h
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let resolution = resolve([&file]);
+    assert!(resolution.errors.is_empty(), "{:?}", resolution.errors);
+    let info = check([&file], &resolution);
+    let offset = src.find("g :=").unwrap();
+    let typ = info.object_type(&src[offset..][..1]).map(Type::to_string);
+    assert_eq!(typ.as_deref(), Some("func(int) string"));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|e| {
+            let span = e.span(src).unwrap();
+            (e.kind, &src[span.start..span.end])
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::NotAssignable, "i"),
+            (ErrorKind::MismatchedTypes, "+"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_undefined_names_and_index_bounds() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+func f(s []int) {
+	var arr [3]int
+	y := nope + 1
+	undefinedVar = 3
+	var t Missing
+	_ = arr[2] + arr[5] + s[10] + s[-1]
+	p := &arr
+	_ = p[3]
+	_, _ = y, t
+}
+";
+    let dot = "package m\n\nimport . \"fmt\"\n\nfunc g() { Println(undeclared) }\n";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let (_, dot_file) = SourceFile::parse(dot).unwrap();
+    let resolution = resolve([&file, &dot_file]);
+    assert!(resolution.errors.is_empty(), "{:?}", resolution.errors);
+    let info = check([&file, &dot_file], &resolution);
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|e| {
+            let span = e.span(src).unwrap();
+            (e.kind, &src[span.start..span.end])
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::Undefined, "nope"),
+            (ErrorKind::Undefined, "undefinedVar"),
+            (ErrorKind::Undefined, "Missing"),
+            (ErrorKind::IndexOutOfRange, "5"),
+            (ErrorKind::IndexOutOfRange, "-"),
+            (ErrorKind::IndexOutOfRange, "3"),
+        ]
+    );
+    assert_eq!(info.errors[0].message, "undefined: nope");
+    assert_eq!(
+        info.errors[3].message,
+        "invalid argument: index 5 out of bounds [0:3]"
+    );
+    assert_eq!(
+        info.errors[4].message,
+        "invalid argument: index -1 must not be negative"
+    );
+}
+
+#[test]
+fn test_check_type_literal_operands() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+func f[U any](n int, s string, m map[int]int, c chan int) {
+	us := make([]U, 0, n)
+	ro := make(<-chan int)
+	mm := make(map[string]int)
+	b := []byte(s)
+	m2 := map[int]int(m)
+	r := (<-chan int)(c)
+	x := <-chan int(c)
+	_, _, _, _, _, _, _ = us, ro, mm, b, m2, r, x
+	_ = make([]int)
+	_ = []int(s)
+	_ = len([]int)
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let object_type = |decl: &str| {
+        let offset = src.find(decl).unwrap();
+        let name = &src[offset..][..decl.find(' ').unwrap()];
+        info.object_type(name)
+            .map(Type::to_string)
+            .unwrap_or_default()
+    };
+    assert_eq!(object_type("us :="), "[]U");
+    assert_eq!(object_type("ro :="), "<-chan int");
+    assert_eq!(object_type("mm :="), "map[string]int");
+    assert_eq!(object_type("b :="), "[]uint8");
+    assert_eq!(object_type("m2 :="), "map[int]int");
+    assert_eq!(object_type("r :="), "<-chan int");
+    assert_eq!(object_type("x :="), "int");
+    let errors: Vec<_> = info.errors.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        errors,
+        [
+            ErrorKind::WrongArgumentCount,
+            ErrorKind::InvalidConversion,
+            ErrorKind::NotAnExpression,
+        ]
+    );
+}
+
+#[test]
+fn test_check_constants() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+type Weekday int
+
+const (
+	_  = iota
+	KB = 1 << (10 * iota)
+	MB
+)
+
+const (
+	Sunday Weekday = iota
+	Monday
+)
+
+const small int8 = 100
+const big = small * 2
+const tooBig uint8 = 256
+const inverse = 1 / (iota - 0)
+
+var buffer [MB / KB]byte
+
+func f(n int) int {
+	var b byte = 1.5
+	_ = n / 0
+	_ = 1.0 / float64(n)
+	_ = int8(200)
+	return 1 << 70
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    // The declaring identifier, which is the first one starting its line or after `const`.
+    let name = |name: &str| {
+        let (at, _) = src
+            .match_indices(name)
+            .find(|(at, _)| src[..*at].ends_with(['\t', ' ']))
+            .unwrap();
+        &src[at..at + name.len()]
+    };
+    let value = |constant: &str| {
+        info.constant(name(constant))
+            .map(|c| (c.to_string(), c.typ.to_string()))
+    };
+    assert_eq!(
+        value("MB"),
+        Some(("1048576".to_string(), "untyped int".to_string()))
+    );
+    assert_eq!(value("Monday"), Some(("1".to_string(), "int".to_string())));
+    assert_eq!(value("big"), None);
+    let buffer = &src[src.find("buffer").unwrap()..][..6];
+    assert_eq!(info.object_type(buffer).unwrap().to_string(), "[1024]uint8");
+
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::Overflow, "*", "constant 200 overflows int8"),
+            (
+                ErrorKind::Overflow,
+                "256",
+                "cannot use 256 (untyped int constant) as uint8 value in constant declaration (overflows)"
+            ),
+            (ErrorKind::DivisionByZero, "/", "invalid operation: division by zero"),
+            (
+                ErrorKind::Truncated,
+                "1.5",
+                "cannot use 1.5 (untyped float constant) as uint8 value in variable declaration (truncated)"
+            ),
+            (ErrorKind::DivisionByZero, "/", "invalid operation: division by zero"),
+            (ErrorKind::Overflow, "int8", "constant 200 overflows int8"),
+            (
+                ErrorKind::Overflow,
+                "1",
+                "cannot use 1 << 70 (untyped int constant 1180591620717411303424) as int value in return statement (overflows)"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_shifts_and_converted_constants() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+const h = 1.5 << 2
+
+func f(i8 int8, s uint) {
+	x := 1.5 << 2
+	var g int = 1.5 << 2
+	_ = 1.0<<2 + 1.5<<s
+	_ = i8 + 1000
+	_ = i8 * 2.5
+	_ = 2.0*i8 + 127
+	i8 -= 129
+	_ = i8 == 'a'
+	_, _ = x, g
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    let shifted = "invalid operation: shifted operand 1.5 (untyped float constant) must be integer";
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::InvalidOperation, "<<", shifted),
+            (ErrorKind::InvalidOperation, "<<", shifted),
+            (ErrorKind::InvalidOperation, "<<", shifted),
+            (ErrorKind::InvalidOperation, "<<", shifted),
+            (
+                ErrorKind::Overflow,
+                "1000",
+                "1000 (untyped int constant) overflows int8"
+            ),
+            (
+                ErrorKind::Truncated,
+                "2.5",
+                "2.5 (untyped float constant) truncated to int8"
+            ),
+            (
+                ErrorKind::Overflow,
+                "129",
+                "129 (untyped int constant) overflows int8"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_constants_fit_default_types() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+const Huge = 1 << 64
+
+func h(interface{}) {}
+
+func f() {
+	_ = Huge
+	x := Huge
+	h(Huge)
+	var y = Huge
+	var i interface{} = 'a' + Huge
+	_, _, _ = x, y, i
+	_ = Huge >> 2
+	h(1 << 62)
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    let huge = "Huge (untyped int constant 18446744073709551616)";
+    assert_eq!(
+        errors,
+        [
+            (
+                ErrorKind::Overflow,
+                "Huge",
+                &*format!("cannot use {} as int value in assignment (overflows)", huge)
+            ),
+            (
+                ErrorKind::Overflow,
+                "Huge",
+                &*format!("cannot use {} as int value in assignment (overflows)", huge)
+            ),
+            (
+                ErrorKind::Overflow,
+                "Huge",
+                &*format!("cannot use {} as int value in argument (overflows)", huge)
+            ),
+            (
+                ErrorKind::Overflow,
+                "Huge",
+                &*format!(
+                    "cannot use {} as int value in variable declaration (overflows)",
+                    huge
+                )
+            ),
+            (
+                ErrorKind::Overflow,
+                "'a'",
+                "cannot use 'a' + Huge (untyped rune constant 18446744073709551713) as int32 value in variable declaration (overflows)"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_binary_operators() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+type MyInt int
+
+func f(i int, i8 int8, f64 float64, c complex128, s string, b bool, m MyInt, p *int, sl []int, mp map[string]int, ch chan int, fn func()) {
+	_ = i + i8
+	_ = i + m
+	_ = m + 1
+	_ = s + s
+	_ = s - s
+	_ = b + b
+	_ = i % f64
+	_ = f64 % 2.0
+	_ = i & 1
+	_ = i &^ 3
+	_ = f64 & 1
+	_ = c < c
+	_ = s < s
+	_ = b < b
+	_ = p < p
+	_ = b && true
+	_ = i && b
+	_ = b || 1
+	_ = sl == nil
+	_ = mp == nil
+	_ = fn == nil
+	_ = p == nil
+	_ = ch == ch
+	_ = sl == sl
+	_ = fn == fn
+	_ = i == nil
+	_ = i / 0
+	_ = f64 / 0
+	_ = i << uint(1)
+	_ = 1 << i
+	_ = i << f64
+	_ = f64 << 1
+	i <<= 1
+	f64 >>= 1
+	f64 += 1
+	s -= s
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (
+                ErrorKind::MismatchedTypes,
+                "+",
+                "invalid operation: i + i8 (mismatched types int and int8)"
+            ),
+            (
+                ErrorKind::MismatchedTypes,
+                "+",
+                "invalid operation: i + m (mismatched types int and MyInt)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "-",
+                "invalid operation: operator - not defined on s - s (value of type string)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "+",
+                "invalid operation: operator + not defined on b + b (value of type bool)"
+            ),
+            (
+                ErrorKind::MismatchedTypes,
+                "%",
+                "invalid operation: i % f64 (mismatched types int and float64)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "%",
+                "invalid operation: operator % not defined on f64 % 2.0 (value of type float64)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "&",
+                "invalid operation: operator & not defined on f64 & 1 (value of type float64)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "<",
+                "invalid operation: operator < not defined on c < c (value of type complex128)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "<",
+                "invalid operation: operator < not defined on b < b (value of type bool)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "<",
+                "invalid operation: operator < not defined on p < p (value of type *int)"
+            ),
+            (
+                ErrorKind::MismatchedTypes,
+                "&&",
+                "invalid operation: i && b (mismatched types int and bool)"
+            ),
+            (
+                ErrorKind::MismatchedTypes,
+                "||",
+                "invalid operation: b || 1 (mismatched types bool and untyped int)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "==",
+                "invalid operation: sl == sl (slice can only be compared to nil)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "==",
+                "invalid operation: fn == fn (func can only be compared to nil)"
+            ),
+            (
+                ErrorKind::MismatchedTypes,
+                "==",
+                "invalid operation: i == nil (mismatched types int and untyped nil)"
+            ),
+            (
+                ErrorKind::DivisionByZero,
+                "/",
+                "invalid operation: division by zero"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "<<",
+                "invalid operation: shift count type float64, must be integer"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "<<",
+                "invalid operation: shifted operand f64 (value of type float64) must be integer"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                ">>=",
+                "invalid operation: shifted operand f64 (value of type float64) must be integer"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "-=",
+                "invalid operation: operator - not defined on s -= s (value of type string)"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_unary_operators() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+func f(i int, u uint, f64 float64, s string, b bool, p *int, ch chan int, r <-chan int, sn chan<- int) {
+	_ = -u
+	_ = ^i
+	_ = !b
+	_ = &i
+	_ = *p
+	_ = <-ch
+	_ = <-r
+	_ = ^f64
+	_ = !i
+	_ = -b
+	_ = +s
+	_ = *i
+	_ = <-i
+	_ = <-sn
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (
+                ErrorKind::InvalidOperation,
+                "^",
+                "invalid operation: operator ^ not defined on f64 (value of type float64)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "!",
+                "invalid operation: operator ! not defined on i (value of type int)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "-",
+                "invalid operation: operator - not defined on b (value of type bool)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "+",
+                "invalid operation: operator + not defined on s (value of type string)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "*",
+                "invalid operation: cannot indirect i (value of type int)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "<-",
+                "invalid operation: cannot receive from i (value of type int)"
+            ),
+            (
+                ErrorKind::InvalidOperation,
+                "<-",
+                "invalid operation: cannot receive from send-only channel sn (value of type chan<- int)"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_conversions() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+type MyInt int
+
+type Celsius float64
+
+type Bytes []byte
+
+type P *int
+
+type S struct{ A int }
+
+type T struct{ A int }
+
+type U struct{ B int }
+
+type Stringer interface{ String() string }
+
+type Named string
+
+func (Named) String() string { return \"\" }
+
+func f(i int, f64 float64, c complex128, s string, b bool, m MyInt, p *int, bs []byte, rs []rune, is []int, st S, ch chan int, iface interface{}) {
+	_ = MyInt(i)
+	_ = int(m)
+	_ = float64(i)
+	_ = int(f64)
+	_ = Celsius(m)
+	_ = string(i)
+	_ = string(bs)
+	_ = string(rs)
+	_ = []byte(s)
+	_ = []rune(s)
+	_ = Bytes(bs)
+	_ = Bytes(s)
+	_ = T(st)
+	_ = P(p)
+	_ = (*MyInt)(p)
+	_ = Stringer(Named(\"\"))
+	_ = (<-chan int)(ch)
+	_ = Named(\"a\")
+	_ = MyInt(2.0)
+	_ = complex128(f64)
+	_ = float64(c)
+	_ = string(is)
+	_ = []int(s)
+	_ = bool(i)
+	_ = int(b)
+	_ = U(st)
+	_ = int(iface)
+	_ = Stringer(i)
+	_ = (chan int)((<-chan int)(ch))
+	_ = int8(1000)
+	_ = float32(1e40)
+	_ = int(1.5)
+	_ = string(1.5)
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (
+                ErrorKind::InvalidConversion,
+                "f64",
+                "cannot convert f64 (value of type float64) to type complex128"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "c",
+                "cannot convert c (value of type complex128) to type float64"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "is",
+                "cannot convert is (value of type []int) to type string"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "s",
+                "cannot convert s (value of type string) to type []int"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "i",
+                "cannot convert i (value of type int) to type bool"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "b",
+                "cannot convert b (value of type bool) to type int"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "st",
+                "cannot convert st (value of type S) to type U"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "iface",
+                "cannot convert iface (value of type interface{}) to type int"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "i",
+                "cannot convert i (value of type int) to type Stringer"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "int",
+                "cannot convert (<-chan int)(ch) (value of type <-chan int) to type chan int"
+            ),
+            (ErrorKind::Overflow, "int8", "constant 1000 overflows int8"),
+            (
+                ErrorKind::Overflow,
+                "float32",
+                "cannot convert 1e40 (untyped float constant) to type float32"
+            ),
+            (
+                ErrorKind::Truncated,
+                "int",
+                "cannot convert 1.5 (untyped float constant) to type int (truncated)"
+            ),
+            (
+                ErrorKind::InvalidConversion,
+                "1.5",
+                "cannot convert 1.5 (untyped float constant) to type string"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_assignability() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+type MyInt int
+
+type Ints []int
+
+type MyInts []int
+
+type Fn func(int) int
+
+type Stringer interface{ String() string }
+
+type Named string
+
+func (Named) String() string { return \"\" }
+
+type Ptr struct{}
+
+func (*Ptr) String() string { return \"\" }
+
+func f(i int, m MyInt, is []int, ints Ints, my MyInts, ch chan int, r <-chan int, sn chan<- int, fn func(int) int, iface interface{}, st Stringer) {
+	var a Ints = is
+	var b []int = ints
+	var c <-chan int = ch
+	var d chan<- int = ch
+	var e Fn = fn
+	var g func(int) int = Fn(nil)
+	var h interface{} = i
+	var j Stringer = Named(\"\")
+	var k Stringer = &Ptr{}
+	var l interface{} = st
+	var n MyInt = i
+	var o int = m
+	var q Ints = my
+	var t chan int = r
+	var u <-chan int = sn
+	var v Stringer = Ptr{}
+	var w Stringer = iface
+	_, _, _, _, _, _, _, _, _, _ = a, b, c, d, e, g, h, j, k, l
+	_, _, _, _, _, _, _ = n, o, q, t, u, v, w
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    let cannot = |what: &str| format!("cannot use {} value in variable declaration", what);
+    assert_eq!(
+        errors,
+        [
+            (
+                ErrorKind::NotAssignable,
+                "i",
+                &*cannot("value of type int as MyInt")
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "m",
+                &*cannot("value of type MyInt as int")
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "my",
+                &*cannot("value of type MyInts as Ints")
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "r",
+                &*cannot("value of type <-chan int as chan int")
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "sn",
+                &*cannot("value of type chan<- int as <-chan int")
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "Ptr",
+                &*format!(
+                    "{}: Ptr does not implement Stringer (method String has pointer receiver)",
+                    cannot("value of type Ptr as Stringer")
+                )
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "iface",
+                &*format!(
+                    "{}: interface{{}} does not implement Stringer (missing method String)",
+                    cannot("value of type interface{} as Stringer")
+                )
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_representability() {
+    use crate::{astable::ASTable, resolve::resolve};
+
+    let src = "package m
+
+type MyBool bool
+
+type MyFloat float64
+
+func f(i int, u uint, f32 float32, s string, b MyBool, mf MyFloat, c64 complex64) {
+	var a float64 = 1 + 0i
+	var c complex128 = 1.5
+	var d MyBool = 1 < 2
+	var e MyFloat = 'a'
+	var g int = 1e3
+	var h byte = 'é'
+	var j int8 = -128
+	var k uint = 1 << 63
+	var l *int = nil
+	var n interface{} = nil
+	_, _, _, _, _, _, _, _, _, _ = a, c, d, e, g, h, j, k, l, n
+	_ = b && true
+	_ = mf * 2.5
+	_ = c64 + 1i
+	_ = i == 1.0
+	var o float64 = 1i
+	var p int32 = 1e10
+	var q uint = -1
+	var r int = 1.5
+	var t uint8 = 256
+	var v float32 = 1e39
+	var w string = 'a'
+	var x int = \"s\"
+	var y int = nil
+	var z string = nil
+	_, _, _, _, _, _, _, _, _, _ = o, p, q, r, t, v, w, x, y, z
+	_ = u + -1
+	_ = f32 + 1e39
+	_ = i + 1.5
+	_ = 1.5 + i
+	_ = i == 1.5
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let info = check([&file], &resolve([&file]));
+    let errors: Vec<_> = info
+        .errors
+        .iter()
+        .map(|error| (error.kind, error.at, error.message.as_str()))
+        .collect();
+    let cannot = |what: &str, reason: &str| {
+        format!(
+            "cannot use {} value in variable declaration{}",
+            what, reason
+        )
+    };
+    assert_eq!(
+        errors,
+        [
+            (
+                ErrorKind::Truncated,
+                "1i",
+                &*cannot(
+                    "1i (untyped complex constant (0 + 1i)) as float64",
+                    " (truncated)"
+                )
+            ),
+            (
+                ErrorKind::Overflow,
+                "1e10",
+                &*cannot(
+                    "1e10 (untyped float constant 10000000000) as int32",
+                    " (overflows)"
+                )
+            ),
+            (
+                ErrorKind::Overflow,
+                "-",
+                &*cannot("-1 (untyped int constant) as uint", " (overflows)")
+            ),
+            (
+                ErrorKind::Truncated,
+                "1.5",
+                &*cannot("1.5 (untyped float constant) as int", " (truncated)")
+            ),
+            (
+                ErrorKind::Overflow,
+                "256",
+                &*cannot("256 (untyped int constant) as uint8", " (overflows)")
+            ),
+            (
+                ErrorKind::Overflow,
+                "1e39",
+                &*cannot("1e39 (untyped float constant) as float32", " (overflows)")
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "'a'",
+                &*cannot("'a' (untyped rune constant 97) as string", "")
+            ),
+            (
+                ErrorKind::NotAssignable,
+                "\"s\"",
+                &*cannot("\"s\" (untyped string constant) as int", "")
+            ),
+            (ErrorKind::NotAssignable, "nil", &*cannot("nil as int", "")),
+            (
+                ErrorKind::NotAssignable,
+                "nil",
+                &*cannot("nil as string", "")
+            ),
+            (
+                ErrorKind::Overflow,
+                "-",
+                "-1 (untyped int constant) overflows uint"
+            ),
+            (
+                ErrorKind::Overflow,
+                "1e39",
+                "1e39 (untyped float constant) overflows float32"
+            ),
+            (
+                ErrorKind::Truncated,
+                "1.5",
+                "1.5 (untyped float constant) truncated to int"
+            ),
+            (
+                ErrorKind::Truncated,
+                "1.5",
+                "1.5 (untyped float constant) truncated to int"
+            ),
+            (
+                ErrorKind::Truncated,
+                "1.5",
+                "1.5 (untyped float constant) truncated to int"
+            ),
+        ]
+    );
+}
@@ -0,0 +1,733 @@
+//! Traversal of a syntax tree by shared reference.
+//!
+//! Each `visit_*` method of [`Visitor`] defaults to the `walk_*` function of the same node, which
+//! visits the node's children in source order. An implementation overrides the methods for the
+//! nodes it is interested in and calls the `walk_*` function from there to keep descending.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, expression::{operand::OperandName, Expression}, visit::Visitor};
+//! #[derive(Default)]
+//! struct Names<'a>(Vec<&'a str>);
+//! impl<'a> Visitor<'a> for Names<'a> {
+//!     fn visit_operand_name(&mut self, name: &'a OperandName<'a>) {
+//!         if let OperandName::Identifier(name) = name {
+//!             self.0.push(name);
+//!         }
+//!     }
+//! }
+//! let (_, expr) = Expression::parse("f(a, b[i]) + c").unwrap();
+//! let mut names = Names::default();
+//! names.visit_expression(&expr);
+//! assert_eq!(names.0, vec!["f", "a", "b", "i", "c"]);
+//! ```
+
+use crate::{
+    comment::CommentGroup,
+    declaration::{ConstDecl, ConstSpec, Declaration, TypeDecl, TypeSpec, VarDecl, VarSpec},
+    expression::{
+        operand::{Element, FunctionLit, KeyedElement, LiteralValue, Operand, OperandName},
+        Expression, PrimaryExpr, Unary, UnaryExpr,
+    },
+    literals::Literal,
+    statement::{
+        control::{
+            CommClause, Else, ExprCaseClause, ForClause, ForStmt, IfStmt, RangeVars, SelectStmt,
+            SwitchStmt, TypeCaseClause, TypeSwitchStmt,
+        },
+        simple::SimpleStmt,
+        Block, Statement,
+    },
+    typ::{
+        FieldDecl, GoType, InterfaceElem, ParameterDecl, Signature, TypeElem, TypeLit, TypeName,
+        TypeParamDecl, TypeTerm,
+    },
+    Function, ImportDeclaration, Receiver, SourceFile, TopLevel,
+};
+
+/// A traversal of a syntax tree borrowed for `'a`, see the [module documentation](self).
+pub trait Visitor<'a> {
+    fn visit_source_file(&mut self, node: &'a SourceFile<'a>) {
+        walk_source_file(self, node)
+    }
+
+    fn visit_comment_group(&mut self, _node: &'a CommentGroup<'a>) {}
+
+    fn visit_import(&mut self, _node: &'a ImportDeclaration<'a>) {}
+
+    fn visit_top_level(&mut self, node: &'a TopLevel<'a>) {
+        walk_top_level(self, node)
+    }
+
+    fn visit_function(&mut self, node: &'a Function<'a>) {
+        walk_function(self, node)
+    }
+
+    fn visit_receiver(&mut self, _node: &'a Receiver<'a>) {}
+
+    fn visit_declaration(&mut self, node: &'a Declaration<'a>) {
+        walk_declaration(self, node)
+    }
+
+    fn visit_const_decl(&mut self, node: &'a ConstDecl<'a>) {
+        walk_const_decl(self, node)
+    }
+
+    fn visit_const_spec(&mut self, node: &'a ConstSpec<'a>) {
+        walk_const_spec(self, node)
+    }
+
+    fn visit_var_decl(&mut self, node: &'a VarDecl<'a>) {
+        walk_var_decl(self, node)
+    }
+
+    fn visit_var_spec(&mut self, node: &'a VarSpec<'a>) {
+        walk_var_spec(self, node)
+    }
+
+    fn visit_type_decl(&mut self, node: &'a TypeDecl<'a>) {
+        walk_type_decl(self, node)
+    }
+
+    fn visit_type_spec(&mut self, node: &'a TypeSpec<'a>) {
+        walk_type_spec(self, node)
+    }
+
+    fn visit_type(&mut self, node: &'a GoType<'a>) {
+        walk_type(self, node)
+    }
+
+    fn visit_type_name(&mut self, _node: &'a TypeName<'a>) {}
+
+    fn visit_type_lit(&mut self, node: &'a TypeLit<'a>) {
+        walk_type_lit(self, node)
+    }
+
+    fn visit_field_decl(&mut self, node: &'a FieldDecl<'a>) {
+        walk_field_decl(self, node)
+    }
+
+    fn visit_interface_elem(&mut self, node: &'a InterfaceElem<'a>) {
+        walk_interface_elem(self, node)
+    }
+
+    fn visit_type_elem(&mut self, node: &'a TypeElem<'a>) {
+        walk_type_elem(self, node)
+    }
+
+    fn visit_type_term(&mut self, node: &'a TypeTerm<'a>) {
+        walk_type_term(self, node)
+    }
+
+    fn visit_type_param_decl(&mut self, node: &'a TypeParamDecl<'a>) {
+        walk_type_param_decl(self, node)
+    }
+
+    fn visit_signature(&mut self, node: &'a Signature<'a>) {
+        walk_signature(self, node)
+    }
+
+    fn visit_parameter_decl(&mut self, node: &'a ParameterDecl<'a>) {
+        walk_parameter_decl(self, node)
+    }
+
+    fn visit_expression(&mut self, node: &'a Expression<'a>) {
+        walk_expression(self, node)
+    }
+
+    fn visit_unary_expr(&mut self, node: &'a UnaryExpr<'a>) {
+        walk_unary_expr(self, node)
+    }
+
+    fn visit_unary(&mut self, node: &'a Unary<'a>) {
+        walk_unary(self, node)
+    }
+
+    fn visit_primary_expr(&mut self, node: &'a PrimaryExpr<'a>) {
+        walk_primary_expr(self, node)
+    }
+
+    fn visit_operand(&mut self, node: &'a Operand<'a>) {
+        walk_operand(self, node)
+    }
+
+    fn visit_operand_name(&mut self, _node: &'a OperandName<'a>) {}
+
+    fn visit_function_lit(&mut self, node: &'a FunctionLit<'a>) {
+        walk_function_lit(self, node)
+    }
+
+    fn visit_literal(&mut self, _node: &'a Literal<'a>) {}
+
+    fn visit_literal_value(&mut self, node: &'a LiteralValue<'a>) {
+        walk_literal_value(self, node)
+    }
+
+    fn visit_keyed_element(&mut self, node: &'a KeyedElement<'a>) {
+        walk_keyed_element(self, node)
+    }
+
+    fn visit_element(&mut self, node: &'a Element<'a>) {
+        walk_element(self, node)
+    }
+
+    fn visit_statement(&mut self, node: &'a Statement<'a>) {
+        walk_statement(self, node)
+    }
+
+    fn visit_block(&mut self, node: &'a Block<'a>) {
+        walk_block(self, node)
+    }
+
+    fn visit_simple_stmt(&mut self, node: &'a SimpleStmt<'a>) {
+        walk_simple_stmt(self, node)
+    }
+
+    fn visit_if_stmt(&mut self, node: &'a IfStmt<'a>) {
+        walk_if_stmt(self, node)
+    }
+
+    fn visit_else(&mut self, node: &'a Else<'a>) {
+        walk_else(self, node)
+    }
+
+    fn visit_for_stmt(&mut self, node: &'a ForStmt<'a>) {
+        walk_for_stmt(self, node)
+    }
+
+    fn visit_for_clause(&mut self, node: &'a ForClause<'a>) {
+        walk_for_clause(self, node)
+    }
+
+    fn visit_range_vars(&mut self, node: &'a RangeVars<'a>) {
+        walk_range_vars(self, node)
+    }
+
+    fn visit_switch_stmt(&mut self, node: &'a SwitchStmt<'a>) {
+        walk_switch_stmt(self, node)
+    }
+
+    fn visit_expr_case_clause(&mut self, node: &'a ExprCaseClause<'a>) {
+        walk_expr_case_clause(self, node)
+    }
+
+    fn visit_type_switch_stmt(&mut self, node: &'a TypeSwitchStmt<'a>) {
+        walk_type_switch_stmt(self, node)
+    }
+
+    fn visit_type_case_clause(&mut self, node: &'a TypeCaseClause<'a>) {
+        walk_type_case_clause(self, node)
+    }
+
+    fn visit_select_stmt(&mut self, node: &'a SelectStmt<'a>) {
+        walk_select_stmt(self, node)
+    }
+
+    fn visit_comm_clause(&mut self, node: &'a CommClause<'a>) {
+        walk_comm_clause(self, node)
+    }
+}
+
+pub fn walk_source_file<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a SourceFile<'a>) {
+    if let Some(doc) = &node.doc {
+        v.visit_comment_group(doc);
+    }
+    for import in &node.imports {
+        v.visit_import(import);
+    }
+    for decl in &node.decls {
+        v.visit_top_level(decl);
+    }
+}
+
+pub fn walk_top_level<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TopLevel<'a>) {
+    match node {
+        TopLevel::Import(imports) => {
+            for import in imports {
+                v.visit_import(import);
+            }
+        }
+        TopLevel::Function(function) => v.visit_function(function),
+        TopLevel::Declaration(decl) => v.visit_declaration(decl),
+        TopLevel::Pkg(_) | TopLevel::BadDecl(_) => {}
+    }
+}
+
+pub fn walk_function<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Function<'a>) {
+    if let Some(doc) = &node.doc {
+        v.visit_comment_group(doc);
+    }
+    if let Some(receiver) = &node.receiver {
+        v.visit_receiver(receiver);
+    }
+    for param in &node.type_params {
+        v.visit_type_param_decl(param);
+    }
+    v.visit_signature(&node.signature);
+    if let Some(body) = &node.body {
+        v.visit_block(body);
+    }
+}
+
+pub fn walk_declaration<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Declaration<'a>) {
+    match node {
+        Declaration::ConstDecl(decl) => v.visit_const_decl(decl),
+        Declaration::TypeDecl(decl) => v.visit_type_decl(decl),
+        Declaration::VarDecl(decl) => v.visit_var_decl(decl),
+    }
+}
+
+pub fn walk_const_decl<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a ConstDecl<'a>) {
+    if let Some(doc) = &node.doc {
+        v.visit_comment_group(doc);
+    }
+    for spec in &node.specs {
+        v.visit_const_spec(spec);
+    }
+}
+
+pub fn walk_const_spec<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a ConstSpec<'a>) {
+    if let Some(typ) = &node.typ {
+        v.visit_type(typ);
+    }
+    for value in &node.values {
+        v.visit_expression(value);
+    }
+}
+
+pub fn walk_var_decl<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a VarDecl<'a>) {
+    if let Some(doc) = &node.doc {
+        v.visit_comment_group(doc);
+    }
+    for spec in &node.specs {
+        v.visit_var_spec(spec);
+    }
+}
+
+pub fn walk_var_spec<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a VarSpec<'a>) {
+    if let Some(typ) = &node.typ {
+        v.visit_type(typ);
+    }
+    for value in &node.values {
+        v.visit_expression(value);
+    }
+}
+
+pub fn walk_type_decl<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeDecl<'a>) {
+    if let Some(doc) = &node.doc {
+        v.visit_comment_group(doc);
+    }
+    for spec in &node.specs {
+        v.visit_type_spec(spec);
+    }
+}
+
+pub fn walk_type_spec<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeSpec<'a>) {
+    match node {
+        TypeSpec::AliasDecl {
+            type_params, typ, ..
+        }
+        | TypeSpec::TypeDef {
+            type_params, typ, ..
+        } => {
+            for param in type_params {
+                v.visit_type_param_decl(param);
+            }
+            v.visit_type(typ);
+        }
+    }
+}
+
+pub fn walk_type<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a GoType<'a>) {
+    match node {
+        GoType::TypeName(name) => v.visit_type_name(name),
+        GoType::Instantiation { name, type_args } => {
+            v.visit_type_name(name);
+            for arg in type_args {
+                v.visit_type(arg);
+            }
+        }
+        GoType::TypeLit(lit) => v.visit_type_lit(lit),
+    }
+}
+
+pub fn walk_type_lit<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeLit<'a>) {
+    match node {
+        TypeLit::ArrayType { len, elem } => {
+            if let Some(len) = len {
+                v.visit_expression(len);
+            }
+            v.visit_type(elem);
+        }
+        TypeLit::SliceType(elem) | TypeLit::PointerType(elem) => v.visit_type(elem),
+        TypeLit::StructType(fields) => {
+            for field in fields {
+                v.visit_field_decl(field);
+            }
+        }
+        TypeLit::FunctionType(signature) => v.visit_signature(signature),
+        TypeLit::InterfaceType(elems) => {
+            for elem in elems {
+                v.visit_interface_elem(elem);
+            }
+        }
+        TypeLit::MapType { key, value } => {
+            v.visit_type(key);
+            v.visit_type(value);
+        }
+        TypeLit::ChannelType { elem, .. } => v.visit_type(elem),
+    }
+}
+
+pub fn walk_field_decl<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a FieldDecl<'a>) {
+    v.visit_type(&node.go_type);
+}
+
+pub fn walk_interface_elem<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a InterfaceElem<'a>) {
+    match node {
+        InterfaceElem::Method { signature, .. } => v.visit_signature(signature),
+        InterfaceElem::Type(elem) => v.visit_type_elem(elem),
+    }
+}
+
+pub fn walk_type_elem<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeElem<'a>) {
+    for term in &node.0 {
+        v.visit_type_term(term);
+    }
+}
+
+pub fn walk_type_term<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeTerm<'a>) {
+    v.visit_type(&node.go_type);
+}
+
+pub fn walk_type_param_decl<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeParamDecl<'a>) {
+    v.visit_type_elem(&node.constraint);
+}
+
+pub fn walk_signature<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Signature<'a>) {
+    for param in &node.params {
+        v.visit_parameter_decl(param);
+    }
+    for result in &node.results {
+        v.visit_parameter_decl(result);
+    }
+}
+
+pub fn walk_parameter_decl<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a ParameterDecl<'a>) {
+    v.visit_type(&node.go_type);
+}
+
+pub fn walk_expression<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Expression<'a>) {
+    match node {
+        Expression::UnaryExpr(expr) => v.visit_unary_expr(expr),
+        Expression::BinExpr { left, right, .. } => {
+            v.visit_expression(left);
+            v.visit_expression(right);
+        }
+        Expression::BadExpr(_) => {}
+    }
+}
+
+pub fn walk_unary_expr<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a UnaryExpr<'a>) {
+    match node {
+        UnaryExpr::PrimaryExpr(expr) => v.visit_primary_expr(expr),
+        UnaryExpr::Unary(unary) => v.visit_unary(unary),
+    }
+}
+
+pub fn walk_unary<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Unary<'a>) {
+    v.visit_unary_expr(&node.expr);
+}
+
+pub fn walk_primary_expr<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a PrimaryExpr<'a>) {
+    match node {
+        PrimaryExpr::Operand(operand) => v.visit_operand(operand),
+        PrimaryExpr::Selector { expr, .. } => v.visit_primary_expr(expr),
+        PrimaryExpr::Index { expr, index } => {
+            v.visit_primary_expr(expr);
+            v.visit_expression(index);
+        }
+        PrimaryExpr::Slice {
+            expr,
+            low,
+            high,
+            max,
+        } => {
+            v.visit_primary_expr(expr);
+            for bound in IntoIterator::into_iter([low, high, max]).flatten() {
+                v.visit_expression(bound);
+            }
+        }
+        PrimaryExpr::TypeAssertion { expr, typ } => {
+            v.visit_primary_expr(expr);
+            v.visit_type(typ);
+        }
+        PrimaryExpr::Instantiation { expr, type_args } => {
+            v.visit_primary_expr(expr);
+            for arg in type_args {
+                v.visit_type(arg);
+            }
+        }
+        PrimaryExpr::Call { func, args, .. } => {
+            v.visit_primary_expr(func);
+            for arg in args {
+                v.visit_expression(arg);
+            }
+        }
+    }
+}
+
+pub fn walk_operand<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Operand<'a>) {
+    match node {
+        Operand::Literal(literal) => v.visit_literal(literal),
+        Operand::OperandName(name) => v.visit_operand_name(name),
+        Operand::FunctionLit(lit) => v.visit_function_lit(lit),
+        Operand::Parenthesized(expr) => v.visit_expression(expr),
+        Operand::TypeLit(lit) => v.visit_type_lit(lit),
+        Operand::CompositeLit { typ, value } => {
+            v.visit_type(typ);
+            v.visit_literal_value(value);
+        }
+    }
+}
+
+pub fn walk_function_lit<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a FunctionLit<'a>) {
+    v.visit_signature(&node.signature);
+    v.visit_block(&node.body);
+}
+
+pub fn walk_literal_value<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a LiteralValue<'a>) {
+    for element in &node.0 {
+        v.visit_keyed_element(element);
+    }
+}
+
+pub fn walk_keyed_element<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a KeyedElement<'a>) {
+    if let Some(key) = &node.key {
+        v.visit_element(key);
+    }
+    v.visit_element(&node.value);
+}
+
+pub fn walk_element<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Element<'a>) {
+    match node {
+        Element::Expression(expr) => v.visit_expression(expr),
+        Element::LiteralValue(value) => v.visit_literal_value(value),
+    }
+}
+
+pub fn walk_statement<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Statement<'a>) {
+    match node {
+        Statement::DeclStmt(decl) => v.visit_declaration(decl),
+        Statement::SimpleStmt(stmt) => v.visit_simple_stmt(stmt),
+        Statement::Block(block) => v.visit_block(block),
+        Statement::IfStmt(stmt) => v.visit_if_stmt(stmt),
+        Statement::SwitchStmt(stmt) => v.visit_switch_stmt(stmt),
+        Statement::TypeSwitchStmt(stmt) => v.visit_type_switch_stmt(stmt),
+        Statement::SelectStmt(stmt) => v.visit_select_stmt(stmt),
+        Statement::ForStmt(stmt) => v.visit_for_stmt(stmt),
+        Statement::GoStmt(call) | Statement::DeferStmt(call) => v.visit_primary_expr(call),
+        Statement::ReturnStmt(results) => {
+            for result in results {
+                v.visit_expression(result);
+            }
+        }
+        Statement::LabeledStmt { stmt, .. } => v.visit_statement(stmt),
+        Statement::BreakStmt(_)
+        | Statement::ContinueStmt(_)
+        | Statement::GotoStmt(_)
+        | Statement::FallthroughStmt
+        | Statement::BadStmt(_) => {}
+    }
+}
+
+pub fn walk_block<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Block<'a>) {
+    for stmt in &node.0 {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_simple_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a SimpleStmt<'a>) {
+    match node {
+        SimpleStmt::EmptyStmt => {}
+        SimpleStmt::ExpressionStmt(expr) | SimpleStmt::IncDecStmt { expr, .. } => {
+            v.visit_expression(expr)
+        }
+        SimpleStmt::SendStmt { channel, value } => {
+            v.visit_expression(channel);
+            v.visit_expression(value);
+        }
+        SimpleStmt::Assignment { lhs, rhs, .. } => {
+            for expr in lhs.iter().chain(rhs) {
+                v.visit_expression(expr);
+            }
+        }
+        SimpleStmt::ShortVarDecl { values, .. } => {
+            for value in values {
+                v.visit_expression(value);
+            }
+        }
+    }
+}
+
+pub fn walk_if_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a IfStmt<'a>) {
+    if let Some(init) = &node.init {
+        v.visit_simple_stmt(init);
+    }
+    v.visit_expression(&node.cond);
+    v.visit_block(&node.then);
+    if let Some(otherwise) = &node.otherwise {
+        v.visit_else(otherwise);
+    }
+}
+
+pub fn walk_else<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a Else<'a>) {
+    match node {
+        Else::If(stmt) => v.visit_if_stmt(stmt),
+        Else::Block(block) => v.visit_block(block),
+    }
+}
+
+pub fn walk_for_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a ForStmt<'a>) {
+    if let Some(clause) = &node.clause {
+        v.visit_for_clause(clause);
+    }
+    v.visit_block(&node.body);
+}
+
+pub fn walk_for_clause<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a ForClause<'a>) {
+    match node {
+        ForClause::Condition(cond) => v.visit_expression(cond),
+        ForClause::ForClause { init, cond, post } => {
+            if let Some(init) = init {
+                v.visit_simple_stmt(init);
+            }
+            if let Some(cond) = cond {
+                v.visit_expression(cond);
+            }
+            if let Some(post) = post {
+                v.visit_simple_stmt(post);
+            }
+        }
+        ForClause::RangeClause { vars, expr } => {
+            if let Some(vars) = vars {
+                v.visit_range_vars(vars);
+            }
+            v.visit_expression(expr);
+        }
+    }
+}
+
+pub fn walk_range_vars<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a RangeVars<'a>) {
+    match node {
+        RangeVars::Declared(_) => {}
+        RangeVars::Assigned(exprs) => {
+            for expr in exprs {
+                v.visit_expression(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_switch_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a SwitchStmt<'a>) {
+    if let Some(init) = &node.init {
+        v.visit_simple_stmt(init);
+    }
+    if let Some(tag) = &node.tag {
+        v.visit_expression(tag);
+    }
+    for clause in &node.clauses {
+        v.visit_expr_case_clause(clause);
+    }
+}
+
+pub fn walk_expr_case_clause<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a ExprCaseClause<'a>) {
+    for expr in node.case.iter().flatten() {
+        v.visit_expression(expr);
+    }
+    for stmt in &node.body {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_type_switch_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeSwitchStmt<'a>) {
+    if let Some(init) = &node.init {
+        v.visit_simple_stmt(init);
+    }
+    v.visit_primary_expr(&node.expr);
+    for clause in &node.clauses {
+        v.visit_type_case_clause(clause);
+    }
+}
+
+pub fn walk_type_case_clause<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a TypeCaseClause<'a>) {
+    for typ in node.case.iter().flatten() {
+        v.visit_type(typ);
+    }
+    for stmt in &node.body {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_select_stmt<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a SelectStmt<'a>) {
+    for clause in &node.clauses {
+        v.visit_comm_clause(clause);
+    }
+}
+
+pub fn walk_comm_clause<'a, V: Visitor<'a> + ?Sized>(v: &mut V, node: &'a CommClause<'a>) {
+    if let Some(comm) = &node.case {
+        v.visit_simple_stmt(comm);
+    }
+    for stmt in &node.body {
+        v.visit_statement(stmt);
+    }
+}
+
+#[test]
+fn test_walk_reaches_nested_nodes() {
+    use crate::astable::ASTable;
+
+    #[derive(Default)]
+    struct Counter<'a> {
+        calls: Vec<&'a str>,
+        types: usize,
+    }
+
+    impl<'a> Visitor<'a> for Counter<'a> {
+        fn visit_primary_expr(&mut self, node: &'a PrimaryExpr<'a>) {
+            if let PrimaryExpr::Call { func, .. } = node {
+                if let PrimaryExpr::Operand(Operand::OperandName(OperandName::Identifier(name))) =
+                    &**func
+                {
+                    self.calls.push(name);
+                }
+            }
+            walk_primary_expr(self, node);
+        }
+
+        fn visit_type_name(&mut self, _node: &'a TypeName<'a>) {
+            self.types += 1;
+        }
+    }
+
+    let src = "package m
+type Pair[K comparable, V any] struct { k K; v V }
+func f(xs []int) (n int) {
+	for _, x := range xs {
+		if g(x) {
+			defer h(x)
+		}
+		switch y := x.(type) {
+		case int:
+			n += k(y)
+		}
+	}
+	return len(map[string]int{\"a\": m(1)})
+}
+";
+    let (_, file) = SourceFile::parse(src).unwrap();
+    let mut counter = Counter::default();
+    counter.visit_source_file(&file);
+    assert_eq!(counter.calls, vec!["g", "h", "k", "len", "m"]);
+    assert_eq!(counter.types, 9);
+}
@@ -0,0 +1,782 @@
+//! Traversal of a syntax tree by mutable reference, for transforms that rewrite it in place.
+//!
+//! [`VisitorMut`] mirrors [`crate::visit::Visitor`]: each `visit_*` method defaults to the `walk_*`
+//! function of this module that visits the node's children in source order.
+//!
+//! ```
+//! use go_parser_rs::{astable::ASTable, expression::{operand::OperandName, Expression}, visit_mut::VisitorMut};
+//! struct Rename;
+//! impl<'a> VisitorMut<'a> for Rename {
+//!     fn visit_operand_name(&mut self, name: &mut OperandName<'a>) {
+//!         if *name == OperandName::Identifier("x") {
+//!             *name = OperandName::Identifier("y");
+//!         }
+//!     }
+//! }
+//! let (_, mut expr) = Expression::parse("x + f(x)").unwrap();
+//! Rename.visit_expression(&mut expr);
+//! assert_eq!(expr, Expression::parse("y + f(y)").unwrap().1);
+//! ```
+
+use crate::{
+    comment::CommentGroup,
+    declaration::{ConstDecl, ConstSpec, Declaration, TypeDecl, TypeSpec, VarDecl, VarSpec},
+    expression::{
+        operand::{Element, FunctionLit, KeyedElement, LiteralValue, Operand, OperandName},
+        Expression, PrimaryExpr, Unary, UnaryExpr,
+    },
+    literals::Literal,
+    statement::{
+        control::{
+            CommClause, Else, ExprCaseClause, ForClause, ForStmt, IfStmt, RangeVars, SelectStmt,
+            SwitchStmt, TypeCaseClause, TypeSwitchStmt,
+        },
+        simple::SimpleStmt,
+        Block, Statement,
+    },
+    typ::{
+        FieldDecl, GoType, InterfaceElem, ParameterDecl, Signature, TypeElem, TypeLit, TypeName,
+        TypeParamDecl, TypeTerm,
+    },
+    Function, ImportDeclaration, Receiver, SourceFile, TopLevel,
+};
+
+/// A traversal that may rewrite the nodes it visits, see the [module documentation](self).
+pub trait VisitorMut<'a> {
+    fn visit_source_file(&mut self, node: &mut SourceFile<'a>) {
+        walk_source_file(self, node)
+    }
+
+    fn visit_comment_group(&mut self, _node: &mut CommentGroup<'a>) {}
+
+    fn visit_import(&mut self, _node: &mut ImportDeclaration<'a>) {}
+
+    fn visit_top_level(&mut self, node: &mut TopLevel<'a>) {
+        walk_top_level(self, node)
+    }
+
+    fn visit_function(&mut self, node: &mut Function<'a>) {
+        walk_function(self, node)
+    }
+
+    fn visit_receiver(&mut self, _node: &mut Receiver<'a>) {}
+
+    fn visit_declaration(&mut self, node: &mut Declaration<'a>) {
+        walk_declaration(self, node)
+    }
+
+    fn visit_const_decl(&mut self, node: &mut ConstDecl<'a>) {
+        walk_const_decl(self, node)
+    }
+
+    fn visit_const_spec(&mut self, node: &mut ConstSpec<'a>) {
+        walk_const_spec(self, node)
+    }
+
+    fn visit_var_decl(&mut self, node: &mut VarDecl<'a>) {
+        walk_var_decl(self, node)
+    }
+
+    fn visit_var_spec(&mut self, node: &mut VarSpec<'a>) {
+        walk_var_spec(self, node)
+    }
+
+    fn visit_type_decl(&mut self, node: &mut TypeDecl<'a>) {
+        walk_type_decl(self, node)
+    }
+
+    fn visit_type_spec(&mut self, node: &mut TypeSpec<'a>) {
+        walk_type_spec(self, node)
+    }
+
+    fn visit_type(&mut self, node: &mut GoType<'a>) {
+        walk_type(self, node)
+    }
+
+    fn visit_type_name(&mut self, _node: &mut TypeName<'a>) {}
+
+    fn visit_type_lit(&mut self, node: &mut TypeLit<'a>) {
+        walk_type_lit(self, node)
+    }
+
+    fn visit_field_decl(&mut self, node: &mut FieldDecl<'a>) {
+        walk_field_decl(self, node)
+    }
+
+    fn visit_interface_elem(&mut self, node: &mut InterfaceElem<'a>) {
+        walk_interface_elem(self, node)
+    }
+
+    fn visit_type_elem(&mut self, node: &mut TypeElem<'a>) {
+        walk_type_elem(self, node)
+    }
+
+    fn visit_type_term(&mut self, node: &mut TypeTerm<'a>) {
+        walk_type_term(self, node)
+    }
+
+    fn visit_type_param_decl(&mut self, node: &mut TypeParamDecl<'a>) {
+        walk_type_param_decl(self, node)
+    }
+
+    fn visit_signature(&mut self, node: &mut Signature<'a>) {
+        walk_signature(self, node)
+    }
+
+    fn visit_parameter_decl(&mut self, node: &mut ParameterDecl<'a>) {
+        walk_parameter_decl(self, node)
+    }
+
+    fn visit_expression(&mut self, node: &mut Expression<'a>) {
+        walk_expression(self, node)
+    }
+
+    fn visit_unary_expr(&mut self, node: &mut UnaryExpr<'a>) {
+        walk_unary_expr(self, node)
+    }
+
+    fn visit_unary(&mut self, node: &mut Unary<'a>) {
+        walk_unary(self, node)
+    }
+
+    fn visit_primary_expr(&mut self, node: &mut PrimaryExpr<'a>) {
+        walk_primary_expr(self, node)
+    }
+
+    fn visit_operand(&mut self, node: &mut Operand<'a>) {
+        walk_operand(self, node)
+    }
+
+    fn visit_operand_name(&mut self, _node: &mut OperandName<'a>) {}
+
+    fn visit_function_lit(&mut self, node: &mut FunctionLit<'a>) {
+        walk_function_lit(self, node)
+    }
+
+    fn visit_literal(&mut self, _node: &mut Literal<'a>) {}
+
+    fn visit_literal_value(&mut self, node: &mut LiteralValue<'a>) {
+        walk_literal_value(self, node)
+    }
+
+    fn visit_keyed_element(&mut self, node: &mut KeyedElement<'a>) {
+        walk_keyed_element(self, node)
+    }
+
+    fn visit_element(&mut self, node: &mut Element<'a>) {
+        walk_element(self, node)
+    }
+
+    fn visit_statement(&mut self, node: &mut Statement<'a>) {
+        walk_statement(self, node)
+    }
+
+    fn visit_block(&mut self, node: &mut Block<'a>) {
+        walk_block(self, node)
+    }
+
+    fn visit_simple_stmt(&mut self, node: &mut SimpleStmt<'a>) {
+        walk_simple_stmt(self, node)
+    }
+
+    fn visit_if_stmt(&mut self, node: &mut IfStmt<'a>) {
+        walk_if_stmt(self, node)
+    }
+
+    fn visit_else(&mut self, node: &mut Else<'a>) {
+        walk_else(self, node)
+    }
+
+    fn visit_for_stmt(&mut self, node: &mut ForStmt<'a>) {
+        walk_for_stmt(self, node)
+    }
+
+    fn visit_for_clause(&mut self, node: &mut ForClause<'a>) {
+        walk_for_clause(self, node)
+    }
+
+    fn visit_range_vars(&mut self, node: &mut RangeVars<'a>) {
+        walk_range_vars(self, node)
+    }
+
+    fn visit_switch_stmt(&mut self, node: &mut SwitchStmt<'a>) {
+        walk_switch_stmt(self, node)
+    }
+
+    fn visit_expr_case_clause(&mut self, node: &mut ExprCaseClause<'a>) {
+        walk_expr_case_clause(self, node)
+    }
+
+    fn visit_type_switch_stmt(&mut self, node: &mut TypeSwitchStmt<'a>) {
+        walk_type_switch_stmt(self, node)
+    }
+
+    fn visit_type_case_clause(&mut self, node: &mut TypeCaseClause<'a>) {
+        walk_type_case_clause(self, node)
+    }
+
+    fn visit_select_stmt(&mut self, node: &mut SelectStmt<'a>) {
+        walk_select_stmt(self, node)
+    }
+
+    fn visit_comm_clause(&mut self, node: &mut CommClause<'a>) {
+        walk_comm_clause(self, node)
+    }
+}
+
+pub fn walk_source_file<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut SourceFile<'a>) {
+    if let Some(doc) = &mut node.doc {
+        v.visit_comment_group(doc);
+    }
+    for import in &mut node.imports {
+        v.visit_import(import);
+    }
+    for decl in &mut node.decls {
+        v.visit_top_level(decl);
+    }
+}
+
+pub fn walk_top_level<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut TopLevel<'a>) {
+    match node {
+        TopLevel::Import(imports) => {
+            for import in imports {
+                v.visit_import(import);
+            }
+        }
+        TopLevel::Function(function) => v.visit_function(function),
+        TopLevel::Declaration(decl) => v.visit_declaration(decl),
+        TopLevel::Pkg(_) | TopLevel::BadDecl(_) => {}
+    }
+}
+
+pub fn walk_function<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Function<'a>) {
+    if let Some(doc) = &mut node.doc {
+        v.visit_comment_group(doc);
+    }
+    if let Some(receiver) = &mut node.receiver {
+        v.visit_receiver(receiver);
+    }
+    for param in &mut node.type_params {
+        v.visit_type_param_decl(param);
+    }
+    v.visit_signature(&mut node.signature);
+    if let Some(body) = &mut node.body {
+        v.visit_block(body);
+    }
+}
+
+pub fn walk_declaration<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Declaration<'a>) {
+    match node {
+        Declaration::ConstDecl(decl) => v.visit_const_decl(decl),
+        Declaration::TypeDecl(decl) => v.visit_type_decl(decl),
+        Declaration::VarDecl(decl) => v.visit_var_decl(decl),
+    }
+}
+
+pub fn walk_const_decl<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut ConstDecl<'a>) {
+    if let Some(doc) = &mut node.doc {
+        v.visit_comment_group(doc);
+    }
+    for spec in &mut node.specs {
+        v.visit_const_spec(spec);
+    }
+}
+
+pub fn walk_const_spec<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut ConstSpec<'a>) {
+    if let Some(typ) = &mut node.typ {
+        v.visit_type(typ);
+    }
+    for value in &mut node.values {
+        v.visit_expression(value);
+    }
+}
+
+pub fn walk_var_decl<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut VarDecl<'a>) {
+    if let Some(doc) = &mut node.doc {
+        v.visit_comment_group(doc);
+    }
+    for spec in &mut node.specs {
+        v.visit_var_spec(spec);
+    }
+}
+
+pub fn walk_var_spec<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut VarSpec<'a>) {
+    if let Some(typ) = &mut node.typ {
+        v.visit_type(typ);
+    }
+    for value in &mut node.values {
+        v.visit_expression(value);
+    }
+}
+
+pub fn walk_type_decl<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut TypeDecl<'a>) {
+    if let Some(doc) = &mut node.doc {
+        v.visit_comment_group(doc);
+    }
+    for spec in &mut node.specs {
+        v.visit_type_spec(spec);
+    }
+}
+
+pub fn walk_type_spec<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut TypeSpec<'a>) {
+    match node {
+        TypeSpec::AliasDecl {
+            type_params, typ, ..
+        }
+        | TypeSpec::TypeDef {
+            type_params, typ, ..
+        } => {
+            for param in type_params {
+                v.visit_type_param_decl(param);
+            }
+            v.visit_type(typ);
+        }
+    }
+}
+
+pub fn walk_type<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut GoType<'a>) {
+    match node {
+        GoType::TypeName(name) => v.visit_type_name(name),
+        GoType::Instantiation { name, type_args } => {
+            v.visit_type_name(name);
+            for arg in type_args {
+                v.visit_type(arg);
+            }
+        }
+        GoType::TypeLit(lit) => v.visit_type_lit(lit),
+    }
+}
+
+pub fn walk_type_lit<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut TypeLit<'a>) {
+    match node {
+        TypeLit::ArrayType { len, elem } => {
+            if let Some(len) = len {
+                v.visit_expression(len);
+            }
+            v.visit_type(elem);
+        }
+        TypeLit::SliceType(elem) | TypeLit::PointerType(elem) => v.visit_type(elem),
+        TypeLit::StructType(fields) => {
+            for field in fields {
+                v.visit_field_decl(field);
+            }
+        }
+        TypeLit::FunctionType(signature) => v.visit_signature(signature),
+        TypeLit::InterfaceType(elems) => {
+            for elem in elems {
+                v.visit_interface_elem(elem);
+            }
+        }
+        TypeLit::MapType { key, value } => {
+            v.visit_type(key);
+            v.visit_type(value);
+        }
+        TypeLit::ChannelType { elem, .. } => v.visit_type(elem),
+    }
+}
+
+pub fn walk_field_decl<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut FieldDecl<'a>) {
+    v.visit_type(&mut node.go_type);
+}
+
+pub fn walk_interface_elem<'a, V: VisitorMut<'a> + ?Sized>(
+    v: &mut V,
+    node: &mut InterfaceElem<'a>,
+) {
+    match node {
+        InterfaceElem::Method { signature, .. } => v.visit_signature(signature),
+        InterfaceElem::Type(elem) => v.visit_type_elem(elem),
+    }
+}
+
+pub fn walk_type_elem<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut TypeElem<'a>) {
+    for term in &mut node.0 {
+        v.visit_type_term(term);
+    }
+}
+
+pub fn walk_type_term<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut TypeTerm<'a>) {
+    v.visit_type(&mut node.go_type);
+}
+
+pub fn walk_type_param_decl<'a, V: VisitorMut<'a> + ?Sized>(
+    v: &mut V,
+    node: &mut TypeParamDecl<'a>,
+) {
+    v.visit_type_elem(&mut node.constraint);
+}
+
+pub fn walk_signature<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Signature<'a>) {
+    for param in &mut node.params {
+        v.visit_parameter_decl(param);
+    }
+    for result in &mut node.results {
+        v.visit_parameter_decl(result);
+    }
+}
+
+pub fn walk_parameter_decl<'a, V: VisitorMut<'a> + ?Sized>(
+    v: &mut V,
+    node: &mut ParameterDecl<'a>,
+) {
+    v.visit_type(&mut node.go_type);
+}
+
+pub fn walk_expression<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Expression<'a>) {
+    match node {
+        Expression::UnaryExpr(expr) => v.visit_unary_expr(expr),
+        Expression::BinExpr { left, right, .. } => {
+            v.visit_expression(left);
+            v.visit_expression(right);
+        }
+        Expression::BadExpr(_) => {}
+    }
+}
+
+pub fn walk_unary_expr<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut UnaryExpr<'a>) {
+    match node {
+        UnaryExpr::PrimaryExpr(expr) => v.visit_primary_expr(expr),
+        UnaryExpr::Unary(unary) => v.visit_unary(unary),
+    }
+}
+
+pub fn walk_unary<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Unary<'a>) {
+    v.visit_unary_expr(&mut node.expr);
+}
+
+pub fn walk_primary_expr<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut PrimaryExpr<'a>) {
+    match node {
+        PrimaryExpr::Operand(operand) => v.visit_operand(operand),
+        PrimaryExpr::Selector { expr, .. } => v.visit_primary_expr(expr),
+        PrimaryExpr::Index { expr, index } => {
+            v.visit_primary_expr(expr);
+            v.visit_expression(index);
+        }
+        PrimaryExpr::Slice {
+            expr,
+            low,
+            high,
+            max,
+        } => {
+            v.visit_primary_expr(expr);
+            for bound in IntoIterator::into_iter([low, high, max]).flatten() {
+                v.visit_expression(bound);
+            }
+        }
+        PrimaryExpr::TypeAssertion { expr, typ } => {
+            v.visit_primary_expr(expr);
+            v.visit_type(typ);
+        }
+        PrimaryExpr::Instantiation { expr, type_args } => {
+            v.visit_primary_expr(expr);
+            for arg in type_args {
+                v.visit_type(arg);
+            }
+        }
+        PrimaryExpr::Call { func, args, .. } => {
+            v.visit_primary_expr(func);
+            for arg in args {
+                v.visit_expression(arg);
+            }
+        }
+    }
+}
+
+pub fn walk_operand<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Operand<'a>) {
+    match node {
+        Operand::Literal(literal) => v.visit_literal(literal),
+        Operand::OperandName(name) => v.visit_operand_name(name),
+        Operand::FunctionLit(lit) => v.visit_function_lit(lit),
+        Operand::Parenthesized(expr) => v.visit_expression(expr),
+        Operand::TypeLit(lit) => v.visit_type_lit(lit),
+        Operand::CompositeLit { typ, value } => {
+            v.visit_type(typ);
+            v.visit_literal_value(value);
+        }
+    }
+}
+
+pub fn walk_function_lit<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut FunctionLit<'a>) {
+    v.visit_signature(&mut node.signature);
+    v.visit_block(&mut node.body);
+}
+
+pub fn walk_literal_value<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut LiteralValue<'a>) {
+    for element in &mut node.0 {
+        v.visit_keyed_element(element);
+    }
+}
+
+pub fn walk_keyed_element<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut KeyedElement<'a>) {
+    if let Some(key) = &mut node.key {
+        v.visit_element(key);
+    }
+    v.visit_element(&mut node.value);
+}
+
+pub fn walk_element<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Element<'a>) {
+    match node {
+        Element::Expression(expr) => v.visit_expression(expr),
+        Element::LiteralValue(value) => v.visit_literal_value(value),
+    }
+}
+
+pub fn walk_statement<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Statement<'a>) {
+    match node {
+        Statement::DeclStmt(decl) => v.visit_declaration(decl),
+        Statement::SimpleStmt(stmt) => v.visit_simple_stmt(stmt),
+        Statement::Block(block) => v.visit_block(block),
+        Statement::IfStmt(stmt) => v.visit_if_stmt(stmt),
+        Statement::SwitchStmt(stmt) => v.visit_switch_stmt(stmt),
+        Statement::TypeSwitchStmt(stmt) => v.visit_type_switch_stmt(stmt),
+        Statement::SelectStmt(stmt) => v.visit_select_stmt(stmt),
+        Statement::ForStmt(stmt) => v.visit_for_stmt(stmt),
+        Statement::GoStmt(call) | Statement::DeferStmt(call) => v.visit_primary_expr(call),
+        Statement::ReturnStmt(results) => {
+            for result in results {
+                v.visit_expression(result);
+            }
+        }
+        Statement::LabeledStmt { stmt, .. } => v.visit_statement(stmt),
+        Statement::BreakStmt(_)
+        | Statement::ContinueStmt(_)
+        | Statement::GotoStmt(_)
+        | Statement::FallthroughStmt
+        | Statement::BadStmt(_) => {}
+    }
+}
+
+pub fn walk_block<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Block<'a>) {
+    for stmt in &mut node.0 {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_simple_stmt<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut SimpleStmt<'a>) {
+    match node {
+        SimpleStmt::EmptyStmt => {}
+        SimpleStmt::ExpressionStmt(expr) | SimpleStmt::IncDecStmt { expr, .. } => {
+            v.visit_expression(expr)
+        }
+        SimpleStmt::SendStmt { channel, value } => {
+            v.visit_expression(channel);
+            v.visit_expression(value);
+        }
+        SimpleStmt::Assignment { lhs, rhs, .. } => {
+            for expr in lhs.iter_mut().chain(rhs) {
+                v.visit_expression(expr);
+            }
+        }
+        SimpleStmt::ShortVarDecl { values, .. } => {
+            for value in values {
+                v.visit_expression(value);
+            }
+        }
+    }
+}
+
+pub fn walk_if_stmt<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut IfStmt<'a>) {
+    if let Some(init) = &mut node.init {
+        v.visit_simple_stmt(init);
+    }
+    v.visit_expression(&mut node.cond);
+    v.visit_block(&mut node.then);
+    if let Some(otherwise) = &mut node.otherwise {
+        v.visit_else(otherwise);
+    }
+}
+
+pub fn walk_else<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut Else<'a>) {
+    match node {
+        Else::If(stmt) => v.visit_if_stmt(stmt),
+        Else::Block(block) => v.visit_block(block),
+    }
+}
+
+pub fn walk_for_stmt<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut ForStmt<'a>) {
+    if let Some(clause) = &mut node.clause {
+        v.visit_for_clause(clause);
+    }
+    v.visit_block(&mut node.body);
+}
+
+pub fn walk_for_clause<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut ForClause<'a>) {
+    match node {
+        ForClause::Condition(cond) => v.visit_expression(cond),
+        ForClause::ForClause { init, cond, post } => {
+            if let Some(init) = init {
+                v.visit_simple_stmt(init);
+            }
+            if let Some(cond) = cond {
+                v.visit_expression(cond);
+            }
+            if let Some(post) = post {
+                v.visit_simple_stmt(post);
+            }
+        }
+        ForClause::RangeClause { vars, expr } => {
+            if let Some(vars) = vars {
+                v.visit_range_vars(vars);
+            }
+            v.visit_expression(expr);
+        }
+    }
+}
+
+pub fn walk_range_vars<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut RangeVars<'a>) {
+    match node {
+        RangeVars::Declared(_) => {}
+        RangeVars::Assigned(exprs) => {
+            for expr in exprs {
+                v.visit_expression(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_switch_stmt<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut SwitchStmt<'a>) {
+    if let Some(init) = &mut node.init {
+        v.visit_simple_stmt(init);
+    }
+    if let Some(tag) = &mut node.tag {
+        v.visit_expression(tag);
+    }
+    for clause in &mut node.clauses {
+        v.visit_expr_case_clause(clause);
+    }
+}
+
+pub fn walk_expr_case_clause<'a, V: VisitorMut<'a> + ?Sized>(
+    v: &mut V,
+    node: &mut ExprCaseClause<'a>,
+) {
+    for expr in node.case.iter_mut().flatten() {
+        v.visit_expression(expr);
+    }
+    for stmt in &mut node.body {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_type_switch_stmt<'a, V: VisitorMut<'a> + ?Sized>(
+    v: &mut V,
+    node: &mut TypeSwitchStmt<'a>,
+) {
+    if let Some(init) = &mut node.init {
+        v.visit_simple_stmt(init);
+    }
+    v.visit_primary_expr(&mut node.expr);
+    for clause in &mut node.clauses {
+        v.visit_type_case_clause(clause);
+    }
+}
+
+pub fn walk_type_case_clause<'a, V: VisitorMut<'a> + ?Sized>(
+    v: &mut V,
+    node: &mut TypeCaseClause<'a>,
+) {
+    for typ in node.case.iter_mut().flatten() {
+        v.visit_type(typ);
+    }
+    for stmt in &mut node.body {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_select_stmt<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut SelectStmt<'a>) {
+    for clause in &mut node.clauses {
+        v.visit_comm_clause(clause);
+    }
+}
+
+pub fn walk_comm_clause<'a, V: VisitorMut<'a> + ?Sized>(v: &mut V, node: &mut CommClause<'a>) {
+    if let Some(comm) = &mut node.case {
+        v.visit_simple_stmt(comm);
+    }
+    for stmt in &mut node.body {
+        v.visit_statement(stmt);
+    }
+}
+
+#[test]
+fn test_rewrite_source_file() {
+    use crate::{astable::ASTable, literals::integer::IntLit, printer::print};
+
+    struct Rewrite;
+
+    impl<'a> VisitorMut<'a> for Rewrite {
+        fn visit_parameter_decl(&mut self, node: &mut ParameterDecl<'a>) {
+            for name in node.identifiers.iter_mut().flatten() {
+                if *name == "x" {
+                    *name = "n";
+                }
+            }
+            walk_parameter_decl(self, node)
+        }
+
+        fn visit_operand_name(&mut self, node: &mut OperandName<'a>) {
+            if *node == OperandName::Identifier("x") {
+                *node = OperandName::Identifier("n");
+            }
+        }
+
+        fn visit_type_name(&mut self, node: &mut TypeName<'a>) {
+            if *node == TypeName::Identifier("int") {
+                *node = TypeName::Identifier("int64");
+            }
+        }
+
+        fn visit_literal(&mut self, node: &mut Literal<'a>) {
+            if *node == Literal::IntLit(IntLit::DecimalLit("1")) {
+                *node = Literal::IntLit(IntLit::DecimalLit("10"));
+            }
+        }
+
+        fn visit_simple_stmt(&mut self, node: &mut SimpleStmt<'a>) {
+            if let SimpleStmt::IncDecStmt { op, .. } = node {
+                *op = "++";
+            }
+            walk_simple_stmt(self, node)
+        }
+    }
+
+    let src = "package m
+
+type Point struct {
+	X int
+}
+
+func f(x int) []Point {
+	for i := 0; i < x; i++ {
+		if x > 1 {
+			x--
+		}
+	}
+	go func() {
+		x = 1
+	}()
+	return []Point{{X: x}, Point{X: 1}}
+}
+";
+    let (_, mut file) = SourceFile::parse(src).unwrap();
+    Rewrite.visit_source_file(&mut file);
+    let want = "package m
+
+type Point struct {
+	X int64
+}
+
+func f(n int64) []Point {
+	for i := 0; i < n; i++ {
+		if n > 10 {
+			n++
+		}
+	}
+	go func() {
+		n = 10
+	}()
+	return []Point{{X: n}, Point{X: 10}}
+}
+";
+    assert_eq!(print(&file), want);
+}
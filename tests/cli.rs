@@ -0,0 +1,56 @@
+use std::process::{Command, Output};
+
+fn go_parser(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_go-parser"))
+        .args(args)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_tokens_and_ast() {
+    let output = go_parser(&["tokens", "hello.go"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert_eq!(
+        lines.next(),
+        Some("1:1\tComment\t\"// Package main prints a greeting.\"")
+    );
+    assert_eq!(lines.next(), Some("2:1\tKeyword\t\"package\""));
+
+    let output = go_parser(&["ast", "--format", "json", "hello.go", "crlf.go"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(stdout.starts_with("{\"kind\":\"SourceFile\",\"start\":0,"));
+}
+
+#[test]
+fn test_check_reports_errors() {
+    let output = go_parser(&["check", "hello.go", "crlf.go"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty() && output.stderr.is_empty());
+
+    let output = go_parser(&["check", "broken.go"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: "), "{}", stderr);
+    assert!(stderr.contains(" --> broken.go:"), "{}", stderr);
+
+    let output = go_parser(&["parse", "hello.go"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_go_ast_format() {
+    let output = go_parser(&["ast", "--format=go-ast", "hello.go", "crlf.go"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(stdout.starts_with("{\"NodeType\":\"File\",\"Pos\":"));
+
+    let output = go_parser(&["tokens", "--format=go-ast", "hello.go"]);
+    assert_eq!(output.status.code(), Some(2));
+}
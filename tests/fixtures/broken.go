@@ -0,0 +1,11 @@
+package broken
+
+func f( {
+	x := [}
+	)
+}
+
+var = 3 @ 4
+	'unterminated
+type T struct {
+	a int
@@ -0,0 +1,51 @@
+package control
+
+func classify(xs []int, ch chan<- string, done <-chan struct{}) (n int, err error) {
+	if len(xs) == 0 {
+		return 0, nil
+	} else if xs[0] < 0 {
+		n = -1
+	} else {
+		n++
+	}
+outer:
+	for i := 0; i < len(xs); i++ {
+		switch x := xs[i]; {
+		case x > 10, x < -10:
+			continue outer
+		case x == 0:
+			fallthrough
+		default:
+			break outer
+		}
+	}
+	for _, p := range []struct{ a, b int }{{1, 2}, {3, 4}} {
+		n += p.a * p.b
+	}
+	for k, v := range map[string]int{"a": 1} {
+		_, _ = k, v
+	}
+	switch v := interface{}(n).(type) {
+	case int, int64:
+		_ = v
+	case nil:
+	}
+	select {
+	case ch <- "x":
+	case <-done:
+		return
+	default:
+	}
+	go func() {
+		defer close(ch)
+	}()
+	f := func(a, b int) int { return a + b }
+	n = f(n, 1) // trailing comment
+	var s = []func() int{func() int { return 1 }}
+	_ = s[0]()
+	if p := (Point{1, 2}); p.X > 0 {
+		goto end
+	}
+end:
+	return n, err
+}
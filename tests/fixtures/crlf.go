@@ -0,0 +1,7 @@
+package crlf
+
+// Doc.
+func f() {
+	x := 1
+	_ = x
+}
@@ -0,0 +1,52 @@
+package shapes
+
+import (
+	"fmt"
+	m "math"
+)
+
+// Sizes of things.
+const (
+	KB = 1 << (10 * (iota + 1)) // kilo
+	MB
+	GB
+)
+
+const Pi = 3.14159
+
+var (
+	origin     = Point{0, 0}
+	unit   int = 1
+)
+
+/* Point is a point
+   in the plane. */
+type Point struct {
+	X, Y float64 `json:"x,y"`
+	name string
+	*fmt.Stringer
+}
+
+type (
+	Shape interface {
+		Area() float64
+		Perimeter() float64
+	}
+	Number interface {
+		~int | ~int64 | float64
+	}
+	Alias = Point
+)
+
+func (p Point) Dist(q Point) float64 {
+	return m.Hypot(q.X-p.X, q.Y-p.Y)
+}
+
+func (p *Point) Move(dx, dy float64) { p.X += dx; p.Y += dy }
+
+func Sum[T Number](xs ...T) (total T) {
+	for _, x := range xs {
+		total += x
+	}
+	return
+}
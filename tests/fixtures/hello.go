@@ -0,0 +1,8 @@
+// Package main prints a greeting.
+package main
+
+import "fmt"
+
+func main() {
+	fmt.Println("Hello, 世界")
+}
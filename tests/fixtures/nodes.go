@@ -0,0 +1,67 @@
+// Package m is a test.
+package m
+
+import (
+	"fmt"
+	str "strings"
+)
+
+import "os"
+
+// T is a type.
+type T struct {
+	a, b int `json:"a"`
+	c    map[string][]*T
+	ch   <-chan int
+}
+
+type (
+	L[E any] []E
+	A        = T
+	N        interface {
+		~int | ~string
+		M(x int) (int, error)
+	}
+)
+
+const (
+	c0 = iota
+	c1
+)
+
+var v, w = f(1, 2), 'x'
+
+func (t *T) M(xs ...int) int {
+	if x := len(xs); x > 0 && t != nil {
+		return -x
+	} else if x == 0 {
+		return 0
+	}
+	for i, x := range xs {
+		t.a += x * i
+	}
+	for i := 0; i < 10; i++ {
+	}
+loop:
+	for {
+		switch t.a {
+		case 1, 2:
+			break loop
+		default:
+			fallthrough
+		}
+	}
+	switch y := v.(type) {
+	case int, string:
+	}
+	select {
+	case v := <-ch:
+	case ch <- 1:
+	default:
+	}
+	go f(xs...)
+	defer fmt.Println(str.ToUpper("a"), xs[1:2], xs[:1:2], T{a: 1}, []int{1, 2}, &T{}, (t))
+	ch := make(C)
+	_ = v.(T)
+	return L[int]{}[0]
+}